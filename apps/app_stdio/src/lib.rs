@@ -0,0 +1,20 @@
+use wit_bindgen::generate;
+
+generate!({
+    world: "stdio-client",
+    path: "../../wit",
+    generate_all,
+});
+
+struct Component;
+
+impl exports::exorun::test::runnable::Guest for Component {
+    fn run() -> String {
+        // Write to both standard streams through WASI
+        println!("Hello from stdout!");
+        eprintln!("Hello from stderr!");
+        "Done".to_string()
+    }
+}
+
+export!(Component);
@@ -0,0 +1,395 @@
+//! Attribute macros for exorun host components and RPC clients.
+//!
+//! Host components install their functions on a `Linker` by name,
+//! so a typo or a wrong signature only shows up when a guest is linked.
+//! `#[system_component]` reads the interface from WIT instead,
+//! and generates the `link` method from the impl block it decorates:
+//!
+//! ```ignore
+//! #[derive(Clone)]
+//! struct Greeter;
+//!
+//! #[system_component(world = "greeter", interface = "test:greet/api")]
+//! impl Greeter {
+//!     fn greet(&self, name: String) -> String {
+//!         format!("hello, {}", name)
+//!     }
+//! }
+//! ```
+//!
+//! Every function in the interface needs a method of the same name
+//! (with `-` written as `_`), taking `&self` and the WIT parameters.
+//! A missing method, or one whose types don't match the WIT, is a compile error.
+//!
+//! The WIT is read from `path` (default `wit`), relative to the crate's manifest,
+//! and the world must import the interface, since host components provide imports.
+//! The type must be `Clone + Send + Sync + 'static`; each function gets its own clone.
+//! Supported WIT types are the primitives, `string`, `list`, `option`, `result`, and `tuple`.
+//!
+//! `#[rpc_client]` goes the other way, turning a unit struct into a typed
+//! client for an interface served by a peer:
+//!
+//! ```ignore
+//! #[rpc_client(world = "calc-client", interface = "test:calc/api")]
+//! pub struct CalcClient;
+//!
+//! let calc = CalcClient::new(peer);
+//! assert_eq!(calc.add(10, 5).await?, 15);
+//! ```
+//!
+//! Each WIT function becomes an async method calling `Peer::call_typed`,
+//! so arguments and replies go through neorpc with the function's own types.
+//! Only primitives and `string` are supported, since the reply is decoded
+//! against wasmtime types built from the WIT, which needs no component.
+
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::format_ident;
+use quote::quote;
+use syn::Ident;
+use syn::ImplItem;
+use syn::ItemImpl;
+use syn::ItemStruct;
+use syn::LitStr;
+use wit_parser::InterfaceId;
+use wit_parser::PackageSourceMap;
+use wit_parser::Resolve;
+use wit_parser::Type;
+use wit_parser::TypeDefKind;
+use wit_parser::WorldItem;
+
+#[proc_macro_attribute]
+pub fn system_component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = Args::default();
+    let parser = args.parser();
+    syn::parse_macro_input!(attr with parser);
+    let item = syn::parse_macro_input!(item as ItemImpl);
+
+    // Keep the impl on error, so its methods don't vanish and cascade more errors
+    match system_component_impl(args, &item) {
+        Ok(ts) => ts.into(),
+        Err(e) => {
+            let e = e.to_compile_error();
+            quote! { #item #e }.into()
+        }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn rpc_client(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = Args::default();
+    let parser = args.parser();
+    syn::parse_macro_input!(attr with parser);
+    let item = syn::parse_macro_input!(item as ItemStruct);
+
+    match rpc_client_impl(args, &item) {
+        Ok(ts) => ts.into(),
+        Err(e) => {
+            let e = e.to_compile_error();
+            quote! { #item #e }.into()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    path: Option<LitStr>,
+    world: Option<LitStr>,
+    interface: Option<LitStr>,
+}
+
+impl Args {
+    fn parser(&mut self) -> impl syn::parse::Parser<Output = ()> + '_ {
+        syn::meta::parser(move |meta| {
+            let value = || meta.value()?.parse::<LitStr>();
+            if meta.path.is_ident("path") {
+                self.path = Some(value()?);
+            } else if meta.path.is_ident("world") {
+                self.world = Some(value()?);
+            } else if meta.path.is_ident("interface") {
+                self.interface = Some(value()?);
+            } else {
+                return Err(meta.error("expected `path`, `world`, or `interface`"));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// An interface imported by a world, read from WIT.
+struct Imported {
+    resolve: Resolve,
+    interface_id: InterfaceId,
+    interface: LitStr,
+    sources: PackageSourceMap,
+}
+
+impl Imported {
+    fn load(args: Args) -> syn::Result<Self> {
+        let world = args.world
+            .ok_or_else(|| syn::Error::new(Span::call_site(), "missing `world = \"...\"`"))?;
+        let interface = args.interface
+            .ok_or_else(|| syn::Error::new(Span::call_site(), "missing `interface = \"...\"`"))?;
+        let path = args.path
+            .unwrap_or_else(|| LitStr::new("wit", Span::call_site()));
+
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+            .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+        let dir = PathBuf::from(manifest_dir).join(path.value());
+
+        let mut resolve = Resolve::default();
+        let (package, sources) = resolve.push_dir(&dir)
+            .map_err(|e| syn::Error::new(path.span(), format!("cannot read WIT from {}: {:#}", dir.display(), e)))?;
+        let world_id = resolve.select_world(&[package], Some(&world.value()))
+            .map_err(|e| syn::Error::new(world.span(), format!("{:#}", e)))?;
+
+        // Host components provide what guests import, and clients call it
+        let interface_id = resolve.worlds[world_id].imports.values()
+            .find_map(|item| match item {
+                WorldItem::Interface { id, .. } if resolve.id_of(*id).as_deref() == Some(&interface.value()) => Some(*id),
+                _ => None,
+            })
+            .ok_or_else(|| syn::Error::new(
+                interface.span(),
+                format!("world '{}' does not import interface '{}'", world.value(), interface.value()),
+            ))?;
+
+        Ok(Self { resolve, interface_id, interface, sources })
+    }
+
+    /// Rebuilds when the WIT changes.
+    fn track_sources(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
+        self.sources.paths().map(|path| {
+            let path = path.display().to_string();
+            quote! { const _: &str = include_str!(#path); }
+        })
+    }
+}
+
+fn system_component_impl(args: Args, item: &ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    let imported = Imported::load(args)?;
+    let Imported { resolve, interface_id, interface, .. } = &imported;
+
+    let self_ty = &item.self_ty;
+    let mut funcs = Vec::new();
+    for (name, func) in &resolve.interfaces[*interface_id].functions {
+        let method = ident(name);
+        let has_method = item.items.iter().any(|item| matches!(item, ImplItem::Fn(f) if f.sig.ident == method));
+        if !has_method {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("missing method `{}` for WIT function '{}' in interface '{}'", method, name, interface.value()),
+            ));
+        }
+
+        let params: Vec<_> = (0..func.params.len()).map(|i| format_ident!("p{}", i)).collect();
+        let param_tys = func.params.iter()
+            .map(|(_, ty)| rust_type(resolve, ty, interface))
+            .collect::<syn::Result<Vec<_>>>()?;
+        let (result_ty, result) = match &func.result {
+            Some(ty) => {
+                let ty = rust_type(resolve, ty, interface)?;
+                (quote! { (#ty,) }, quote! { (this.#method(#(#params),*),) })
+            }
+            None => (quote! { () }, quote! { this.#method(#(#params),*) }),
+        };
+
+        funcs.push(quote! {
+            {
+                let this = ::std::clone::Clone::clone(self);
+                instance
+                    .func_wrap(
+                        #name,
+                        move |_caller: ::wasmtime::StoreContextMut<'_, ::exorun::context::ExorunCtx>,
+                              (#(#params,)*): (#(#param_tys,)*)|
+                              -> ::wasmtime::Result<#result_ty> {
+                            ::std::result::Result::Ok(#result)
+                        },
+                    )
+                    .map_err(|e| ::exorun::host::Error::Link(e.to_string()))?;
+            }
+        });
+    }
+
+    let sources = imported.track_sources();
+    let link_doc = format!("Links this component to the linker, installing the `{}` interface.", interface.value());
+    Ok(quote! {
+        #item
+
+        impl #self_ty {
+            #[doc = #link_doc]
+            pub fn link(
+                &self,
+                linker: &mut ::wasmtime::component::Linker<::exorun::context::ExorunCtx>,
+            ) -> ::exorun::host::Result<()> {
+                #(#sources)*
+                let mut instance = linker
+                    .instance(#interface)
+                    .map_err(|e| ::exorun::host::Error::Link(e.to_string()))?;
+                #(#funcs)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn rpc_client_impl(args: Args, item: &ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
+    if !matches!(item.fields, syn::Fields::Unit) || !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&item.ident, "#[rpc_client] expects a unit struct, like `struct Client;`"));
+    }
+    let imported = Imported::load(args)?;
+    let Imported { resolve, interface_id, interface, .. } = &imported;
+
+    let mut methods = Vec::new();
+    for (name, func) in &resolve.interfaces[*interface_id].functions {
+        // Params travel as a tuple, which `IntoVals` covers up to six
+        if func.params.len() > 6 {
+            return Err(syn::Error::new(
+                interface.span(),
+                format!("WIT function '{}' has more than 6 params, which #[rpc_client] does not support", name),
+            ));
+        }
+        let method = ident(name);
+        let params: Vec<_> = func.params.iter().map(|(name, _)| ident(name)).collect();
+        let param_tys = func.params.iter()
+            .map(|(_, ty)| client_type(resolve, ty, interface).map(|(rust, _)| rust))
+            .collect::<syn::Result<Vec<_>>>()?;
+        let (result_ty, result_types) = match &func.result {
+            Some(ty) => {
+                let (rust, wasm) = client_type(resolve, ty, interface)?;
+                (rust, quote! { ::std::vec![#wasm] })
+            }
+            None => (quote! { () }, quote! { ::std::vec::Vec::new() }),
+        };
+
+        let doc = format!("Calls `{}` on the remote.", name);
+        methods.push(quote! {
+            #[doc = #doc]
+            pub async fn #method(&self, #(#params: #param_tys),*) -> ::exorun::peer::Result<#result_ty> {
+                self.peer.call_typed(&self.target, #name, (#(#params,)*), #result_types).await
+            }
+        });
+    }
+
+    let sources = imported.track_sources();
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let name = &item.ident;
+    let new_doc = format!("A client calling `{}` on `peer`, under the interface's own name as target.", interface.value());
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #name {
+            peer: ::std::sync::Arc<::exorun::peer::Peer>,
+            target: ::std::string::String,
+        }
+
+        impl #name {
+            #[doc = #new_doc]
+            pub fn new(peer: ::std::sync::Arc<::exorun::peer::Peer>) -> Self {
+                #(#sources)*
+                Self::with_target(peer, #interface)
+            }
+
+            /// A client calling the interface exposed under `target`, as with `Runtime::expose`.
+            pub fn with_target(
+                peer: ::std::sync::Arc<::exorun::peer::Peer>,
+                target: impl ::std::convert::Into<::std::string::String>,
+            ) -> Self {
+                Self { peer, target: target.into() }
+            }
+
+            #(#methods)*
+        }
+    })
+}
+
+/// A Rust identifier for a WIT name, raw if it is a keyword.
+fn ident(name: &str) -> Ident {
+    let name = name.replace('-', "_");
+    syn::parse_str::<Ident>(&name).unwrap_or_else(|_| Ident::new_raw(&name, Span::call_site()))
+}
+
+/// The Rust type and matching wasmtime `Type` of a WIT type a client can call with.
+fn client_type(resolve: &Resolve, ty: &Type, interface: &LitStr) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let variant = match ty {
+        Type::Bool => quote! { Bool },
+        Type::U8 => quote! { U8 },
+        Type::U16 => quote! { U16 },
+        Type::U32 => quote! { U32 },
+        Type::U64 => quote! { U64 },
+        Type::S8 => quote! { S8 },
+        Type::S16 => quote! { S16 },
+        Type::S32 => quote! { S32 },
+        Type::S64 => quote! { S64 },
+        Type::F32 => quote! { Float32 },
+        Type::F64 => quote! { Float64 },
+        Type::Char => quote! { Char },
+        Type::String => quote! { String },
+        Type::ErrorContext => return Err(unsupported("error-context", interface, "rpc_client")),
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => return client_type(resolve, ty, interface),
+            other => return Err(unsupported(other.as_str(), interface, "rpc_client")),
+        },
+    };
+    let rust = rust_type(resolve, ty, interface)?;
+    Ok((rust, quote! { ::wasmtime::component::Type::#variant }))
+}
+
+/// The Rust type wasmtime lifts and lowers for a WIT type.
+fn rust_type(resolve: &Resolve, ty: &Type, interface: &LitStr) -> syn::Result<proc_macro2::TokenStream> {
+    let tokens = match ty {
+        Type::Bool => quote! { bool },
+        Type::U8 => quote! { u8 },
+        Type::U16 => quote! { u16 },
+        Type::U32 => quote! { u32 },
+        Type::U64 => quote! { u64 },
+        Type::S8 => quote! { i8 },
+        Type::S16 => quote! { i16 },
+        Type::S32 => quote! { i32 },
+        Type::S64 => quote! { i64 },
+        Type::F32 => quote! { f32 },
+        Type::F64 => quote! { f64 },
+        Type::Char => quote! { char },
+        Type::String => quote! { ::std::string::String },
+        Type::ErrorContext => return Err(unsupported("error-context", interface, "system_component")),
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(ty) => rust_type(resolve, ty, interface)?,
+            TypeDefKind::List(ty) => {
+                let ty = rust_type(resolve, ty, interface)?;
+                quote! { ::std::vec::Vec<#ty> }
+            }
+            TypeDefKind::Option(ty) => {
+                let ty = rust_type(resolve, ty, interface)?;
+                quote! { ::std::option::Option<#ty> }
+            }
+            TypeDefKind::Result(result) => {
+                let ok = match &result.ok {
+                    Some(ty) => rust_type(resolve, ty, interface)?,
+                    None => quote! { () },
+                };
+                let err = match &result.err {
+                    Some(ty) => rust_type(resolve, ty, interface)?,
+                    None => quote! { () },
+                };
+                quote! { ::std::result::Result<#ok, #err> }
+            }
+            TypeDefKind::Tuple(tuple) => {
+                let tys = tuple.types.iter()
+                    .map(|ty| rust_type(resolve, ty, interface))
+                    .collect::<syn::Result<Vec<_>>>()?;
+                quote! { (#(#tys,)*) }
+            }
+            other => return Err(unsupported(other.as_str(), interface, "system_component")),
+        },
+    };
+    Ok(tokens)
+}
+
+fn unsupported(kind: &str, interface: &LitStr, macro_name: &str) -> syn::Error {
+    syn::Error::new(
+        interface.span(),
+        format!("WIT {} types are not supported by #[{}]", kind, macro_name),
+    )
+}
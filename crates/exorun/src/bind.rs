@@ -13,12 +13,17 @@ use wasmtime::component::LinkerInstance;
 use wasmtime::component::Type;
 use wasmtime::component::Val;
 use neorpc::CallEncoder;
+use neorpc::FailureReason;
 
 use crate::context::ExorunCtx;
+use crate::ledger::FuncKind;
 use crate::ledger::Ledger;
-use crate::runtime::PeerId;
-use crate::runtime::InstanceId;
+use crate::peer;
 use crate::peer::PeerInstance;
+use crate::runtime;
+use crate::runtime::InstanceId;
+use crate::runtime::PeerId;
+use crate::runtime::Runtime;
 
 #[derive(Debug)]
 pub enum Error {
@@ -69,10 +74,12 @@ impl Binder {
             .map_err(Error::Linker)?;
 
         for (method_name, signature) in schema.funcs.iter() {
+            // Resource functions can't be bridged; left unlinked, instantiation reports them
+            if signature.kind != FuncKind::Freestanding { continue; }
             Binder::peer_method(
                 &mut linker_instance,
                 method_name,
-                target.peer_id.clone(),
+                target.peer_id,
                 target.target_id.clone(),
                 signature.results.clone(),
             )?;
@@ -81,10 +88,6 @@ impl Binder {
         Ok(())
     }
 
-    // TODO: we pass result types here but maybe we can
-    //       prepare special data for the decoder that
-    //       has instructions for how to decode specific types
-    //       and we calculate this once instead of tree-walking
     /// Generates the async closure for a specific method within an instance.
     /// The closure resolves the peer_id to a Peer at call time via the Runtime
     /// in ExorunCtx, enabling transparent reconnection.
@@ -98,42 +101,17 @@ impl Binder {
         let method_name_owned = method_name.to_string();
 
         linker_instance.func_new_async(method_name, move |store, _func_ty, args, results| {
-            let peer_id = peer_id.clone();
             let result_types = result_types.clone();
             let target_id = target_id.clone();
             let method_name = method_name_owned.clone();
 
-            // TODO: get rid of map_err by writing helper function
-            //       or automatic conversion for given error types
             Box::new(async move {
                 // Get runtime from store context and resolve peer_id to peer
-                let runtime = store.data().runtime.clone();
-                let peer = runtime.get_peer(peer_id)
-                    .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
-
-                // prepare the call by incrementing seq and reserving pending
-                let (seq, rx) = peer.prepare_call(result_types);
-
-                // encode arguments directly without copying
-                let args_bytes = neorpc::encode_vals_to_bytes(args)
-                    .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
-
-                // build the payload
-                let payload = CallEncoder::new(seq, &target_id, &method_name, &args_bytes)
-                    .into_bytes()
-                    .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
-
-                // send and await response
-                let return_vals = peer.send_and_await(seq, payload, rx)
+                let runtime = Arc::clone(&store.data().runtime);
+                let return_vals = call_peer(&runtime, peer_id, &target_id, &method_name, args, result_types)
                     .await
                     .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
-
-                // copy out return vals
-                for (i, val) in return_vals.into_iter().enumerate() {
-                    results[i] = val;
-                }
-
-                Ok(())
+                copy_results(return_vals, results)
             })
         }).map_err(Error::Linker)?;
 
@@ -157,6 +135,7 @@ impl Binder {
             .map_err(Error::Linker)?;
 
         for (method_name, signature) in schema.funcs.iter() {
+            if signature.kind != FuncKind::Freestanding { continue; }
             Binder::local_method(
                 &mut linker_instance,
                 method_name,
@@ -193,36 +172,180 @@ impl Binder {
                 let call_results = runtime.call(target_id, &interface_name, &method_name, &args_vec)
                     .await
                     .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+                copy_results(call_results, results)
+            })
+        }).map_err(Error::Linker)?;
 
-                if call_results.len() != results.len() {
-                    return Err(wasmtime::Error::msg(format!(
-                        "Result count mismatch: expected {}, got {}",
-                        results.len(),
-                        call_results.len()
-                    )));
-                }
+        Ok(())
+    }
 
-                for (i, val) in call_results.into_iter().enumerate() {
-                    results[i] = val;
-                }
+    /// Links an interface to a chain of targets, tried in order on each call.
+    ///
+    /// A call moves on to the next target only when one is unreachable:
+    /// a missing instance or peer, or a peer that is disconnected.
+    /// Any other outcome, including a guest's domain error, is the answer.
+    /// If every target is unreachable, the last error is returned.
+    pub fn chain_interface(
+        linker: &mut Linker<ExorunCtx>,
+        ledger: &Ledger,
+        interface_name: &str,
+        targets: &[Linkable],
+    ) -> Result<()> {
+        let schema = ledger.imports.get(interface_name)
+            .ok_or_else(|| Error::InterfaceNotFound(interface_name.to_string()))?;
 
-                Ok(())
-            })
-        }).map_err(Error::Linker)?;
+        let mut linker_instance = linker.instance(interface_name)
+            .map_err(Error::Linker)?;
+
+        let targets: Arc<[Linkable]> = targets.into();
+        for (method_name, signature) in schema.funcs.iter() {
+            if signature.kind != FuncKind::Freestanding { continue; }
+            let interface_name = interface_name.to_string();
+            let method = method_name.to_string();
+            let targets = Arc::clone(&targets);
+            let result_types = signature.results.clone();
+
+            linker_instance.func_new_async(method_name, move |store, _func_ty, args, results| {
+                let interface_name = interface_name.clone();
+                let method = method.clone();
+                let targets = Arc::clone(&targets);
+                let result_types = result_types.clone();
+
+                Box::new(async move {
+                    let runtime = Arc::clone(&store.data().runtime);
+                    let mut last = None;
+                    for target in targets.iter() {
+                        let outcome = match target {
+                            Linkable::LocalInstance(id) => runtime.call(*id, &interface_name, &method, args)
+                                .await
+                                .map_err(TargetError::Runtime),
+                            Linkable::Remote(peer) => {
+                                call_peer(&runtime, peer.peer_id, &peer.target_id, &method, args, result_types.clone()).await
+                            }
+                        };
+                        match outcome {
+                            Ok(vals) => return copy_results(vals, results),
+                            Err(e) if e.is_unreachable() => last = Some(e),
+                            Err(e) => return Err(wasmtime::Error::msg(e.to_string())),
+                        }
+                    }
+                    let last = last.map_or_else(|| "no targets to call".to_string(), |e| e.to_string());
+                    Err(wasmtime::Error::msg(last))
+                })
+            }).map_err(Error::Linker)?;
+        }
 
         Ok(())
     }
 }
 
+/// One target in a chain linked with `InstanceBuilder::link_chain`.
+#[derive(Clone)]
+pub enum Linkable {
+    /// An instance in this runtime.
+    LocalInstance(InstanceId),
+    /// An instance served by a peer.
+    Remote(PeerInstance),
+}
+
+/// Why a call to a single target failed.
+#[derive(Debug)]
+enum TargetError {
+    Runtime(runtime::Error),
+    Peer(peer::Error),
+}
+
+impl TargetError {
+    /// Whether the target couldn't be reached at all, so the next one may be tried.
+    fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            TargetError::Runtime(runtime::Error::InstanceNotFound(_) | runtime::Error::PeerNotFound(_))
+                | TargetError::Peer(
+                    peer::Error::Disconnected
+                        | peer::Error::Shutdown
+                        | peer::Error::Interrupted(_)
+                        | peer::Error::Transport(_)
+                        | peer::Error::Remote(FailureReason::InstanceNotFound)
+                        | peer::Error::Remote(FailureReason::DomainSpecific(neorpc::UNKNOWN_TARGET, _))
+                )
+        )
+    }
+}
+
+impl std::fmt::Display for TargetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TargetError::Runtime(e) => write!(f, "{}", e),
+            TargetError::Peer(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// TODO: we pass result types here but maybe we can
+//       prepare special data for the decoder that
+//       has instructions for how to decode specific types
+//       and we calculate this once instead of tree-walking
+/// Calls a method on a peer's target, resolving the peer at call time
+/// so a reconnected peer is picked up.
+async fn call_peer(
+    runtime: &Runtime,
+    peer_id: PeerId,
+    target_id: &str,
+    method_name: &str,
+    args: &[Val],
+    result_types: Vec<Type>,
+) -> std::result::Result<Vec<Val>, TargetError> {
+    let peer = runtime.get_peer(peer_id).map_err(TargetError::Runtime)?;
+
+    // prepare the call by incrementing seq and reserving pending
+    let (seq, rx) = peer.prepare_call(result_types);
+
+    // encode arguments directly without copying
+    let args_bytes = neorpc::encode_vals_to_bytes(args)
+        .map_err(|e| TargetError::Peer(peer::Error::NeoRpc(e)))?;
+
+    #[cfg(feature = "tracing")]
+    let trace = crate::trace::CallTrace::start(target_id, method_name, seq);
+
+    // build the payload
+    let call = CallEncoder::new(seq, target_id, method_name, &args_bytes, None);
+    #[cfg(feature = "tracing")]
+    let call = call.with_trace_id(trace.trace_id);
+    let payload = call.into_bytes()
+        .map_err(|e| TargetError::Peer(peer::Error::NeoRpc(e)))?;
+
+    // send and await response
+    let outcome = peer.send_and_await(seq, payload, rx).await;
+    #[cfg(feature = "tracing")]
+    trace.finish(&outcome);
+    outcome.map_err(TargetError::Peer)
+}
+
+/// Copies a call's return values into the host function's result slots.
+fn copy_results(vals: Vec<Val>, results: &mut [Val]) -> wasmtime::Result<()> {
+    if vals.len() != results.len() {
+        return Err(wasmtime::Error::msg(format!(
+            "Result count mismatch: expected {}, got {}",
+            results.len(),
+            vals.len()
+        )));
+    }
+    for (slot, val) in results.iter_mut().zip(vals) {
+        *slot = val;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use neopack::Decoder;
     use neopack::Encoder;
     use neorpc::ReplyOkEncoder;
     use neorpc::RpcFrame;
     use neorpc::encode_vals_to_bytes;
+    use neorpc::frame_bytes;
     use wasmtime::component::Component;
     use wasmtime::Engine;
     use wasmtime::Store;
@@ -248,8 +371,7 @@ mod tests {
     #[async_trait::async_trait]
     impl Transport for MockTransport {
         async fn send(&self, payload: &[u8]) -> crate::transport::Result<()> {
-            let mut dec = Decoder::new(payload);
-            let frame = RpcFrame::decode(&mut dec).expect("Mock received invalid frame");
+            let frame = RpcFrame::decode(payload).expect("Mock received invalid frame");
 
             let seq = match frame {
                 RpcFrame::Call(c) => c.seq,
@@ -259,7 +381,7 @@ mod tests {
             let mut enc = Encoder::new();
             let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
             ReplyOkEncoder::new(seq, &empty_bytes).encode(&mut enc).unwrap();
-            let response = enc.into_bytes().unwrap();
+            let response = frame_bytes(enc).unwrap();
             *self.pending.lock().await = Some(response);
             Ok(())
         }
@@ -275,7 +397,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_bind_remote_interface_success() {
-        let engine = Engine::new(&wasmtime::Config::new().async_support(true)).unwrap();
+        let engine = Engine::new(wasmtime::Config::new().async_support(true)).unwrap();
 
         let wat = r#"
             (component
@@ -322,6 +444,27 @@ mod tests {
         run.call_async(&mut store, ()).await.expect("Execution failed");
     }
 
+    #[test]
+    fn test_chain_falls_back_only_when_unreachable() {
+        let unreachable = [
+            TargetError::Runtime(crate::runtime::Error::InstanceNotFound(InstanceId(1))),
+            TargetError::Runtime(crate::runtime::Error::PeerNotFound(PeerId(1))),
+            TargetError::Peer(crate::peer::Error::Disconnected),
+            TargetError::Peer(crate::peer::Error::Transport(crate::transport::Error::Timeout)),
+            TargetError::Peer(crate::peer::Error::Remote(FailureReason::InstanceNotFound)),
+            TargetError::Peer(crate::peer::Error::Remote(neorpc::unknown_target())),
+        ];
+        let answers = [
+            TargetError::Peer(crate::peer::Error::Remote(FailureReason::DomainSpecific(7, "no".into()))),
+            TargetError::Peer(crate::peer::Error::Remote(FailureReason::AppTrapped)),
+            TargetError::Peer(crate::peer::Error::Timeout),
+            TargetError::Runtime(crate::runtime::Error::OutOfFuel),
+        ];
+
+        assert!(unreachable.iter().all(TargetError::is_unreachable));
+        assert!(!answers.iter().any(TargetError::is_unreachable));
+    }
+
     #[tokio::test]
     async fn test_bind_missing_interface_error() {
         let engine = Engine::default();
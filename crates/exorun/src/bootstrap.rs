@@ -0,0 +1,160 @@
+//! # Bootstrap bundles for self-replication
+//!
+//! A bundle carries everything a fresh runtime needs to catch up with the
+//! one that produced it: the bytes of every component registered from
+//! source, each under its original id and content hash, plus a descriptor
+//! the new runtime can dial to reach its origin.
+//!
+//! ## Wire format
+//!
+//! The bundle is a neopack map:
+//!
+//! - `origin`: optional `TransportDescriptor` saying how to reach the exporting runtime.
+//! - `components`: list of maps with `id` (u64), `hash` (32 bytes, blake3 of
+//!   the component bytes), and `bytes`.
+//!
+//! Unknown keys are skipped so the format can grow.
+
+use neopack::Decoder;
+use neopack::Encoder;
+use neopack::Pack;
+use neopack::Unpack;
+
+use crate::runtime::ComponentId;
+use crate::transport::TransportDescriptor;
+
+#[derive(Debug)]
+pub enum Error {
+    Encode(neopack::Error),
+    Malformed(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "bundle encoding error: {}", e),
+            Self::Malformed(msg) => write!(f, "malformed bundle: {}", msg),
+        }
+    }
+}
+
+impl From<neopack::Error> for Error {
+    fn from(e: neopack::Error) -> Self {
+        Self::Encode(e)
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Blake3 hash identifying a component's bytes.
+pub type ContentHash = blake3::Hash;
+
+/// Hashes component bytes the way bundles and the runtime expect.
+pub fn content_hash(bytes: &[u8]) -> ContentHash {
+    blake3::hash(bytes)
+}
+
+/// One component as registered on the exporting runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundledComponent {
+    /// The id on the exporting runtime.
+    pub id: ComponentId,
+    pub hash: ContentHash,
+    pub bytes: Vec<u8>,
+}
+
+/// A snapshot of a runtime's components, and where to find it again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BootstrapBundle {
+    pub origin: Option<TransportDescriptor>,
+    pub components: Vec<BundledComponent>,
+}
+
+impl BootstrapBundle {
+    /// Records how importers should dial back to the origin.
+    pub fn with_origin(mut self, origin: TransportDescriptor) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        enc.map_begin()?;
+
+        if let Some(origin) = &self.origin {
+            enc.variant_begin("origin")?;
+            origin.pack(&mut enc)?;
+            enc.variant_end()?;
+        }
+
+        enc.variant_begin("components")?;
+        enc.list_begin()?;
+        for component in &self.components {
+            enc.map_begin()?;
+            enc.variant_begin("id")?;
+            enc.u64(component.id.0)?;
+            enc.variant_end()?;
+            enc.variant_begin("hash")?;
+            enc.bytes(component.hash.as_bytes())?;
+            enc.variant_end()?;
+            enc.variant_begin("bytes")?;
+            enc.bytes(&component.bytes)?;
+            enc.variant_end()?;
+            enc.map_end()?;
+        }
+        enc.list_end()?;
+        enc.variant_end()?;
+
+        enc.map_end()?;
+        Ok(enc.into_bytes()?)
+    }
+
+    /// Decodes a bundle. Hashes are carried as-is; the importer checks them.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut dec = Decoder::new(bytes);
+        let mut map = dec.map()?;
+        let mut bundle = Self::default();
+
+        while let Some((key, mut val)) = map.next()? {
+            match key {
+                "origin" => bundle.origin = Some(TransportDescriptor::unpack(&mut val)?),
+                "components" => {
+                    for item in val.list()? {
+                        bundle.components.push(decode_component(item)?);
+                    }
+                }
+                _ => val.skip()?,
+            }
+        }
+
+        Ok(bundle)
+    }
+}
+
+fn decode_component(mut dec: Decoder<'_>) -> Result<BundledComponent> {
+    let mut map = dec.map()?;
+    let mut id = None;
+    let mut hash = None;
+    let mut bytes = None;
+
+    while let Some((key, mut val)) = map.next()? {
+        match key {
+            "id" => id = Some(ComponentId(val.u64()?)),
+            "hash" => {
+                let raw = val.bytes()?;
+                hash = Some(<[u8; 32]>::try_from(raw).map(ContentHash::from)
+                    .map_err(|_| Error::Malformed(format!("hash is {} bytes, expected 32", raw.len())))?);
+            }
+            "bytes" => bytes = Some(val.bytes()?.to_vec()),
+            _ => val.skip()?,
+        }
+    }
+
+    Ok(BundledComponent {
+        id: id.ok_or(Error::Malformed("component missing id".into()))?,
+        hash: hash.ok_or(Error::Malformed("component missing hash".into()))?,
+        bytes: bytes.ok_or(Error::Malformed("component missing bytes".into()))?,
+    })
+}
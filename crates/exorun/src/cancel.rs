@@ -0,0 +1,33 @@
+//! # Cancellation of running calls
+//!
+//! A `CancellationToken` is handed to `Runtime::call_with_cancel`.
+//! The runtime's epoch ticker checks it while guest code runs,
+//! and interrupts the call once it has been cancelled.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// Shared flag that cancels the calls it was passed to.
+///
+/// Clones share the flag, so one clone can be kept to cancel
+/// while another is moved into the call.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every call using this token, now and in the future.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
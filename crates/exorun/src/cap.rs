@@ -0,0 +1,113 @@
+//! # Expiring capabilities
+//!
+//! A capability grants access to one scope, such as `Meta`'s spawning, until
+//! a wall-clock deadline. It is minted by signing the scope and deadline with
+//! an ed25519 secret key, using the same raw 32-byte keys as the `Auth` host
+//! component. A runtime given the matching public key with
+//! `Runtime::trust_capability_issuer` accepts it until it expires. An
+//! instance built with a `VirtualClock` is held to deadlines by that clock.
+//!
+//! ## Invariants
+//!
+//! - **Distinct Failures**: A capability past its deadline is reported as
+//!   `Expired` only if its signature holds; any tampering reports `BadSignature`.
+//! - **Bound Fields**: The signature covers the scope and deadline together,
+//!   so neither can be changed without invalidating it.
+
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use neopack::Pack;
+use neopack::Unpack;
+
+use crate::host::Auth;
+
+/// Prefixed to the signed message, so a capability signature can't be
+/// mistaken for a signature over anything else made with the same key.
+const DOMAIN: &[u8] = b"exorun:cap/v1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The runtime has no issuer key to check capabilities against.
+    NoIssuer,
+    /// A gated operation was attempted without a capability.
+    Missing { scope: String },
+    /// The capability grants a different scope than the one needed.
+    WrongScope { expected: String, actual: String },
+    /// The signature doesn't match the issuer key, scope, and deadline.
+    BadSignature,
+    /// The capability is genuine but its deadline has passed.
+    Expired { expires_at: u64, now: u64 },
+    /// The secret key given to `Capability::mint` is malformed.
+    Key(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoIssuer => write!(f, "no capability issuer is trusted"),
+            Self::Missing { scope } => write!(f, "no capability held for '{}'", scope),
+            Self::WrongScope { expected, actual } => write!(f, "capability is for '{}', expected '{}'", actual, expected),
+            Self::BadSignature => write!(f, "capability signature is invalid"),
+            Self::Expired { expires_at, now } => write!(f, "capability expired at {}, now {}", expires_at, now),
+            Self::Key(msg) => write!(f, "cannot mint capability: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A signed, time-boxed grant of access to `scope`.
+#[derive(Clone, Debug, PartialEq, Eq, Pack, Unpack)]
+pub struct Capability {
+    pub scope: String,
+    /// Seconds since the Unix epoch after which the capability is refused.
+    pub expires_at: u64,
+    /// 64-byte ed25519 signature over the scope and deadline.
+    pub signature: Vec<u8>,
+}
+
+impl Capability {
+    /// Signs a capability for `scope` with a 32-byte ed25519 secret key.
+    pub fn mint(secret: &[u8], scope: impl Into<String>, expires_at: u64) -> Result<Self> {
+        let scope = scope.into();
+        let signature = Auth::sign(secret, &signed_message(&scope, expires_at))
+            .map_err(|e| Error::Key(e.to_string()))?;
+        Ok(Self { scope, expires_at, signature })
+    }
+
+    /// Checks the signature against `issuer`, a 32-byte public key, then the deadline.
+    pub fn verify(&self, issuer: &[u8]) -> Result<()> {
+        self.verify_at(issuer, now())
+    }
+
+    /// Checks the capability as `verify` does, taking `now` as the current time.
+    ///
+    /// `now` is in seconds since the Unix epoch, for checks against a clock
+    /// other than the wall clock, such as an instance's `VirtualClock`.
+    pub fn verify_at(&self, issuer: &[u8], now: u64) -> Result<()> {
+        if !Auth::verify(issuer, &signed_message(&self.scope, self.expires_at), &self.signature) {
+            return Err(Error::BadSignature);
+        }
+        if now >= self.expires_at {
+            return Err(Error::Expired { expires_at: self.expires_at, now });
+        }
+        Ok(())
+    }
+}
+
+/// Seconds since the Unix epoch, by the wall clock.
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn signed_message(scope: &str, expires_at: u64) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(DOMAIN.len() + 16 + scope.len());
+    msg.extend_from_slice(DOMAIN);
+    msg.extend_from_slice(&(scope.len() as u64).to_le_bytes());
+    msg.extend_from_slice(scope.as_bytes());
+    msg.extend_from_slice(&expires_at.to_le_bytes());
+    msg
+}
@@ -0,0 +1,93 @@
+//! # Virtual time
+//!
+//! A `VirtualClock` is handed to `InstanceBuilder::with_virtual_clock`, and
+//! the instance's WASI wall and monotonic clocks then read from it instead of
+//! the OS. Time stands still until `VirtualClock::advance` moves it forward,
+//! so tests of time-dependent components are deterministic.
+//!
+//! ## Invariants
+//!
+//! - **One Source**: Both clocks read the same elapsed time, so advancing by
+//!   a duration moves the wall clock and the monotonic clock by exactly that much.
+//! - **Forward Only**: Virtual time never goes backwards.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use wasmtime_wasi::HostMonotonicClock;
+use wasmtime_wasi::HostWallClock;
+
+/// A clock that only moves when told to, shared by the instances given it.
+#[derive(Debug)]
+pub struct VirtualClock {
+    /// Wall-clock time when the clock was made, since the Unix epoch.
+    start: Duration,
+    /// Nanoseconds advanced since then.
+    elapsed: AtomicU64,
+}
+
+impl VirtualClock {
+    /// Makes a clock whose wall time starts at the real current time.
+    pub fn new() -> Self {
+        Self::at(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default())
+    }
+
+    /// Makes a clock whose wall time starts `since_epoch` after the Unix epoch.
+    pub fn at(since_epoch: Duration) -> Self {
+        Self { start: since_epoch, elapsed: AtomicU64::new(0) }
+    }
+
+    /// Moves time forward by `by`, for every instance reading this clock.
+    pub fn advance(&self, by: Duration) {
+        let by = u64::try_from(by.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self.elapsed.fetch_update(Ordering::AcqRel, Ordering::Acquire, |elapsed| {
+            Some(elapsed.saturating_add(by))
+        });
+    }
+
+    /// Time advanced since the clock was made.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::Acquire))
+    }
+
+    /// The current wall time, since the Unix epoch.
+    pub fn now(&self) -> Duration {
+        self.start + self.elapsed()
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `wasi:clocks/wall-clock` read from a `VirtualClock`.
+pub(crate) struct VirtualWallClock(pub(crate) Arc<VirtualClock>);
+
+impl HostWallClock for VirtualWallClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self) -> Duration {
+        self.0.now()
+    }
+}
+
+/// `wasi:clocks/monotonic-clock` read from a `VirtualClock`, in nanoseconds.
+pub(crate) struct VirtualMonotonicClock(pub(crate) Arc<VirtualClock>);
+
+impl HostMonotonicClock for VirtualMonotonicClock {
+    fn resolution(&self) -> u64 {
+        1
+    }
+
+    fn now(&self) -> u64 {
+        self.0.elapsed.load(Ordering::Acquire)
+    }
+}
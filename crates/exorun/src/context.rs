@@ -4,12 +4,18 @@
 
 use std::sync::Arc;
 
+use wasmtime::ResourceLimiter;
+use wasmtime::StoreLimits;
 use wasmtime::component::ResourceTable;
 use wasmtime_wasi::WasiCtx;
 use wasmtime_wasi::WasiCtxBuilder;
 use wasmtime_wasi::WasiCtxView;
 use wasmtime_wasi::WasiView;
 
+use crate::cancel::CancellationToken;
+use crate::clock::VirtualClock;
+use crate::runtime::InstanceId;
+use crate::runtime::PeerId;
 use crate::runtime::Runtime;
 
 /// Builder for constructing an ExorunCtx.
@@ -54,6 +60,13 @@ impl ContextBuilder {
             table: ResourceTable::new(),
             user_data: self.user_data,
             runtime,
+            limits: TrackedLimits::default(),
+            cancel: None,
+            instance_id: None,
+            caller: None,
+            budget: None,
+            spawned: 0,
+            registered: 0,
         }
     }
 }
@@ -64,6 +77,71 @@ impl Default for ContextBuilder {
     }
 }
 
+/// Execution limits for a single instance.
+///
+/// Applied by `InstanceBuilder::with_budget` before instantiation.
+/// Fuel is spent by instantiation and every call; once it runs out,
+/// calls fail with `runtime::Error::OutOfFuel`.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Total fuel the instance may consume over its lifetime.
+    pub fuel: u64,
+    /// Cap on each linear memory; growth past it fails inside the guest.
+    pub max_memory_bytes: usize,
+}
+
+/// Store limits that also record how much linear memory the instance holds.
+///
+/// Every memory is sized through `memory_growing`, including at
+/// instantiation, so the totals cover all of the instance's memories.
+#[derive(Default)]
+pub(crate) struct TrackedLimits {
+    inner: StoreLimits,
+    pub(crate) current_memory_bytes: usize,
+    pub(crate) peak_memory_bytes: usize,
+}
+
+impl TrackedLimits {
+    pub(crate) fn new(inner: StoreLimits) -> Self {
+        Self { inner, current_memory_bytes: 0, peak_memory_bytes: 0 }
+    }
+}
+
+impl ResourceLimiter for TrackedLimits {
+    fn memory_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> wasmtime::Result<bool> {
+        let allowed = self.inner.memory_growing(current, desired, maximum)?;
+        if allowed {
+            self.current_memory_bytes = self.current_memory_bytes - current + desired;
+            self.peak_memory_bytes = self.peak_memory_bytes.max(self.current_memory_bytes);
+        }
+        Ok(allowed)
+    }
+
+    fn memory_grow_failed(&mut self, error: wasmtime::Error) -> wasmtime::Result<()> {
+        self.inner.memory_grow_failed(error)
+    }
+
+    fn table_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> wasmtime::Result<bool> {
+        self.inner.table_growing(current, desired, maximum)
+    }
+
+    fn table_grow_failed(&mut self, error: wasmtime::Error) -> wasmtime::Result<()> {
+        self.inner.table_grow_failed(error)
+    }
+
+    fn instances(&self) -> usize {
+        self.inner.instances()
+    }
+
+    fn tables(&self) -> usize {
+        self.inner.tables()
+    }
+
+    fn memories(&self) -> usize {
+        self.inner.memories()
+    }
+}
+
 /// Per-instance execution context stored in Wasmtime's Store.
 ///
 /// Holds mutable state scoped to a single component instance. Provides:
@@ -76,6 +154,19 @@ pub struct ExorunCtx {
     pub(crate) table: ResourceTable,
     pub(crate) user_data: anymap::Map<dyn anymap::any::Any + Send + Sync>,
     pub(crate) runtime: Arc<Runtime>,
+    pub(crate) limits: TrackedLimits,
+    /// Token of the call in progress, checked on every epoch tick.
+    pub(crate) cancel: Option<CancellationToken>,
+    /// Set by `InstanceBuilder::build` before instantiation.
+    pub(crate) instance_id: Option<InstanceId>,
+    /// The peer whose call is in progress, for calls served by `Runtime::serve_peer`.
+    pub(crate) caller: Option<PeerId>,
+    /// The `Budget` the instance was built with, passed on to instances it spawns.
+    pub(crate) budget: Option<Budget>,
+    /// Instances spawned through `Meta`, counted against its spawn limit.
+    pub(crate) spawned: usize,
+    /// Components registered through `Meta`, counted against its registration limit.
+    pub(crate) registered: usize,
 }
 
 impl ExorunCtx {
@@ -83,6 +174,26 @@ impl ExorunCtx {
     pub fn get<T: anymap::any::Any + Send + Sync>(&self) -> Option<&T> {
         self.user_data.get::<T>()
     }
+
+    /// The id of the instance this store belongs to.
+    ///
+    /// `None` only for a store built by hand rather than by `InstanceBuilder`.
+    pub fn instance_id(&self) -> Option<InstanceId> {
+        self.instance_id
+    }
+
+    /// The peer that made the call currently running, or `None` for a local call.
+    pub fn caller(&self) -> Option<PeerId> {
+        self.caller
+    }
+
+    /// Seconds since the Unix epoch by the instance's `VirtualClock`, or by the wall clock without one.
+    pub(crate) fn now_secs(&self) -> u64 {
+        match self.user_data.get::<Arc<VirtualClock>>() {
+            Some(clock) => clock.now().as_secs(),
+            None => crate::cap::now(),
+        }
+    }
 }
 
 impl WasiView for ExorunCtx {
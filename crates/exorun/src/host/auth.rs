@@ -0,0 +1,179 @@
+//! # Auth host component
+//!
+//! Provides ed25519 key generation, signing, and verification to Wasm components
+//! through `exorun:auth/keys`, and XChaCha20Poly1305 authenticated encryption
+//! through `exorun:auth/crypto`.
+//! Keys cross the boundary as raw bytes: 32-byte public and secret keys,
+//! 64-byte signatures, 32-byte symmetric keys, and 24-byte nonces.
+//!
+//! ## Invariants
+//!
+//! - **Total Verification**: `verify` answers `false` for keys or signatures
+//!   of the wrong length, or public keys that are not valid curve points,
+//!   rather than trapping the guest.
+//! - **Errors, not Traps**: `seal` and `open` report bad key or nonce lengths
+//!   and failed authentication as `Err` strings the guest can handle.
+
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::XChaCha20Poly1305;
+use chacha20poly1305::XNonce;
+use chacha20poly1305::aead::Aead;
+use ed25519_dalek::Signature;
+use ed25519_dalek::Signer;
+use ed25519_dalek::SigningKey;
+use ed25519_dalek::Verifier;
+use ed25519_dalek::VerifyingKey;
+use wasmtime::component::ComponentType;
+use wasmtime::component::Lift;
+use wasmtime::component::Linker;
+use wasmtime::component::Lower;
+
+use crate::context::ExorunCtx;
+use crate::host::Error;
+use crate::host::Result;
+
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 24;
+
+/// An ed25519 keypair as raw bytes.
+#[derive(Clone, Debug, PartialEq, Eq, ComponentType, Lift, Lower)]
+#[component(record)]
+pub struct Keypair {
+    pub public: Vec<u8>,
+    pub secret: Vec<u8>,
+}
+
+/// Auth host component for public-key cryptography.
+///
+/// Provides the `exorun:auth/keys` and `exorun:auth/crypto` interfaces to Wasm components.
+/// Holds no state: every key is supplied by the guest.
+#[derive(Clone, Debug, Default)]
+pub struct Auth;
+
+impl Auth {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Generates a fresh keypair from the OS random source.
+    pub fn generate() -> Keypair {
+        let key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+        Keypair {
+            public: key.verifying_key().to_bytes().to_vec(),
+            secret: key.to_bytes().to_vec(),
+        }
+    }
+
+    /// Signs `msg` with a 32-byte secret key.
+    pub fn sign(secret: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        let secret: [u8; 32] = secret.try_into()
+            .map_err(|_| Error::Crypto(format!("secret key is {} bytes, expected 32", secret.len())))?;
+        let key = SigningKey::from_bytes(&secret);
+        Ok(key.sign(msg).to_bytes().to_vec())
+    }
+
+    /// Checks `sig` over `msg` against a public key, never failing.
+    pub fn verify(public: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+        let Ok(public) = <[u8; 32]>::try_from(public) else { return false };
+        let Ok(key) = VerifyingKey::from_bytes(&public) else { return false };
+        let Ok(sig) = Signature::from_slice(sig) else { return false };
+        key.verify(msg, &sig).is_ok()
+    }
+
+    /// Encrypts and authenticates `plaintext` under a 32-byte key and 24-byte nonce.
+    ///
+    /// A nonce must never be reused with the same key.
+    pub fn seal(key: &[u8], nonce: &[u8], plaintext: &[u8]) -> std::result::Result<Vec<u8>, String> {
+        let (cipher, nonce) = Self::cipher(key, nonce)?;
+        cipher.encrypt(&nonce, plaintext).map_err(|_| "encryption failed".to_string())
+    }
+
+    /// Decrypts `ciphertext` sealed under the same key and nonce.
+    pub fn open(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> std::result::Result<Vec<u8>, String> {
+        let (cipher, nonce) = Self::cipher(key, nonce)?;
+        cipher.decrypt(&nonce, ciphertext).map_err(|_| "auth tag mismatch".to_string())
+    }
+
+    fn cipher(key: &[u8], nonce: &[u8]) -> std::result::Result<(XChaCha20Poly1305, XNonce), String> {
+        if key.len() != KEY_SIZE {
+            return Err(format!("key is {} bytes, expected {}", key.len(), KEY_SIZE));
+        }
+        if nonce.len() != NONCE_SIZE {
+            return Err(format!("nonce is {} bytes, expected {}", nonce.len(), NONCE_SIZE));
+        }
+        let cipher = XChaCha20Poly1305::new_from_slice(key).map_err(|e| e.to_string())?;
+        Ok((cipher, *XNonce::from_slice(nonce)))
+    }
+
+    /// Links this component to the linker, installing the
+    /// `exorun:auth/keys` and `exorun:auth/crypto` interfaces.
+    ///
+    /// Both are installed at once, so a guest importing both needs only one
+    /// `link_system` call, naming either interface.
+    pub fn link(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        self.link_keys(linker)?;
+        self.link_crypto(linker)
+    }
+
+    fn link_keys(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        let mut instance = linker
+            .instance("exorun:auth/keys")
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        instance
+            .func_wrap(
+                "generate",
+                |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (): ()| {
+                    Ok((Self::generate(),))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        instance
+            .func_wrap(
+                "sign",
+                |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (secret, msg): (Vec<u8>, Vec<u8>)| {
+                    let sig = Self::sign(&secret, &msg).map_err(wasmtime::Error::msg)?;
+                    Ok((sig,))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        instance
+            .func_wrap(
+                "verify",
+                |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (public, msg, sig): (Vec<u8>, Vec<u8>, Vec<u8>)| {
+                    Ok((Self::verify(&public, &msg, &sig),))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn link_crypto(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        let mut instance = linker
+            .instance("exorun:auth/crypto")
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        instance
+            .func_wrap(
+                "seal",
+                |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (key, nonce, plaintext): (Vec<u8>, Vec<u8>, Vec<u8>)| {
+                    Ok((Self::seal(&key, &nonce, &plaintext),))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        instance
+            .func_wrap(
+                "open",
+                |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (key, nonce, ciphertext): (Vec<u8>, Vec<u8>, Vec<u8>)| {
+                    Ok((Self::open(&key, &nonce, &ciphertext),))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        Ok(())
+    }
+}
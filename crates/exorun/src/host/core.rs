@@ -0,0 +1,300 @@
+//! # Signed append-only log host component
+//!
+//! Provides a persistent, hypercore-style log to Wasm components.
+//! Every entry is chained into a running SHA-256 hash, and the hash after
+//! each append is signed with the log's ed25519 key.
+//!
+//! ## On-disk format
+//!
+//! The file is a sequence of records, each laid out as:
+//! `[len: u32 LE][data: len bytes][signature: 64 bytes]`
+//!
+//! The running hash is `h_0 = [0; 32]` and
+//! `h_i = sha256(h_{i-1} || len as u64 LE || data)`.
+//! The signature of entry `i` covers `h_{i+1}`, the hash including that entry.
+//!
+//! ## Invariants
+//!
+//! - **Verified on Open**: `Core::open` replays the whole file and rejects it
+//!   if any signature fails, so a loaded log is always authentic.
+//! - **Total Reads**: `get` past the end returns `None`, never a trap.
+//! - **Bounded Records**: A length header running past the end of the file is
+//!   `Corrupt`, and is never trusted to size an allocation.
+//! - **No Torn Appends**: A failed append is cut back off the file, so the log
+//!   on disk always ends on a whole record and can still be opened.
+//! - **Waits, Never Traps on Sharing**: Instances sharing a `Core` queue for
+//!   the log, and the file I/O runs on a blocking thread.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use ed25519_dalek::Signature;
+use ed25519_dalek::Signer;
+use ed25519_dalek::SigningKey;
+use ed25519_dalek::Verifier;
+use ed25519_dalek::VerifyingKey;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::sync::Mutex;
+use wasmtime::component::Linker;
+
+use crate::context::ExorunCtx;
+use crate::host::Error;
+use crate::host::Result;
+
+const LEN_SIZE: u64 = 4;
+const SIG_SIZE: u64 = Signature::BYTE_SIZE as u64;
+
+/// Signed append-only log host component.
+///
+/// Provides the `exorun:core/log` interface to Wasm components.
+/// Clones share the same underlying file.
+#[derive(Clone)]
+pub struct Core {
+    log: Arc<Mutex<Log>>,
+    verifying_key: VerifyingKey,
+}
+
+impl std::fmt::Debug for Core {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Core")
+            .field("verifying_key", &self.verifying_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Open file plus the index rebuilt from it.
+struct Log {
+    file: File,
+    key: SigningKey,
+    /// Byte offset of each record's length header.
+    offsets: Vec<u64>,
+    /// Running hash over every entry so far.
+    head: [u8; 32],
+    /// Offset one past the last record.
+    end: u64,
+}
+
+impl Core {
+    /// Opens the log at `path`, creating it if missing.
+    ///
+    /// Existing entries are verified against `key`'s public half;
+    /// a bad signature or truncated record fails with `Error::Corrupt`.
+    pub fn open(path: impl AsRef<Path>, key: SigningKey) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(Error::Io)?;
+
+        let verifying_key = key.verifying_key();
+        let mut log = Log { file, key, offsets: Vec::new(), head: [0; 32], end: 0 };
+        log.replay()?;
+
+        Ok(Self { log: Arc::new(Mutex::new(log)), verifying_key })
+    }
+
+    /// The public key entries are signed with.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+
+    /// Appends an entry and returns its index.
+    pub async fn append(&self, data: &[u8]) -> Result<u64> {
+        let data = data.to_vec();
+        with_log(&self.log, move |log| log.append(&data)).await
+    }
+
+    /// Number of entries in the log.
+    pub async fn len(&self) -> u64 {
+        self.log.lock().await.offsets.len() as u64
+    }
+
+    /// Returns true if the log has no entries.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Reads the entry at `index`, or `None` past the end.
+    pub async fn get(&self, index: u64) -> Result<Option<Vec<u8>>> {
+        with_log(&self.log, move |log| log.get(index)).await
+    }
+
+    /// Re-reads the file and checks every signature.
+    pub async fn verify(&self) -> Result<()> {
+        with_log(&self.log, Log::replay).await
+    }
+
+    /// Links this log to the linker, installing the `exorun:core/log` interface.
+    ///
+    /// Calls wait their turn for the log rather than trapping when another
+    /// instance holds it, and do their file I/O off the async workers.
+    pub fn link(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        let log = self.log.clone();
+
+        let mut instance = linker
+            .instance("exorun:core/log")
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        // Bind the 'append' function
+        instance
+            .func_wrap_async(
+                "append",
+                {
+                    let log = log.clone();
+                    move |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (data,): (Vec<u8>,)| {
+                        let log = log.clone();
+                        Box::new(async move {
+                            let index = with_log(&log, move |log| log.append(&data)).await
+                                .map_err(wasmtime::Error::msg)?;
+                            Ok((index,))
+                        })
+                    }
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        // Bind the 'length' function
+        instance
+            .func_wrap_async(
+                "length",
+                {
+                    let log = log.clone();
+                    move |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (): ()| {
+                        let log = log.clone();
+                        Box::new(async move { Ok((log.lock().await.offsets.len() as u64,)) })
+                    }
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        // Bind the 'get' function
+        instance
+            .func_wrap_async(
+                "get",
+                move |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (index,): (u64,)| {
+                    let log = log.clone();
+                    Box::new(async move {
+                        let entry = with_log(&log, move |log| log.get(index)).await
+                            .map_err(wasmtime::Error::msg)?;
+                        Ok((entry,))
+                    })
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Waits for the log, then runs `op` on a blocking thread, since it reads,
+/// writes and syncs the file.
+async fn with_log<T: Send + 'static>(
+    log: &Arc<Mutex<Log>>,
+    op: impl FnOnce(&mut Log) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    let mut guard = Arc::clone(log).lock_owned().await;
+    tokio::task::spawn_blocking(move || op(&mut guard))
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+}
+
+impl Log {
+    fn append(&mut self, data: &[u8]) -> Result<u64> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| Error::Corrupt(format!("entry of {} bytes exceeds u32 length", data.len())))?;
+        let head = chain(&self.head, data);
+        let signature = self.key.sign(&head);
+
+        let mut record = Vec::with_capacity(data.len() + (LEN_SIZE + SIG_SIZE) as usize);
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(data);
+        record.extend_from_slice(&signature.to_bytes());
+
+        // An earlier append may have failed to cut its partial record back off
+        if self.file.metadata().map_err(Error::Io)?.len() != self.end {
+            self.file.set_len(self.end).map_err(Error::Io)?;
+        }
+        if let Err(e) = self.file.write_all(&record).and_then(|()| self.file.sync_data()) {
+            // Best effort; if this fails too, the next append retries it
+            let _ = self.file.set_len(self.end);
+            return Err(Error::Io(e));
+        }
+
+        let index = self.offsets.len() as u64;
+        self.offsets.push(self.end);
+        self.end += record.len() as u64;
+        self.head = head;
+        Ok(index)
+    }
+
+    fn get(&mut self, index: u64) -> Result<Option<Vec<u8>>> {
+        let Some(&offset) = usize::try_from(index).ok().and_then(|i| self.offsets.get(i)) else {
+            return Ok(None);
+        };
+        let (data, _) = self.read_record(offset, self.end)?;
+        Ok(Some(data))
+    }
+
+    /// Rebuilds the index from disk, verifying the hash chain as it goes.
+    fn replay(&mut self) -> Result<()> {
+        let total = self.file.metadata().map_err(Error::Io)?.len();
+        let verifying_key = self.key.verifying_key();
+
+        let mut offsets = Vec::new();
+        let mut head = [0; 32];
+        let mut offset = 0;
+        while offset < total {
+            let (data, signature) = self.read_record(offset, total)?;
+            head = chain(&head, &data);
+            verifying_key.verify(&head, &signature).map_err(|_| {
+                Error::Corrupt(format!("bad signature on entry {}", offsets.len()))
+            })?;
+            offsets.push(offset);
+            offset += LEN_SIZE + data.len() as u64 + SIG_SIZE;
+        }
+
+        self.offsets = offsets;
+        self.head = head;
+        self.end = offset;
+        Ok(())
+    }
+
+    /// Reads the record at `offset`, which must end by `limit`.
+    fn read_record(&mut self, offset: u64, limit: u64) -> Result<(Vec<u8>, Signature)> {
+        let truncated = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::Corrupt(format!("truncated record at offset {}", offset)),
+            _ => Error::Io(e),
+        };
+
+        self.file.seek(SeekFrom::Start(offset)).map_err(Error::Io)?;
+        let mut len = [0u8; LEN_SIZE as usize];
+        self.file.read_exact(&mut len).map_err(truncated)?;
+        let len = u32::from_le_bytes(len) as u64;
+        if offset + LEN_SIZE + len + SIG_SIZE > limit {
+            return Err(Error::Corrupt(format!("record at offset {} claims {} bytes, past the end", offset, len)));
+        }
+        let mut data = vec![0u8; len as usize];
+        self.file.read_exact(&mut data).map_err(truncated)?;
+        let mut signature = [0u8; SIG_SIZE as usize];
+        self.file.read_exact(&mut signature).map_err(truncated)?;
+
+        Ok((data, Signature::from_bytes(&signature)))
+    }
+}
+
+/// Extends the running hash with one entry.
+fn chain(head: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(head);
+    hasher.update((data.len() as u64).to_le_bytes());
+    hasher.update(data);
+    hasher.finalize().into()
+}
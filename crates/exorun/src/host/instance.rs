@@ -11,6 +11,10 @@ use crate::host::Result;
 use crate::host::Wasi;
 use crate::host::Logger;
 use crate::host::Kv;
+use crate::host::Core;
+use crate::host::Auth;
+use crate::host::Writer;
+use crate::host::Meta;
 
 /// Exhaustive enum of all system components supported by the runtime.
 ///
@@ -27,6 +31,18 @@ pub enum HostInstance {
     /// Key-Value store system component for in-memory storage.
     /// Provides the `exorun:host/kv` interface.
     Kv(Kv),
+    /// Signed append-only log backed by a file.
+    /// Provides the `exorun:core/log` interface.
+    Core(Core),
+    /// Stateless ed25519 signatures and XChaCha20Poly1305 encryption.
+    /// Provides the `exorun:auth/keys` and `exorun:auth/crypto` interfaces.
+    Auth(Auth),
+    /// Lamport clocks for ordering messages from multiple writers.
+    /// Provides the `exorun:writer/clock` interface.
+    Writer(Writer),
+    /// Registering components and spawning instances on the running runtime.
+    /// Provides the `exorun:meta/spawn` interface, and needs `InstanceBuilder::allow_meta`.
+    Meta(Meta),
 }
 
 impl HostInstance {
@@ -43,6 +59,14 @@ impl HostInstance {
             HostInstance::Logger(_) => ("Logger", "exorun:host/logging"),
             HostInstance::Kv(_) if interface == "exorun:host/kv" => return Ok(()),
             HostInstance::Kv(_) => ("Kv", "exorun:host/kv"),
+            HostInstance::Core(_) if interface == "exorun:core/log" => return Ok(()),
+            HostInstance::Core(_) => ("Core", "exorun:core/log"),
+            HostInstance::Auth(_) if interface == "exorun:auth/keys" || interface == "exorun:auth/crypto" => return Ok(()),
+            HostInstance::Auth(_) => ("Auth", "exorun:auth/{keys,crypto}"),
+            HostInstance::Writer(_) if interface == "exorun:writer/clock" => return Ok(()),
+            HostInstance::Writer(_) => ("Writer", "exorun:writer/clock"),
+            HostInstance::Meta(_) if interface == "exorun:meta/spawn" => return Ok(()),
+            HostInstance::Meta(_) => ("Meta", "exorun:meta/spawn"),
         };
 
         Err(crate::host::Error::Link(format!(
@@ -59,12 +83,16 @@ impl HostInstance {
     pub fn link(
         &self,
         linker: &mut Linker<ExorunCtx>,
-        _context_builder: &mut ContextBuilder
+        context_builder: &mut ContextBuilder
     ) -> Result<()> {
         match self {
-            HostInstance::Wasi(wasi) => wasi.link(linker),
+            HostInstance::Wasi(wasi) => wasi.link(linker, context_builder),
             HostInstance::Logger(logger) => logger.link(linker),
             HostInstance::Kv(kv) => kv.link(linker),
+            HostInstance::Core(core) => core.link(linker),
+            HostInstance::Auth(auth) => auth.link(linker),
+            HostInstance::Writer(writer) => writer.link(linker),
+            HostInstance::Meta(meta) => meta.link(linker),
         }
     }
 }
@@ -0,0 +1,156 @@
+//! # Runtime self-management host component
+//!
+//! Lets Wasm components register new components and spin up instances of
+//! them on the runtime they run in, through the `Runtime` in their `ExorunCtx`.
+//!
+//! ## Capabilities
+//!
+//! Spawning is as powerful as the runtime itself, so linking `Meta` is not
+//! enough: the instance must also be built with `InstanceBuilder::allow_meta`.
+//! If the runtime trusts a capability issuer, each call also needs a live
+//! capability for `SCOPE`, so access can be time-boxed.
+//! Instances spawned by a guest get no links, and so no `Meta` of their own.
+//!
+//! ## Limits
+//!
+//! A spawned instance inherits its spawner's `Budget`, with fuel capped at
+//! what the spawner has left, so a budgeted guest can't escape its limits by
+//! spawning. Each spawner may also spawn at most `max_spawns` instances and
+//! register at most `max_registrations` components, since registered
+//! components stay in the runtime. Compiling one runs on a blocking thread.
+
+use std::sync::Arc;
+
+use wasmtime::component::Linker;
+
+use crate::cap::Capability;
+use crate::context::Budget;
+use crate::context::ExorunCtx;
+use crate::host::Error;
+use crate::host::Result;
+use crate::runtime::ComponentId;
+
+/// The capability scope gating both `register` and `instantiate`.
+pub const SCOPE: &str = "exorun:meta/spawn";
+
+/// How many instances each spawner may spawn, unless set with `Meta::with_max_spawns`.
+pub const DEFAULT_MAX_SPAWNS: usize = 16;
+
+/// How many components each instance may register, unless set with `Meta::with_max_registrations`.
+pub const DEFAULT_MAX_REGISTRATIONS: usize = 16;
+
+/// Runtime self-management host component.
+///
+/// Provides the `exorun:meta/spawn` interface to Wasm components:
+/// - `register(bytes: list<u8>) -> u64` adds a component, returning its id
+/// - `instantiate(component-id: u64) -> u64` builds an instance, returning its id
+///
+/// Either fails if the runtime refuses, e.g. on invalid bytes or an unknown id,
+/// or if the instance's capability for `SCOPE` is missing or expired.
+/// `register` also fails once the caller has registered `max_registrations` components,
+/// and `instantiate` once it has spawned `max_spawns` instances.
+#[derive(Clone, Debug)]
+pub struct Meta {
+    max_spawns: usize,
+    max_registrations: usize,
+}
+
+impl Default for Meta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Meta {
+    pub fn new() -> Self {
+        Self { max_spawns: DEFAULT_MAX_SPAWNS, max_registrations: DEFAULT_MAX_REGISTRATIONS }
+    }
+
+    /// Caps how many instances each instance linked to this `Meta` may spawn.
+    pub fn with_max_spawns(mut self, max_spawns: usize) -> Self {
+        self.max_spawns = max_spawns;
+        self
+    }
+
+    /// Caps how many components each instance linked to this `Meta` may register.
+    pub fn with_max_registrations(mut self, max_registrations: usize) -> Self {
+        self.max_registrations = max_registrations;
+        self
+    }
+
+    /// Links this component to the linker, installing the `exorun:meta/spawn` interface.
+    pub fn link(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        let mut instance = linker
+            .instance("exorun:meta/spawn")
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        let max_registrations = self.max_registrations;
+        instance
+            .func_wrap_async(
+                "register",
+                move |mut caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (bytes,): (Vec<u8>,)| {
+                    let admitted = admit_registration(&mut caller, max_registrations);
+                    let runtime = Arc::clone(&caller.data().runtime);
+                    Box::new(async move {
+                        admitted?;
+                        let id = tokio::task::spawn_blocking(move || runtime.add_component_bytes(&bytes))
+                            .await?
+                            .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+                        Ok((id.0,))
+                    })
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        let max_spawns = self.max_spawns;
+        instance
+            .func_wrap_async(
+                "instantiate",
+                move |mut caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (component_id,): (u64,)| {
+                    let admitted = admit_spawn(&mut caller, max_spawns);
+                    let runtime = Arc::clone(&caller.data().runtime);
+                    Box::new(async move {
+                        let mut builder = runtime.instantiate(ComponentId(component_id));
+                        if let Some(budget) = admitted? {
+                            builder = builder.with_budget(budget);
+                        }
+                        let id = builder.build().await
+                            .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+                        Ok((id.0,))
+                    })
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Checks the caller may register another component, and counts it.
+fn admit_registration(caller: &mut wasmtime::StoreContextMut<'_, ExorunCtx>, max_registrations: usize) -> wasmtime::Result<()> {
+    let ctx = caller.data();
+    ctx.runtime.authorize(ctx.user_data.get::<Capability>(), SCOPE, ctx.now_secs())
+        .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+    if ctx.registered >= max_registrations {
+        return Err(wasmtime::Error::msg(format!("instance has already registered its limit of {} components", max_registrations)));
+    }
+    caller.data_mut().registered += 1;
+    Ok(())
+}
+
+/// Checks the caller may spawn another instance, counts it, and returns the
+/// budget the new instance inherits.
+fn admit_spawn(caller: &mut wasmtime::StoreContextMut<'_, ExorunCtx>, max_spawns: usize) -> wasmtime::Result<Option<Budget>> {
+    let ctx = caller.data();
+    ctx.runtime.authorize(ctx.user_data.get::<Capability>(), SCOPE, ctx.now_secs())
+        .map_err(|e| wasmtime::Error::msg(e.to_string()))?;
+    if ctx.spawned >= max_spawns {
+        return Err(wasmtime::Error::msg(format!("instance has already spawned its limit of {} instances", max_spawns)));
+    }
+    let budget = ctx.budget.map(|budget| Budget {
+        fuel: caller.get_fuel().unwrap_or(0).min(budget.fuel),
+        ..budget
+    });
+    caller.data_mut().spawned += 1;
+    Ok(budget)
+}
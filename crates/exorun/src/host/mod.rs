@@ -5,21 +5,41 @@
 //!
 //! Host components are organized as an exhaustive enum,
 //! with each variant implemented in its own module under `src/host/`.
+//!
+//! With the `macros` feature, `#[system_component]` generates a component's
+//! `link` method from a WIT interface, in place of the hand-written `func_wrap` calls.
 
 pub mod instance;
 pub mod wasi;
 pub mod logger;
 pub mod kv;
+pub mod core;
+pub mod auth;
+pub mod writer;
+pub mod meta;
+pub mod shared;
 
 pub use instance::HostInstance;
 pub use wasi::Wasi;
+pub use wasi::WasiBuilder;
 pub use logger::Logger;
 pub use kv::Kv;
+pub use self::core::Core;
+pub use auth::Auth;
+pub use writer::Writer;
+pub use meta::Meta;
+pub use shared::SystemComponent;
+pub use shared::link_with_ctx;
+#[cfg(feature = "macros")]
+pub use exorun_macros::system_component;
 
 #[derive(Debug)]
 pub enum Error {
     Link(String),
     Wasmtime(wasmtime::Error),
+    Io(std::io::Error),
+    Corrupt(String),
+    Crypto(String),
 }
 
 impl std::fmt::Display for Error {
@@ -27,6 +47,9 @@ impl std::fmt::Display for Error {
         match self {
             Self::Link(msg) => write!(f, "link error: {}", msg),
             Self::Wasmtime(e) => write!(f, "wasmtime error: {}", e),
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Corrupt(msg) => write!(f, "corrupt log: {}", msg),
+            Self::Crypto(msg) => write!(f, "crypto error: {}", msg),
         }
     }
 }
@@ -0,0 +1,98 @@
+//! # System components shared between instances
+//!
+//! A `SystemComponent` installs its host functions into any number of linkers.
+//! Linked through `InstanceBuilder::link_system_shared`, one `Arc` serves
+//! every instance, and the component's own interior mutability (e.g. the
+//! `Mutex` behind `Kv`) is what lets those instances see each other's writes.
+//!
+//! A component that needs to know who is calling, for instance to decide what
+//! a caller may do, installs its functions with `link_with_ctx`, whose handler
+//! sees the calling instance's `ExorunCtx`.
+
+use std::sync::Arc;
+
+use wasmtime::component::Linker;
+use wasmtime::component::Val;
+
+use crate::context::ExorunCtx;
+use crate::host::Error;
+use crate::host::Result;
+use crate::host::Kv;
+use crate::host::Logger;
+use crate::host::Core;
+use crate::host::Auth;
+use crate::host::Writer;
+
+/// Installs `methods` of `interface`, each one calling `handler` with the
+/// caller's context, the method name, and the arguments.
+///
+/// The handler's results must match the method's result count, or the call traps.
+/// `ExorunCtx::instance_id` and `ExorunCtx::caller` identify who is calling.
+pub fn link_with_ctx<F>(
+    linker: &mut Linker<ExorunCtx>,
+    interface: &str,
+    methods: &[&str],
+    handler: F,
+) -> Result<()>
+where
+    F: Fn(&mut ExorunCtx, &str, &[Val]) -> wasmtime::Result<Vec<Val>> + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    let mut instance = linker
+        .instance(interface)
+        .map_err(|e| Error::Link(e.to_string()))?;
+
+    for &method in methods {
+        let handler = Arc::clone(&handler);
+        let name = method.to_string();
+        instance
+            .func_new(method, move |mut store, _func_ty, args, results| {
+                let vals = handler(store.data_mut(), &name, args)?;
+                if vals.len() != results.len() {
+                    return Err(wasmtime::Error::msg(format!(
+                        "'{}' returned {} results, expected {}", name, vals.len(), results.len()
+                    )));
+                }
+                for (slot, val) in results.iter_mut().zip(vals) {
+                    *slot = val;
+                }
+                Ok(())
+            })
+            .map_err(|e| Error::Link(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// A host component that can be installed into many instances' linkers.
+pub trait SystemComponent: Send + Sync {
+    /// The interfaces this component provides.
+    fn interfaces(&self) -> &[&str];
+
+    /// Adds this component's host functions to `linker`.
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()>;
+}
+
+impl SystemComponent for Logger {
+    fn interfaces(&self) -> &[&str] { &["exorun:host/logging"] }
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> { self.link(linker) }
+}
+
+impl SystemComponent for Kv {
+    fn interfaces(&self) -> &[&str] { &["exorun:host/kv"] }
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> { self.link(linker) }
+}
+
+impl SystemComponent for Core {
+    fn interfaces(&self) -> &[&str] { &["exorun:core/log"] }
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> { self.link(linker) }
+}
+
+impl SystemComponent for Auth {
+    fn interfaces(&self) -> &[&str] { &["exorun:auth/keys", "exorun:auth/crypto"] }
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> { self.link(linker) }
+}
+
+impl SystemComponent for Writer {
+    fn interfaces(&self) -> &[&str] { &["exorun:writer/clock"] }
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> { self.link(linker) }
+}
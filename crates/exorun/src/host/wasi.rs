@@ -2,34 +2,262 @@
 //!
 //! Provides standard WASI (WebAssembly System Interface) functionality to Wasm components.
 //! This includes filesystem access, stdio, environment variables, and other OS-level capabilities.
+//!
+//! ## Filesystem capabilities
+//!
+//! A guest sees exactly the directories mounted with `WasiBuilder::preopen_ro`
+//! and `WasiBuilder::preopen_rw`, and nothing else. Paths are resolved
+//! inside each mount, so `..` cannot climb out of it; opening outside the
+//! allowlist, or writing through a read-only mount, fails with a WASI error.
+//!
+//! ## Standard output
+//!
+//! By default guest stdout and stderr go nowhere. With `WasiBuilder::capture_stdio`
+//! each stream is buffered in memory, to be read with `Wasi::take_stdout` and
+//! `Wasi::take_stderr`. Clones of a `Wasi` share its buffers, so the output is
+//! still there after the instance that wrote it is gone. A take empties the
+//! buffer, so a long-lived guest holds only what hasn't been taken yet.
+//! Each buffer holds at most `DEFAULT_CAPTURE_BYTES`, or the limit set with
+//! `WasiBuilder::capture_limit`; once full, further output is dropped until
+//! the next take makes room.
+
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
 
+use tokio::io::AsyncWrite;
 use wasmtime::component::Linker;
+use wasmtime_wasi::DirPerms;
+use wasmtime_wasi::FilePerms;
+use wasmtime_wasi::cli::IsTerminal;
+use wasmtime_wasi::cli::StdoutStream;
 
+use crate::context::ContextBuilder;
 use crate::context::ExorunCtx;
+use crate::host::Error;
 use crate::host::Result;
 
+/// Bytes each captured stream buffers, unless set with `WasiBuilder::capture_limit`.
+pub const DEFAULT_CAPTURE_BYTES: usize = 1 << 20;
+
 /// WASI system component that provides standard WASI functionality.
 ///
 /// This component links the WASI interfaces (filesystem, stdio, etc.) to the guest.
-/// Configuration is handled through the ContextBuilder's WASI methods.
+/// Other configuration is handled through the ContextBuilder's WASI methods.
 #[derive(Clone, Debug, Default)]
-pub struct Wasi;
+pub struct Wasi {
+    mounts: Vec<Mount>,
+    stdio: Option<CapturedStdio>,
+}
+
+/// In-memory stdout and stderr, kept apart.
+#[derive(Clone, Debug)]
+struct CapturedStdio {
+    stdout: Capture,
+    stderr: Capture,
+}
+
+/// One captured stream: the bytes written and not yet taken.
+#[derive(Clone, Debug)]
+struct Capture {
+    buf: Arc<Mutex<Vec<u8>>>,
+    limit: usize,
+}
+
+impl Capture {
+    fn new(limit: usize) -> Self {
+        Self { buf: Arc::default(), limit }
+    }
+
+    /// Returns the text written since the last take, and drops it from the buffer.
+    ///
+    /// A character split across writes waits for its remaining bytes.
+    fn take(&self) -> String {
+        let mut buf = self.buf.lock().unwrap();
+        let end = match std::str::from_utf8(&buf) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => buf.len(),
+        };
+        let taken: Vec<u8> = buf.drain(..end).collect();
+        String::from_utf8_lossy(&taken).into_owned()
+    }
+}
+
+impl IsTerminal for Capture {
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+impl StdoutStream for Capture {
+    fn async_stream(&self) -> Box<dyn AsyncWrite + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl AsyncWrite for Capture {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, bytes: &[u8]) -> Poll<io::Result<usize>> {
+        // Output past the limit is dropped, but reported as written so the guest carries on
+        let mut buf = self.buf.lock().unwrap();
+        let room = self.limit.saturating_sub(buf.len());
+        buf.extend_from_slice(&bytes[..bytes.len().min(room)]);
+        Poll::Ready(Ok(bytes.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A host directory exposed to the guest under `guest`.
+#[derive(Clone, Debug)]
+struct Mount {
+    host: PathBuf,
+    guest: String,
+    writable: bool,
+}
 
 impl Wasi {
+    /// WASI with no filesystem access.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Starts a WASI configuration with an explicit mount allowlist.
+    pub fn builder() -> WasiBuilder {
+        WasiBuilder::default()
+    }
+
+    /// Takes everything the guest wrote to stdout since the last call.
+    ///
+    /// Empty unless built with `WasiBuilder::capture_stdio`.
+    pub fn take_stdout(&self) -> String {
+        self.stdio.as_ref().map(|stdio| stdio.stdout.take()).unwrap_or_default()
+    }
+
+    /// Takes everything the guest wrote to stderr since the last call.
+    ///
+    /// Empty unless built with `WasiBuilder::capture_stdio`.
+    pub fn take_stderr(&self) -> String {
+        self.stdio.as_ref().map(|stdio| stdio.stderr.take()).unwrap_or_default()
     }
 
     /// Links WASI to the linker and context builder.
     ///
-    /// This installs WASI interfaces into the linker. WASI configuration
-    /// is done via ContextBuilder methods directly (e.g., inheriting stdio,
-    /// mounting directories), so the context_builder parameter is unused here.
+    /// This installs WASI interfaces into the linker and preopens each mount
+    /// in the context builder with read-only or read-write permissions.
     pub fn link(
         &self,
         linker: &mut Linker<ExorunCtx>,
+        context_builder: &mut ContextBuilder,
     ) -> Result<()> {
         wasmtime_wasi::p2::add_to_linker_async(linker)?;
+
+        if let Some(stdio) = &self.stdio {
+            context_builder.wasi.stdout(stdio.stdout.clone());
+            context_builder.wasi.stderr(stdio.stderr.clone());
+        }
+
+        for mount in &self.mounts {
+            let (dir_perms, file_perms) = match mount.writable {
+                true => (DirPerms::all(), FilePerms::all()),
+                false => (DirPerms::READ, FilePerms::READ),
+            };
+            context_builder.wasi
+                .preopened_dir(&mount.host, &mount.guest, dir_perms, file_perms)
+                .map_err(|e| Error::Link(format!("cannot mount {}: {}", mount.host.display(), e)))?;
+        }
+
         Ok(())
     }
 }
+
+/// Builder for a `Wasi` whose filesystem access is exactly the declared mounts.
+#[derive(Default)]
+pub struct WasiBuilder {
+    mounts: Vec<Mount>,
+    capture_stdio: bool,
+    capture_limit: Option<usize>,
+}
+
+impl WasiBuilder {
+    /// Mounts `host` at `guest` for reading only.
+    pub fn preopen_ro(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.mounts.push(Mount { host: host.into(), guest: guest.into(), writable: false });
+        self
+    }
+
+    /// Mounts `host` at `guest` for reading and writing.
+    pub fn preopen_rw(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.mounts.push(Mount { host: host.into(), guest: guest.into(), writable: true });
+        self
+    }
+
+    /// Buffers guest stdout and stderr in memory instead of discarding them.
+    pub fn capture_stdio(mut self) -> Self {
+        self.capture_stdio = true;
+        self
+    }
+
+    /// Caps each captured stream at `bytes` not yet taken, in place of `DEFAULT_CAPTURE_BYTES`.
+    pub fn capture_limit(mut self, bytes: usize) -> Self {
+        self.capture_limit = Some(bytes);
+        self
+    }
+
+    pub fn build(self) -> Wasi {
+        let limit = self.capture_limit.unwrap_or(DEFAULT_CAPTURE_BYTES);
+        let stdio = self.capture_stdio.then(|| CapturedStdio {
+            stdout: Capture::new(limit),
+            stderr: Capture::new(limit),
+        });
+        Wasi { mounts: self.mounts, stdio }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_holds_only_untaken_output() {
+        let capture = Capture::new(DEFAULT_CAPTURE_BYTES);
+        let mut stream = Pin::from(capture.async_stream());
+        let chunk = "x".repeat(1 << 16);
+        for _ in 0..64 {
+            stream.write_all(chunk.as_bytes()).await.unwrap();
+            assert_eq!(capture.take(), chunk);
+        }
+        assert!(capture.buf.lock().unwrap().capacity() < 1 << 20);
+
+        // A split character waits for the rest of its bytes
+        stream.write_all(&"é".as_bytes()[..1]).await.unwrap();
+        assert_eq!(capture.take(), "");
+        stream.write_all(&"é".as_bytes()[1..]).await.unwrap();
+        assert_eq!(capture.take(), "é");
+    }
+
+    #[tokio::test]
+    async fn test_capture_drops_output_past_limit() {
+        let capture = Capture::new(8);
+        let mut stream = Pin::from(capture.async_stream());
+        stream.write_all(b"hello").await.unwrap();
+        stream.write_all(b" world").await.unwrap();
+        stream.write_all(b"!").await.unwrap();
+        assert_eq!(capture.take(), "hello wo");
+
+        // Taking makes room again
+        stream.write_all(b"again").await.unwrap();
+        assert_eq!(capture.take(), "again");
+    }
+}
@@ -0,0 +1,143 @@
+//! # Writer host component
+//!
+//! Provides Lamport clocks for ordering messages from multiple writers.
+//! Each message is stamped with its writer and a counter; stamps compare by
+//! counter first and writer second, which gives a total order every replica
+//! agrees on.
+//!
+//! ## Clock rules
+//!
+//! - `tick(writer)` returns a stamp one past the highest counter this clock
+//!   has issued or observed, so new stamps follow everything seen so far.
+//! - `merge(a, b)` observes both stamps and returns the later one.
+//!
+//! ## Invariants
+//!
+//! - **Monotonic Ticks**: Successive ticks for one writer strictly increase.
+//! - **Lattice Merge**: `merge` is commutative, associative, and idempotent,
+//!   so replicas converge no matter how often or in what order they exchange stamps.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use wasmtime::component::ComponentType;
+use wasmtime::component::Lift;
+use wasmtime::component::Linker;
+use wasmtime::component::Lower;
+
+use crate::context::ExorunCtx;
+use crate::host::Error;
+use crate::host::Result;
+
+/// A logical timestamp issued to one writer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, ComponentType, Lift, Lower)]
+#[component(record)]
+pub struct Stamp {
+    pub writer: String,
+    pub counter: u64,
+}
+
+impl Ord for Stamp {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.counter, &self.writer).cmp(&(other.counter, &other.writer))
+    }
+}
+
+impl PartialOrd for Stamp {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Writer host component.
+///
+/// Provides the `exorun:writer/clock` interface to Wasm components.
+/// Clones share the same clock.
+#[derive(Clone, Debug)]
+pub struct Writer {
+    /// Highest counter issued to or observed from each writer.
+    counters: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl Writer {
+    /// Creates a clock that has seen nothing.
+    pub fn new() -> Self {
+        Self {
+            counters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Issues the next stamp for `writer`.
+    pub async fn tick(&self, writer: &str) -> Stamp {
+        tick(&mut *self.counters.lock().await, writer)
+    }
+
+    /// Observes both stamps and returns the later one.
+    pub async fn merge(&self, a: Stamp, b: Stamp) -> Stamp {
+        merge(&mut *self.counters.lock().await, a, b)
+    }
+
+    /// Returns the highest counter known for each writer.
+    pub async fn get_counters(&self) -> HashMap<String, u64> {
+        self.counters.lock().await.clone()
+    }
+
+    /// Links this clock to the linker, installing the `exorun:writer/clock` interface.
+    pub fn link(&self, linker: &mut Linker<ExorunCtx>) -> Result<()> {
+        let counters = self.counters.clone();
+
+        let mut instance = linker
+            .instance("exorun:writer/clock")
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        // Bind the 'tick' function
+        instance
+            .func_wrap(
+                "tick",
+                {
+                    let counters = counters.clone();
+                    move |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (writer,): (String,)| {
+                        let mut guard = counters.try_lock()
+                            .map_err(|_| wasmtime::Error::msg("writer mutex contention"))?;
+                        Ok((tick(&mut guard, &writer),))
+                    }
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        // Bind the 'merge' function
+        instance
+            .func_wrap(
+                "merge",
+                move |_caller: wasmtime::StoreContextMut<'_, ExorunCtx>, (a, b): (Stamp, Stamp)| {
+                    let mut guard = counters.try_lock()
+                        .map_err(|_| wasmtime::Error::msg("writer mutex contention"))?;
+                    Ok((merge(&mut guard, a, b),))
+                },
+            )
+            .map_err(|e| Error::Link(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn tick(counters: &mut HashMap<String, u64>, writer: &str) -> Stamp {
+    let counter = counters.values().copied().max().unwrap_or(0) + 1;
+    counters.insert(writer.to_string(), counter);
+    Stamp { writer: writer.to_string(), counter }
+}
+
+fn merge(counters: &mut HashMap<String, u64>, a: Stamp, b: Stamp) -> Stamp {
+    for stamp in [&a, &b] {
+        let known = counters.entry(stamp.writer.clone()).or_insert(0);
+        *known = (*known).max(stamp.counter);
+    }
+    a.max(b)
+}
@@ -0,0 +1,29 @@
+//! # Hooks around calls into an instance
+//!
+//! Interceptors are attached per interface with `InstanceBuilder::intercept`
+//! and run on every call into that interface, whether made locally with
+//! `Runtime::call` or served to a peer. They suit checks like authorization,
+//! and record-keeping like audit logs.
+
+use neorpc::FailureReason;
+use wasmtime::component::Val;
+
+use crate::runtime;
+
+/// Runs before and after each call into an interface.
+///
+/// Both hooks default to doing nothing, so an interceptor implements only
+/// the ones it needs. They run while the instance is locked, so keep them quick.
+pub trait CallInterceptor: Send + Sync {
+    /// Checks a call before it runs.
+    ///
+    /// Returning `Err` rejects the call without entering the guest: local
+    /// callers get `runtime::Error::Rejected`, and peers a `ReplyErr` with
+    /// the reason, usually a `FailureReason::DomainSpecific`.
+    fn before(&self, _method: &str, _args: &[Val]) -> Result<(), FailureReason> {
+        Ok(())
+    }
+
+    /// Sees the outcome of a call that `before` let through.
+    fn after(&self, _method: &str, _outcome: &runtime::Result<Vec<Val>>) {}
+}
@@ -7,6 +7,7 @@
 //! ## Philosophy
 //!
 //! - **Link-Time Safety**: We validate that interfaces are "wire-safe" (contain no resources) at creation time.
+//!   Resource constructors, methods, and statics are recorded by kind but never bridged.
 //! - **Schema Registry**: We store `wasmtime::component::Type` handles, allowing O(1) lookup during the hot path of an RPC call.
 
 use std::collections::HashMap;
@@ -15,6 +16,7 @@ use wasmtime::component::types::ComponentFunc;
 use wasmtime::component::types::ComponentItem;
 use wasmtime::component::Component;
 use wasmtime::component::Type;
+use neorpc::MethodSchema;
 
 /// Ledger errors.
 #[derive(Debug, Clone)]
@@ -27,9 +29,9 @@ pub enum Error {
     StreamNotWireSafe,
     /// Type contains error contexts which cannot cross network boundaries.
     ErrorContextNotWireSafe,
-    /// Parameter contains forbidden type.
+    /// Parameter contains a forbidden type, or disagrees with the linked export.
     InvalidParameter { import_name: String, details: String },
-    /// Result contains forbidden type.
+    /// Result contains a forbidden type, or disagrees with the linked export.
     InvalidResult { import_name: String, details: String },
 }
 
@@ -40,8 +42,8 @@ impl std::fmt::Display for Error {
             Error::FutureNotWireSafe => write!(f, "futures cannot cross network boundaries"),
             Error::StreamNotWireSafe => write!(f, "streams cannot cross network boundaries"),
             Error::ErrorContextNotWireSafe => write!(f, "error contexts cannot cross network boundaries"),
-            Error::InvalidParameter { import_name, details } => write!(f, "import '{}' is not wire-safe: parameter contains forbidden type: {}", import_name, details),
-            Error::InvalidResult { import_name, details } => write!(f, "import '{}' is not wire-safe: result contains forbidden type: {}", import_name, details),
+            Error::InvalidParameter { import_name, details } => write!(f, "import '{}' has an invalid parameter: {}", import_name, details),
+            Error::InvalidResult { import_name, details } => write!(f, "import '{}' has an invalid result: {}", import_name, details),
         }
     }
 }
@@ -103,6 +105,27 @@ impl Ledger {
     pub fn get_interface_func(&self, interface: &str, method: &str) -> Option<&FuncSignature> {
         self.imports.get(interface).and_then(|i| i.funcs.get(method))
     }
+
+    /// Schema fingerprint of every imported method, for a peer handshake.
+    pub fn import_fingerprint(&self) -> u64 {
+        fingerprint_of(&self.imports)
+    }
+
+    /// Schema fingerprint of every exported method, for a peer handshake.
+    pub fn export_fingerprint(&self) -> u64 {
+        fingerprint_of(&self.exports)
+    }
+}
+
+fn fingerprint_of(interfaces: &HashMap<String, InterfaceSchema>) -> u64 {
+    neorpc::fingerprint(interfaces.iter().flat_map(|(interface, schema)| {
+        schema.funcs.iter().filter(|(_, sig)| sig.kind == FuncKind::Freestanding).map(move |(method, sig)| MethodSchema {
+            interface,
+            method,
+            params: &sig.params,
+            results: &sig.results,
+        })
+    }))
 }
 
 /// Validates that an import interface is compatible with an export interface.
@@ -123,7 +146,7 @@ pub fn validate_compatibility(
         let export_sig = export.funcs.get(func_name).ok_or_else(|| {
             Error::InvalidParameter {
                 import_name: format!("{}#{}", interface_name, func_name),
-                details: format!("function not found in target's exports"),
+                details: "function not found in target's exports".to_string(),
             }
         })?;
 
@@ -171,7 +194,15 @@ impl InterfaceSchema {
         for (func_name, func_item) in inst_ty.exports(engine) {
             let ComponentItem::ComponentFunc(func_ty) = func_item else { continue };
             let import_name = format!("{name}#{func_name}");
-            let sig = FuncSignature::from_func_ty(&func_ty, &import_name)?;
+            let sig = match FuncKind::parse(func_name) {
+                FuncKind::Freestanding => FuncSignature::from_func_ty(&func_ty, &import_name)?,
+                // Resource handles never cross a boundary, so these are kept as-is
+                kind => FuncSignature {
+                    params: func_ty.params().map(|(_, ty)| ty).collect(),
+                    results: func_ty.results().collect(),
+                    kind,
+                },
+            };
             funcs.insert(func_name.to_string(), sig);
         }
 
@@ -184,6 +215,46 @@ impl InterfaceSchema {
 pub struct FuncSignature {
     pub params: Vec<Type>,
     pub results: Vec<Type>,
+    pub kind: FuncKind,
+}
+
+/// Whether a function stands alone or belongs to a resource.
+///
+/// Parsed from the WIT-style export name, e.g. `[method]file.read`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FuncKind {
+    /// A plain function, the only kind that can be bridged.
+    Freestanding,
+    /// `[constructor]resource`
+    Constructor(String),
+    /// `[method]resource.name`
+    Method(String),
+    /// `[static]resource.name`
+    Static(String),
+}
+
+impl FuncKind {
+    /// Classifies a function by its export name.
+    pub fn parse(name: &str) -> Self {
+        let resource = |rest: &str| rest.split_once('.').map_or(rest, |(r, _)| r).to_string();
+        if let Some(rest) = name.strip_prefix("[constructor]") {
+            FuncKind::Constructor(rest.to_string())
+        } else if let Some(rest) = name.strip_prefix("[method]") {
+            FuncKind::Method(resource(rest))
+        } else if let Some(rest) = name.strip_prefix("[static]") {
+            FuncKind::Static(resource(rest))
+        } else {
+            FuncKind::Freestanding
+        }
+    }
+
+    /// The resource this function belongs to, if any.
+    pub fn resource(&self) -> Option<&str> {
+        match self {
+            FuncKind::Freestanding => None,
+            FuncKind::Constructor(r) | FuncKind::Method(r) | FuncKind::Static(r) => Some(r),
+        }
+    }
 }
 
 impl FuncSignature {
@@ -201,14 +272,14 @@ impl FuncSignature {
             })?;
 
         let results: Vec<Type> = func_ty.results()
-            .map(|ty| check_wire_safe(ty))
+            .map(check_wire_safe)
             .collect::<Result<Vec<_>>>()
             .map_err(|e| Error::InvalidResult {
                 import_name: import_name.to_string(),
                 details: e.to_string(),
             })?;
 
-        Ok(Self { params, results })
+        Ok(Self { params, results, kind: FuncKind::Freestanding })
     }
 }
 
@@ -361,6 +432,55 @@ mod tests {
         assert!(ledger.get_interface_func("bad", "process-list").is_none());
     }
 
+    #[test]
+    fn test_ledger_classifies_resource_funcs() {
+        let c = compile(r#"
+            (component
+                (import "files" (instance $files
+                    (export "file" (type $file (sub resource)))
+                    (export "[constructor]file" (func (param "path" string) (result (own $file))))
+                    (export "[method]file.read" (func (param "self" (borrow $file)) (param "n" u32) (result (list u8))))
+                    (export "[static]file.open" (func (param "path" string) (result (own $file))))
+                    (export "exists" (func (param "path" string) (result bool))))))
+        "#);
+
+        let ledger = Ledger::from_component(&c).expect("Ledger creation failed");
+        let kind = |name| ledger.get_interface_func("files", name).expect(name).kind.clone();
+
+        assert_eq!(kind("exists"), FuncKind::Freestanding);
+        assert_eq!(kind("[constructor]file"), FuncKind::Constructor("file".into()));
+        assert_eq!(kind("[method]file.read"), FuncKind::Method("file".into()));
+        assert_eq!(kind("[static]file.open"), FuncKind::Static("file".into()));
+        assert_eq!(kind("[method]file.read").resource(), Some("file"));
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_added_method() {
+        let base = Ledger::from_component(&compile(r#"
+            (component
+                (import "kv" (instance
+                    (export "get" (func (param "k" string) (result string)))
+                    (export "set" (func (param "k" string) (param "v" string))))))
+        "#)).unwrap();
+        let reordered = Ledger::from_component(&compile(r#"
+            (component
+                (import "kv" (instance
+                    (export "set" (func (param "k" string) (param "v" string)))
+                    (export "get" (func (param "k" string) (result string))))))
+        "#)).unwrap();
+        let extended = Ledger::from_component(&compile(r#"
+            (component
+                (import "kv" (instance
+                    (export "get" (func (param "k" string) (result string)))
+                    (export "set" (func (param "k" string) (param "v" string)))
+                    (export "delete" (func (param "k" string))))))
+        "#)).unwrap();
+
+        assert_eq!(base.import_fingerprint(), reordered.import_fingerprint());
+        assert_ne!(base.import_fingerprint(), extended.import_fingerprint());
+        assert_ne!(base.import_fingerprint(), base.export_fingerprint());
+    }
+
     #[test]
     fn test_ledger_allows_complex_pure_data() {
         let c = compile(r#"
@@ -37,19 +37,38 @@
 //! In addition to the standard wasi components for e.g. scoped filesystem access, time, randomness, etc.
 
 pub mod bind;
+pub mod bootstrap;
+pub mod cancel;
+pub mod cap;
+pub mod clock;
 pub mod peer;
 pub mod context;
+pub mod intercept;
 pub mod local;
 pub mod ledger;
+pub mod pool;
 pub mod runtime;
 pub mod host;
 pub mod transport;
+pub mod typed;
+#[cfg(feature = "tracing")]
+pub mod trace;
 
 // Re-export commonly used types
 pub use runtime::Runtime;
 pub use runtime::ComponentId;
 pub use runtime::InstanceId;
 pub use runtime::PeerId;
+pub use pool::PoolId;
+pub use runtime::RuntimeEvent;
+pub use runtime::InstanceMetrics;
+pub use runtime::ShutdownReport;
+pub use context::Budget;
+pub use bootstrap::BootstrapBundle;
+pub use cancel::CancellationToken;
+pub use cap::Capability;
+pub use clock::VirtualClock;
+pub use intercept::CallInterceptor;
 
 #[cfg(test)]
 mod tests;
@@ -2,23 +2,38 @@
 //!
 //! Provides a fluent API for composing an instance with various linking strategies.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 use wasmtime::component::Linker;
-use wasmtime::Store;
+use wasmtime::StoreLimitsBuilder;
 
 use crate::bind;
 use crate::bind::Binder;
+use crate::bind::Linkable;
+use crate::cap::Capability;
+use crate::clock::VirtualClock;
+use crate::clock::VirtualMonotonicClock;
+use crate::clock::VirtualWallClock;
+use crate::context::Budget;
 use crate::context::ContextBuilder;
+use crate::context::TrackedLimits;
 use crate::ledger;
 use crate::runtime;
 use crate::runtime::ComponentId;
 use crate::runtime::InstanceId;
 use crate::runtime::InstanceState;
 use crate::runtime::Runtime;
+use crate::runtime::RuntimeEvent;
 use crate::peer::PeerInstance;
 use crate::host::HostInstance;
+use crate::host::SystemComponent;
 use crate::host;
+use crate::intercept::CallInterceptor;
 
 #[derive(Debug)]
 pub enum Error {
@@ -28,6 +43,7 @@ pub enum Error {
     Ledger(ledger::Error),
     Linker(wasmtime::Error),
     Instantiate(wasmtime::Error),
+    Budget(wasmtime::Error),
 }
 
 impl std::fmt::Display for Error {
@@ -39,6 +55,7 @@ impl std::fmt::Display for Error {
             Self::Ledger(e) => write!(f, "ledger error: {}", e),
             Self::Linker(e) => write!(f, "linker error: {}", e),
             Self::Instantiate(e) => write!(f, "instantiate error: {}", e),
+            Self::Budget(e) => write!(f, "budget error: {}", e),
         }
     }
 }
@@ -72,10 +89,92 @@ impl From<ledger::Error> for Error {
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Linking strategy for an interface.
+#[derive(Clone)]
 pub enum Link {
     System { interface: String, instance: HostInstance },
+    Shared { interface: String, instance: Arc<dyn SystemComponent> },
     Local  { interface: String, instance: InstanceId },
     Remote { interface: String, instance: PeerInstance  },
+    Chain  { interface: String, targets: Vec<Linkable>  },
+}
+
+impl Link {
+    /// The interface this link provides.
+    pub fn interface(&self) -> &str {
+        match self {
+            Link::System { interface, .. } => interface,
+            Link::Shared { interface, .. } => interface,
+            Link::Local { interface, .. } => interface,
+            Link::Remote { interface, .. } => interface,
+            Link::Chain { interface, .. } => interface,
+        }
+    }
+}
+
+/// Adds `link`, replacing any earlier link for the same interface.
+fn push_link(links: &mut Vec<Link>, link: Link) {
+    links.retain(|existing| existing.interface() != link.interface());
+    links.push(link);
+}
+
+/// A reusable set of links, built once and applied to many instances.
+///
+/// Host instances are cloned on apply, so instances sharing a profile
+/// share e.g. the same `Logger` buffer or `Kv` store.
+#[derive(Clone, Default)]
+pub struct LinkProfile {
+    links: Vec<Link>,
+}
+
+impl LinkProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn link_system(mut self, interface: impl Into<String>, component: HostInstance) -> Self {
+        push_link(&mut self.links, Link::System {
+            interface: interface.into(),
+            instance: component,
+        });
+        self
+    }
+
+    pub fn link_system_shared(mut self, interface: impl Into<String>, component: Arc<dyn SystemComponent>) -> Self {
+        push_link(&mut self.links, Link::Shared {
+            interface: interface.into(),
+            instance: component,
+        });
+        self
+    }
+
+    pub fn link_local(mut self, interface: impl Into<String>, target: InstanceId) -> Self {
+        push_link(&mut self.links, Link::Local {
+            interface: interface.into(),
+            instance: target,
+        });
+        self
+    }
+
+    pub fn link_remote(mut self, interface: impl Into<String>, target: PeerInstance) -> Self {
+        push_link(&mut self.links, Link::Remote {
+            interface: interface.into(),
+            instance: target,
+        });
+        self
+    }
+
+    pub fn link_chain(mut self, interface: impl Into<String>, targets: Vec<Linkable>) -> Self {
+        push_link(&mut self.links, Link::Chain {
+            interface: interface.into(),
+            targets,
+        });
+        self
+    }
+
+    /// The links in this profile, one per interface.
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
 }
 
 /// Fluent builder for creating instances with configured links.
@@ -84,6 +183,11 @@ pub struct InstanceBuilder {
     component_id: ComponentId,
     links: Vec<Link>,
     context_builder: ContextBuilder,
+    budget: Option<Budget>,
+    rng_seed: Option<u64>,
+    error_mapping: HashSet<String>,
+    interceptors: HashMap<String, Vec<Arc<dyn CallInterceptor>>>,
+    meta_allowed: bool,
 }
 
 impl InstanceBuilder {
@@ -93,11 +197,31 @@ impl InstanceBuilder {
             component_id,
             links: Vec::new(),
             context_builder: ContextBuilder::new(),
+            budget: None,
+            rng_seed: None,
+            error_mapping: HashSet::new(),
+            interceptors: HashMap::new(),
+            meta_allowed: false,
         }
     }
 
+    /// Links an interface to a host instance.
+    ///
+    /// Linking an interface again replaces the earlier link.
     pub fn link_system(mut self, interface: impl Into<String>, component: HostInstance) -> Self {
-        self.links.push(Link::System {
+        push_link(&mut self.links, Link::System {
+            interface: interface.into(),
+            instance: component,
+        });
+        self
+    }
+
+    /// Links an interface to a system component shared with other instances.
+    ///
+    /// The component is installed into this instance's linker at build time;
+    /// every instance given the same `Arc` sees the same backing state.
+    pub fn link_system_shared(mut self, interface: impl Into<String>, component: Arc<dyn SystemComponent>) -> Self {
+        push_link(&mut self.links, Link::Shared {
             interface: interface.into(),
             instance: component,
         });
@@ -105,7 +229,7 @@ impl InstanceBuilder {
     }
 
     pub fn link_local(mut self, interface: impl Into<String>, target: InstanceId) -> Self {
-        self.links.push(Link::Local {
+        push_link(&mut self.links, Link::Local {
             interface: interface.into(),
             instance: target,
         });
@@ -113,14 +237,106 @@ impl InstanceBuilder {
     }
 
     pub fn link_remote(mut self, interface: impl Into<String>, target: PeerInstance) -> Self {
-        self.links.push(Link::Remote {
+        push_link(&mut self.links, Link::Remote {
             interface: interface.into(),
             instance: target,
         });
         self
     }
 
+    /// Links an interface to several targets, tried in order on each call.
+    ///
+    /// A call falls through to the next target only when one is unreachable,
+    /// e.g. a removed local instance or a disconnected peer; a guest's own
+    /// error is returned as is. Local targets aren't validated at build time.
+    pub fn link_chain(mut self, interface: impl Into<String>, targets: Vec<Linkable>) -> Self {
+        push_link(&mut self.links, Link::Chain {
+            interface: interface.into(),
+            targets,
+        });
+        self
+    }
+
+    /// Adds every link in `profile`, as if each were linked in turn.
+    ///
+    /// Links made after this replace the profile's for the same interface.
+    pub fn apply(mut self, profile: &LinkProfile) -> Self {
+        for link in profile.links() {
+            push_link(&mut self.links, link.clone());
+        }
+        self
+    }
+
+    /// Caps the fuel and memory available to the instance.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Serves `Err` results of `interface`'s methods to peers as failures.
+    ///
+    /// When a method returning a single `result` yields `Err`, `Runtime::serve_call`
+    /// replies with `FailureReason::DomainSpecific` instead of an `Ok` payload,
+    /// so remote callers see `peer::Error::Remote`. Methods without a `result`
+    /// return type, and local calls through `Runtime::call`, are unaffected.
+    pub fn with_error_mapping(mut self, interface: impl Into<String>) -> Self {
+        self.error_mapping.insert(interface.into());
+        self
+    }
+
+    /// Runs `interceptor` around every call into `interface`.
+    ///
+    /// This covers local calls through `Runtime::call` and calls served to
+    /// peers alike. Interceptors on the same interface run in the order added.
+    pub fn intercept(mut self, interface: impl Into<String>, interceptor: Arc<dyn CallInterceptor>) -> Self {
+        self.interceptors.entry(interface.into()).or_default().push(interceptor);
+        self
+    }
+
+    /// Lets the instance use a linked `Meta` to register components and spawn instances.
+    ///
+    /// Without this, linking `HostInstance::Meta` fails the build. The grant
+    /// is per builder, so applying a `LinkProfile` that links `Meta` doesn't confer it.
+    pub fn allow_meta(mut self) -> Self {
+        self.meta_allowed = true;
+        self
+    }
+
+    /// Gives the instance a capability, checked each time it attempts an operation gated on its scope.
+    ///
+    /// Only matters once the runtime trusts an issuer with
+    /// `Runtime::trust_capability_issuer`; until then nothing is gated.
+    /// An instance holds at most one capability, so this replaces any earlier one.
+    pub fn with_capability(mut self, cap: Capability) -> Self {
+        self.context_builder.insert(cap);
+        self
+    }
+
+    /// Makes the instance's WASI randomness a ChaCha stream seeded with `seed`.
+    ///
+    /// Instances with the same seed see the same random bytes, for tests and
+    /// replay. Without a seed, randomness comes from the OS as usual.
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// Makes the instance's WASI wall and monotonic clocks read from `clock`.
+    ///
+    /// Time then only moves with `VirtualClock::advance`, for tests and
+    /// replay. Without a virtual clock, both read the OS clocks as usual.
+    /// Capability deadlines are checked against the same clock.
+    pub fn with_virtual_clock(mut self, clock: Arc<VirtualClock>) -> Self {
+        self.context_builder.wasi.wall_clock(VirtualWallClock(Arc::clone(&clock)));
+        self.context_builder.wasi.monotonic_clock(VirtualMonotonicClock(Arc::clone(&clock)));
+        self.context_builder.insert(clock);
+        self
+    }
+
     pub async fn build(mut self) -> Result<InstanceId> {
+        if self.runtime.is_shut_down() {
+            return Err(Error::Runtime(runtime::Error::Shutdown));
+        }
         let component = self.runtime.get_component(self.component_id)?;
         let my_ledger = self.runtime.get_ledger(self.component_id)?;
 
@@ -132,11 +348,25 @@ impl InstanceBuilder {
                 Link::System { interface, instance: host_instance } => {
                     // Validate that the host instance can provide this interface
                     host_instance.validate_interface(interface)?;
+                    if matches!(host_instance, HostInstance::Meta(_)) && !self.meta_allowed {
+                        return Err(Error::Host(host::Error::Link(format!(
+                            "linking Meta to '{}' needs InstanceBuilder::allow_meta", interface
+                        ))));
+                    }
                     host_instance.link(&mut linker, &mut self.context_builder)?;
                 }
+                Link::Shared { interface, instance: component } => {
+                    if !component.interfaces().contains(&interface.as_str()) {
+                        return Err(Error::Host(host::Error::Link(format!(
+                            "shared system component cannot provide interface '{}' (expected one of {:?})",
+                            interface, component.interfaces()
+                        ))));
+                    }
+                    component.install(&mut linker)?;
+                }
                 Link::Local { interface, instance: target_id } => {
                     // Bidirectional validation: check target exports match my imports
-                    self.validate_local_link(interface, *target_id)?;
+                    Self::validate_local_link(&self.runtime, self.component_id, interface, *target_id).await?;
                     Binder::local_interface(&mut linker, &my_ledger, interface, *target_id)?;
                 }
                 Link::Remote { interface, instance: target } => {
@@ -144,11 +374,29 @@ impl InstanceBuilder {
                     // (we don't have the remote component's ledger)
                     Binder::peer_interface(&mut linker, &my_ledger, interface, target.clone())?;
                 }
+                Link::Chain { interface, targets } => {
+                    // Targets may be missing by design, so none are validated up front
+                    Binder::chain_interface(&mut linker, &my_ledger, interface, targets)?;
+                }
             }
         }
 
-        let ctx = self.context_builder.build(Arc::clone(&self.runtime));
-        let mut store = Store::new(self.runtime.engine(), ctx);
+        if let Some(seed) = self.rng_seed {
+            self.seed_random(seed);
+        }
+
+        // The id is taken before instantiation, so host functions run by a start function can see it
+        let instance_id = self.runtime.next_instance_id();
+        let mut ctx = self.context_builder.build(Arc::clone(&self.runtime));
+        ctx.instance_id = Some(instance_id);
+        ctx.budget = self.budget;
+        if let Some(budget) = &self.budget {
+            ctx.limits = TrackedLimits::new(StoreLimitsBuilder::new().memory_size(budget.max_memory_bytes).build());
+        }
+        let mut store = self.runtime.new_store(ctx);
+        if let Some(budget) = &self.budget {
+            store.set_fuel(budget.fuel).map_err(Error::Budget)?;
+        }
 
         let instance = linker
             .instantiate_async(&mut store, &component)
@@ -157,17 +405,48 @@ impl InstanceBuilder {
 
         let state = InstanceState {
             component_id: self.component_id,
+            ledger: my_ledger,
             store,
             instance,
+            call_count: 0,
+            fuel_consumed: 0,
+            error_mapping: self.error_mapping,
+            poisoned: false,
+            interceptors: self.interceptors,
         };
 
-        let instance_id = self.runtime.add_instance(state);
+        self.runtime.add_instance(instance_id, state);
+        self.runtime.emit(RuntimeEvent::InstanceStarted(instance_id, self.component_id));
         Ok(instance_id)
     }
 
+    /// Replaces every WASI random source with one derived from `seed`.
+    ///
+    /// Each source reads its own ChaCha stream, so they don't repeat each other.
+    fn seed_random(&mut self, seed: u64) {
+        let stream = |n| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            rng.set_stream(n);
+            rng
+        };
+        let mut seeds = stream(2);
+        let insecure_seed = (seeds.next_u64() as u128) << 64 | seeds.next_u64() as u128;
+
+        let wasi = &mut self.context_builder.wasi;
+        wasi.secure_random(stream(0));
+        wasi.insecure_random(stream(1));
+        wasi.insecure_random_seed(insecure_seed);
+    }
+
     /// Validates that a local link is compatible: my import matches target's export.
-    fn validate_local_link(&self, interface: &str, target_id: InstanceId) -> Result<()> {
-        let my_ledger = self.runtime.get_ledger(self.component_id)?;
+    ///
+    /// Runs before instantiation, so a mismatch names the interface and
+    /// function instead of surfacing as an opaque wasmtime link error.
+    ///
+    /// Takes the runtime rather than `&self`, whose WASI context isn't `Sync`,
+    /// so `build` stays `Send` and can be spawned.
+    async fn validate_local_link(runtime: &Runtime, component_id: ComponentId, interface: &str, target_id: InstanceId) -> Result<()> {
+        let my_ledger = runtime.get_ledger(component_id)?;
         
         // Get my import schema
         let my_import = my_ledger.imports.get(interface)
@@ -177,15 +456,14 @@ impl InstanceBuilder {
             })?;
         
         // Get target's component ID and ledger
-        let target_state = self.runtime.instances
+        // Wait out any call in flight on the target rather than misreporting it as missing
+        let target_state = runtime.instances
             .get(&target_id)
+            .map(|entry| Arc::clone(entry.value()))
             .ok_or(runtime::Error::InstanceNotFound(target_id))?;
         
-        let target_component_id = target_state.value().try_lock()
-            .map_err(|_| runtime::Error::InstanceNotFound(target_id))?
-            .component_id;
-        
-        let target_ledger = self.runtime.get_ledger(target_component_id)?;
+        // The target's own ledger, which still holds if its component was since updated
+        let target_ledger = target_state.lock().await.ledger.clone();
         
         // Get target's export schema
         let target_export = target_ledger.exports.get(interface)
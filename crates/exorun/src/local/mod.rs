@@ -5,3 +5,5 @@
 pub mod builder;
 
 pub use builder::InstanceBuilder;
+pub use builder::LinkProfile;
+pub use crate::bind::Linkable;
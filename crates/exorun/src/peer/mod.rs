@@ -7,9 +7,30 @@
 //! ## Features
 //!
 //! - **Lifecycle Management**: Peers track their connection state (Connected, Disconnected, Shutdown)
-//! - **Reconnection**: Transports can be replaced without losing peer identity
+//! - **Reconnection**: Transports can be replaced without losing peer identity,
+//!   manually or automatically via a reconnect callback
 //! - **Configurable Timeouts**: Per-peer and per-call timeout configuration
-//! - **Backpressure**: Optional limit on pending requests
+//! - **Backpressure**: Optional limit on pending requests, either rejecting
+//!   excess calls (`max_pending`) or queueing them (`with_max_inflight`)
+//! - **Health**: `health()` reports liveness, last round-trip time, and load
+//! - **Keepalive**: Optional pings on idle connections (`with_keepalive`),
+//!   so a connection that died silently is noticed before the next call
+//! - **Message Bounds**: Optional cap on received frame size (`with_max_message_bytes`),
+//!   checked before a frame is decoded, and on the size of a reply sent in ReplyChunks
+//! - **Serving**: Inbound Call frames go to a handler (`with_call_handler`),
+//!   optionally behind a per-peer token bucket (`with_rate_limit`); a Cancel
+//!   frame aborts the task serving its seq, and no reply is sent. The Calls
+//!   of a Batch are served one by one, and ReplyBatch entries complete calls
+//!   like separate Replies
+//! - **Notifications**: `notify` sends a call that wants no reply; inbound
+//!   Notify frames go to the same handler, with its reply discarded
+//! - **Targets**: `advertise` tells the remote which call targets this side
+//!   serves, and `targets` lists the ones the remote advertised
+//! - **Schema Check**: With `PeerConfig::schema_fingerprint` set, a Handshake
+//!   carrying any other fingerprint fails pending and later calls with
+//!   `neorpc::schema_mismatch()`
+//! - **Typed Clients**: With the `macros` feature, `#[rpc_client]` generates
+//!   a client with one method per function of a WIT interface
 //!
 //! ## Example
 //!
@@ -18,6 +39,7 @@
 //! let config = PeerConfig {
 //!     call_timeout: Duration::from_secs(10),
 //!     max_pending: 100,
+//!     schema_fingerprint: Some(ledger.import_fingerprint()),
 //! };
 //! let peer = Peer::new("alice", transport, config);
 //!
@@ -27,31 +49,58 @@
 //! // On disconnect, reconnect with new transport
 //! let ws = WebSocketTransport::connect("wss://peer.example.com/rpc").await?;
 //! peer.reconnect(ws).await?;
+//!
+//! // Or let the peer redial by itself whenever the transport fails
+//! let peer = peer.with_reconnect(|| Box::pin(async {
+//!     let t = QuicTransport::connect("peer.example.com:4433").await?;
+//!     Ok(Box::new(t) as Box<dyn Transport>)
+//! }));
 //! ```
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "macros")]
+pub use exorun_macros::rpc_client;
+
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::Weak;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
 use dashmap::DashMap;
-use tokio::sync::{oneshot, Notify};
+use tokio::sync::{oneshot, watch, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::task::AbortHandle;
 use tokio::task::JoinHandle;
 
 use neopack::Decoder;
-use neopack::Encoder;
+use neorpc::BatchDecoder;
 use neorpc::CallEncoder;
+use neorpc::ChunkReassembler;
 use neorpc::FailureReason;
+use neorpc::HandshakeEncoder;
+use neorpc::NotifyEncoder;
+use neorpc::PingEncoder;
+use neorpc::PongEncoder;
+use neorpc::ReplyChunkDecoder;
+use neorpc::ReplyErrEncoder;
 use neorpc::RpcFrame;
+use neorpc::decode_seq;
 use neorpc::decode_vals;
 use wasmtime::component::Type;
 use wasmtime::component::Val;
 
 use crate::runtime::PeerId;
+use crate::runtime::Runtime;
 use crate::transport::Transport;
 use crate::transport;
+use crate::typed;
+use crate::typed::FromVals;
+use crate::typed::IntoVals;
 
 // =============================================================================
 // Error Types
@@ -69,6 +118,9 @@ pub enum Error {
     Remote(FailureReason),
     /// Request timed out waiting for response.
     Timeout,
+    /// The transport failed while the call was in flight.
+    /// The remote may or may not have run it; retrying is safe for idempotent calls.
+    Interrupted(transport::Error),
     /// Response channel was closed unexpectedly.
     ChannelClosed,
     /// Response sequence number didn't match request.
@@ -81,6 +133,8 @@ pub enum Error {
     TooManyPendingRequests { limit: usize },
     /// Cannot reconnect because peer is already connected.
     AlreadyConnected,
+    /// The reply was larger than the peer accepts, so it was dropped unread.
+    MessageTooLarge { size: usize, limit: usize },
 }
 
 impl std::fmt::Display for Error {
@@ -91,6 +145,7 @@ impl std::fmt::Display for Error {
             Self::NeoPack(e) => write!(f, "neopack error: {}", e),
             Self::Remote(reason) => write!(f, "remote failure: {:?}", reason),
             Self::Timeout => write!(f, "request timed out"),
+            Self::Interrupted(e) => write!(f, "call interrupted: {}", e),
             Self::ChannelClosed => write!(f, "response channel closed"),
             Self::SequenceMismatch { expected, received } => {
                 write!(f, "sequence mismatch: expected {}, received {}", expected, received)
@@ -101,12 +156,29 @@ impl std::fmt::Display for Error {
                 write!(f, "too many pending requests (limit: {})", limit)
             }
             Self::AlreadyConnected => write!(f, "peer is already connected"),
+            Self::MessageTooLarge { size, limit } => {
+                write!(f, "message of {} bytes exceeds the {} byte limit", size, limit)
+            }
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Returns true if the same call may succeed when retried later.
+    pub fn is_retriable(&self) -> bool {
+        matches!(
+            self,
+            Self::Interrupted(_)
+                | Self::Disconnected
+                | Self::Timeout
+                | Self::TooManyPendingRequests { .. }
+                | Self::Remote(FailureReason::Overloaded { .. })
+        )
+    }
+}
+
 impl From<transport::Error> for Error {
     fn from(e: transport::Error) -> Self {
         Self::Transport(e)
@@ -138,6 +210,9 @@ pub struct PeerConfig {
     pub call_timeout: Duration,
     /// Maximum number of pending requests (0 = unlimited).
     pub max_pending: usize,
+    /// Schema fingerprint the remote's Handshake must carry, e.g. a ledger's
+    /// `import_fingerprint`. `None` accepts any.
+    pub schema_fingerprint: Option<u64>,
 }
 
 impl Default for PeerConfig {
@@ -145,10 +220,20 @@ impl Default for PeerConfig {
         Self {
             call_timeout: Duration::from_secs(30),
             max_pending: 0, // unlimited
+            schema_fingerprint: None,
         }
     }
 }
 
+/// Inbound call budget for one peer, enforced by `Peer::with_rate_limit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Calls admitted per second once the burst is spent.
+    pub per_sec: u32,
+    /// Calls admitted back to back after a quiet spell.
+    pub burst: u32,
+}
+
 // =============================================================================
 // Peer State
 // =============================================================================
@@ -175,13 +260,77 @@ impl PeerState {
     }
 }
 
+/// A snapshot of a peer's liveness, as returned by `Peer::health`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerHealth {
+    /// Whether the peer is `Connected`; false once the transport is lost or after shutdown.
+    pub connected: bool,
+    /// Time from preparing the most recently answered call to receiving its reply.
+    pub last_rtt: Option<Duration>,
+    /// Calls awaiting a reply right now.
+    pub inflight: usize,
+    /// Calls prepared over the peer's lifetime, across reconnects.
+    pub total_calls: u64,
+}
+
 // =============================================================================
 // Internal Types
 // =============================================================================
 
+/// Future returned by a reconnect callback.
+pub type ReconnectFuture = Pin<Box<dyn Future<Output = transport::Result<Box<dyn Transport>>> + Send>>;
+
+/// Callback that dials a fresh transport after the current one fails.
+pub type ReconnectFn = dyn Fn() -> ReconnectFuture + Send + Sync;
+
+/// Future returned by a call handler, resolving to the Reply frame to send back.
+pub type CallFuture = Pin<Box<dyn Future<Output = Option<Vec<u8>>> + Send>>;
+
+/// Callback that serves an inbound Call or Notify frame, e.g. with `Runtime::serve_call`.
+pub type CallHandler = dyn Fn(Vec<u8>) -> CallFuture + Send + Sync;
+
+/// Token bucket behind a `RateLimit`, refilled lazily on each take.
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self { limit, tokens: limit.burst as f64, refilled: Instant::now() }
+    }
+
+    /// Takes a token, or returns the milliseconds until one is available.
+    fn take(&mut self) -> std::result::Result<(), u32> {
+        let now = Instant::now();
+        let per_sec = self.limit.per_sec as f64;
+        let refill = now.duration_since(self.refilled).as_secs_f64() * per_sec;
+        self.tokens = (self.tokens + refill).min(self.limit.burst as f64);
+        self.refilled = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if self.limit.per_sec == 0 || self.limit.burst == 0 {
+            return Err(u32::MAX);
+        }
+        Err(((1.0 - self.tokens) / per_sec * 1000.0).ceil() as u32)
+    }
+}
+
+/// Time left before an absolute deadline in unix milliseconds, zero once it has passed.
+fn until_deadline(deadline_ms: u64) -> Duration {
+    let deadline = SystemTime::UNIX_EPOCH + Duration::from_millis(deadline_ms);
+    deadline.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO)
+}
+
 /// Response data correlating to a request.
 struct PendingResponse {
     result_types: Vec<Type>,
+    /// When the call was prepared, for measuring round-trip time.
+    sent_at: Instant,
     tx: oneshot::Sender<Result<Vec<Val>>>,
 }
 
@@ -194,6 +343,40 @@ pub struct PeerInstance {
     pub target_id: String,
 }
 
+impl PeerInstance {
+    /// Whether the peer is registered with `runtime` and has advertised this target.
+    ///
+    /// A remote that never advertised its targets reads as having none.
+    pub fn exists(&self, runtime: &Runtime) -> bool {
+        runtime.get_peer(self.peer_id)
+            .is_ok_and(|peer| peer.inner.targets.lock().unwrap().contains(&self.target_id))
+    }
+}
+
+/// A live transport and the signal its pump listens on.
+#[derive(Clone)]
+struct Connection {
+    transport: Arc<dyn Transport>,
+    /// Notified by callers whose send failed, so the pump tears this connection down.
+    failed: Arc<Notify>,
+    /// Notified by the keepalive task when a ping goes unanswered.
+    unanswered: Arc<Notify>,
+}
+
+impl Connection {
+    fn new(transport: Box<dyn Transport>) -> Self {
+        Self {
+            transport: Arc::from(transport),
+            failed: Arc::new(Notify::new()),
+            unanswered: Arc::new(Notify::new()),
+        }
+    }
+
+    fn is(&self, other: &Connection) -> bool {
+        Arc::ptr_eq(&self.failed, &other.failed)
+    }
+}
+
 /// Shared state between Peer and pump task.
 struct PeerInner {
     peer_name: String,
@@ -201,7 +384,30 @@ struct PeerInner {
     state: AtomicU8,
     pending: DashMap<u64, PendingResponse>,
     seq_gen: AtomicU64,
+    total_calls: AtomicU64,
+    last_rtt: std::sync::Mutex<Option<Duration>>,
+    /// Nonce of the latest Pong received.
+    pong: watch::Sender<u64>,
+    /// Nonce of the latest Ping sent, shared so a restarted keepalive keeps counting up.
+    ping_nonce: AtomicU64,
+    /// Received frames longer than this are dropped before decoding.
+    max_message_bytes: AtomicUsize,
+    /// Whether an oversized frame also tears down the connection.
+    oversize_reset: AtomicBool,
     shutdown_notify: Notify,
+    connection: tokio::sync::Mutex<Option<Connection>>,
+    reconnect: std::sync::Mutex<Option<Arc<ReconnectFn>>>,
+    call_handler: std::sync::Mutex<Option<Arc<CallHandler>>>,
+    /// Inbound calls admitted so far, if the peer is rate limited.
+    rate_limit: std::sync::Mutex<Option<TokenBucket>>,
+    /// Call targets from the remote's latest Handshake.
+    targets: std::sync::Mutex<Vec<String>>,
+    /// Whether the remote's latest Handshake failed `PeerConfig::schema_fingerprint`.
+    schema_mismatch: AtomicBool,
+    /// Chunked replies still arriving, by seq.
+    chunks: DashMap<u64, ChunkReassembler>,
+    /// Tasks serving inbound Calls, by seq, so a Cancel can abort them.
+    serving: Arc<DashMap<u64, AbortHandle>>,
 }
 
 // =============================================================================
@@ -224,11 +430,16 @@ struct PeerInner {
 ///
 /// When a peer becomes disconnected, you can call `reconnect()` with a new
 /// transport. The peer identity (and PeerId) is preserved, allowing existing
-/// bindings to continue working.
+/// bindings to continue working. With `with_reconnect`, the peer does this
+/// itself each time the transport fails.
+///
+/// Either way, calls in flight when the transport fails are resolved
+/// promptly with `Error::Interrupted` rather than left waiting.
 pub struct Peer {
     inner: Arc<PeerInner>,
     pump_handle: tokio::sync::Mutex<Option<JoinHandle<()>>>,
-    transport: tokio::sync::Mutex<Option<Arc<dyn Transport>>>,
+    inflight: Option<Arc<Semaphore>>,
+    keepalive: Option<JoinHandle<()>>,
 }
 
 impl Peer {
@@ -241,26 +452,139 @@ impl Peer {
         transport: Box<dyn Transport>,
         config: PeerConfig,
     ) -> Self {
-        let transport: Arc<dyn Transport> = Arc::from(transport);
-        
+        let connection = Connection::new(transport);
+
         let inner = Arc::new(PeerInner {
             peer_name: peer_name.into(),
             config,
             state: AtomicU8::new(PeerState::Connected as u8),
             pending: DashMap::new(),
             seq_gen: AtomicU64::new(1),
+            total_calls: AtomicU64::new(0),
+            last_rtt: std::sync::Mutex::new(None),
+            pong: watch::Sender::new(0),
+            ping_nonce: AtomicU64::new(0),
+            max_message_bytes: AtomicUsize::new(usize::MAX),
+            oversize_reset: AtomicBool::new(false),
             shutdown_notify: Notify::new(),
+            connection: tokio::sync::Mutex::new(Some(connection.clone())),
+            reconnect: std::sync::Mutex::new(None),
+            call_handler: std::sync::Mutex::new(None),
+            rate_limit: std::sync::Mutex::new(None),
+            targets: std::sync::Mutex::new(Vec::new()),
+            schema_mismatch: AtomicBool::new(false),
+            chunks: DashMap::new(),
+            serving: Arc::new(DashMap::new()),
         });
 
-        let pump_handle = Self::spawn_pump(inner.clone(), transport.clone());
+        let pump_handle = Self::spawn_pump(inner.clone(), connection);
 
         Self {
             inner,
             pump_handle: tokio::sync::Mutex::new(Some(pump_handle)),
-            transport: tokio::sync::Mutex::new(Some(transport)),
+            inflight: None,
+            keepalive: None,
         }
     }
 
+    /// Caps the number of calls outstanding on the transport at once.
+    ///
+    /// Unlike `PeerConfig::max_pending`, which rejects excess calls, calls
+    /// beyond the cap wait for a slot. A slot is freed when its call gets a
+    /// reply or fails. Waiting calls fail with `Error::Shutdown` on shutdown.
+    pub fn with_max_inflight(mut self, max_inflight: usize) -> Self {
+        self.inflight = Some(Arc::new(Semaphore::new(max_inflight)));
+        self
+    }
+
+    /// Pings the remote every `interval` while no calls are in flight.
+    ///
+    /// A ping not answered with a Pong within another `interval` fails the
+    /// connection as if the transport had dropped: pending calls are
+    /// interrupted, the peer goes `Disconnected`, and any reconnect callback runs.
+    /// Pings are their own frame type, so they never use up a call seq.
+    ///
+    /// The keepalive task stops when the peer is shut down or dropped.
+    pub fn with_keepalive(mut self, interval: Duration) -> Self {
+        if let Some(old) = self.keepalive.take() {
+            old.abort();
+        }
+        self.keepalive = Some(tokio::spawn(Self::keepalive(Arc::downgrade(&self.inner), interval)));
+        self
+    }
+
+    /// Drops received frames longer than `max_bytes` before decoding them.
+    ///
+    /// Only the length is checked, so a hostile peer can't make us allocate
+    /// decoded values out of proportion to the bytes it sent. If the frame's
+    /// seq can still be read, the call it answers fails with
+    /// `Error::MessageTooLarge`; other calls are unaffected.
+    pub fn with_max_message_bytes(self, max_bytes: usize) -> Self {
+        self.inner.max_message_bytes.store(max_bytes, Ordering::SeqCst);
+        self
+    }
+
+    /// Makes an oversized frame also tear down the connection.
+    ///
+    /// Pending calls are interrupted as on any transport failure,
+    /// and any reconnect callback runs.
+    pub fn with_oversize_reset(self) -> Self {
+        self.inner.oversize_reset.store(true, Ordering::SeqCst);
+        self
+    }
+
+    /// Installs a callback the peer uses to redial after its transport fails.
+    ///
+    /// On failure, pending calls are failed with `Error::Interrupted`, the peer
+    /// goes `Disconnected`, and the callback is invoked once. If it yields a
+    /// transport the peer returns to `Connected`; if it errors the peer stays
+    /// `Disconnected` until `reconnect` is called by hand.
+    ///
+    /// The callback runs again on every later failure; any backoff between
+    /// attempts is up to it.
+    pub fn with_reconnect<F>(self, reconnect: F) -> Self
+    where
+        F: Fn() -> ReconnectFuture + Send + Sync + 'static,
+    {
+        *self.inner.reconnect.lock().unwrap() = Some(Arc::new(reconnect));
+        self
+    }
+
+    /// Serves Call frames the remote sends, replying with whatever `handler` returns.
+    ///
+    /// Each call is handled on its own task, so a slow call doesn't hold up
+    /// replies to our own calls. Without a handler, an inbound Call is a
+    /// protocol violation that tears down the connection.
+    ///
+    /// Notify frames are handed over too, and whatever the handler returns
+    /// for them is dropped. Without a handler they are dropped unserved.
+    pub fn with_call_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(Vec<u8>) -> CallFuture + Send + Sync + 'static,
+    {
+        self.set_call_handler(handler);
+        self
+    }
+
+    /// Replaces the call handler of a peer already in use, see `with_call_handler`.
+    pub fn set_call_handler<F>(&self, handler: F)
+    where
+        F: Fn(Vec<u8>) -> CallFuture + Send + Sync + 'static,
+    {
+        *self.inner.call_handler.lock().unwrap() = Some(Arc::new(handler));
+    }
+
+    /// Caps how fast this peer's inbound calls are served.
+    ///
+    /// Calls are admitted from a bucket of `burst` tokens refilled at `per_sec`.
+    /// A call arriving to an empty bucket is answered straight away with
+    /// `FailureReason::Overloaded`, saying when the next token is due,
+    /// and never reaches the call handler. Each peer has its own bucket.
+    pub fn with_rate_limit(self, limit: RateLimit) -> Self {
+        *self.inner.rate_limit.lock().unwrap() = Some(TokenBucket::new(limit));
+        self
+    }
+
     /// Returns the peer name for logging and diagnostics.
     pub fn peer_name(&self) -> &str {
         &self.inner.peer_name
@@ -271,6 +595,29 @@ impl Peer {
         PeerState::from_u8(self.inner.state.load(Ordering::SeqCst))
     }
 
+    /// Reports liveness, latency, and load, e.g. for a status panel.
+    pub fn health(&self) -> PeerHealth {
+        PeerHealth {
+            connected: self.state() == PeerState::Connected,
+            last_rtt: *self.inner.last_rtt.lock().unwrap(),
+            inflight: self.inner.pending.len(),
+            total_calls: self.inner.total_calls.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The call targets the remote advertised in its latest Handshake.
+    ///
+    /// Empty until the remote advertises any. A later Handshake replaces the list.
+    pub fn targets(&self) -> Vec<String> {
+        self.inner.targets.lock().unwrap().clone()
+    }
+
+    /// Sends a Handshake announcing `fingerprint` and the call targets this side serves.
+    pub async fn advertise(&self, fingerprint: u64, targets: Vec<String>) -> Result<()> {
+        let payload = HandshakeEncoder::new(fingerprint).with_targets(targets).into_bytes()?;
+        self.send_unanswered(&payload).await
+    }
+
     /// Replaces the transport, restarting the pump.
     ///
     /// This allows reconnecting to a peer via a different protocol or address
@@ -291,9 +638,9 @@ impl Peer {
             return Err(Error::AlreadyConnected);
         }
 
-        let transport: Arc<dyn Transport> = Arc::from(transport);
-        
-        // Cancel old pump if still somehow running
+        let connection = Connection::new(transport);
+
+        // Cancel old pump, which may be mid-way through an automatic reconnect
         {
             let mut handle_guard = self.pump_handle.lock().await;
             if let Some(handle) = handle_guard.take() {
@@ -305,10 +652,10 @@ impl Peer {
         self.inner.state.store(PeerState::Connected as u8, Ordering::SeqCst);
 
         // Spawn new pump
-        let new_handle = Self::spawn_pump(self.inner.clone(), transport.clone());
-        
+        let new_handle = Self::spawn_pump(self.inner.clone(), connection.clone());
+
         // Store new transport and handle
-        *self.transport.lock().await = Some(transport);
+        *self.inner.connection.lock().await = Some(connection);
         *self.pump_handle.lock().await = Some(new_handle);
 
         Ok(())
@@ -324,6 +671,11 @@ impl Peer {
     pub async fn shutdown(&self) {
         // Set state to shutdown
         self.inner.state.store(PeerState::Shutdown as u8, Ordering::SeqCst);
+
+        // Release calls queued for an inflight slot
+        if let Some(inflight) = &self.inflight {
+            inflight.close();
+        }
         
         // Signal pump to stop
         self.inner.shutdown_notify.notify_waiters();
@@ -338,7 +690,7 @@ impl Peer {
         }
         
         // Drop transport
-        *self.transport.lock().await = None;
+        *self.inner.connection.lock().await = None;
         
         // Notify all pending requests
         Self::notify_all_pending(&self.inner.pending, Error::Shutdown);
@@ -355,6 +707,60 @@ impl Peer {
         self.call_with_timeout(target, method, args, result_types, self.inner.config.call_timeout).await
     }
 
+    /// Makes an RPC call with Rust values, e.g. `call_typed::<(u32, u32), u32>`.
+    ///
+    /// The reply is decoded with `result_types`, as for `call`, which must
+    /// describe `R`. Unlike `Runtime::call_typed` there is no ledger to check
+    /// params against, so a mismatch there surfaces as the remote's failure.
+    pub async fn call_typed<P: IntoVals, R: FromVals>(
+        &self,
+        target: &str,
+        method: &str,
+        params: P,
+        result_types: Vec<Type>,
+    ) -> Result<R> {
+        let mismatch = |types: &[Type]| Error::NeoRpc(neorpc::Error::TypeMismatch {
+            expected: std::any::type_name::<R>().to_string(),
+            found: typed::describe(types),
+        });
+        if !R::matches(&result_types) {
+            return Err(mismatch(&result_types));
+        }
+        let described = result_types.clone();
+        let results = self.call(target, method, &params.into_vals(), result_types).await?;
+        R::from_vals(results).ok_or_else(|| mismatch(&described))
+    }
+
+    /// Sends a call that wants no reply, returning once the transport accepts it.
+    ///
+    /// No seq or pending slot is used, so nothing reports whether the remote
+    /// ran it, or even received it.
+    pub async fn notify(&self, target: &str, method: &str, args: &[Val]) -> Result<()> {
+        let args_bytes = neorpc::encode_vals_to_bytes(args)?;
+        let payload = NotifyEncoder::new(target, method, &args_bytes).into_bytes()?;
+        self.send_unanswered(&payload).await
+    }
+
+    /// Sends a frame no reply will correlate with, failing the connection if the send does.
+    async fn send_unanswered(&self, payload: &[u8]) -> Result<()> {
+        match self.state() {
+            PeerState::Shutdown => return Err(Error::Shutdown),
+            PeerState::Disconnected => return Err(Error::Disconnected),
+            PeerState::Connected => {}
+        }
+
+        let Some(connection) = self.inner.connection.lock().await.clone() else {
+            return Err(Error::Disconnected);
+        };
+        if let Err(e) = connection.transport.send(payload).await {
+            if !matches!(e, transport::Error::PayloadTooLarge) {
+                connection.failed.notify_one();
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
     /// Makes an RPC call with a custom timeout.
     pub async fn call_with_timeout(
         &self,
@@ -379,44 +785,14 @@ impl Peer {
             return Err(Error::TooManyPendingRequests { limit: max_pending });
         }
 
+        let _permit = self.acquire_inflight().await?;
         let (seq, rx) = self.prepare_call(result_types);
 
         // Encode the call
         let args_bytes = neorpc::encode_vals_to_bytes(args)?;
-        let mut enc = Encoder::new();
-        CallEncoder::new(seq, target, method, &args_bytes).encode(&mut enc)?;
-        let payload = enc.into_bytes()?;
-
-        // Get transport (might be None if disconnected between check and here)
-        let transport = {
-            let guard = self.transport.lock().await;
-            match &*guard {
-                Some(t) => t.clone(),
-                None => {
-                    self.inner.pending.remove(&seq);
-                    return Err(Error::Disconnected);
-                }
-            }
-        };
-
-        // Send the request
-        if let Err(e) = transport.send(&payload).await {
-            self.inner.pending.remove(&seq);
-            return Err(e.into());
-        }
+        let payload = CallEncoder::new(seq, target, method, &args_bytes, None).into_bytes()?;
 
-        // Await response with timeout
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => {
-                self.inner.pending.remove(&seq);
-                Err(Error::ChannelClosed)
-            }
-            Err(_) => {
-                self.inner.pending.remove(&seq);
-                Err(Error::Timeout)
-            }
-        }
+        self.dispatch(seq, &payload, rx, timeout).await
     }
 
     /// Prepares an RPC call by incrementing the sequence number and registering
@@ -431,8 +807,10 @@ impl Peer {
         let seq = self.inner.seq_gen.fetch_add(1, Ordering::Relaxed);
         let (tx, rx) = oneshot::channel();
 
+        self.inner.total_calls.fetch_add(1, Ordering::Relaxed);
         self.inner.pending.insert(seq, PendingResponse {
             result_types,
+            sent_at: Instant::now(),
             tx,
         });
 
@@ -460,11 +838,47 @@ impl Peer {
             return Err(Error::Disconnected);
         }
 
-        // Get transport
-        let transport = {
-            let guard = self.transport.lock().await;
+        let _permit = match self.acquire_inflight().await {
+            Ok(permit) => permit,
+            Err(e) => {
+                self.inner.pending.remove(&seq);
+                return Err(e);
+            }
+        };
+        self.dispatch(seq, &payload, rx, self.inner.config.call_timeout).await
+    }
+
+    // =========================================================================
+    // Private Helpers
+    // =========================================================================
+
+    /// Waits for an inflight slot, if the peer has a cap.
+    async fn acquire_inflight(&self) -> Result<Option<OwnedSemaphorePermit>> {
+        let Some(inflight) = &self.inflight else {
+            return Ok(None);
+        };
+        let permit = inflight.clone().acquire_owned().await.map_err(|_| Error::Shutdown)?;
+        Ok(Some(permit))
+    }
+
+    /// Sends a registered call and awaits its response.
+    async fn dispatch(
+        &self,
+        seq: u64,
+        payload: &[u8],
+        rx: oneshot::Receiver<Result<Vec<Val>>>,
+        timeout: Duration,
+    ) -> Result<Vec<Val>> {
+        if self.inner.schema_mismatch.load(Ordering::SeqCst) {
+            self.inner.pending.remove(&seq);
+            return Err(Error::Remote(neorpc::schema_mismatch()));
+        }
+
+        // Get transport (might be None if disconnected between check and here)
+        let connection = {
+            let guard = self.inner.connection.lock().await;
             match &*guard {
-                Some(t) => t.clone(),
+                Some(c) => c.clone(),
                 None => {
                     self.inner.pending.remove(&seq);
                     return Err(Error::Disconnected);
@@ -473,13 +887,16 @@ impl Peer {
         };
 
         // Send the request
-        if let Err(e) = transport.send(&payload).await {
+        if let Err(e) = connection.transport.send(payload).await {
             self.inner.pending.remove(&seq);
+            // An oversized payload says nothing about the connection itself
+            if !matches!(e, transport::Error::PayloadTooLarge) {
+                connection.failed.notify_one();
+            }
             return Err(e.into());
         }
 
-        // Await response with configured timeout
-        let timeout = self.inner.config.call_timeout;
+        // Await response with timeout
         match tokio::time::timeout(timeout, rx).await {
             Ok(Ok(result)) => result,
             Ok(Err(_)) => {
@@ -493,50 +910,152 @@ impl Peer {
         }
     }
 
-    // =========================================================================
-    // Private Helpers
-    // =========================================================================
+    /// Sends a ping on each idle tick and fails connections that stop answering.
+    async fn keepalive(inner: Weak<PeerInner>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let Some(inner) = inner.upgrade() else { return };
+            match PeerState::from_u8(inner.state.load(Ordering::SeqCst)) {
+                PeerState::Shutdown => return,
+                PeerState::Disconnected => continue,
+                PeerState::Connected => {}
+            }
+            // Replies to real calls already prove the connection is alive
+            if !inner.pending.is_empty() {
+                continue;
+            }
+            let Some(connection) = inner.connection.lock().await.clone() else { continue };
+
+            // Nonces never repeat, or an earlier keepalive's pongs would answer this one's pings
+            let nonce = inner.ping_nonce.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut pongs = inner.pong.subscribe();
+            let Ok(ping) = PingEncoder::new(nonce).into_bytes() else { continue };
+            if connection.transport.send(&ping).await.is_err() {
+                connection.failed.notify_one();
+                continue;
+            }
+
+            let answered = tokio::time::timeout(interval, pongs.wait_for(|n| *n >= nonce)).await;
+            if !matches!(answered, Ok(Ok(_))) {
+                connection.unanswered.notify_one();
+            }
+        }
+    }
 
     /// Spawns the pump task that reads from the transport.
-    fn spawn_pump(inner: Arc<PeerInner>, transport: Arc<dyn Transport>) -> JoinHandle<()> {
+    ///
+    /// When the connection fails, the task drains pending calls and, if a
+    /// reconnect callback is installed, dials a new transport and keeps pumping.
+    fn spawn_pump(inner: Arc<PeerInner>, connection: Connection) -> JoinHandle<()> {
         tokio::spawn(async move {
-            let error = loop {
-                tokio::select! {
-                    // Check for shutdown signal
-                    _ = inner.shutdown_notify.notified() => {
-                        break Error::Shutdown;
+            let mut connection = connection;
+            loop {
+                let error = Self::pump(&inner, &connection).await;
+
+                // Stop new calls from reaching the dead transport
+                {
+                    let mut guard = inner.connection.lock().await;
+                    if guard.as_ref().is_some_and(|c| c.is(&connection)) {
+                        *guard = None;
                     }
-                    // Read from transport
-                    result = transport.recv() => {
-                        match result {
-                            Ok(Some(msg)) => {
-                                if let Err(e) = Self::handle_message(&msg, &inner.pending) {
-                                    eprintln!("[{}] Error handling message in pump: {}", inner.peer_name, e);
-                                    break e;
+                }
+
+                // Update state to disconnected (unless already shutdown)
+                let current = inner.state.load(Ordering::SeqCst);
+                if current != PeerState::Shutdown as u8 {
+                    inner.state.store(PeerState::Disconnected as u8, Ordering::SeqCst);
+                }
+
+                // Notify all pending requests with the error
+                let shutdown = matches!(error, Error::Shutdown) || current == PeerState::Shutdown as u8;
+                Self::notify_all_pending(&inner.pending, error);
+                inner.chunks.clear();
+                if shutdown {
+                    return;
+                }
+
+                let Some(reconnect) = inner.reconnect.lock().unwrap().clone() else {
+                    return;
+                };
+                let transport = match reconnect().await {
+                    Ok(transport) => transport,
+                    Err(_e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(peer = %inner.peer_name, error = %_e, "reconnect failed");
+                        return;
+                    }
+                };
+
+                // Only resume if nobody shut down the peer while we were dialing
+                connection = Connection::new(transport);
+                let mut guard = inner.connection.lock().await;
+                if inner.state.load(Ordering::SeqCst) != PeerState::Disconnected as u8 {
+                    return;
+                }
+                *guard = Some(connection.clone());
+                inner.state.store(PeerState::Connected as u8, Ordering::SeqCst);
+            }
+        })
+    }
+
+    /// Routes replies from one connection until it fails, returning why.
+    async fn pump(inner: &PeerInner, connection: &Connection) -> Error {
+        loop {
+            tokio::select! {
+                // Check for shutdown signal
+                _ = inner.shutdown_notify.notified() => {
+                    return Error::Shutdown;
+                }
+                // A caller's send failed on this connection
+                _ = connection.failed.notified() => {
+                    return Error::Interrupted(transport::Error::ConnectionLost("Send failed".into()));
+                }
+                // The keepalive got no answer on this connection
+                _ = connection.unanswered.notified() => {
+                    return Error::Interrupted(transport::Error::Timeout);
+                }
+                // Read from transport
+                result = connection.transport.recv() => {
+                    match result {
+                        Ok(Some(msg)) => {
+                            let limit = inner.max_message_bytes.load(Ordering::Relaxed);
+                            if msg.len() > limit {
+                                Self::reject_oversized(&msg, limit, inner);
+                                if inner.oversize_reset.load(Ordering::Relaxed) {
+                                    let reason = format!("Received {} bytes, over the {} byte limit", msg.len(), limit);
+                                    return Error::Interrupted(transport::Error::ConnectionLost(reason));
                                 }
+                                continue;
                             }
-                            Ok(None) => {
-                                // Stream closed (EOF)
-                                break Error::Transport(transport::Error::ConnectionLost("Stream closed".into()));
-                            }
-                            Err(e) => {
-                                eprintln!("[{}] Transport error in pump: {}", inner.peer_name, e);
-                                break Error::Transport(e);
+                            let answer = match Self::handle_message(&msg, inner, connection) {
+                                Ok(answer) => answer,
+                                Err(e) => {
+                                    eprintln!("[{}] Error handling message in pump: {}", inner.peer_name, e);
+                                    return e;
+                                }
+                            };
+                            if let Some(answer) = answer
+                                && let Err(e) = connection.transport.send(&answer).await
+                            {
+                                return Error::Interrupted(e);
                             }
                         }
+                        Ok(None) => {
+                            // Stream closed (EOF)
+                            return Error::Interrupted(transport::Error::ConnectionLost("Stream closed".into()));
+                        }
+                        Err(e) => {
+                            eprintln!("[{}] Transport error in pump: {}", inner.peer_name, e);
+                            return Error::Interrupted(e);
+                        }
                     }
                 }
-            };
-
-            // Update state to disconnected (unless already shutdown)
-            let current = inner.state.load(Ordering::SeqCst);
-            if current != PeerState::Shutdown as u8 {
-                inner.state.store(PeerState::Disconnected as u8, Ordering::SeqCst);
             }
-
-            // Notify all pending requests with the error
-            Self::notify_all_pending(&inner.pending, error);
-        })
+        }
     }
 
     /// Notify all pending requests with the given error.
@@ -550,27 +1069,230 @@ impl Peer {
         }
     }
 
-    /// Handle an incoming message from the transport.
-    fn handle_message(msg: &[u8], pending: &DashMap<u64, PendingResponse>) -> Result<()> {
-        let mut dec = Decoder::new(msg);
-        let frame = RpcFrame::decode(&mut dec)?;
+    /// Fails the call an oversized frame answers, reading no more than its seq.
+    fn reject_oversized(msg: &[u8], limit: usize, inner: &PeerInner) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(peer = %inner.peer_name, size = msg.len(), limit, "dropping message over the size limit");
+        let Ok(seq) = decode_seq(msg) else {
+            return;
+        };
+        if let Some((_, pending_resp)) = inner.pending.remove(&seq) {
+            let _ = pending_resp.tx.send(Err(Error::MessageTooLarge { size: msg.len(), limit }));
+        }
+    }
+
+    /// Serves an inbound Call on its own task, unless the rate limit refuses it.
+    ///
+    /// Returns the refusal to send back; a served call's reply is sent by its task.
+    /// A call whose `deadline_ms` passes first is answered with
+    /// `FailureReason::DeadlineExceeded`, and the handler's late reply dropped.
+    fn admit_call(
+        seq: u64,
+        deadline_ms: Option<u64>,
+        msg: &[u8],
+        inner: &PeerInner,
+        connection: &Connection,
+    ) -> Result<Option<Vec<u8>>> {
+        if let Some(bucket) = inner.rate_limit.lock().unwrap().as_mut()
+            && let Err(retry_after_ms) = bucket.take()
+        {
+            let refusal = ReplyErrEncoder::new(seq, FailureReason::Overloaded { retry_after_ms });
+            return Ok(Some(refusal.into_bytes()?));
+        }
 
-        let RpcFrame::Reply(reply) = frame else {
+        let remaining = deadline_ms.map(until_deadline);
+        if remaining == Some(Duration::ZERO) {
+            return Ok(Some(ReplyErrEncoder::new(seq, FailureReason::DeadlineExceeded).into_bytes()?));
+        }
+
+        let Some(handler) = inner.call_handler.lock().unwrap().clone() else {
             return Err(Error::NeoRpc(neorpc::Error::ProtocolViolation(
-                "Pump received Call frame instead of Reply".into(),
+                "Pump received Call frame but has no call handler".into(),
             )));
         };
+        // The handler gets a task of its own, for a Cancel to abort
+        let handler = tokio::spawn(handler(msg.to_vec()));
+        inner.serving.insert(seq, handler.abort_handle());
+        let serving = Arc::clone(&inner.serving);
+        let connection = connection.clone();
+        tokio::spawn(async move {
+            let reply = match remaining {
+                None => handler.await.ok().flatten(),
+                // The handler runs on to completion rather than being dropped mid-call
+                Some(remaining) => match tokio::time::timeout(remaining, handler).await {
+                    Ok(joined) => joined.ok().flatten(),
+                    Err(_) => ReplyErrEncoder::new(seq, FailureReason::DeadlineExceeded).into_bytes().ok(),
+                },
+            };
+            serving.remove(&seq);
+            if let Some(reply) = reply
+                && connection.transport.send(&reply).await.is_err()
+            {
+                connection.failed.notify_one();
+            }
+        });
+        Ok(None)
+    }
 
-        let seq = reply.seq;
+    /// Serves each Call of a Batch as if it had arrived on its own.
+    ///
+    /// Replies go back as separate frames. An entry that fails to decode has
+    /// no seq to answer, so it is skipped; the rest are still served.
+    fn admit_batch(batch: BatchDecoder, inner: &PeerInner, connection: &Connection) -> Result<Option<Vec<u8>>> {
+        let mut refusals = Vec::new();
+        for call in batch.flatten() {
+            let mut args = call.args.clone();
+            let Some(args) = args.next_item_bytes().map_err(neorpc::Error::from)? else {
+                continue;
+            };
+            let mut encoder = CallEncoder::new(call.seq, call.target, call.method, args, call.deadline_ms);
+            encoder.trace_id = call.trace_id;
+            let frame = encoder.into_bytes()?;
+            if let Some(refusal) = Self::admit_call(call.seq, call.deadline_ms, &frame, inner, connection)? {
+                refusals.push(refusal);
+            }
+        }
+
+        if !refusals.is_empty() {
+            let connection = connection.clone();
+            tokio::spawn(async move {
+                for refusal in refusals {
+                    if connection.transport.send(&refusal).await.is_err() {
+                        connection.failed.notify_one();
+                        return;
+                    }
+                }
+            });
+        }
+        Ok(None)
+    }
 
+    /// Aborts the handler serving call `seq`, if it is still running.
+    ///
+    /// The caller has given up on the reply, so none is sent.
+    fn cancel_call(seq: u64, inner: &PeerInner) {
+        if let Some((_, handler)) = inner.serving.remove(&seq) {
+            handler.abort();
+        }
+    }
+
+    /// Serves an inbound Notify on its own task, dropping whatever the handler returns.
+    ///
+    /// A notification refused by the rate limit, or with no handler to take
+    /// it, is dropped; there is no reply to carry the refusal.
+    fn admit_notify(msg: &[u8], inner: &PeerInner) {
+        if let Some(bucket) = inner.rate_limit.lock().unwrap().as_mut()
+            && bucket.take().is_err()
+        {
+            return;
+        }
+        let Some(handler) = inner.call_handler.lock().unwrap().clone() else {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(peer = %inner.peer_name, "dropping Notify frame: no call handler");
+            return;
+        };
+        tokio::spawn(handler(msg.to_vec()));
+    }
+
+    /// Handle an incoming message from the transport.
+    ///
+    /// Returns a frame to send back: a Pong for a Ping, or a refused Call.
+    fn handle_message(msg: &[u8], inner: &PeerInner, connection: &Connection) -> Result<Option<Vec<u8>>> {
+        let frame = RpcFrame::decode(msg)?;
+
+        let reply = match frame {
+            RpcFrame::Reply(reply) => reply,
+            RpcFrame::ReplyChunk(chunk) => return Self::handle_chunk(&chunk, inner),
+            RpcFrame::Call(call) => return Self::admit_call(call.seq, call.deadline_ms, msg, inner, connection),
+            RpcFrame::Batch(batch) => return Self::admit_batch(batch, inner, connection),
+            RpcFrame::ReplyBatch(replies) => {
+                // An entry that fails to decode has no seq to fail, so it is skipped
+                for reply in replies.flatten() {
+                    Self::complete_call(reply.seq, reply.status, inner)?;
+                }
+                return Ok(None);
+            }
+            RpcFrame::Notify(_) => {
+                Self::admit_notify(msg, inner);
+                return Ok(None);
+            }
+            RpcFrame::Cancel(cancel) => {
+                Self::cancel_call(cancel.seq, inner);
+                return Ok(None);
+            }
+            RpcFrame::Ping(ping) => return Ok(Some(PongEncoder::new(ping.nonce).into_bytes()?)),
+            RpcFrame::Pong(pong) => {
+                inner.pong.send_modify(|latest| *latest = (*latest).max(pong.nonce));
+                return Ok(None);
+            }
+            RpcFrame::Handshake(handshake) => {
+                let verified = inner.config.schema_fingerprint.map_or(Ok(()), |local| handshake.verify(local));
+                inner.schema_mismatch.store(verified.is_err(), Ordering::SeqCst);
+                if let Err(reason) = verified {
+                    // Replies to calls already out would be decoded against the wrong schema
+                    Self::notify_all_pending(&inner.pending, Error::Remote(reason));
+                }
+                *inner.targets.lock().unwrap() = handshake.targets;
+                return Ok(None);
+            }
+        };
+
+        Self::complete_call(reply.seq, reply.status, inner)
+    }
+
+    /// Buffers a ReplyChunk, completing its call once every chunk has arrived.
+    ///
+    /// Chunks for a call nobody is waiting on are dropped. A chunk that breaks
+    /// reassembly, or goes over its limits, fails only its own call; the
+    /// reply as a whole is held to the peer's `max_message_bytes`.
+    fn handle_chunk(chunk: &ReplyChunkDecoder, inner: &PeerInner) -> Result<Option<Vec<u8>>> {
+        let seq = chunk.seq;
+        if !inner.pending.contains_key(&seq) {
+            inner.chunks.remove(&seq);
+            return Ok(None);
+        }
+        if !inner.chunks.contains_key(&seq) {
+            // Calls that timed out partway through their chunks leave buffers behind
+            inner.chunks.retain(|seq, _| inner.pending.contains_key(seq));
+        }
+
+        let max_bytes = inner.max_message_bytes.load(Ordering::Relaxed).min(neorpc::DEFAULT_MAX_BYTES);
+        let mut reassembler = inner.chunks
+            .entry(seq)
+            .or_insert_with(|| ChunkReassembler::with_limits(seq, neorpc::DEFAULT_MAX_CHUNKS, max_bytes));
+        let pushed = reassembler.push(chunk);
+        if pushed.is_ok() && !reassembler.is_complete() {
+            return Ok(None);
+        }
+        drop(reassembler);
+
+        let Some((_, reassembler)) = inner.chunks.remove(&seq) else { return Ok(None) };
+        match pushed.and_then(|()| reassembler.finish()) {
+            Ok(payload) => Self::complete_call(seq, Ok(Decoder::new(&payload)), inner),
+            Err(e) => {
+                if let Some((_, pending_resp)) = inner.pending.remove(&seq) {
+                    let _ = pending_resp.tx.send(Err(Error::NeoRpc(e)));
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Hands a reply's outcome to the call waiting on `seq`.
+    fn complete_call(
+        seq: u64,
+        status: std::result::Result<Decoder<'_>, FailureReason>,
+        inner: &PeerInner,
+    ) -> Result<Option<Vec<u8>>> {
         // Find and remove the pending request
-        let Some((_, pending_resp)) = pending.remove(&seq) else {
+        let Some((_, pending_resp)) = inner.pending.remove(&seq) else {
             // No pending request for this sequence - might be a duplicate or very late response
-            return Ok(());
+            return Ok(None);
         };
+        *inner.last_rtt.lock().unwrap() = Some(pending_resp.sent_at.elapsed());
 
         // Decode the result
-        let result = match reply.status {
+        let result = match status {
             Ok(val_decoder) => {
                 let vals = decode_vals(val_decoder, &pending_resp.result_types)
                     .map_err(Error::from)?;
@@ -591,6 +1313,14 @@ impl Peer {
         // Send result to waiting caller (ignore if receiver dropped)
         let _ = pending_resp.tx.send(result);
 
-        Ok(())
+        Ok(None)
+    }
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        if let Some(keepalive) = self.keepalive.take() {
+            keepalive.abort();
+        }
     }
 }
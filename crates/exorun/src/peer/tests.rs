@@ -1,7 +1,7 @@
 //! Comprehensive tests for peer lifecycle, reconnection, and configuration.
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use tokio::sync::{mpsc, Mutex, Notify};
@@ -9,7 +9,7 @@ use tokio::time::timeout;
 use wasmtime::component::{Type, Val};
 
 use crate::transport::{self, Transport};
-use super::{Peer, PeerConfig, PeerState, Error};
+use super::{Peer, PeerConfig, PeerHealth, PeerState, RateLimit, Error};
 
 // =============================================================================
 // Test Transports
@@ -31,11 +31,9 @@ impl EchoTransport {
 #[async_trait::async_trait]
 impl Transport for EchoTransport {
     async fn send(&self, payload: &[u8]) -> transport::Result<()> {
-        use neopack::{Decoder, Encoder};
         use neorpc::{RpcFrame, ReplyOkEncoder, encode_vals_to_bytes};
         
-        let mut dec = Decoder::new(payload);
-        let frame = RpcFrame::decode(&mut dec)
+        let frame = RpcFrame::decode(payload)
             .map_err(|e| transport::Error::Io(e.to_string()))?;
         
         let seq = match frame {
@@ -43,15 +41,13 @@ impl Transport for EchoTransport {
             _ => return Err(transport::Error::Io("Expected Call".into())),
         };
         
-        let mut enc = Encoder::new();
         let results = encode_vals_to_bytes(&[Val::String("echo".into())])
             .map_err(|e| transport::Error::Io(e.to_string()))?;
-        ReplyOkEncoder::new(seq, &results)
-            .encode(&mut enc)
+        let reply = ReplyOkEncoder::new(seq, &results)
+            .into_bytes()
             .map_err(|e| transport::Error::Io(e.to_string()))?;
         
-        *self.pending.lock().await = Some(enc.into_bytes()
-            .map_err(|e| transport::Error::Io(e.to_string()))?);
+        *self.pending.lock().await = Some(reply);
         Ok(())
     }
 
@@ -399,6 +395,7 @@ async fn test_max_pending_rejects_excess() {
     let config = PeerConfig {
         call_timeout: Duration::from_secs(30),
         max_pending: 2,
+        ..Default::default()
     };
     let peer = Arc::new(Peer::new("test", Box::new(hanging), config));
     
@@ -418,6 +415,94 @@ async fn test_max_pending_rejects_excess() {
     notify.notify_one();
 }
 
+/// Replies to every call after a delay, tracking how many are outstanding.
+struct SlowTransport {
+    replies_tx: mpsc::UnboundedSender<Vec<u8>>,
+    replies_rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+    outstanding: Arc<std::sync::atomic::AtomicUsize>,
+    max_outstanding: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Transport for SlowTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        use neorpc::{RpcFrame, ReplyOkEncoder, encode_vals_to_bytes};
+
+        let now = self.outstanding.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_outstanding.fetch_max(now, Ordering::SeqCst);
+
+        let RpcFrame::Call(call) = RpcFrame::decode(payload).unwrap() else {
+            return Err(transport::Error::Io("Expected Call".into()));
+        };
+        let results = encode_vals_to_bytes(&[]).unwrap();
+        let reply = ReplyOkEncoder::new(call.seq, &results).into_bytes().unwrap();
+
+        let replies_tx = self.replies_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let _ = replies_tx.send(reply);
+        });
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        let reply = self.replies_rx.lock().await.recv().await;
+        self.outstanding.fetch_sub(1, Ordering::SeqCst);
+        Ok(reply)
+    }
+}
+
+#[tokio::test]
+async fn test_max_inflight_queues_excess_calls() {
+    const MAX_INFLIGHT: usize = 3;
+
+    let (replies_tx, replies_rx) = mpsc::unbounded_channel();
+    let max_outstanding = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let transport = SlowTransport {
+        replies_tx,
+        replies_rx: Mutex::new(replies_rx),
+        outstanding: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        max_outstanding: max_outstanding.clone(),
+    };
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default())
+        .with_max_inflight(MAX_INFLIGHT);
+    let peer = Arc::new(peer);
+
+    let mut tasks = Vec::new();
+    for _ in 0..MAX_INFLIGHT + 5 {
+        let p = peer.clone();
+        tasks.push(tokio::spawn(async move { p.call("t", "m", &[], vec![]).await }));
+    }
+
+    // Every call completes: excess calls queue instead of failing.
+    for task in tasks {
+        task.await.unwrap().expect("queued call should succeed");
+    }
+    assert_eq!(max_outstanding.load(Ordering::SeqCst), MAX_INFLIGHT);
+    assert!(peer.inner.pending.is_empty());
+}
+
+#[tokio::test]
+async fn test_max_inflight_waiters_fail_on_shutdown() {
+    let (hanging, notify) = HangingTransport::new();
+    let peer = Arc::new(Peer::new("test", Box::new(hanging), PeerConfig::default()).with_max_inflight(1));
+
+    let mut tasks = Vec::new();
+    for _ in 0..3 {
+        let p = peer.clone();
+        tasks.push(tokio::spawn(async move { p.call("t", "m", &[], vec![]).await }));
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(peer.inner.pending.len(), 1);
+
+    peer.shutdown().await;
+    for task in tasks {
+        assert!(matches!(task.await.unwrap(), Err(Error::Shutdown)));
+    }
+
+    notify.notify_one();
+}
+
 // =============================================================================
 // Zombie Peer Bug Tests
 // =============================================================================
@@ -464,7 +549,6 @@ async fn test_pump_death_updates_state_to_disconnected() {
 
 #[tokio::test]
 async fn test_concurrent_calls_all_complete() {
-    use neopack::{Decoder, Encoder};
     use neorpc::{RpcFrame, ReplyOkEncoder, decode_vals, encode_vals_to_bytes};
     
     let (client_tx, mut server_rx) = mpsc::unbounded_channel();
@@ -509,16 +593,13 @@ async fn test_concurrent_calls_all_complete() {
     // Server: respond to all (in order for simplicity)
     for _ in 0..10 {
         let req = server_rx.recv().await.unwrap();
-        let mut dec = Decoder::new(&req);
-        let frame = RpcFrame::decode(&mut dec).unwrap();
+        let frame = RpcFrame::decode(&req).unwrap();
         if let RpcFrame::Call(call) = frame {
             let args = decode_vals(call.args, &[Type::U32]).unwrap();
             let input = match &args[0] { Val::U32(v) => *v, _ => 0 };
             
-            let mut enc = Encoder::new();
             let results = encode_vals_to_bytes(&[Val::U32(input * 2)]).unwrap();
-            ReplyOkEncoder::new(call.seq, &results).encode(&mut enc).unwrap();
-            server_tx.send(enc.into_bytes().unwrap()).unwrap();
+            server_tx.send(ReplyOkEncoder::new(call.seq, &results).into_bytes().unwrap()).unwrap();
         }
     }
     
@@ -572,6 +653,812 @@ async fn test_send_failure_removes_pending_and_returns_error() {
     assert!(matches!(result, Err(Error::Transport(_))));
 }
 
+// =============================================================================
+// Reconnect Policy Tests
+// =============================================================================
+
+/// Accepts `sends` messages without ever replying, then fails every send.
+struct FlakyTransport {
+    sends_left: std::sync::atomic::AtomicUsize,
+}
+
+impl FlakyTransport {
+    fn new(sends: usize) -> Self {
+        Self { sends_left: std::sync::atomic::AtomicUsize::new(sends) }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for FlakyTransport {
+    async fn send(&self, _payload: &[u8]) -> transport::Result<()> {
+        let took = self.sends_left.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+        took.map(|_| ()).map_err(|_| transport::Error::ConnectionLost("Link dropped".into()))
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        std::future::pending().await
+    }
+}
+
+/// Echo transport whose `recv` waits for a reply instead of reporting EOF.
+struct PatientEchoTransport {
+    echo: EchoTransport,
+    ready: Notify,
+}
+
+#[async_trait::async_trait]
+impl Transport for PatientEchoTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        self.echo.send(payload).await?;
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(msg) = self.echo.pending.lock().await.take() {
+                return Ok(Some(msg));
+            }
+            self.ready.notified().await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_send_failure_interrupts_pending_calls() {
+    let peer = Arc::new(Peer::new("test", Box::new(FlakyTransport::new(3)), PeerConfig::default()));
+
+    let mut tasks = Vec::new();
+    for _ in 0..3 {
+        let p = peer.clone();
+        tasks.push(tokio::spawn(async move { p.call("t", "m", &[], vec![]).await }));
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(peer.inner.pending.len(), 3);
+
+    // The fourth send fails, which must take the outstanding calls down with it.
+    let result = peer.call("t", "m", &[], vec![]).await;
+    assert!(matches!(result, Err(Error::Transport(transport::Error::ConnectionLost(_)))));
+
+    for task in tasks {
+        let result = timeout(Duration::from_millis(500), task)
+            .await
+            .expect("pending call should resolve promptly")
+            .unwrap();
+        let err = result.unwrap_err();
+        assert!(matches!(err, Error::Interrupted(_)), "Expected Interrupted, got {:?}", err);
+        assert!(err.is_retriable());
+    }
+
+    assert!(peer.inner.pending.is_empty());
+    assert_eq!(peer.state(), PeerState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_reconnect_callback_restores_peer() {
+    let dials = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let peer = Peer::new("test", Box::new(FlakyTransport::new(1)), PeerConfig::default())
+        .with_reconnect({
+            let dials = dials.clone();
+            move || {
+                dials.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async {
+                    let transport = PatientEchoTransport { echo: EchoTransport::new(), ready: Notify::new() };
+                    Ok(Box::new(transport) as Box<dyn Transport>)
+                })
+            }
+        });
+    let peer = Arc::new(peer);
+
+    let p = peer.clone();
+    let hung = tokio::spawn(async move { p.call("t", "m", &[], vec![]).await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert!(peer.call("t", "m", &[], vec![]).await.is_err());
+    let result = timeout(Duration::from_millis(500), hung).await.unwrap().unwrap();
+    assert!(matches!(result, Err(Error::Interrupted(_))));
+
+    for _ in 0..50 {
+        if peer.state() == PeerState::Connected { break; }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(peer.state(), PeerState::Connected);
+    assert_eq!(dials.load(Ordering::SeqCst), 1);
+
+    let result = peer.call("t", "m", &[], vec![Type::String]).await.expect("call after reconnect");
+    assert_eq!(result, vec![Val::String("echo".into())]);
+}
+
+#[tokio::test]
+async fn test_failed_reconnect_leaves_peer_disconnected() {
+    let peer = Peer::new("test", Box::new(EofTransport), PeerConfig::default())
+        .with_reconnect(|| Box::pin(async { Err(transport::Error::ConnectionLost("No route".into())) }));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(peer.state(), PeerState::Disconnected);
+
+    // Manual reconnection still works afterwards.
+    let transport = PatientEchoTransport { echo: EchoTransport::new(), ready: Notify::new() };
+    peer.reconnect(Box::new(transport)).await.expect("manual reconnect");
+    assert!(peer.call("t", "m", &[], vec![Type::String]).await.is_ok());
+}
+
+// =============================================================================
+// Health Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_health_after_one_call() {
+    let transport = PatientEchoTransport { echo: EchoTransport::new(), ready: Notify::new() };
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default());
+
+    let fresh = peer.health();
+    assert_eq!(fresh, PeerHealth { connected: true, last_rtt: None, inflight: 0, total_calls: 0 });
+
+    peer.call("t", "m", &[], vec![Type::String]).await.expect("ping");
+
+    let health = peer.health();
+    assert!(health.connected);
+    assert!(health.last_rtt.is_some());
+    assert_eq!(health.inflight, 0);
+    assert_eq!(health.total_calls, 1);
+}
+
+#[tokio::test]
+async fn test_health_counts_inflight_calls() {
+    let (transport, _notify) = HangingTransport::new();
+    let peer = Arc::new(Peer::new("test", Box::new(transport), PeerConfig::default()));
+
+    let p = peer.clone();
+    let task = tokio::spawn(async move { p.call("t", "m", &[], vec![]).await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let health = peer.health();
+    assert_eq!(health.inflight, 1);
+    assert_eq!(health.total_calls, 1);
+    assert_eq!(health.last_rtt, None);
+
+    peer.shutdown().await;
+    let _ = task.await;
+    assert_eq!(peer.health().inflight, 0);
+    assert!(!peer.health().connected);
+}
+
+#[tokio::test]
+async fn test_health_disconnected_after_transport_loss() {
+    let peer = Peer::new("test", Box::new(EofTransport), PeerConfig::default());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert!(!peer.health().connected);
+}
+
+// =============================================================================
+// Keepalive Tests
+// =============================================================================
+
+/// Answers every Ping with a Pong, counting the pings it sees.
+struct PongTransport {
+    pings: Arc<std::sync::atomic::AtomicUsize>,
+    /// Pings past this many go unanswered.
+    answer_limit: usize,
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl PongTransport {
+    fn new() -> (Self, Arc<std::sync::atomic::AtomicUsize>) {
+        Self::answering(usize::MAX)
+    }
+
+    fn answering(answer_limit: usize) -> (Self, Arc<std::sync::atomic::AtomicUsize>) {
+        let pings = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { pings: pings.clone(), answer_limit, tx, rx: Mutex::new(rx) }, pings)
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for PongTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        use neorpc::{PongEncoder, RpcFrame};
+
+        let Ok(RpcFrame::Ping(ping)) = RpcFrame::decode(payload) else {
+            return Err(transport::Error::Io("Expected Ping".into()));
+        };
+        if self.pings.fetch_add(1, Ordering::SeqCst) >= self.answer_limit {
+            return Ok(());
+        }
+        let pong = PongEncoder::new(ping.nonce).into_bytes()
+            .map_err(|e| transport::Error::Io(e.to_string()))?;
+        let _ = self.tx.send(pong);
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        Ok(self.rx.lock().await.recv().await)
+    }
+}
+
+#[tokio::test]
+async fn test_keepalive_keeps_answering_peer_connected() {
+    let (transport, pings) = PongTransport::new();
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default())
+        .with_keepalive(Duration::from_millis(20));
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    assert!(pings.load(Ordering::SeqCst) >= 3, "expected several pings");
+    assert_eq!(peer.state(), PeerState::Connected);
+    assert!(peer.health().connected);
+    assert_eq!(peer.health().total_calls, 0, "pings are not calls");
+}
+
+#[tokio::test]
+async fn test_keepalive_disconnects_silent_peer() {
+    let (transport, _notify) = HangingTransport::new();
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default())
+        .with_keepalive(Duration::from_millis(20));
+
+    for _ in 0..50 {
+        if peer.state() == PeerState::Disconnected { break; }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(peer.state(), PeerState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_restarted_keepalive_detects_silent_peer() {
+    let (transport, pings) = PongTransport::answering(20);
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default())
+        .with_keepalive(Duration::from_millis(10));
+    while pings.load(Ordering::SeqCst) < 20 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    // The new keepalive's pings must not count as answered by the old one's pongs
+    let peer = peer.with_keepalive(Duration::from_millis(10));
+    for _ in 0..10 {
+        if peer.state() == PeerState::Disconnected { break; }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(peer.state(), PeerState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_keepalive_stops_when_peer_dropped() {
+    let (transport, pings) = PongTransport::new();
+    let peer = Peer::new("test", Box::new(transport), PeerConfig::default())
+        .with_keepalive(Duration::from_millis(10));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    drop(peer);
+    let after_drop = pings.load(Ordering::SeqCst);
+    assert!(after_drop > 0);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(pings.load(Ordering::SeqCst), after_drop);
+}
+
+// =============================================================================
+// Message Size Tests
+// =============================================================================
+
+/// Replies to method `big` with a 4 KiB string and to anything else with "ok".
+struct SizedReplyTransport {
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+    rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl SizedReplyTransport {
+    fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self { tx, rx: Mutex::new(rx) }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for SizedReplyTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        use neorpc::{RpcFrame, ReplyOkEncoder, encode_vals_to_bytes};
+
+        let Ok(RpcFrame::Call(call)) = RpcFrame::decode(payload) else {
+            return Err(transport::Error::Io("Expected Call".into()));
+        };
+        let text = match call.method {
+            "big" => "x".repeat(4096),
+            _ => "ok".to_string(),
+        };
+        let results = encode_vals_to_bytes(&[Val::String(text)])
+            .map_err(|e| transport::Error::Io(e.to_string()))?;
+        let reply = ReplyOkEncoder::new(call.seq, &results).into_bytes()
+            .map_err(|e| transport::Error::Io(e.to_string()))?;
+        let _ = self.tx.send(reply);
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        Ok(self.rx.lock().await.recv().await)
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_reply_fails_only_its_call() {
+    let peer = Peer::new("test", Box::new(SizedReplyTransport::new()), PeerConfig::default())
+        .with_max_message_bytes(1024);
+
+    let result = peer.call("t", "big", &[], vec![Type::String]).await;
+    match result {
+        Err(Error::MessageTooLarge { size, limit }) => {
+            assert!(size > 4096);
+            assert_eq!(limit, 1024);
+        }
+        other => panic!("Expected MessageTooLarge, got {:?}", other),
+    }
+
+    // The connection survives and small replies still get through
+    assert_eq!(peer.state(), PeerState::Connected);
+    let results = peer.call("t", "small", &[], vec![Type::String]).await.expect("small reply");
+    assert_eq!(results, vec![Val::String("ok".into())]);
+}
+
+#[tokio::test]
+async fn test_oversized_reply_resets_connection_when_asked() {
+    let peer = Peer::new("test", Box::new(SizedReplyTransport::new()), PeerConfig::default())
+        .with_max_message_bytes(1024)
+        .with_oversize_reset();
+
+    let result = peer.call("t", "big", &[], vec![Type::String]).await;
+    assert!(matches!(result, Err(Error::MessageTooLarge { .. })), "got {:?}", result);
+
+    for _ in 0..50 {
+        if peer.state() == PeerState::Disconnected { break; }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(peer.state(), PeerState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_unlimited_by_default() {
+    let peer = Peer::new("test", Box::new(SizedReplyTransport::new()), PeerConfig::default());
+
+    let results = peer.call("t", "big", &[], vec![Type::String]).await.expect("big reply");
+    assert_eq!(results, vec![Val::String("x".repeat(4096))]);
+}
+
+/// Reads the Call the peer sent, returning its seq.
+async fn next_call_seq(remote: &mut InboundController) -> u64 {
+    let frame = timeout(Duration::from_secs(1), remote.outbound.recv())
+        .await
+        .expect("call in time")
+        .expect("call");
+    neorpc::decode_seq(&frame).expect("call seq")
+}
+
+#[tokio::test]
+async fn test_chunked_reply_is_reassembled() {
+    let (transport, mut remote) = inbound_transport();
+    let peer = Arc::new(Peer::new("test", Box::new(transport), PeerConfig::default()));
+
+    let call = tokio::spawn({
+        let peer = Arc::clone(&peer);
+        async move { peer.call("t", "big", &[], vec![Type::String]).await }
+    });
+    let seq = next_call_seq(&mut remote).await;
+
+    let results = neorpc::encode_vals_to_bytes(&[Val::String("x".repeat(100))]).unwrap();
+    let (a, rest) = results.split_at(40);
+    let (b, c) = rest.split_at(40);
+    for (index, last, payload) in [(2, true, c), (0, false, a), (1, false, b)] {
+        remote.inbound.send(neorpc::ReplyChunkEncoder::new(seq, index, last, payload).into_bytes().unwrap()).unwrap();
+    }
+
+    let results = call.await.unwrap().expect("chunked reply");
+    assert_eq!(results, vec![Val::String("x".repeat(100))]);
+    assert_eq!(peer.state(), PeerState::Connected);
+}
+
+#[tokio::test]
+async fn test_chunked_reply_over_limit_fails_only_its_call() {
+    let (transport, mut remote) = inbound_transport();
+    let peer = Arc::new(Peer::new("test", Box::new(transport), PeerConfig::default()).with_max_message_bytes(256));
+
+    let call = tokio::spawn({
+        let peer = Arc::clone(&peer);
+        async move { peer.call("t", "big", &[], vec![Type::String]).await }
+    });
+    let seq = next_call_seq(&mut remote).await;
+
+    // Each chunk fits the limit, but together they don't
+    for index in 0..3 {
+        remote.inbound.send(neorpc::ReplyChunkEncoder::new(seq, index, false, &[0; 100]).into_bytes().unwrap()).unwrap();
+    }
+    let result = call.await.unwrap();
+    assert!(matches!(result, Err(Error::NeoRpc(neorpc::Error::ProtocolViolation(_)))), "got {:?}", result);
+    assert_eq!(peer.state(), PeerState::Connected);
+}
+
+// =============================================================================
+// Rate Limit Tests
+// =============================================================================
+
+/// Plays a remote that calls us: frames pushed by the test arrive at the
+/// peer, and frames the peer sends come back to the test.
+struct InboundTransport {
+    inbound: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+    outbound: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+struct InboundController {
+    inbound: mpsc::UnboundedSender<Vec<u8>>,
+    outbound: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+fn inbound_transport() -> (InboundTransport, InboundController) {
+    let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+    let (outbound_tx, outbound_rx) = mpsc::unbounded_channel();
+    let transport = InboundTransport { inbound: Mutex::new(inbound_rx), outbound: outbound_tx };
+    (transport, InboundController { inbound: inbound_tx, outbound: outbound_rx })
+}
+
+#[async_trait::async_trait]
+impl Transport for InboundTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        let _ = self.outbound.send(payload.to_vec());
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        Ok(self.inbound.lock().await.recv().await)
+    }
+}
+
+impl InboundController {
+    /// Sends calls `first..first + count` and returns each reply's outcome, by seq.
+    async fn hammer(&mut self, first: u64, count: u64) -> Vec<(u64, Result<(), neorpc::FailureReason>)> {
+        let args = neorpc::encode_vals_to_bytes(&[]).unwrap();
+        for seq in first..first + count {
+            let call = neorpc::CallEncoder::new(seq, "svc", "m", &args, None).into_bytes().unwrap();
+            self.inbound.send(call).unwrap();
+        }
+
+        let mut outcomes = Vec::new();
+        for _ in 0..count {
+            let frame = timeout(Duration::from_secs(1), self.outbound.recv())
+                .await
+                .expect("reply in time")
+                .expect("reply");
+            let Ok(neorpc::RpcFrame::Reply(reply)) = neorpc::RpcFrame::decode(&frame) else {
+                panic!("Expected Reply");
+            };
+            outcomes.push((reply.seq, reply.status.map(|_| ())));
+        }
+        outcomes.sort_by_key(|(seq, _)| *seq);
+        outcomes
+    }
+}
+
+/// A peer answering every admitted call with an empty result.
+fn served_peer(transport: InboundTransport, limit: RateLimit) -> Peer {
+    Peer::new("caller", Box::new(transport), PeerConfig::default())
+        .with_call_handler(|frame| Box::pin(async move {
+            let seq = neorpc::decode_seq(&frame).ok()?;
+            let results = neorpc::encode_vals_to_bytes(&[]).ok()?;
+            neorpc::ReplyOkEncoder::new(seq, &results).into_bytes().ok()
+        }))
+        .with_rate_limit(limit)
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejects_calls_over_burst() {
+    let (transport, mut remote) = inbound_transport();
+    let _peer = served_peer(transport, RateLimit { per_sec: 1, burst: 3 });
+
+    let outcomes = remote.hammer(1, 10).await;
+    let served: Vec<_> = outcomes.iter().filter(|(_, outcome)| outcome.is_ok()).map(|(seq, _)| *seq).collect();
+    assert_eq!(served, vec![1, 2, 3]);
+
+    for (_, outcome) in &outcomes[3..] {
+        match outcome {
+            Err(neorpc::FailureReason::Overloaded { retry_after_ms }) => {
+                assert!(*retry_after_ms > 0 && *retry_after_ms <= 1000, "retry after {}", retry_after_ms);
+            }
+            other => panic!("Expected Overloaded, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limit_refills() {
+    let (transport, mut remote) = inbound_transport();
+    let _peer = served_peer(transport, RateLimit { per_sec: 50, burst: 2 });
+
+    let outcomes = remote.hammer(1, 3).await;
+    assert!(outcomes[0].1.is_ok() && outcomes[1].1.is_ok());
+    assert!(matches!(outcomes[2].1, Err(neorpc::FailureReason::Overloaded { .. })));
+
+    // 50 per second is a token every 20ms
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let outcomes = remote.hammer(4, 2).await;
+    assert!(outcomes.iter().all(|(_, outcome)| outcome.is_ok()), "got {:?}", outcomes);
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_per_peer() {
+    let (transport_a, mut remote_a) = inbound_transport();
+    let (transport_b, mut remote_b) = inbound_transport();
+    let _a = served_peer(transport_a, RateLimit { per_sec: 1, burst: 2 });
+    let _b = served_peer(transport_b, RateLimit { per_sec: 1, burst: 2 });
+
+    let outcomes = remote_a.hammer(1, 5).await;
+    assert_eq!(outcomes.iter().filter(|(_, outcome)| outcome.is_ok()).count(), 2);
+
+    // Exhausting one peer's bucket leaves the other's full
+    let outcomes = remote_b.hammer(1, 2).await;
+    assert!(outcomes.iter().all(|(_, outcome)| outcome.is_ok()), "got {:?}", outcomes);
+}
+
+#[tokio::test]
+async fn test_batch_calls_are_served_one_by_one() {
+    let (transport, mut remote) = inbound_transport();
+    let _peer = served_peer(transport, RateLimit { per_sec: 1, burst: 2 });
+
+    let args = neorpc::encode_vals_to_bytes(&[]).unwrap();
+    let mut batch = neorpc::BatchCallEncoder::new();
+    for seq in 1..=3 {
+        batch.push(neorpc::CallEncoder::new(seq, "svc", "m", &args, None));
+    }
+    remote.inbound.send(batch.into_bytes().unwrap()).unwrap();
+
+    // Each entry counts against the rate limit as a Call of its own would
+    let mut outcomes = Vec::new();
+    for _ in 0..3 {
+        let frame = timeout(Duration::from_secs(1), remote.outbound.recv()).await.expect("reply in time").expect("reply");
+        let Ok(neorpc::RpcFrame::Reply(reply)) = neorpc::RpcFrame::decode(&frame) else {
+            panic!("Expected Reply");
+        };
+        outcomes.push((reply.seq, reply.status.is_ok()));
+    }
+    outcomes.sort();
+    assert_eq!(outcomes, vec![(1, true), (2, true), (3, false)]);
+}
+
+#[tokio::test]
+async fn test_reply_batch_completes_each_call() {
+    let (transport, mut remote) = inbound_transport();
+    let peer = Arc::new(Peer::new("test", Box::new(transport), PeerConfig::default()));
+
+    let mut calls = Vec::new();
+    let mut seqs = Vec::new();
+    for method in ["a", "b"] {
+        let peer = Arc::clone(&peer);
+        calls.push(tokio::spawn(async move { peer.call("t", method, &[], vec![Type::U32]).await }));
+        seqs.push(next_call_seq(&mut remote).await);
+    }
+
+    let results = neorpc::encode_vals_to_bytes(&[Val::U32(7)]).unwrap();
+    let mut replies = neorpc::ReplyBatchEncoder::new();
+    replies.push_err(seqs[1], neorpc::FailureReason::DeadlineExceeded);
+    replies.push_ok(seqs[0], &results);
+    remote.inbound.send(replies.into_bytes().unwrap()).unwrap();
+
+    let second = calls.pop().unwrap().await.unwrap();
+    let first = calls.pop().unwrap().await.unwrap();
+    assert_eq!(first.expect("first call"), vec![Val::U32(7)]);
+    assert!(matches!(second, Err(Error::Remote(neorpc::FailureReason::DeadlineExceeded))), "got {:?}", second);
+    assert_eq!(peer.state(), PeerState::Connected);
+}
+
+// =============================================================================
+// Call Deadline Tests
+// =============================================================================
+
+fn unix_ms_from_now(offset: Duration) -> u64 {
+    (std::time::SystemTime::now() + offset)
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Sends one call with `deadline_ms` and returns the outcome of its reply.
+async fn call_with_deadline(remote: &mut InboundController, seq: u64, deadline_ms: u64) -> Result<(), neorpc::FailureReason> {
+    let args = neorpc::encode_vals_to_bytes(&[]).unwrap();
+    let call = neorpc::CallEncoder::new(seq, "svc", "m", &args, Some(deadline_ms)).into_bytes().unwrap();
+    remote.inbound.send(call).unwrap();
+
+    let frame = timeout(Duration::from_secs(1), remote.outbound.recv())
+        .await
+        .expect("reply in time")
+        .expect("reply");
+    let Ok(neorpc::RpcFrame::Reply(reply)) = neorpc::RpcFrame::decode(&frame) else {
+        panic!("Expected Reply");
+    };
+    assert_eq!(reply.seq, seq);
+    reply.status.map(|_| ())
+}
+
+/// A peer whose handler takes `delay` to answer each call.
+fn slow_peer(transport: InboundTransport, delay: Duration, served: Arc<AtomicUsize>) -> Peer {
+    Peer::new("caller", Box::new(transport), PeerConfig::default())
+        .with_call_handler(move |frame| {
+            let served = Arc::clone(&served);
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                served.fetch_add(1, Ordering::SeqCst);
+                let seq = neorpc::decode_seq(&frame).ok()?;
+                let results = neorpc::encode_vals_to_bytes(&[]).ok()?;
+                neorpc::ReplyOkEncoder::new(seq, &results).into_bytes().ok()
+            })
+        })
+}
+
+#[tokio::test]
+async fn test_call_past_deadline_replies_deadline_exceeded() {
+    let (transport, mut remote) = inbound_transport();
+    let served = Arc::new(AtomicUsize::new(0));
+    let _peer = slow_peer(transport, Duration::from_millis(200), Arc::clone(&served));
+
+    let outcome = call_with_deadline(&mut remote, 1, unix_ms_from_now(Duration::from_millis(50))).await;
+    assert_eq!(outcome, Err(neorpc::FailureReason::DeadlineExceeded));
+
+    // The handler still finishes, but its late reply is never sent
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+    assert!(remote.outbound.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_call_within_deadline_is_served() {
+    let (transport, mut remote) = inbound_transport();
+    let served = Arc::new(AtomicUsize::new(0));
+    let _peer = slow_peer(transport, Duration::from_millis(10), Arc::clone(&served));
+
+    let outcome = call_with_deadline(&mut remote, 1, unix_ms_from_now(Duration::from_secs(5))).await;
+    assert_eq!(outcome, Ok(()));
+
+    // A deadline already gone is refused without reaching the handler
+    let outcome = call_with_deadline(&mut remote, 2, unix_ms_from_now(Duration::ZERO) - 1000).await;
+    assert_eq!(outcome, Err(neorpc::FailureReason::DeadlineExceeded));
+    assert_eq!(served.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_cancel_aborts_served_call_and_keeps_connection() {
+    let (transport, mut remote) = inbound_transport();
+    let served = Arc::new(AtomicUsize::new(0));
+    let _peer = slow_peer(transport, Duration::from_millis(100), Arc::clone(&served));
+
+    let args = neorpc::encode_vals_to_bytes(&[]).unwrap();
+    remote.inbound.send(neorpc::CallEncoder::new(1, "svc", "m", &args, None).into_bytes().unwrap()).unwrap();
+    remote.inbound.send(neorpc::CancelEncoder::new(1).into_bytes().unwrap()).unwrap();
+    // A Cancel for a call that isn't running is ignored
+    remote.inbound.send(neorpc::CancelEncoder::new(99).into_bytes().unwrap()).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(served.load(Ordering::SeqCst), 0);
+    assert!(remote.outbound.try_recv().is_err());
+
+    let outcomes = remote.hammer(2, 1).await;
+    assert_eq!(outcomes, vec![(2, Ok(()))]);
+}
+
+// =============================================================================
+// Notification Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_notify_runs_handler_without_reply() {
+    let (transport, mut remote) = inbound_transport();
+    let (ran_tx, mut ran) = mpsc::unbounded_channel();
+    let _peer = Peer::new("caller", Box::new(transport), PeerConfig::default())
+        .with_call_handler(move |frame| {
+            let ran_tx = ran_tx.clone();
+            Box::pin(async move {
+                match neorpc::RpcFrame::decode(&frame).ok()? {
+                    neorpc::RpcFrame::Notify(notify) if notify.method == "trap" => panic!("handler trapped"),
+                    neorpc::RpcFrame::Notify(notify) => {
+                        let _ = ran_tx.send((notify.target.to_string(), notify.method.to_string()));
+                        // A reply to a notification goes nowhere
+                        let results = neorpc::encode_vals_to_bytes(&[]).ok()?;
+                        neorpc::ReplyOkEncoder::new(0, &results).into_bytes().ok()
+                    }
+                    neorpc::RpcFrame::Call(call) => {
+                        let results = neorpc::encode_vals_to_bytes(&[]).ok()?;
+                        neorpc::ReplyOkEncoder::new(call.seq, &results).into_bytes().ok()
+                    }
+                    _ => None,
+                }
+            })
+        });
+
+    let args = neorpc::encode_vals_to_bytes(&[]).unwrap();
+    for method in ["trap", "ship"] {
+        remote.inbound.send(neorpc::NotifyEncoder::new("log", method, &args).into_bytes().unwrap()).unwrap();
+    }
+    let ran = timeout(Duration::from_secs(1), ran.recv()).await.expect("handler ran").expect("notify");
+    assert_eq!(ran, ("log".to_string(), "ship".to_string()));
+
+    // The trapped handler left the pump serving, and only the call is answered
+    let outcomes = remote.hammer(1, 1).await;
+    assert_eq!(outcomes, vec![(1, Ok(()))]);
+    assert!(remote.outbound.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_notify_sends_frame_without_pending() {
+    let (transport, mut remote) = inbound_transport();
+    let peer = Peer::new("caller", Box::new(transport), PeerConfig::default());
+
+    peer.notify("metrics", "record", &[Val::U64(7)]).await.expect("notify");
+    let frame = remote.outbound.try_recv().expect("notify sent");
+    let Ok(neorpc::RpcFrame::Notify(notify)) = neorpc::RpcFrame::decode(&frame) else {
+        panic!("Expected Notify");
+    };
+    assert_eq!((notify.target, notify.method), ("metrics", "record"));
+    assert_eq!(neorpc::decode_vals(notify.args, &[Type::U64]).unwrap(), vec![Val::U64(7)]);
+    let health = peer.health();
+    assert_eq!((health.inflight, health.total_calls), (0, 0));
+
+    peer.shutdown().await;
+    assert!(matches!(peer.notify("metrics", "record", &[]).await, Err(Error::Shutdown)));
+}
+
+// =============================================================================
+// Schema Check Tests
+// =============================================================================
+
+/// Sends the peer a Handshake and waits until the pump has taken it in.
+async fn handshake(remote: &InboundController, peer: &Peer, fingerprint: u64, target: &str) {
+    let frame = neorpc::HandshakeEncoder::new(fingerprint).with_targets(vec![target.to_string()]).into_bytes().unwrap();
+    remote.inbound.send(frame).unwrap();
+    timeout(Duration::from_secs(1), async {
+        while peer.targets() != [target] {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }).await.expect("handshake taken in");
+}
+
+#[tokio::test]
+async fn test_mismatched_handshake_fails_calls() {
+    let (transport, mut remote) = inbound_transport();
+    let config = PeerConfig { schema_fingerprint: Some(7), ..Default::default() };
+    let peer = Peer::new("test", Box::new(transport), config);
+
+    handshake(&remote, &peer, 8, "old").await;
+    let err = peer.call("svc", "m", &[], vec![]).await.expect_err("schemas differ");
+    assert!(matches!(&err, Error::Remote(reason) if *reason == neorpc::schema_mismatch()), "got {:?}", err);
+    assert!(remote.outbound.try_recv().is_err());
+    assert_eq!(peer.health().inflight, 0);
+
+    // A later matching Handshake lets calls through again
+    handshake(&remote, &peer, 7, "new").await;
+    let call = peer.call("svc", "m", &[], vec![]);
+    let answer = async {
+        let frame = remote.outbound.recv().await.expect("call sent");
+        let seq = neorpc::decode_seq(&frame).unwrap();
+        let results = neorpc::encode_vals_to_bytes(&[]).unwrap();
+        remote.inbound.send(neorpc::ReplyOkEncoder::new(seq, &results).into_bytes().unwrap()).unwrap();
+    };
+    let (result, ()) = tokio::join!(call, answer);
+    assert_eq!(result.expect("schemas agree"), vec![]);
+}
+
+#[tokio::test]
+async fn test_mismatched_handshake_fails_pending_calls() {
+    let (transport, mut remote) = inbound_transport();
+    let config = PeerConfig { schema_fingerprint: Some(7), ..Default::default() };
+    let peer = Peer::new("test", Box::new(transport), config);
+
+    let call = peer.call("svc", "m", &[], vec![]);
+    let mismatch = async {
+        remote.outbound.recv().await.expect("call sent");
+        handshake(&remote, &peer, 8, "svc").await;
+    };
+    let (result, ()) = tokio::join!(call, mismatch);
+    let err = result.expect_err("schemas differ");
+    assert!(matches!(&err, Error::Remote(reason) if *reason == neorpc::schema_mismatch()), "got {:?}", err);
+    assert_eq!(peer.health().inflight, 0);
+}
+
 // =============================================================================
 // Successful Call Tests
 // =============================================================================
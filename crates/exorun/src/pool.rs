@@ -0,0 +1,171 @@
+//! # Pools of warm instances
+//!
+//! Instantiation takes long enough to matter on a request path. A pool keeps
+//! instances of one component built ahead of time, and
+//! `Runtime::with_pooled_instance` checks one out for the length of a closure.
+//!
+//! Checkouts wait on a fair semaphore, so callers are served in arrival order.
+//! An instance goes back to the pool only if the closure finished and the
+//! instance is still usable; one that trapped, or whose closure panicked or
+//! was dropped part way, is removed and a replacement built in the background.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+use crate::local::LinkProfile;
+use crate::local::builder;
+use crate::runtime::ComponentId;
+use crate::runtime::InstanceId;
+use crate::runtime::Runtime;
+
+/// Strong type for pool identifiers.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PoolId(pub u64);
+
+impl std::fmt::Display for PoolId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pool-{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    PoolNotFound(PoolId),
+    /// Building one of the pool's instances failed.
+    Instantiate(builder::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PoolNotFound(id) => write!(f, "pool not found: {}", id),
+            Self::Instantiate(e) => write!(f, "cannot build pooled instance: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<builder::Error> for Error {
+    fn from(e: builder::Error) -> Self {
+        Self::Instantiate(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Instances of one component, each linked the same way.
+pub(crate) struct Pool {
+    component_id: ComponentId,
+    profile: LinkProfile,
+    idle: std::sync::Mutex<VecDeque<InstanceId>>,
+    /// One permit per instance, held for as long as it is checked out or being replaced.
+    permits: Arc<Semaphore>,
+}
+
+impl Pool {
+    /// Builds a pool of `size` instances, at least one.
+    pub(crate) async fn new(
+        runtime: &Arc<Runtime>,
+        component_id: ComponentId,
+        size: usize,
+        profile: LinkProfile,
+    ) -> Result<Self> {
+        let size = size.max(1);
+        let pool = Self {
+            component_id,
+            profile,
+            idle: std::sync::Mutex::new(VecDeque::with_capacity(size)),
+            permits: Arc::new(Semaphore::new(size)),
+        };
+        for _ in 0..size {
+            match pool.build(runtime).await {
+                Ok(instance_id) => pool.idle.lock().unwrap().push_back(instance_id),
+                Err(e) => {
+                    pool.clear(runtime);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(pool)
+    }
+
+    async fn build(&self, runtime: &Arc<Runtime>) -> Result<InstanceId> {
+        Ok(runtime.instantiate(self.component_id).apply(&self.profile).build().await?)
+    }
+
+    /// Removes every idle instance from the runtime.
+    fn clear(&self, runtime: &Runtime) {
+        for instance_id in self.idle.lock().unwrap().drain(..) {
+            let _ = runtime.remove_instance(instance_id);
+        }
+    }
+
+    /// Waits for a free instance and checks it out.
+    ///
+    /// If a replacement failed to build earlier, one is built now instead.
+    pub(crate) async fn checkout(self: &Arc<Self>, runtime: &Arc<Runtime>) -> Result<Checkout> {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .expect("pool semaphore is never closed");
+        let idle = self.idle.lock().unwrap().pop_front();
+        let instance_id = match idle {
+            Some(instance_id) => instance_id,
+            None => self.build(runtime).await?,
+        };
+        Ok(Checkout {
+            runtime: Arc::clone(runtime),
+            pool: Arc::clone(self),
+            instance_id,
+            permit: Some(permit),
+        })
+    }
+}
+
+/// An instance out of its pool.
+///
+/// Dropped without `finish`, as when the closure using it panics or its
+/// future is cancelled, the instance is discarded and replaced.
+pub(crate) struct Checkout {
+    runtime: Arc<Runtime>,
+    pool: Arc<Pool>,
+    instance_id: InstanceId,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Checkout {
+    pub(crate) fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// Returns the instance to the pool, or replaces it if it can't be called again.
+    pub(crate) async fn finish(mut self) {
+        if self.runtime.is_usable(self.instance_id).await {
+            self.pool.idle.lock().unwrap().push_back(self.instance_id);
+            self.permit = None;
+        }
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else { return };
+        let _ = self.runtime.remove_instance(self.instance_id);
+
+        // The permit stays taken until the replacement is in, so the next
+        // waiter gets a warm instance rather than building its own
+        let Ok(handle) = tokio::runtime::Handle::try_current() else { return };
+        let runtime = Arc::clone(&self.runtime);
+        let pool = Arc::clone(&self.pool);
+        handle.spawn(async move {
+            if let Ok(instance_id) = pool.build(&runtime).await {
+                pool.idle.lock().unwrap().push_back(instance_id);
+            }
+            drop(permit);
+        });
+    }
+}
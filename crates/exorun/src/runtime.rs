@@ -7,25 +7,71 @@
 //!
 //! Uses DashMap for concurrent access without global locking, enabling high-throughput
 //! scenarios where multiple tasks register apps or spawn instances simultaneously.
+//!
+//! Instances can also be exposed to peers under a target name, and `serve_call`
+//! answers their Call frames.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
 
 use dashmap::DashMap;
+use neorpc::FailureReason;
+use neorpc::ReplyErrEncoder;
+use neorpc::ReplyOkEncoder;
+use neorpc::RpcFrame;
 use tokio::sync::Mutex;
+use tokio::sync::broadcast;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
 use wasmtime::Engine;
 use wasmtime::Store;
+use wasmtime::UpdateDeadline;
 use wasmtime::component::Component;
 use wasmtime::component::Instance;
+use wasmtime::component::Type;
 use wasmtime::component::Val;
 
+use crate::bootstrap;
+use crate::cap;
+use crate::cap::Capability;
+use crate::cancel::CancellationToken;
+use crate::intercept::CallInterceptor;
+use crate::bootstrap::BootstrapBundle;
+use crate::bootstrap::BundledComponent;
+use crate::bootstrap::ContentHash;
+use crate::ledger::FuncKind;
 use crate::ledger::Ledger;
 use crate::local::InstanceBuilder;
+use crate::local::LinkProfile;
+use crate::peer;
 use crate::peer::Peer;
+use crate::peer::PeerConfig;
 use crate::peer::PeerInstance;
+use crate::pool;
+use crate::pool::Pool;
+use crate::pool::PoolId;
 use crate::context::ExorunCtx;
 use crate::ledger;
+use crate::transport;
+use crate::transport::Transport;
+use crate::transport::TransportDescriptor;
+use crate::typed;
+use crate::typed::FromVals;
+use crate::typed::IntoVals;
 
 /// Strong type for component identifiers.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -73,6 +119,33 @@ impl std::fmt::Display for InstanceId {
     }
 }
 
+/// Lifecycle notifications published by the runtime.
+///
+/// Delivered through [`Runtime::subscribe_events`].
+#[derive(Clone, Debug)]
+pub enum RuntimeEvent {
+    /// An instance finished instantiating.
+    InstanceStarted(InstanceId, ComponentId),
+    /// A call into an instance returned, with its wall-clock duration.
+    InstanceCallCompleted(InstanceId, Duration),
+    /// A call into an instance trapped or ran out of fuel.
+    InstanceTrapped(InstanceId, String),
+    PeerAdded(PeerId),
+    PeerRemoved(PeerId),
+}
+
+/// Events buffered per subscriber before it starts lagging.
+const EVENT_CAPACITY: usize = 256;
+
+/// How often `shutdown` checks whether in-flight work has drained.
+const SHUTDOWN_POLL: Duration = Duration::from_millis(5);
+
+/// How often the engine's epoch advances.
+///
+/// A cancelled call is interrupted at the next tick, so this bounds how long it
+/// keeps running. Guest code also yields to other tasks on every tick.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
 #[derive(Debug)]
 pub enum Error {
     ComponentNotFound(ComponentId),
@@ -81,9 +154,42 @@ pub enum Error {
     InterfaceNotFound { interface: String },
     FunctionNotFound { interface: String, function: String },
     FunctionLookupFailed,
+    /// Typed call arguments or results disagree with the function's signature.
+    Signature { interface: String, function: String, details: String },
+    /// The instance exhausted the fuel granted by its `Budget`.
+    OutOfFuel,
+    /// Guest code trapped during a call; fuel exhaustion is `OutOfFuel` instead.
+    Trap(TrapKind),
+    /// The call's `CancellationToken` fired before it returned.
+    ///
+    /// A call interrupted mid-execution traps, so later calls into the same
+    /// instance fail with `InstancePoisoned`; start a new one to keep going.
+    Cancelled,
+    /// A `call_with_timeout` call didn't return in time.
+    ///
+    /// The call is abandoned mid-execution, so the instance is poisoned.
+    Timeout,
+    /// An earlier call into the instance trapped, failed in a host import, was
+    /// cancelled, timed out, or ran out of fuel.
+    InstancePoisoned(InstanceId),
+    /// The runtime was shut down, before or during the operation.
+    Shutdown,
     Engine(wasmtime::Error),
     Component(wasmtime::Error),
     Ledger(ledger::Error),
+    Bootstrap(bootstrap::Error),
+    /// A bundled component's bytes do not match the hash it was shipped with.
+    ContentMismatch(ComponentId),
+    /// The origin named in a bootstrap bundle could not be reached.
+    Dial(transport::Error),
+    /// An inbound frame could not be decoded, or a reply encoded.
+    Rpc(neorpc::Error),
+    /// A `CallInterceptor` refused the call before it ran.
+    Rejected(FailureReason),
+    /// A frame could not be sent to a peer.
+    Peer(peer::Error),
+    /// The module cache directory could not be created.
+    Cache(std::io::Error),
 }
 
 impl std::fmt::Display for Error {
@@ -95,9 +201,78 @@ impl std::fmt::Display for Error {
             Self::InterfaceNotFound { interface } => write!(f, "interface '{}' not found", interface),
             Self::FunctionNotFound { interface, function } => write!(f, "function '{}' not found in interface '{}'", function, interface),
             Self::FunctionLookupFailed => write!(f, "failed to get function from instance"),
+            Self::Signature { interface, function, details } => write!(f, "signature mismatch calling '{}' in '{}': {}", function, interface, details),
+            Self::OutOfFuel => write!(f, "instance ran out of fuel"),
+            Self::Trap(kind) => write!(f, "guest trapped: {}", kind),
+            Self::Cancelled => write!(f, "call cancelled"),
+            Self::Timeout => write!(f, "call timed out"),
+            Self::Shutdown => write!(f, "runtime is shut down"),
+            Self::InstancePoisoned(id) => write!(f, "instance {:?} trapped earlier and cannot be called again", id),
             Self::Engine(e) => write!(f, "engine error: {}", e),
             Self::Component(e) => write!(f, "component error: {}", e),
             Self::Ledger(e) => write!(f, "ledger error: {}", e),
+            Self::Bootstrap(e) => write!(f, "bootstrap error: {}", e),
+            Self::ContentMismatch(id) => write!(f, "bundled component {} does not match its hash", id),
+            Self::Dial(e) => write!(f, "cannot dial origin: {}", e),
+            Self::Rpc(e) => write!(f, "rpc error: {}", e),
+            Self::Rejected(reason) => write!(f, "call rejected: {:?}", reason),
+            Self::Peer(e) => write!(f, "peer error: {}", e),
+            Self::Cache(e) => write!(f, "module cache error: {}", e),
+        }
+    }
+}
+
+/// Why guest code trapped, from wasmtime's trap code.
+///
+/// Calls that fail without a trap code, such as a host function returning an
+/// error, are `Error::Component` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrapKind {
+    /// An `unreachable` instruction ran, usually a guest panic.
+    Unreachable,
+    /// A load or store fell outside linear memory.
+    MemoryOutOfBounds,
+    StackOverflow,
+    IntegerOverflow,
+    IntegerDivisionByZero,
+    /// A float could not be converted to an integer.
+    BadConversionToInteger,
+    /// A table access fell outside the table.
+    TableOutOfBounds,
+    /// An indirect call hit a null entry or one with the wrong signature.
+    BadIndirectCall,
+    /// Any other trap, by wasmtime's description of it.
+    Other(String),
+}
+
+impl From<wasmtime::Trap> for TrapKind {
+    fn from(trap: wasmtime::Trap) -> Self {
+        match trap {
+            wasmtime::Trap::UnreachableCodeReached => Self::Unreachable,
+            wasmtime::Trap::MemoryOutOfBounds => Self::MemoryOutOfBounds,
+            wasmtime::Trap::StackOverflow => Self::StackOverflow,
+            wasmtime::Trap::IntegerOverflow => Self::IntegerOverflow,
+            wasmtime::Trap::IntegerDivisionByZero => Self::IntegerDivisionByZero,
+            wasmtime::Trap::BadConversionToInteger => Self::BadConversionToInteger,
+            wasmtime::Trap::TableOutOfBounds => Self::TableOutOfBounds,
+            wasmtime::Trap::IndirectCallToNull | wasmtime::Trap::BadSignature => Self::BadIndirectCall,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for TrapKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "unreachable code reached"),
+            Self::MemoryOutOfBounds => write!(f, "out of bounds memory access"),
+            Self::StackOverflow => write!(f, "stack overflow"),
+            Self::IntegerOverflow => write!(f, "integer overflow"),
+            Self::IntegerDivisionByZero => write!(f, "integer divide by zero"),
+            Self::BadConversionToInteger => write!(f, "invalid conversion to integer"),
+            Self::TableOutOfBounds => write!(f, "out of bounds table access"),
+            Self::BadIndirectCall => write!(f, "bad indirect call"),
+            Self::Other(desc) => write!(f, "{}", desc),
         }
     }
 }
@@ -108,10 +283,37 @@ impl From<ledger::Error> for Error {
     }
 }
 
+impl From<bootstrap::Error> for Error {
+    fn from(e: bootstrap::Error) -> Self {
+        Self::Bootstrap(e)
+    }
+}
+
+impl From<peer::Error> for Error {
+    fn from(e: peer::Error) -> Self {
+        Self::Peer(e)
+    }
+}
+
+impl From<neorpc::Error> for Error {
+    fn from(e: neorpc::Error) -> Self {
+        Self::Rpc(e)
+    }
+}
+
 impl std::error::Error for Error {}
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// What `Runtime::import_bootstrap` registered.
+#[derive(Debug)]
+pub struct BootstrapImport {
+    /// Maps each component id on the origin to its id on this runtime.
+    pub components: HashMap<ComponentId, ComponentId>,
+    /// The peer connected to the origin, if the bundle named one.
+    pub origin: Option<PeerId>,
+}
+
 /// Internal state for a running instance.
 /// The Store is !Send, so we wrap it in Arc<Mutex> for async access.
 ///
@@ -119,8 +321,57 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// but most users should use `Runtime::instantiate()` instead.
 pub struct InstanceState {
     pub component_id: ComponentId,
+    /// The ledger of the component as it was instantiated, which
+    /// `Runtime::update_component` leaves alone.
+    pub ledger: Ledger,
     pub store: Store<ExorunCtx>,
     pub instance: Instance,
+    /// Calls made through `Runtime::call`, including ones that trapped.
+    pub call_count: u64,
+    /// Fuel spent by those calls, not counting instantiation.
+    pub fuel_consumed: u64,
+    /// Interfaces whose `result` errors are served as `FailureReason::DomainSpecific`.
+    pub error_mapping: HashSet<String>,
+    /// Set once a call traps or a host import fails. Wasmtime can't enter an
+    /// instance again after either, so later calls fail with `Error::InstancePoisoned`.
+    pub poisoned: bool,
+    /// Hooks run around every call, by interface.
+    pub interceptors: HashMap<String, Vec<Arc<dyn CallInterceptor>>>,
+}
+
+/// What became of in-flight work during `Runtime::shutdown`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Calls and peer RPCs that finished before the timeout.
+    pub completed: usize,
+    /// Calls and peer RPCs still running at the timeout, which were aborted.
+    pub aborted: usize,
+}
+
+/// Counts a call into an instance as in flight until dropped.
+struct InflightCall<'a>(&'a AtomicUsize);
+
+impl<'a> InflightCall<'a> {
+    fn enter(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self(count)
+    }
+}
+
+impl Drop for InflightCall<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Resource usage of one instance, as reported by `Runtime::instance_metrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstanceMetrics {
+    pub fuel_consumed: u64,
+    /// Linear memory currently held, summed across the instance's memories.
+    pub current_memory_bytes: usize,
+    pub peak_memory_bytes: usize,
+    pub call_count: u64,
 }
 
 /// The central runtime for managing Wasm components and their instances.
@@ -135,59 +386,335 @@ pub struct Runtime {
     pub(crate) peers: DashMap<PeerId, Arc<Peer>>,
     pub(crate) components: DashMap<ComponentId, Component>,
     pub(crate) ledgers: DashMap<ComponentId, Ledger>,
+    /// Times each component has been replaced by `update_component`.
+    versions: DashMap<ComponentId, u64>,
     pub(crate) instances: DashMap<InstanceId, Arc<Mutex<InstanceState>>>,
+    /// The component behind each instance, readable without locking its state.
+    instance_components: DashMap<InstanceId, ComponentId>,
+    /// Source bytes of components registered with `add_component_bytes`.
+    sources: DashMap<ComponentId, (ContentHash, Arc<[u8]>)>,
+    by_hash: DashMap<ContentHash, ComponentId>,
+    /// Directory of compiled components set by `enable_module_cache`.
+    module_cache: RwLock<Option<PathBuf>>,
+    /// Public key set by `trust_capability_issuer`.
+    capability_issuer: RwLock<Option<Vec<u8>>>,
+    /// Peers dialed by `import_bootstrap`, keyed by how they were reached.
+    origins: DashMap<TransportDescriptor, PeerId>,
+    /// Instance interfaces callable by peers, keyed by target name.
+    exposed: DashMap<String, (InstanceId, String)>,
+    pools: DashMap<PoolId, Arc<Pool>>,
+    /// Set once the epoch ticker thread is running.
+    ticker: OnceLock<()>,
+    /// Set by `shutdown`; refuses new instances and calls.
+    shut_down: AtomicBool,
+    /// Set when `shutdown` times out; interrupts guest code still running.
+    aborting: AtomicBool,
+    /// Calls into instances that haven't returned yet.
+    inflight_calls: AtomicUsize,
+    events: broadcast::Sender<RuntimeEvent>,
     next_peer_id: AtomicU64,
     next_component_id: AtomicU64,
     next_instance_id: AtomicU64,
+    next_pool_id: AtomicU64,
 }
 
 impl Runtime {
     /// Creates a new runtime with default engine configuration.
+    ///
+    /// Fuel metering is enabled so instances can be given a `Budget`,
+    /// and epoch interruption so calls can be cancelled.
     pub fn new() -> Result<Arc<Self>> {
         let mut config = wasmtime::Config::new();
         config.async_support(true);
         config.wasm_component_model(true);
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
 
         let engine = Engine::new(&config).map_err(Error::Engine)?;
 
-        Ok(Arc::new(Self {
+        let runtime = Arc::new(Self {
             engine,
             components: DashMap::new(),
             ledgers: DashMap::new(),
+            versions: DashMap::new(),
             peers: DashMap::new(),
             instances: DashMap::new(),
+            instance_components: DashMap::new(),
+            sources: DashMap::new(),
+            by_hash: DashMap::new(),
+            module_cache: RwLock::new(None),
+            capability_issuer: RwLock::new(None),
+            origins: DashMap::new(),
+            exposed: DashMap::new(),
+            pools: DashMap::new(),
+            ticker: OnceLock::new(),
+            shut_down: AtomicBool::new(false),
+            aborting: AtomicBool::new(false),
+            inflight_calls: AtomicUsize::new(0),
+            events: broadcast::channel(EVENT_CAPACITY).0,
             next_component_id: AtomicU64::new(1),
             next_peer_id: AtomicU64::new(1),
             next_instance_id: AtomicU64::new(1),
-        }))
+            next_pool_id: AtomicU64::new(1),
+        });
+        runtime.start_ticker();
+        Ok(runtime)
     }
 
     /// Creates a new runtime with a custom engine configuration.
+    ///
+    /// `call_with_cancel` and `shutdown` only interrupt running guest code if
+    /// the engine was configured with `epoch_interruption`. The epoch ticker
+    /// starts with the first of them, rather than straight away.
     pub fn with_engine(engine: Engine) -> Arc<Self> {
         Arc::new(Self {
             engine,
             components: DashMap::new(),
             ledgers: DashMap::new(),
+            versions: DashMap::new(),
             peers: DashMap::new(),
             instances: DashMap::new(),
+            instance_components: DashMap::new(),
+            sources: DashMap::new(),
+            by_hash: DashMap::new(),
+            module_cache: RwLock::new(None),
+            capability_issuer: RwLock::new(None),
+            origins: DashMap::new(),
+            exposed: DashMap::new(),
+            pools: DashMap::new(),
+            ticker: OnceLock::new(),
+            shut_down: AtomicBool::new(false),
+            aborting: AtomicBool::new(false),
+            inflight_calls: AtomicUsize::new(0),
+            events: broadcast::channel(EVENT_CAPACITY).0,
             next_component_id: AtomicU64::new(1),
             next_peer_id: AtomicU64::new(1),
             next_instance_id: AtomicU64::new(1),
+            next_pool_id: AtomicU64::new(1),
         })
     }
 
     /// Returns a reference to the wasmtime Engine.
+    ///
+    /// The default engine meters fuel and checks epochs, so a `Store` built
+    /// directly on it starts with no fuel and traps as soon as wasm runs.
+    /// Use `new_store` instead.
     pub fn engine(&self) -> &Engine {
         &self.engine
     }
 
+    /// Creates a store on this runtime's engine, ready to run wasm.
+    ///
+    /// The store has unlimited fuel and the memory limits in `ctx`. Guest code
+    /// yields at every epoch tick, and is interrupted there if its call's
+    /// `CancellationToken` has fired or `shutdown` is aborting.
+    pub fn new_store(&self, ctx: ExorunCtx) -> Store<ExorunCtx> {
+        let mut store = Store::new(&self.engine, ctx);
+        store.limiter(|ctx| &mut ctx.limits);
+
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(|ctx| match &ctx.data().cancel {
+            Some(token) if token.is_cancelled() => Err(wasmtime::Trap::Interrupt.into()),
+            _ if ctx.data().runtime.is_aborting() => Err(wasmtime::Trap::Interrupt.into()),
+            _ => Ok(UpdateDeadline::Yield(1)),
+        });
+
+        // Engines without fuel metering reject this, and don't need it
+        let _ = store.set_fuel(u64::MAX);
+        store
+    }
+
+    /// Stops the runtime, giving in-flight work up to `timeout` to finish.
+    ///
+    /// New instantiations and calls fail with `Error::Shutdown` from the start.
+    /// Calls into instances and calls awaiting a peer reply are then waited on,
+    /// and each counts once in the report. Whatever is still running at the
+    /// timeout is aborted: guest code is interrupted at the next epoch tick,
+    /// and pending peer calls fail as every peer is shut down.
+    ///
+    /// Shutting down again does nothing and returns an empty report.
+    pub async fn shutdown(self: Arc<Self>, timeout: Duration) -> ShutdownReport {
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return ShutdownReport::default();
+        }
+
+        let in_flight = || {
+            let peer_calls: usize = self.peers.iter().map(|entry| entry.value().health().inflight).sum();
+            self.inflight_calls.load(Ordering::SeqCst) + peer_calls
+        };
+        let started = in_flight();
+        let deadline = Instant::now() + timeout;
+        while in_flight() > 0 {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            tokio::time::sleep(left.min(SHUTDOWN_POLL)).await;
+        }
+
+        let aborted = in_flight();
+        if aborted > 0 {
+            self.aborting.store(true, Ordering::SeqCst);
+            self.start_ticker();
+        }
+        let peers: Vec<_> = self.peers.iter().map(|entry| Arc::clone(entry.value())).collect();
+        for peer in peers {
+            peer.shutdown().await;
+        }
+
+        ShutdownReport { completed: started.saturating_sub(aborted), aborted }
+    }
+
+    /// Whether `shutdown` has been called.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    /// Whether `shutdown` timed out and is interrupting running guest code.
+    pub(crate) fn is_aborting(&self) -> bool {
+        self.aborting.load(Ordering::SeqCst)
+    }
+
+    /// Starts the thread advancing the engine's epoch every `EPOCH_TICK`, if not already running.
+    ///
+    /// The thread stops once the engine is dropped.
+    fn start_ticker(&self) {
+        self.ticker.get_or_init(|| {
+            let engine = self.engine.weak();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                let Some(engine) = engine.upgrade() else { break };
+                engine.increment_epoch();
+            });
+        });
+    }
+
+    /// Subscribes to lifecycle events from this point on.
+    ///
+    /// Publishing never waits on subscribers: a receiver that falls more than
+    /// `EVENT_CAPACITY` events behind gets `RecvError::Lagged` and skips ahead.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publishes an event, ignoring the absence of subscribers.
+    pub(crate) fn emit(&self, event: RuntimeEvent) {
+        let _ = self.events.send(event);
+    }
+
     /// Registers a compiled component and returns its unique ID.
     ///
     /// The component bytes are compiled if not already a Component.
     /// Also creates and stores a ledger for the component.
+    /// The bytes are kept so the component can be shipped in a `BootstrapBundle`.
     pub fn add_component_bytes(&self, bytes: &[u8]) -> Result<ComponentId> {
+        self.add_source(bytes, bootstrap::content_hash(bytes))
+    }
+
+    /// Registers component bytes unless identical bytes already are.
+    ///
+    /// Returns the existing id and `false` when the content hash is known,
+    /// skipping compilation; otherwise registers as `add_component_bytes`
+    /// would and returns the new id and `true`. Concurrent calls with the
+    /// same new bytes may each compile, but later calls resolve to one id.
+    pub fn add_component_dedup(&self, bytes: &[u8]) -> Result<(ComponentId, bool)> {
+        let hash = bootstrap::content_hash(bytes);
+        if let Some(id) = self.by_hash.get(&hash).map(|entry| *entry.value()) {
+            return Ok((id, false));
+        }
+        Ok((self.add_source(bytes, hash)?, true))
+    }
+
+    /// Keeps compiled components in `dir`, so a restarted runtime loads them instead of compiling.
+    ///
+    /// `add_component_bytes`, `add_component_dedup`, and `update_component`
+    /// then look for an artifact keyed by the bytes' content hash and the
+    /// engine's compatibility hash. The latter covers the wasmtime version and
+    /// every compilation setting, so a changed engine misses the cache rather
+    /// than loading something it can't run. Artifacts that fail to load are
+    /// recompiled and overwritten, and failing to write one is not an error.
+    ///
+    /// Loading an artifact maps its machine code without checking it, so `dir`
+    /// must only be writable by whoever runs the runtime.
+    pub fn enable_module_cache(&self, dir: PathBuf) -> Result<()> {
+        std::fs::create_dir_all(&dir).map_err(Error::Cache)?;
+        *self.module_cache.write().unwrap() = Some(dir);
+        Ok(())
+    }
+
+    /// Accepts capabilities signed by the secret key matching `public`, a 32-byte ed25519 key.
+    ///
+    /// From then on, gated operations such as `Meta`'s spawning require the
+    /// instance to hold a live capability for their scope, given with
+    /// `InstanceBuilder::with_capability`. Replaces any earlier issuer.
+    pub fn trust_capability_issuer(&self, public: &[u8]) {
+        *self.capability_issuer.write().unwrap() = Some(public.to_vec());
+    }
+
+    /// Checks `cap` was signed by the trusted issuer and hasn't expired.
+    pub fn verify_capability(&self, cap: &Capability) -> cap::Result<()> {
+        let issuer = self.capability_issuer.read().unwrap();
+        cap.verify(issuer.as_deref().ok_or(cap::Error::NoIssuer)?)
+    }
+
+    /// Checks that `held` grants `scope` at `now`, if this runtime gates operations on capabilities.
+    pub(crate) fn authorize(&self, held: Option<&Capability>, scope: &str, now: u64) -> cap::Result<()> {
+        if self.capability_issuer.read().unwrap().is_none() {
+            return Ok(());
+        }
+        let cap = held.ok_or_else(|| cap::Error::Missing { scope: scope.to_string() })?;
+        if cap.scope != scope {
+            return Err(cap::Error::WrongScope { expected: scope.to_string(), actual: cap.scope.clone() });
+        }
+        let issuer = self.capability_issuer.read().unwrap();
+        cap.verify_at(issuer.as_deref().ok_or(cap::Error::NoIssuer)?, now)
+    }
+
+    /// Compiles component bytes, through the module cache if one is enabled.
+    fn compile(&self, bytes: &[u8], hash: ContentHash) -> Result<Component> {
+        let Some(path) = self.cache_path(hash) else {
+            return Component::new(&self.engine, bytes).map_err(Error::Component);
+        };
+        // SAFETY: `enable_module_cache` requires the directory to be trusted,
+        // and wasmtime refuses artifacts from an incompatible engine.
+        if let Ok(component) = unsafe { Component::deserialize_file(&self.engine, &path) } {
+            return Ok(component);
+        }
         let component = Component::new(&self.engine, bytes).map_err(Error::Component)?;
-        self.add_component(component)
+        if let Ok(artifact) = component.serialize() {
+            let _ = write_replacing(&path, &artifact);
+        }
+        Ok(component)
+    }
+
+    fn cache_path(&self, hash: ContentHash) -> Option<PathBuf> {
+        let dir = self.module_cache.read().unwrap().clone()?;
+        let mut engine = std::hash::DefaultHasher::new();
+        self.engine.precompile_compatibility_hash().hash(&mut engine);
+        Some(dir.join(format!("{}-{:016x}.cwasm", hash.to_hex(), engine.finish())))
+    }
+
+    fn add_source(&self, bytes: &[u8], hash: ContentHash) -> Result<ComponentId> {
+        let component = self.compile(bytes, hash)?;
+        let id = self.add_component(component)?;
+        self.sources.insert(id, (hash, Arc::from(bytes)));
+        self.by_hash.entry(hash).or_insert(id);
+        Ok(id)
+    }
+
+    /// Drops `id` from the hash index, handing the hash to another
+    /// component with the same bytes if there is one.
+    fn unindex_hash(&self, hash: ContentHash, id: ComponentId) {
+        if self.by_hash.remove_if(&hash, |_, owner| *owner == id).is_none() {
+            return;
+        }
+        let heir = self.sources.iter()
+            .filter(|entry| entry.value().0 == hash && *entry.key() != id)
+            .map(|entry| *entry.key())
+            .min_by_key(|other| other.0);
+        if let Some(heir) = heir {
+            self.by_hash.entry(hash).or_insert(heir);
+        }
     }
 
     /// Registers a pre-compiled component and returns its unique ID.
@@ -198,9 +725,47 @@ impl Runtime {
         let id = ComponentId(self.next_component_id.fetch_add(1, Ordering::Relaxed));
         self.components.insert(id, component);
         self.ledgers.insert(id, ledger);
+        self.versions.insert(id, 0);
         Ok(id)
     }
 
+    /// Replaces a component's bytes, keeping its id.
+    ///
+    /// New instantiations use the new version. Instances already running
+    /// keep the component, and ledger, they were built from.
+    /// On error, the old version stays in place.
+    pub fn update_component(&self, id: ComponentId, bytes: &[u8]) -> Result<()> {
+        if !self.components.contains_key(&id) {
+            return Err(Error::ComponentNotFound(id));
+        }
+        let hash = bootstrap::content_hash(bytes);
+        let component = self.compile(bytes, hash)?;
+        let ledger = Ledger::from_component(&component)?;
+
+        {
+            let mut slot = self.components.get_mut(&id).ok_or(Error::ComponentNotFound(id))?;
+            *slot = component;
+        }
+        self.ledgers.insert(id, ledger);
+        *self.versions.entry(id).or_insert(0) += 1;
+
+        if let Some((old_hash, _)) = self.sources.insert(id, (hash, Arc::from(bytes))) {
+            self.unindex_hash(old_hash, id);
+        }
+        self.by_hash.entry(hash).or_insert(id);
+        Ok(())
+    }
+
+    /// How many times a component has been replaced by `update_component`.
+    ///
+    /// Starts at 0 when the component is registered.
+    pub fn component_version(&self, id: ComponentId) -> Result<u64> {
+        self.versions
+            .get(&id)
+            .map(|entry| *entry.value())
+            .ok_or(Error::ComponentNotFound(id))
+    }
+
     /// Retrieves a component by ID.
     pub fn get_component(&self, id: ComponentId) -> Result<Component> {
         self.components
@@ -209,7 +774,110 @@ impl Runtime {
             .ok_or(Error::ComponentNotFound(id))
     }
 
+    /// Lists every registered component, sorted by id.
+    pub fn list_components(&self) -> Vec<ComponentId> {
+        let mut components: Vec<_> = self.components.iter().map(|entry| *entry.key()).collect();
+        components.sort_by_key(|id| id.0);
+        components
+    }
+
+    /// Unregisters a component and its ledger.
+    ///
+    /// Instances already created from the component keep running;
+    /// only new instantiations are affected.
+    pub fn remove_component(&self, id: ComponentId) -> Result<()> {
+        self.components.remove(&id).ok_or(Error::ComponentNotFound(id))?;
+        self.ledgers.remove(&id);
+        self.versions.remove(&id);
+        if let Some((_, (hash, _))) = self.sources.remove(&id) {
+            self.unindex_hash(hash, id);
+        }
+        Ok(())
+    }
+
+    /// Snapshots every component registered from bytes into a bundle.
+    ///
+    /// Components added pre-compiled with `add_component` have no source
+    /// and are left out. Set the origin with `BootstrapBundle::with_origin`.
+    pub fn export_bootstrap(&self) -> Result<BootstrapBundle> {
+        let mut components: Vec<BundledComponent> = self.sources
+            .iter()
+            .map(|entry| {
+                let (hash, bytes) = entry.value();
+                BundledComponent { id: *entry.key(), hash: *hash, bytes: bytes.to_vec() }
+            })
+            .collect();
+        components.sort_by_key(|c| c.id.0);
+        Ok(BootstrapBundle { origin: None, components })
+    }
+
+    /// Registers a bundle's components and dials back to its origin.
+    ///
+    /// Components are matched by content hash, so a component this runtime
+    /// already has is reused rather than registered again, and importing the
+    /// same bundle twice is a no-op. Likewise the origin is dialed through
+    /// `transport_factory` only if no peer from an earlier import is still
+    /// registered for that descriptor. Pass `transport::dial` to use the
+    /// built-in transports.
+    pub async fn import_bootstrap<F, Fut>(
+        &self,
+        bundle: BootstrapBundle,
+        transport_factory: F,
+    ) -> Result<BootstrapImport>
+    where
+        F: FnOnce(TransportDescriptor) -> Fut,
+        Fut: Future<Output = transport::Result<Box<dyn Transport>>>,
+    {
+        // Check every hash before registering anything
+        for component in &bundle.components {
+            if bootstrap::content_hash(&component.bytes) != component.hash {
+                return Err(Error::ContentMismatch(component.id));
+            }
+        }
+
+        let mut components = HashMap::new();
+        for component in &bundle.components {
+            let existing = self.by_hash.get(&component.hash).map(|entry| *entry.value());
+            let local = match existing {
+                Some(id) => id,
+                None => self.add_component_bytes(&component.bytes)?,
+            };
+            components.insert(component.id, local);
+        }
+
+        let origin = match bundle.origin {
+            Some(desc) => Some(self.dial_origin(desc, transport_factory).await?),
+            None => None,
+        };
+
+        Ok(BootstrapImport { components, origin })
+    }
+
+    async fn dial_origin<F, Fut>(&self, desc: TransportDescriptor, transport_factory: F) -> Result<PeerId>
+    where
+        F: FnOnce(TransportDescriptor) -> Fut,
+        Fut: Future<Output = transport::Result<Box<dyn Transport>>>,
+    {
+        if let Some(peer_id) = self.origins.get(&desc).map(|entry| *entry.value())
+            && self.peers.contains_key(&peer_id)
+        {
+            return Ok(peer_id);
+        }
+
+        let transport = transport_factory(desc.clone()).await.map_err(Error::Dial)?;
+        let peer = Peer::new(desc.to_string(), transport, PeerConfig::default());
+        let peer_id = self.add_peer(Arc::new(peer));
+        self.origins.insert(desc, peer_id);
+        Ok(peer_id)
+    }
+
     /// Retrieves a ledger by component ID.
+    ///
+    /// The ledger is built once when the component is registered, or
+    /// replaced by `update_component`, and cloned from there on each call.
+    /// A component with no wire-safe interfaces has an empty ledger.
+    /// Tooling reads it here to introspect the methods and types a component
+    /// imports and exports.
     pub fn get_ledger(&self, id: ComponentId) -> Result<Ledger> {
         self.ledgers
             .get(&id)
@@ -217,14 +885,39 @@ impl Runtime {
             .ok_or(Error::ComponentNotFound(id))
     }
 
-    /// Registers an instance and returns its unique ID.
+    /// Allocates a unique instance ID; one left unused by a failed build is never reissued.
+    pub(crate) fn next_instance_id(&self) -> InstanceId {
+        InstanceId(self.next_instance_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Registers an instance under an ID from `next_instance_id`.
     ///
     /// This is an internal API used by the InstanceBuilder.
     /// Users should use `Runtime::instantiate()` instead.
-    pub(crate) fn add_instance(&self, state: InstanceState) -> InstanceId {
-        let id = InstanceId(self.next_instance_id.fetch_add(1, Ordering::Relaxed));
+    pub(crate) fn add_instance(&self, id: InstanceId, state: InstanceState) {
+        let component_id = state.component_id;
         self.instances.insert(id, Arc::new(Mutex::new(state)));
-        id
+        self.instance_components.insert(id, component_id);
+    }
+
+    /// Unregisters an instance, dropping its store once no call holds it.
+    pub fn remove_instance(&self, id: InstanceId) -> Result<()> {
+        self.instance_components.remove(&id);
+        self.instances.remove(&id).ok_or(Error::InstanceNotFound(id))?;
+        Ok(())
+    }
+
+    /// Lists every instance with the component it was built from, sorted by id.
+    ///
+    /// Only fully registered instances appear. Like the other `list_*`
+    /// methods, this never waits on a lock, not even one held by a running call.
+    pub fn list_instances(&self) -> Vec<(InstanceId, ComponentId)> {
+        let mut instances: Vec<_> = self.instance_components
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        instances.sort_by_key(|(id, _)| id.0);
+        instances
     }
 
     /// Creates an instance builder for the given component.
@@ -233,6 +926,50 @@ impl Runtime {
         InstanceBuilder::new(Arc::clone(self), component_id)
     }
 
+    /// Builds `size` instances of a component, linked per `profile`, for `with_pooled_instance`.
+    ///
+    /// A pool holds at least one instance. If any of them fails to build,
+    /// the ones already built are removed and the error returned.
+    pub async fn create_pool(
+        self: &Arc<Self>,
+        component_id: ComponentId,
+        size: usize,
+        profile: LinkProfile,
+    ) -> pool::Result<PoolId> {
+        let pool = Pool::new(self, component_id, size, profile).await?;
+        let id = PoolId(self.next_pool_id.fetch_add(1, Ordering::Relaxed));
+        self.pools.insert(id, Arc::new(pool));
+        Ok(id)
+    }
+
+    /// Runs `f` with an instance checked out of a pool, then returns it.
+    ///
+    /// Waits, first come first served, while every instance is checked out.
+    /// An instance that traps is discarded and replaced instead of returned,
+    /// as is one whose closure panics or is dropped before finishing.
+    pub async fn with_pooled_instance<F, Fut, T>(self: &Arc<Self>, pool_id: PoolId, f: F) -> pool::Result<T>
+    where
+        F: FnOnce(InstanceId) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let pool = self.pools
+            .get(&pool_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or(pool::Error::PoolNotFound(pool_id))?;
+        let checkout = pool.checkout(self).await?;
+        let output = f(checkout.instance_id()).await;
+        checkout.finish().await;
+        Ok(output)
+    }
+
+    /// Whether an instance is registered and can still be called.
+    pub(crate) async fn is_usable(&self, instance_id: InstanceId) -> bool {
+        let Some(state_arc) = self.instances.get(&instance_id).map(|entry| Arc::clone(entry.value())) else {
+            return false;
+        };
+        !state_arc.lock().await.poisoned
+    }
+
     /// Calls an exported function on an instance.
     ///
     /// Uses pre-computed export indices for O(1) lookup instead of
@@ -244,26 +981,106 @@ impl Runtime {
         function: &str,
         args: &[Val],
     ) -> Result<Vec<Val>> {
+        self.call_inner(instance_id, interface, function, args, CallScope::default()).await
+    }
+
+    /// Calls a function, interrupting it with `Error::Cancelled` once `cancel` fires.
+    ///
+    /// Guest code is interrupted within an `EPOCH_TICK` of cancellation. A call
+    /// cancelled before it starts doesn't run at all, and leaves the instance usable.
+    /// One interrupted mid-execution traps, so the instance is then poisoned
+    /// like after any other trap.
+    pub async fn call_with_cancel(
+        &self,
+        instance_id: InstanceId,
+        interface: &str,
+        function: &str,
+        args: &[Val],
+        cancel: CancellationToken,
+    ) -> Result<Vec<Val>> {
+        if cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        self.start_ticker();
+        self.call_inner(instance_id, interface, function, args, CallScope { cancel: Some(cancel), caller: None }).await
+    }
+
+    /// Calls a function, giving up with `Error::Timeout` once `timeout` has passed.
+    ///
+    /// Guest code yields at every `EPOCH_TICK`, so a spinning guest is noticed
+    /// within a tick of the deadline, as is one blocked in a host import. The
+    /// call is then dropped, unwinding the guest's stack where it stood. That
+    /// leaves the instance mid-call, so it is poisoned and later calls fail with
+    /// `Error::InstancePoisoned`; start a new one to keep going.
+    pub async fn call_with_timeout(
+        &self,
+        instance_id: InstanceId,
+        interface: &str,
+        function: &str,
+        args: &[Val],
+        timeout: Duration,
+    ) -> Result<Vec<Val>> {
+        self.start_ticker();
+        let call = self.call_inner(instance_id, interface, function, args, CallScope::default());
+        tokio::time::timeout(timeout, call).await.unwrap_or(Err(Error::Timeout))
+    }
+
+    async fn call_inner(
+        &self,
+        instance_id: InstanceId,
+        interface: &str,
+        function: &str,
+        args: &[Val],
+        scope: CallScope,
+    ) -> Result<Vec<Val>> {
+        if self.is_shut_down() {
+            return Err(Error::Shutdown);
+        }
+        let _inflight = InflightCall::enter(&self.inflight_calls);
+
         let state_arc = self.instances
             .get(&instance_id)
             .map(|entry| Arc::clone(entry.value()))
             .ok_or(Error::InstanceNotFound(instance_id))?;
 
         let mut state = state_arc.lock().await;
-        let InstanceState { component_id, instance, store } = &mut *state;
+        if state.poisoned {
+            return Err(Error::InstancePoisoned(instance_id));
+        }
 
-        // Get component to lookup export indices
-        let component = self.get_component(*component_id)?;
+        let interceptors = state.interceptors.get(interface).cloned().unwrap_or_default();
+        for interceptor in &interceptors {
+            interceptor.before(function, args).map_err(Error::Rejected)?;
+        }
+        let outcome = self.call_locked(instance_id, &mut state, interface, function, args, scope).await;
+        for interceptor in &interceptors {
+            interceptor.after(function, &outcome);
+        }
+        outcome
+    }
+
+    /// Runs a call on an instance already locked and known not to be poisoned.
+    async fn call_locked(
+        &self,
+        instance_id: InstanceId,
+        state: &mut InstanceState,
+        interface: &str,
+        function: &str,
+        args: &[Val],
+        scope: CallScope,
+    ) -> Result<Vec<Val>> {
+        let InstanceState { instance, store, call_count, fuel_consumed, poisoned, .. } = state;
 
-        // Get export indices
-        let inst_idx = component
-            .get_export_index(None, interface)
+        // Get export indices from the instance itself, so calls keep working
+        // even if the component has since been removed from the runtime
+        let inst_idx = instance
+            .get_export_index(&mut *store, None, interface)
             .ok_or_else(|| Error::InterfaceNotFound {
                 interface: interface.to_string()
             })?;
 
-        let func_idx = component
-            .get_export_index(Some(&inst_idx), function)
+        let func_idx = instance
+            .get_export_index(&mut *store, Some(&inst_idx), function)
             .ok_or_else(|| Error::FunctionNotFound {
                 interface: interface.to_string(),
                 function: function.to_string()
@@ -271,29 +1088,155 @@ impl Runtime {
 
         // Get function from instance
         let func = instance
-            .get_func(&mut *store, &func_idx)
+            .get_func(&mut *store, func_idx)
             .ok_or(Error::FunctionLookupFailed)?;
 
-        // Determine result count from function type
+        // Check arguments here, so a bad call fails without entering the guest
         let func_ty = func.ty(&mut *store);
+        let params: Vec<Type> = func_ty.params().map(|(_, ty)| ty).collect();
+        if params.len() != args.len() || !params.iter().zip(args).all(|(ty, val)| typed::shape_matches(ty, val)) {
+            return Err(Error::Signature {
+                interface: interface.to_string(),
+                function: function.to_string(),
+                details: format!("expected params {}, got {} args", typed::describe(&params), args.len()),
+            });
+        }
+
+        // Determine result count from function type
         let result_count = func_ty.results().len();
         let mut results = vec![Val::Bool(false); result_count];
 
-        func.call_async(&mut *store, args, &mut results)
+        let started = Instant::now();
+        let fuel_before = store.get_fuel().unwrap_or(0);
+        store.data_mut().cancel = scope.cancel;
+        store.data_mut().caller = scope.caller;
+        // Stays set if this future is dropped mid-call, as by `call_with_timeout`,
+        // since the instance can't be entered again after that. Arguments were
+        // checked above, so any error here, a trap or one a host import returned,
+        // unwound the guest and leaves it just as unenterable.
+        *poisoned = true;
+        let called = func.call_async(&mut *store, args, &mut results).await;
+        *poisoned = called.is_err();
+        store.data_mut().caller = None;
+        let cancelled = store.data_mut().cancel.take().is_some_and(|token| token.is_cancelled());
+        *call_count += 1;
+        *fuel_consumed += fuel_before.saturating_sub(store.get_fuel().unwrap_or(0));
+        if let Err(e) = called {
+            self.emit(RuntimeEvent::InstanceTrapped(instance_id, e.to_string()));
+            return Err(match e.downcast_ref::<wasmtime::Trap>() {
+                Some(wasmtime::Trap::OutOfFuel) => Error::OutOfFuel,
+                Some(wasmtime::Trap::Interrupt) if cancelled => Error::Cancelled,
+                Some(wasmtime::Trap::Interrupt) if self.is_aborting() => Error::Shutdown,
+                Some(&trap) => Error::Trap(trap.into()),
+                None => Error::Component(e),
+            });
+        }
+
+        // Without this the instance refuses to be entered again
+        func.post_return_async(&mut *store)
             .await
             .map_err(Error::Component)?;
 
+        self.emit(RuntimeEvent::InstanceCallCompleted(instance_id, started.elapsed()));
         Ok(results)
     }
 
+    /// Calls a function with Rust values, e.g. `call_typed::<(u32, u32), u32>`.
+    ///
+    /// Params and results are checked against the component's `Ledger`
+    /// signature before the call, so a mismatch fails with `Error::Signature`
+    /// without running any guest code. See `typed` for the accepted shapes.
+    pub async fn call_typed<P: IntoVals, R: FromVals>(
+        &self,
+        instance_id: InstanceId,
+        interface: &str,
+        function: &str,
+        params: P,
+    ) -> Result<R> {
+        let ledger = self.instances
+            .get(&instance_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or(Error::InstanceNotFound(instance_id))?
+            .lock().await
+            .ledger
+            .clone();
+
+        let sig = ledger.exports
+            .get(interface)
+            .ok_or_else(|| Error::InterfaceNotFound { interface: interface.to_string() })?
+            .funcs
+            .get(function)
+            .ok_or_else(|| Error::FunctionNotFound {
+                interface: interface.to_string(),
+                function: function.to_string(),
+            })?;
+
+        let mismatch = |details: String| Error::Signature {
+            interface: interface.to_string(),
+            function: function.to_string(),
+            details,
+        };
+        if !P::matches(&sig.params) {
+            return Err(mismatch(format!(
+                "expected params {}, got {}",
+                typed::describe(&sig.params),
+                std::any::type_name::<P>(),
+            )));
+        }
+        if !R::matches(&sig.results) {
+            return Err(mismatch(format!(
+                "expected results {}, got {}",
+                typed::describe(&sig.results),
+                std::any::type_name::<R>(),
+            )));
+        }
+
+        let results = self.call(instance_id, interface, function, &params.into_vals()).await?;
+        R::from_vals(results).ok_or_else(|| mismatch("results did not decode".to_string()))
+    }
+
+    /// Reports the resource usage of an instance.
+    ///
+    /// Memory counts what the instance holds since instantiation; fuel and
+    /// calls count only `Runtime::call`, so an instance never called reports
+    /// zero for both. Waits for any call in progress on the instance.
+    pub async fn instance_metrics(&self, instance_id: InstanceId) -> Result<InstanceMetrics> {
+        let state_arc = self.instances
+            .get(&instance_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or(Error::InstanceNotFound(instance_id))?;
+
+        let state = state_arc.lock().await;
+        let limits = &state.store.data().limits;
+        Ok(InstanceMetrics {
+            fuel_consumed: state.fuel_consumed,
+            current_memory_bytes: limits.current_memory_bytes,
+            peak_memory_bytes: limits.peak_memory_bytes,
+            call_count: state.call_count,
+        })
+    }
+
     /// Registers a peer with the runtime and returns its unique ID.
     /// The peer name is stored in the Peer for logging and diagnostics.
     pub fn add_peer(&self, peer: Arc<Peer>) -> PeerId {
         let id = PeerId(self.next_peer_id.fetch_add(1, Ordering::Relaxed));
         self.peers.insert(id, peer);
+        self.emit(RuntimeEvent::PeerAdded(id));
         id
     }
 
+    /// Unregisters a peer and shuts it down.
+    ///
+    /// Calls still waiting on the peer fail with `peer::Error::Shutdown`,
+    /// and the pump task stops, releasing the transport.
+    /// Later lookups of the id return `Error::PeerNotFound`.
+    pub async fn remove_peer(&self, peer_id: PeerId) -> Result<()> {
+        let (_, peer) = self.peers.remove(&peer_id).ok_or(Error::PeerNotFound(peer_id))?;
+        peer.shutdown().await;
+        self.emit(RuntimeEvent::PeerRemoved(peer_id));
+        Ok(())
+    }
+
     /// Retrieves the peer handle for a given peer ID.
     /// Returns an error if the peer is not registered.
     pub fn get_peer(&self, peer_id: PeerId) -> Result<Arc<Peer>> {
@@ -302,4 +1245,255 @@ impl Runtime {
             .map(|entry| Arc::clone(entry.value()))
             .ok_or(Error::PeerNotFound(peer_id))
     }
+
+    /// Lists every registered peer with its name, sorted by id.
+    pub fn list_peers(&self) -> Vec<(PeerId, String)> {
+        let mut peers: Vec<_> = self.peers
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().peer_name().to_string()))
+            .collect();
+        peers.sort_by_key(|(id, _)| id.0);
+        peers
+    }
+
+    /// Makes `interface` of an instance callable by peers as `target`.
+    ///
+    /// This is the `target_id` a remote runtime passes to `PeerId::get_instance`.
+    /// Exposing a target again replaces it.
+    pub fn expose(&self, target: impl Into<String>, instance_id: InstanceId, interface: impl Into<String>) {
+        self.exposed.insert(target.into(), (instance_id, interface.into()));
+    }
+
+    /// Runs an inbound Call frame against an exposed instance and returns the Reply frame.
+    ///
+    /// Every failure to run the call is answered with a `ReplyErr`, so the
+    /// caller always hears back; a target that isn't exposed is
+    /// `neorpc::unknown_target()`. Only a frame that isn't a Call is an error,
+    /// since it has no seq to reply to.
+    pub async fn serve_call(&self, frame: &[u8]) -> Result<Vec<u8>> {
+        let RpcFrame::Call(call) = RpcFrame::decode(frame)? else {
+            return Err(Error::Rpc(neorpc::Error::ProtocolViolation("Expected Call".into())));
+        };
+
+        let seq = call.seq;
+        #[cfg(feature = "tracing")]
+        let span = crate::trace::serve_span(&call);
+        let serve = async {
+            match self.exposed.get(call.target).map(|entry| entry.value().clone()) {
+                Some((instance_id, interface)) => self.run_call(instance_id, &interface, call, None).await,
+                None => Err(neorpc::unknown_target()),
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let serve = serve.instrument(span);
+        let outcome = serve.await;
+        reply_frame(seq, outcome)
+    }
+
+    /// Serves every Call `peer_id` sends against `instance_id`.
+    ///
+    /// A Call's target names the exported interface to run it on, so a caller
+    /// reaches it with `link_remote(interface, peer_id.get_instance(interface))`.
+    /// The peer's pump reads the frames and each call runs on its own task,
+    /// answered with a `ReplyOk` or a `ReplyErr`; a guest trap is `AppTrapped`,
+    /// and an interface the instance doesn't export is `neorpc::unknown_target()`.
+    /// `advertise` tells the peer which interfaces those are.
+    /// Notifications run the same way, with their outcome dropped.
+    /// Replaces any call handler the peer had.
+    pub fn serve_peer(self: &Arc<Self>, peer_id: PeerId, instance_id: InstanceId) -> Result<()> {
+        let peer = self.get_peer(peer_id)?;
+        if !self.instances.contains_key(&instance_id) {
+            return Err(Error::InstanceNotFound(instance_id));
+        }
+
+        // The runtime owns the peer, so the handler must not own the runtime
+        let runtime = Arc::downgrade(self);
+        peer.set_call_handler(move |frame| {
+            let runtime = runtime.clone();
+            Box::pin(async move {
+                let runtime = runtime.upgrade()?;
+                match RpcFrame::decode(&frame).ok()? {
+                    RpcFrame::Call(call) => {
+                        let seq = call.seq;
+                        let interface = call.target.to_string();
+                        // Picks up the caller's trace id, so both ends of the call correlate
+                        #[cfg(feature = "tracing")]
+                        let span = crate::trace::serve_span(&call);
+                        let run = runtime.run_call(instance_id, &interface, call, Some(peer_id));
+                        #[cfg(feature = "tracing")]
+                        let run = run.instrument(span);
+                        let outcome = run.await;
+                        reply_frame(seq, outcome).ok()
+                    }
+                    RpcFrame::Notify(notify) => {
+                        let interface = notify.target.to_string();
+                        let call = neorpc::CallDecoder {
+                            seq: 0,
+                            target: notify.target,
+                            method: notify.method,
+                            args: notify.args,
+                            deadline_ms: None,
+                            trace_id: None,
+                        };
+                        let _ = runtime.run_call(instance_id, &interface, call, Some(peer_id)).await;
+                        None
+                    }
+                    _ => None,
+                }
+            })
+        });
+        Ok(())
+    }
+
+    /// Tells `peer_id` which targets `instance_id` serves: its exported interfaces,
+    /// the targets `serve_peer` takes Calls on.
+    ///
+    /// The Handshake carries the instance's export fingerprint, and the remote
+    /// lists the targets with `Peer::targets`.
+    pub async fn advertise(&self, peer_id: PeerId, instance_id: InstanceId) -> Result<()> {
+        let peer = self.get_peer(peer_id)?;
+        let state_arc = self.instances
+            .get(&instance_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or(Error::InstanceNotFound(instance_id))?;
+        let (fingerprint, targets) = {
+            let state = state_arc.lock().await;
+            let mut targets: Vec<String> = state.ledger.exports.keys().cloned().collect();
+            targets.sort();
+            (state.ledger.export_fingerprint(), targets)
+        };
+        Ok(peer.advertise(fingerprint, targets).await?)
+    }
+
+    /// Decodes a call's args, runs it, and applies the instance's error mapping.
+    ///
+    /// `caller` is the peer the call came from, as seen by `ExorunCtx::caller`.
+    async fn run_call(
+        &self,
+        instance_id: InstanceId,
+        interface: &str,
+        call: neorpc::CallDecoder<'_>,
+        caller: Option<PeerId>,
+    ) -> std::result::Result<Vec<Val>, FailureReason> {
+        let state_arc = self.instances
+            .get(&instance_id)
+            .map(|entry| Arc::clone(entry.value()))
+            .ok_or(FailureReason::InstanceNotFound)?;
+        let (sig, mapped) = {
+            let state = state_arc.lock().await;
+            let sig = state.ledger.exports
+                .get(interface)
+                .ok_or_else(neorpc::unknown_target)?
+                .funcs
+                .get(call.method)
+                .filter(|sig| sig.kind == FuncKind::Freestanding)
+                .cloned()
+                .ok_or(FailureReason::MethodNotFound)?;
+            (sig, state.error_mapping.contains(interface))
+        };
+
+        let args = neorpc::decode_vals(call.args, &sig.params)
+            .map_err(|e| FailureReason::ProtocolViolation(e.to_string()))?;
+        let mut results = self.call_inner(instance_id, interface, call.method, &args, CallScope { cancel: None, caller })
+            .await
+            .map_err(|e| match e {
+                Error::InstanceNotFound(_) => FailureReason::InstanceNotFound,
+                Error::InterfaceNotFound { .. } | Error::FunctionNotFound { .. } => FailureReason::MethodNotFound,
+                Error::OutOfFuel => FailureReason::OutOfFuel,
+                Error::Rejected(reason) => reason,
+                _ => FailureReason::AppTrapped,
+            })?;
+
+        if mapped
+            && let [Type::Result(ty)] = sig.results.as_slice()
+            && let [Val::Result(Err(_))] = results.as_slice()
+            && let Some(Val::Result(Err(payload))) = results.pop()
+        {
+            return Err(domain_failure(ty.err(), payload.map(|val| *val)));
+        }
+        Ok(results)
+    }
+}
+
+/// What a call's store context carries only while the call runs.
+#[derive(Default)]
+struct CallScope {
+    cancel: Option<CancellationToken>,
+    caller: Option<PeerId>,
+}
+
+/// Encodes the Reply frame for a served call's outcome.
+fn reply_frame(seq: u64, outcome: std::result::Result<Vec<Val>, FailureReason>) -> Result<Vec<u8>> {
+    let reply = match outcome {
+        Ok(results) => {
+            let results = neorpc::encode_vals_to_bytes(&results)?;
+            ReplyOkEncoder::new(seq, &results).into_bytes()
+        }
+        Err(reason) => ReplyErrEncoder::new(seq, reason).into_bytes(),
+    };
+    Ok(reply?)
+}
+
+/// Writes `bytes` to `path` through a private temporary file, so readers
+/// never see a partial artifact even while other writers race on the path.
+fn write_replacing(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+    let temp = path.with_extension(format!("{}-{}.tmp", std::process::id(), NEXT_TEMP.fetch_add(1, Ordering::Relaxed)));
+    std::fs::write(&temp, bytes)?;
+    std::fs::rename(&temp, path).inspect_err(|_| { let _ = std::fs::remove_file(&temp); })
+}
+
+/// Turns the `Err` payload of a guest `result` into a domain failure.
+///
+/// The code and message come from the payload's shape:
+/// - `string`: code 0, the string as message
+/// - an integer: that code, no message
+/// - `enum` or `variant`: the case's index as code, a string payload or the case name as message
+/// - a record with `code` and `message` fields: those
+/// - no payload: code 0, "error"; anything else: code 0, the payload printed
+///
+/// Codes in `neorpc::RESERVED_CODES` become 0, so a guest can't pass its
+/// error off as one of neorpc's own, like `unknown_target`.
+fn domain_failure(ty: Option<Type>, payload: Option<Val>) -> FailureReason {
+    let case_index = |name: &str| -> u32 {
+        let names: Vec<String> = match &ty {
+            Some(Type::Enum(e)) => e.names().map(str::to_string).collect(),
+            Some(Type::Variant(v)) => v.cases().map(|c| c.name.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        names.iter().position(|n| n == name).unwrap_or(0) as u32
+    };
+
+    let (code, message) = match payload {
+        None => (0, "error".to_string()),
+        Some(Val::String(message)) => (0, message),
+        Some(Val::U8(n)) => (n as u32, String::new()),
+        Some(Val::U16(n)) => (n as u32, String::new()),
+        Some(Val::U32(n)) => (n, String::new()),
+        Some(Val::S8(n)) => (n as u32, String::new()),
+        Some(Val::S16(n)) => (n as u32, String::new()),
+        Some(Val::S32(n)) => (n as u32, String::new()),
+        Some(Val::Enum(name)) => (case_index(&name), name),
+        Some(Val::Variant(name, inner)) => match inner.map(|val| *val) {
+            Some(Val::String(message)) => (case_index(&name), message),
+            _ => (case_index(&name), name),
+        },
+        Some(Val::Record(fields)) => {
+            let code = fields.iter().find_map(|(key, val)| match (key.as_str(), val) {
+                ("code", Val::U32(code)) => Some(*code),
+                _ => None,
+            });
+            let message = fields.iter().find_map(|(key, val)| match (key.as_str(), val) {
+                ("message", Val::String(message)) => Some(message.clone()),
+                _ => None,
+            });
+            match (code, message) {
+                (Some(code), Some(message)) => (code, message),
+                _ => (0, format!("{:?}", Val::Record(fields))),
+            }
+        }
+        Some(other) => (0, format!("{:?}", other)),
+    };
+    let code = if code >= neorpc::RESERVED_CODES { 0 } else { code };
+    FailureReason::DomainSpecific(code, message)
 }
@@ -11,6 +11,7 @@ use neorpc::ReplyErrEncoder;
 use neorpc::RpcFrame;
 use neorpc::decode_vals;
 use neorpc::encode_vals_to_bytes;
+use neorpc::frame_bytes;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
 use wasmtime::component::Type;
@@ -70,8 +71,7 @@ impl PingPongTransport {
     }
 
     fn decode_frame<'a>(&self, payload: &'a [u8]) -> transport::Result<RpcFrame<'a>> {
-        let mut dec = Decoder::new(payload);
-        RpcFrame::decode(&mut dec).map_err(|e| {
+        RpcFrame::decode(payload).map_err(|e| {
             transport::Error::Io(format!("Failed to decode: {}", e))
         })
     }
@@ -100,7 +100,7 @@ impl PingPongTransport {
         ReplyOkEncoder::new(seq, &results_bytes).encode(&mut enc).map_err(|e| {
             transport::Error::Io(format!("Failed to encode reply: {}", e))
         })?;
-        enc.into_bytes().map_err(|e| {
+        frame_bytes(enc).map_err(|e| {
             transport::Error::Io(format!("Failed to finalize bytes: {}", e))
         })
     }
@@ -117,9 +117,14 @@ impl Transport for PingPongTransport {
                 self.validate_ping(&args)?;
                 self.encode_pong(call.seq)?
             }
-            RpcFrame::Reply(_) => {
+            RpcFrame::Reply(_) | RpcFrame::ReplyChunk(_) => {
                 return Err(transport::Error::Io("Received Reply frame in transport".into()));
             }
+            RpcFrame::Cancel(_) => return Ok(()),
+            RpcFrame::Batch(_) | RpcFrame::ReplyBatch(_) => {
+                return Err(transport::Error::Io("Received Batch frame in transport".into()));
+            }
+            RpcFrame::Notify(_) | RpcFrame::Ping(_) | RpcFrame::Pong(_) | RpcFrame::Handshake(_) => return Ok(()),
         };
 
         *self.pending.lock().await = Some(response);
@@ -150,8 +155,8 @@ impl Transport for CallReturningTransport {
     async fn send(&self, _payload: &[u8]) -> transport::Result<()> {
         let mut enc = Encoder::new();
         let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
-        CallEncoder::new(999, "target", "method", &empty_bytes).encode(&mut enc).unwrap();
-        let response = enc.into_bytes().unwrap();
+        CallEncoder::new(999, "target", "method", &empty_bytes, None).encode(&mut enc).unwrap();
+        let response = frame_bytes(enc).unwrap();
         *self.pending.lock().await = Some(response);
         Ok(())
     }
@@ -178,8 +183,7 @@ impl FailureTransport {
 impl Transport for FailureTransport {
     async fn send(&self, payload: &[u8]) -> transport::Result<()> {
         // Decode to get seq
-        let mut dec = Decoder::new(payload);
-        let frame = RpcFrame::decode(&mut dec).unwrap();
+        let frame = RpcFrame::decode(payload).unwrap();
         let seq = match frame {
             RpcFrame::Call(call) => call.seq,
             _ => 0,
@@ -187,7 +191,7 @@ impl Transport for FailureTransport {
 
         let mut enc = Encoder::new();
         ReplyErrEncoder::new(seq, FailureReason::AppTrapped).encode(&mut enc).unwrap();
-        let response = enc.into_bytes().unwrap();
+        let response = frame_bytes(enc).unwrap();
         *self.pending.lock().await = Some(response);
         Ok(())
     }
@@ -373,8 +377,7 @@ async fn test_concurrent_correlation() {
 
     for req_bytes in requests {
         // Decode request to get seq and input value
-        let mut dec = Decoder::new(&req_bytes);
-        let frame = RpcFrame::decode(&mut dec).unwrap();
+        let frame = RpcFrame::decode(&req_bytes).unwrap();
 
         if let RpcFrame::Call(call) = frame {
             // Extract input value
@@ -390,7 +393,7 @@ async fn test_concurrent_correlation() {
 
             let mut enc = Encoder::new();
             ReplyOkEncoder::new(call.seq, &results_bytes).encode(&mut enc).unwrap();
-            let reply_bytes = enc.into_bytes().unwrap();
+            let reply_bytes = frame_bytes(enc).unwrap();
 
             server_tx.send(reply_bytes).unwrap();
         }
@@ -423,8 +426,7 @@ async fn test_failure_fidelity() {
     impl Transport for DomainErrorTransport {
         async fn send(&self, payload: &[u8]) -> transport::Result<()> {
             // Decode to get seq
-            let mut dec = Decoder::new(payload);
-            let frame = RpcFrame::decode(&mut dec).unwrap();
+            let frame = RpcFrame::decode(payload).unwrap();
             let seq = match frame {
                 RpcFrame::Call(call) => call.seq,
                 _ => 0,
@@ -435,7 +437,7 @@ async fn test_failure_fidelity() {
             ReplyErrEncoder::new(seq, FailureReason::DomainSpecific(42, "Auth failed".into()))
                 .encode(&mut enc)
                 .unwrap();
-            let response = enc.into_bytes().unwrap();
+            let response = frame_bytes(enc).unwrap();
             *self.pending.lock().await = Some(response);
             Ok(())
         }
@@ -462,3 +464,40 @@ async fn test_failure_fidelity() {
         _ => panic!("Expected Remote(DomainSpecific), got {:?}", err),
     }
 }
+
+/// Mock transport that accepts every call and never answers.
+struct SilentTransport;
+
+#[async_trait::async_trait]
+impl Transport for SilentTransport {
+    async fn send(&self, _payload: &[u8]) -> transport::Result<()> {
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_remove_peer_fails_in_flight_calls() {
+    use crate::runtime;
+    use crate::Runtime;
+
+    let rt = Runtime::new().unwrap();
+    let peer = Arc::new(Peer::new("silent", Box::new(SilentTransport), PeerConfig::default()));
+    let peer_id = rt.add_peer(Arc::clone(&peer));
+
+    let call = tokio::spawn(async move {
+        peer.call("target", "method", &[], vec![]).await
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    rt.remove_peer(peer_id).await.expect("remove registered peer");
+
+    let err = call.await.unwrap().unwrap_err();
+    assert!(matches!(err, peer::Error::Shutdown), "Expected Shutdown, got {:?}", err);
+
+    assert!(matches!(rt.get_peer(peer_id), Err(runtime::Error::PeerNotFound(id)) if id == peer_id));
+    assert!(matches!(rt.remove_peer(peer_id).await, Err(runtime::Error::PeerNotFound(_))));
+}
@@ -0,0 +1,76 @@
+//! # Tracing for remote calls
+//!
+//! Opens a `rpc.call` span around each call a remote link makes,
+//! and a `rpc.serve` span for the side that handles it.
+//! The caller picks a random trace id and sends it in the Call frame,
+//! so both spans carry the same id and can be correlated across peers.
+
+use std::time::Instant;
+
+use neorpc::CallDecoder;
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::peer;
+
+/// A remote call being traced, from send until its reply.
+pub struct CallTrace {
+    /// The id sent along with the call.
+    pub trace_id: u128,
+    span: Span,
+    started: Instant,
+}
+
+impl CallTrace {
+    /// Opens the `rpc.call` span for a call with a fresh trace id.
+    pub fn start(target: &str, method: &str, seq: u64) -> Self {
+        let trace_id = rand::random::<u128>();
+        let span = tracing::info_span!(
+            "rpc.call",
+            target,
+            method,
+            seq,
+            trace_id,
+            elapsed_us = Empty,
+            outcome = Empty,
+        );
+        Self { trace_id, span, started: Instant::now() }
+    }
+
+    /// Records how long the call took and how it ended.
+    ///
+    /// A failed call is logged at `warn`, with the remote's `FailureReason` if it sent one.
+    pub fn finish<T>(self, result: &peer::Result<T>) {
+        self.span.record("elapsed_us", self.started.elapsed().as_micros() as u64);
+        match result {
+            Ok(_) => {
+                self.span.record("outcome", "ok");
+            }
+            Err(peer::Error::Remote(reason)) => {
+                self.span.record("outcome", "remote");
+                tracing::warn!(parent: &self.span, ?reason, "remote call failed");
+            }
+            Err(error) => {
+                self.span.record("outcome", "error");
+                tracing::warn!(parent: &self.span, %error, "remote call failed");
+            }
+        }
+    }
+}
+
+/// Opens the `rpc.serve` span for handling an inbound call.
+///
+/// Carries over the caller's trace id, if the call has one.
+pub fn serve_span(call: &CallDecoder) -> Span {
+    let span = tracing::info_span!(
+        "rpc.serve",
+        target = call.target,
+        method = call.method,
+        seq = call.seq,
+        trace_id = Empty,
+    );
+    if let Some(trace_id) = call.trace_id {
+        span.record("trace_id", trace_id);
+    }
+    span
+}
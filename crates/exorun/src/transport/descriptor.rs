@@ -0,0 +1,88 @@
+//! # Transport descriptors
+//!
+//! A descriptor says how to reach the other end of a transport, in a form
+//! that can be packed with neopack and shipped to another runtime (for
+//! example inside a `BootstrapBundle`), then turned back into a live
+//! transport with `dial`.
+
+use std::fmt;
+#[cfg(feature = "quic")]
+use std::net::SocketAddr;
+
+use neopack::Pack;
+use neopack::Unpack;
+#[cfg(feature = "quic")]
+use quinn::ClientConfig;
+#[cfg(feature = "quic")]
+use quinn::Endpoint;
+
+use super::Error;
+use super::LocalTransport;
+#[cfg(feature = "quic")]
+use super::QuicTransport;
+use super::Result;
+use super::TcpTransport;
+use super::Transport;
+
+/// Where a transport connects, and over what.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Pack, Unpack)]
+pub enum TransportDescriptor {
+    /// A TCP `host:port` address.
+    Tcp(String),
+    /// A QUIC server at `addr`, presenting a certificate for `server_name`.
+    Quic { addr: String, server_name: String },
+    /// A WebSocket server at a `ws://` URL.
+    WebSocket(String),
+    /// An in-process channel; there is nothing to dial over the network.
+    LocalChannel,
+}
+
+impl fmt::Display for TransportDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{}", addr),
+            Self::Quic { addr, server_name } => write!(f, "quic://{} ({})", addr, server_name),
+            Self::WebSocket(url) => write!(f, "{}", url),
+            Self::LocalChannel => write!(f, "local"),
+        }
+    }
+}
+
+/// Opens a new transport to wherever `desc` points.
+///
+/// QUIC connections are made from a fresh client endpoint that trusts the
+/// platform's root certificates. QUIC and WebSocket descriptors can only be
+/// dialed when built with the `quic` and `websocket` features respectively.
+/// A `LocalChannel` has no far end to reach, so dialing one yields a
+/// loopback channel.
+pub async fn dial(desc: &TransportDescriptor) -> Result<Box<dyn Transport>> {
+    match desc {
+        TransportDescriptor::Tcp(addr) => Ok(Box::new(TcpTransport::connect(addr).await?)),
+        #[cfg(feature = "quic")]
+        TransportDescriptor::Quic { addr, server_name } => {
+            let addr: SocketAddr = addr.parse()
+                .map_err(|e| Error::Io(format!("invalid quic address {}: {}", addr, e)))?;
+            let bind: SocketAddr = match addr {
+                SocketAddr::V4(_) => "0.0.0.0:0".parse().unwrap(),
+                SocketAddr::V6(_) => "[::]:0".parse().unwrap(),
+            };
+            let mut endpoint = Endpoint::client(bind)
+                .map_err(|e| Error::Io(format!("bind {}: {}", bind, e)))?;
+            let config = ClientConfig::try_with_platform_verifier()
+                .map_err(|e| Error::Io(format!("client config: {}", e)))?;
+            endpoint.set_default_client_config(config);
+            Ok(Box::new(QuicTransport::connect(&endpoint, addr, server_name).await?))
+        }
+        #[cfg(not(feature = "quic"))]
+        TransportDescriptor::Quic { addr, .. } => {
+            Err(Error::Io(format!("cannot dial quic://{}: built without the quic feature", addr)))
+        }
+        #[cfg(feature = "websocket")]
+        TransportDescriptor::WebSocket(url) => Ok(Box::new(super::WebSocketTransport::connect(url).await?)),
+        #[cfg(not(feature = "websocket"))]
+        TransportDescriptor::WebSocket(url) => {
+            Err(Error::Io(format!("cannot dial {}: built without the websocket feature", url)))
+        }
+        TransportDescriptor::LocalChannel => Ok(Box::new(LocalTransport::loopback())),
+    }
+}
@@ -12,6 +12,23 @@
 //! - **Message-Passing**: The fundamental interaction model is asynchronous message passing.
 //!   Request-response, streams, and other patterns are built on top using sequence numbers.
 
+pub mod tcp;
+#[cfg(feature = "quic")]
+pub mod quic;
+pub mod local;
+pub mod descriptor;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+pub use tcp::TcpTransport;
+pub use local::LocalTransport;
+pub use descriptor::TransportDescriptor;
+pub use descriptor::dial;
+#[cfg(feature = "quic")]
+pub use quic::QuicTransport;
+#[cfg(feature = "websocket")]
+pub use websocket::WebSocketTransport;
+
 use std::fmt;
 
 /// Errors that occur at the network/transport layer.
@@ -46,7 +63,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 ///
 /// This trait is designed to be object-safe (`Arc<dyn Transport>`).
 /// It provides low-level message send/receive primitives. Higher-level
-/// patterns like request-response are implemented in the Client.
+/// patterns like request-response are implemented in the `Peer`, which
+/// correlates replies to calls by sequence number.
 #[async_trait::async_trait]
 pub trait Transport: Send + Sync + 'static {
     /// Queues a raw message for transmission.
@@ -72,4 +90,12 @@ pub trait Transport: Send + Sync + 'static {
     /// - Messages are returned in order
     /// - Each message is complete (no partial reads)
     async fn recv(&self) -> Result<Option<Vec<u8>>>;
+
+    /// Describes how to dial the remote end again, e.g. with `dial`.
+    ///
+    /// Transports that cannot be re-dialed over a network, such as in-process
+    /// channels and test doubles, keep the default of `LocalChannel`.
+    fn descriptor(&self) -> TransportDescriptor {
+        TransportDescriptor::LocalChannel
+    }
 }
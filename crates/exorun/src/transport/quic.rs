@@ -0,0 +1,305 @@
+//! # QUIC transport
+//!
+//! Moves messages over a QUIC connection, giving every call its own
+//! bidirectional stream so a slow reply never holds up the others.
+//! Messages on a stream are framed as a 4-byte LE length header followed by the body.
+//!
+//! ## Stream mapping
+//!
+//! - Sending a `Call` (or any frame that isn't a reply) opens a fresh stream,
+//!   writes the message, and finishes the send side. Every message the remote
+//!   writes back on that stream is delivered by `recv`.
+//! - A `Call` arriving on a remote-opened stream is delivered by `recv`, and the
+//!   stream is held under the call's `seq`. Sending the `Reply` (or final
+//!   `ReplyChunk`) for that `seq` writes it there and finishes the stream.
+//!
+//! Unlike the other transports, this one peeks at neorpc frames to route
+//! replies. A reply with no waiting stream falls back to a fresh stream, which
+//! the remote pump still correlates by `seq`.
+//!
+//! ## Invariants
+//!
+//! - **Bounded Streams**: Endpoints built from `server_config`/`client_config`
+//!   allow at most `max_concurrent_streams` open streams per direction;
+//!   further calls wait in `open_bi` until one closes.
+//! - **Bounded Reads**: A header announcing more than `DEFAULT_MAX_MESSAGE_SIZE`
+//!   bytes resets the stream before anything is allocated.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use neorpc::RpcFrame;
+use quinn::ClientConfig;
+use quinn::Connection;
+use quinn::ConnectionError;
+use quinn::Endpoint;
+use quinn::RecvStream;
+use quinn::SendStream;
+use quinn::ServerConfig;
+use quinn::TransportConfig;
+use quinn::VarInt;
+use quinn::rustls::RootCertStore;
+use quinn::rustls::pki_types::CertificateDer;
+use quinn::rustls::pki_types::PrivateKeyDer;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::Error;
+use super::Result;
+use super::Transport;
+use super::TransportDescriptor;
+use super::tcp::DEFAULT_MAX_MESSAGE_SIZE;
+
+/// Messages buffered for `recv` before stream readers wait.
+const INBOUND_QUEUE: usize = 1024;
+
+/// A `Transport` over one QUIC connection.
+pub struct QuicTransport {
+    connection: Connection,
+    /// The name the remote's certificate was (or would be) checked against.
+    server_name: String,
+    inbound_tx: mpsc::Sender<Result<Option<Vec<u8>>>>,
+    inbound_rx: Mutex<mpsc::Receiver<Result<Option<Vec<u8>>>>>,
+    /// Send halves of remote-opened streams, keyed by the seq of the call they carried.
+    reply_streams: Arc<DashMap<u64, SendStream>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl QuicTransport {
+    /// Dials `addr` from `endpoint`, which must have a default client config.
+    pub async fn connect(endpoint: &Endpoint, addr: SocketAddr, server_name: &str) -> Result<Self> {
+        let connecting = endpoint.connect(addr, server_name)
+            .map_err(|e| Error::ConnectionLost(format!("connect {}: {}", addr, e)))?;
+        let connection = connecting.await
+            .map_err(|e| Error::ConnectionLost(format!("connect {}: {}", addr, e)))?;
+        let mut transport = Self::from_connection(connection);
+        transport.server_name = server_name.to_string();
+        Ok(transport)
+    }
+
+    /// Accepts connections on `endpoint` in the background.
+    ///
+    /// Each completed handshake yields a transport on the returned channel.
+    /// Failed handshakes are skipped. The channel closes with the endpoint.
+    pub fn accept_loop(endpoint: Endpoint) -> mpsc::Receiver<QuicTransport> {
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Some(incoming) = endpoint.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match incoming.await {
+                        Ok(connection) => {
+                            let _ = tx.send(QuicTransport::from_connection(connection)).await;
+                        }
+                        Err(_e) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::warn!(error = %_e, "quic handshake failed");
+                        }
+                    }
+                });
+            }
+        });
+        rx
+    }
+
+    /// Wraps an established connection and starts accepting the remote's streams.
+    ///
+    /// The remote's IP address stands in for its server name in `descriptor`.
+    pub fn from_connection(connection: Connection) -> Self {
+        let server_name = connection.remote_address().ip().to_string();
+        let (inbound_tx, inbound_rx) = mpsc::channel(INBOUND_QUEUE);
+        let reply_streams = Arc::new(DashMap::new());
+
+        let accept_task = tokio::spawn(Self::accept_streams(
+            connection.clone(),
+            inbound_tx.clone(),
+            reply_streams.clone(),
+        ));
+
+        Self {
+            connection,
+            server_name,
+            inbound_tx,
+            inbound_rx: Mutex::new(inbound_rx),
+            reply_streams,
+            accept_task,
+        }
+    }
+
+    /// The address of the remote end.
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.connection.remote_address()
+    }
+
+    /// Delivers every stream the remote opens until the connection ends.
+    async fn accept_streams(
+        connection: Connection,
+        inbound: mpsc::Sender<Result<Option<Vec<u8>>>>,
+        reply_streams: Arc<DashMap<u64, SendStream>>,
+    ) {
+        loop {
+            match connection.accept_bi().await {
+                Ok((send, recv)) => {
+                    tokio::spawn(Self::read_incoming(send, recv, inbound.clone(), reply_streams.clone()));
+                }
+                Err(ConnectionError::ApplicationClosed(_) | ConnectionError::LocallyClosed) => {
+                    let _ = inbound.send(Ok(None)).await;
+                    return;
+                }
+                Err(e) => {
+                    let _ = inbound.send(Err(Error::ConnectionLost(e.to_string()))).await;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Reads a remote-opened stream, parking its send half if it carries a call.
+    async fn read_incoming(
+        mut send: SendStream,
+        mut recv: RecvStream,
+        inbound: mpsc::Sender<Result<Option<Vec<u8>>>>,
+        reply_streams: Arc<DashMap<u64, SendStream>>,
+    ) {
+        let Ok(Some(first)) = read_message(&mut recv).await else { return };
+
+        // Park the stream before delivering the call, so the reply always finds it
+        match RpcFrame::decode(&first) {
+            Ok(RpcFrame::Call(call)) => { reply_streams.insert(call.seq, send); }
+            _ => { let _ = send.finish(); }
+        }
+
+        let mut next = Some(first);
+        while let Some(msg) = next {
+            if inbound.send(Ok(Some(msg))).await.is_err() {
+                return;
+            }
+            next = read_message(&mut recv).await.ok().flatten();
+        }
+    }
+
+    /// Reads the remote's messages on a stream we opened.
+    async fn read_outgoing(mut recv: RecvStream, inbound: mpsc::Sender<Result<Option<Vec<u8>>>>) {
+        while let Ok(Some(msg)) = read_message(&mut recv).await {
+            if inbound.send(Ok(Some(msg))).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for QuicTransport {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        self.connection.close(VarInt::from_u32(0), b"transport dropped");
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for QuicTransport {
+    async fn send(&self, payload: &[u8]) -> Result<()> {
+        if payload.len() > DEFAULT_MAX_MESSAGE_SIZE {
+            return Err(Error::PayloadTooLarge);
+        }
+
+        // Replies go back on the stream their call arrived on
+        if let Some((seq, last)) = reply_route(payload)
+            && let Some((_, mut stream)) = self.reply_streams.remove(&seq)
+        {
+            write_message(&mut stream, payload).await?;
+            if last {
+                let _ = stream.finish();
+            } else {
+                self.reply_streams.insert(seq, stream);
+            }
+            return Ok(());
+        }
+
+        let (mut send, recv) = self.connection.open_bi().await
+            .map_err(|e| Error::ConnectionLost(e.to_string()))?;
+        write_message(&mut send, payload).await?;
+        let _ = send.finish();
+
+        tokio::spawn(Self::read_outgoing(recv, self.inbound_tx.clone()));
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Option<Vec<u8>>> {
+        match self.inbound_rx.lock().await.recv().await {
+            Some(msg) => msg,
+            None => Ok(None),
+        }
+    }
+
+    fn descriptor(&self) -> TransportDescriptor {
+        TransportDescriptor::Quic {
+            addr: self.remote_addr().to_string(),
+            server_name: self.server_name.clone(),
+        }
+    }
+}
+
+/// Builds a server config presenting `cert_chain`, capped at `max_concurrent_streams`.
+pub fn server_config(
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+    max_concurrent_streams: u32,
+) -> Result<ServerConfig> {
+    let mut config = ServerConfig::with_single_cert(cert_chain, key)
+        .map_err(|e| Error::Io(format!("server config: {}", e)))?;
+    config.transport_config(stream_limits(max_concurrent_streams));
+    Ok(config)
+}
+
+/// Builds a client config trusting `roots`, capped at `max_concurrent_streams`.
+pub fn client_config(roots: RootCertStore, max_concurrent_streams: u32) -> Result<ClientConfig> {
+    let mut config = ClientConfig::with_root_certificates(Arc::new(roots))
+        .map_err(|e| Error::Io(format!("client config: {}", e)))?;
+    config.transport_config(stream_limits(max_concurrent_streams));
+    Ok(config)
+}
+
+fn stream_limits(max_concurrent_streams: u32) -> Arc<TransportConfig> {
+    let mut transport = TransportConfig::default();
+    transport.max_concurrent_bidi_streams(VarInt::from_u32(max_concurrent_streams));
+    transport.max_concurrent_uni_streams(VarInt::from_u32(0));
+    Arc::new(transport)
+}
+
+/// Returns the seq of a reply frame, and whether it is the last one for that call.
+fn reply_route(payload: &[u8]) -> Option<(u64, bool)> {
+    match RpcFrame::decode(payload).ok()? {
+        RpcFrame::Reply(reply) => Some((reply.seq, true)),
+        RpcFrame::ReplyChunk(chunk) => Some((chunk.seq, chunk.last)),
+        _ => None,
+    }
+}
+
+async fn write_message(stream: &mut SendStream, payload: &[u8]) -> Result<()> {
+    let header = (payload.len() as u32).to_le_bytes();
+    stream.write_all(&header).await.map_err(|e| Error::ConnectionLost(e.to_string()))?;
+    stream.write_all(payload).await.map_err(|e| Error::ConnectionLost(e.to_string()))?;
+    Ok(())
+}
+
+/// Reads one framed message, or `None` once the remote finishes the stream.
+async fn read_message(stream: &mut RecvStream) -> Result<Option<Vec<u8>>> {
+    let mut header = [0u8; 4];
+    match stream.read_exact(&mut header).await {
+        Ok(()) => {}
+        Err(quinn::ReadExactError::FinishedEarly(0)) => return Ok(None),
+        Err(e) => return Err(Error::ConnectionLost(e.to_string())),
+    }
+
+    let len = u32::from_le_bytes(header) as usize;
+    if len > DEFAULT_MAX_MESSAGE_SIZE {
+        let _ = stream.stop(VarInt::from_u32(0));
+        return Err(Error::PayloadTooLarge);
+    }
+
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body).await.map_err(|e| Error::ConnectionLost(e.to_string()))?;
+    Ok(Some(body))
+}
@@ -0,0 +1,171 @@
+//! # Length-prefixed TCP transport
+//!
+//! Moves messages over a TCP stream, each framed as a 4-byte LE length header
+//! followed by the body.
+//!
+//! ## Invariants
+//!
+//! - **Bounded Reads**: A header announcing more than `max_message_size` bytes is
+//!   rejected before anything is allocated, and the connection is dropped.
+//! - **Sticky Failure**: Once a read or write fails, the transport reports
+//!   `ConnectionLost` forever after. Reconnecting means dialing a fresh transport
+//!   and handing it to `Peer::reconnect`.
+
+use std::net::SocketAddr;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::sync::Mutex;
+
+use super::Error;
+use super::Result;
+use super::Transport;
+use super::TransportDescriptor;
+
+/// Default cap on a single message body (16 MiB).
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// A `Transport` over one TCP connection.
+pub struct TcpTransport {
+    peer_addr: SocketAddr,
+    max_message_size: usize,
+    reader: Mutex<Option<OwnedReadHalf>>,
+    writer: Mutex<Option<OwnedWriteHalf>>,
+}
+
+impl TcpTransport {
+    /// Dials `addr` and wraps the resulting stream.
+    pub async fn connect(addr: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await
+            .map_err(|e| Error::ConnectionLost(format!("connect {}: {}", addr, e)))?;
+        Self::from_stream(stream)
+    }
+
+    /// Binds a listener on `addr`; call `TcpAcceptor::accept` in a loop to take connections.
+    pub async fn listen(addr: &str) -> Result<TcpAcceptor> {
+        let listener = TcpListener::bind(addr).await
+            .map_err(|e| Error::Io(format!("bind {}: {}", addr, e)))?;
+        Ok(TcpAcceptor { listener, max_message_size: DEFAULT_MAX_MESSAGE_SIZE })
+    }
+
+    /// Wraps an already-connected stream.
+    pub fn from_stream(stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true).map_err(|e| Error::Io(e.to_string()))?;
+        let peer_addr = stream.peer_addr().map_err(|e| Error::Io(e.to_string()))?;
+        let (reader, writer) = stream.into_split();
+        Ok(Self {
+            peer_addr,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            reader: Mutex::new(Some(reader)),
+            writer: Mutex::new(Some(writer)),
+        })
+    }
+
+    /// Sets the largest message body this transport will send or accept.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size.min(u32::MAX as usize);
+        self
+    }
+
+    /// The address of the remote end.
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer_addr
+    }
+
+    fn lost(&self, why: impl std::fmt::Display) -> Error {
+        Error::ConnectionLost(format!("{}: {}", self.peer_addr, why))
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for TcpTransport {
+    async fn send(&self, payload: &[u8]) -> Result<()> {
+        if payload.len() > self.max_message_size {
+            return Err(Error::PayloadTooLarge);
+        }
+
+        let mut guard = self.writer.lock().await;
+        let writer = guard.as_mut().ok_or_else(|| self.lost("connection closed"))?;
+
+        let header = (payload.len() as u32).to_le_bytes();
+        let written = async {
+            writer.write_all(&header).await?;
+            writer.write_all(payload).await?;
+            writer.flush().await
+        }.await;
+
+        if let Err(e) = written {
+            *guard = None;
+            return Err(self.lost(e));
+        }
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Option<Vec<u8>>> {
+        let mut guard = self.reader.lock().await;
+        let Some(reader) = guard.as_mut() else {
+            return Err(self.lost("connection closed"));
+        };
+
+        // A clean EOF can only happen on a frame boundary.
+        let mut header = [0u8; 4];
+        let read = match reader.read(&mut header).await {
+            Ok(0) => {
+                *guard = None;
+                return Ok(None);
+            }
+            Ok(n) => reader.read_exact(&mut header[n..]).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = read {
+            *guard = None;
+            return Err(self.lost(e));
+        }
+
+        let len = u32::from_le_bytes(header) as usize;
+        if len > self.max_message_size {
+            *guard = None;
+            return Err(Error::PayloadTooLarge);
+        }
+
+        let mut body = vec![0u8; len];
+        if let Err(e) = reader.read_exact(&mut body).await {
+            *guard = None;
+            return Err(self.lost(e));
+        }
+        Ok(Some(body))
+    }
+
+    fn descriptor(&self) -> TransportDescriptor {
+        TransportDescriptor::Tcp(self.peer_addr.to_string())
+    }
+}
+
+/// Accepts inbound TCP connections as `TcpTransport`s.
+pub struct TcpAcceptor {
+    listener: TcpListener,
+    max_message_size: usize,
+}
+
+impl TcpAcceptor {
+    /// Sets the message size cap applied to every accepted transport.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// The address the listener is bound to.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Waits for the next connection.
+    pub async fn accept(&self) -> Result<TcpTransport> {
+        let (stream, _) = self.listener.accept().await.map_err(|e| Error::Io(e.to_string()))?;
+        Ok(TcpTransport::from_stream(stream)?.with_max_message_size(self.max_message_size))
+    }
+}
@@ -0,0 +1,176 @@
+//! # WebSocket transport
+//!
+//! Moves messages over a WebSocket connection, for runtimes hosted in a
+//! browser where raw TCP and QUIC are out of reach. Each message travels as
+//! one binary WebSocket message, so no framing of our own is needed and
+//! payloads are never read as UTF-8.
+//!
+//! ## Invariants
+//!
+//! - **Binary Only**: A text message from the remote is rejected with `Io`;
+//!   the connection stays up.
+//! - **Control Frames**: Pings are answered and pongs dropped inside `recv`,
+//!   so callers only ever see data messages.
+//! - **Sticky Failure**: Once the remote closes the connection, or a read or
+//!   write fails, the transport reports `ConnectionLost` forever after.
+//! - **Bounded Reads**: A message over `max_message_size` is refused by the
+//!   WebSocket layer before it is buffered whole, and the connection is dropped.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use futures_util::Sink;
+use futures_util::SinkExt;
+use futures_util::Stream;
+use futures_util::StreamExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+use super::Error;
+use super::Result;
+use super::Transport;
+use super::TransportDescriptor;
+use super::tcp::DEFAULT_MAX_MESSAGE_SIZE;
+
+type WsSink = Pin<Box<dyn Sink<Message, Error = tungstenite::Error> + Send>>;
+type WsStream = Pin<Box<dyn Stream<Item = tungstenite::Result<Message>> + Send>>;
+
+/// A `Transport` over one WebSocket connection.
+pub struct WebSocketTransport {
+    url: String,
+    max_message_size: usize,
+    sink: Mutex<Option<WsSink>>,
+    stream: Mutex<Option<WsStream>>,
+}
+
+impl WebSocketTransport {
+    /// Dials a `ws://` URL and completes the WebSocket handshake.
+    ///
+    /// `wss://` URLs are refused, as no TLS backend is built in.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let (ws, _) = tokio_tungstenite::connect_async_with_config(url, Some(config(DEFAULT_MAX_MESSAGE_SIZE)), true)
+            .await
+            .map_err(|e| Error::ConnectionLost(format!("connect {}: {}", url, e)))?;
+        Ok(Self::from_stream(ws, url))
+    }
+
+    /// Binds a listener on `addr`; call `WebSocketAcceptor::accept` in a loop to take connections.
+    pub async fn listen(addr: &str) -> Result<WebSocketAcceptor> {
+        let listener = TcpListener::bind(addr).await
+            .map_err(|e| Error::Io(format!("bind {}: {}", addr, e)))?;
+        Ok(WebSocketAcceptor { listener, max_message_size: DEFAULT_MAX_MESSAGE_SIZE })
+    }
+
+    /// Wraps a WebSocket that has already completed its handshake.
+    ///
+    /// `url` is where the remote end can be dialed, as reported by `descriptor`.
+    pub fn from_stream<S>(ws: WebSocketStream<S>, url: &str) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let max_message_size = ws.get_config().max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+        let (sink, stream) = ws.split();
+        Self {
+            url: url.to_string(),
+            max_message_size,
+            sink: Mutex::new(Some(Box::pin(sink))),
+            stream: Mutex::new(Some(Box::pin(stream))),
+        }
+    }
+
+    /// The URL of the remote end.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn lost(&self, why: impl std::fmt::Display) -> Error {
+        Error::ConnectionLost(format!("{}: {}", self.url, why))
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for WebSocketTransport {
+    async fn send(&self, payload: &[u8]) -> Result<()> {
+        if payload.len() > self.max_message_size {
+            return Err(Error::PayloadTooLarge);
+        }
+
+        let mut guard = self.sink.lock().await;
+        let sink = guard.as_mut().ok_or_else(|| self.lost("connection closed"))?;
+        if let Err(e) = sink.send(Message::binary(payload.to_vec())).await {
+            *guard = None;
+            return Err(self.lost(e));
+        }
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Option<Vec<u8>>> {
+        let mut guard = self.stream.lock().await;
+        let Some(stream) = guard.as_mut() else {
+            return Err(self.lost("connection closed"));
+        };
+
+        loop {
+            let why = match stream.next().await {
+                Some(Ok(Message::Binary(bytes))) => return Ok(Some(bytes.to_vec())),
+                Some(Ok(Message::Text(_))) => return Err(Error::Io("expected a binary message, got text".into())),
+                // Pongs for pings are queued by tungstenite and go out with the next read or write
+                Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => continue,
+                Some(Ok(Message::Close(Some(frame)))) => format!("closed by remote: {} {}", frame.code, frame.reason),
+                Some(Ok(Message::Close(None))) | None => "closed by remote".to_string(),
+                Some(Err(tungstenite::Error::Capacity(_))) => {
+                    *guard = None;
+                    return Err(Error::PayloadTooLarge);
+                }
+                Some(Err(e)) => e.to_string(),
+            };
+            *guard = None;
+            return Err(self.lost(why));
+        }
+    }
+
+    fn descriptor(&self) -> TransportDescriptor {
+        TransportDescriptor::WebSocket(self.url.clone())
+    }
+}
+
+/// Accepts inbound WebSocket connections as `WebSocketTransport`s.
+pub struct WebSocketAcceptor {
+    listener: TcpListener,
+    max_message_size: usize,
+}
+
+impl WebSocketAcceptor {
+    /// Sets the message size cap applied to every accepted transport.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// The address the listener is bound to.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Waits for the next connection and completes its handshake.
+    pub async fn accept(&self) -> Result<WebSocketTransport> {
+        let (stream, peer_addr) = self.listener.accept().await.map_err(|e| Error::Io(e.to_string()))?;
+        stream.set_nodelay(true).map_err(|e| Error::Io(e.to_string()))?;
+        let ws = tokio_tungstenite::accept_async_with_config(stream, Some(config(self.max_message_size)))
+            .await
+            .map_err(|e| Error::ConnectionLost(format!("handshake with {}: {}", peer_addr, e)))?;
+        Ok(WebSocketTransport::from_stream(ws, &format!("ws://{}", peer_addr)))
+    }
+}
+
+fn config(max_message_size: usize) -> WebSocketConfig {
+    WebSocketConfig::default()
+        .max_message_size(Some(max_message_size))
+        .max_frame_size(Some(max_message_size))
+}
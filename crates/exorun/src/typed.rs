@@ -0,0 +1,250 @@
+//! # Typed calls: Rust values in, Rust values out
+//!
+//! `Runtime::call` speaks `Val`, which is flexible but noisy at call sites.
+//! The traits here let `Runtime::call_typed` take a tuple of Rust values
+//! and hand back a Rust value, checked against the function's `Ledger`
+//! signature before anything runs.
+//!
+//! ## Shapes
+//!
+//! - Params are a tuple: `()` for no params, `(a,)` for one, `(a, b)` for two, and so on.
+//! - Results are `()` for a function with no result, or any `TypedVal` for one result.
+//!   A component function has at most one result, so a tuple here means a tuple-typed result.
+
+use wasmtime::component::Type;
+use wasmtime::component::Val;
+
+/// A Rust type with a component-model counterpart.
+pub trait TypedVal: Sized {
+    /// Whether a value of this type can stand in for `ty`.
+    fn matches(ty: &Type) -> bool;
+    fn into_val(self) -> Val;
+    /// Converts back, or `None` if `val` has a different shape.
+    fn from_val(val: Val) -> Option<Self>;
+}
+
+/// A list of params, as passed to `Runtime::call_typed`.
+pub trait IntoVals {
+    fn matches(types: &[Type]) -> bool;
+    fn into_vals(self) -> Vec<Val>;
+}
+
+/// The results of a call, as returned by `Runtime::call_typed`.
+pub trait FromVals: Sized {
+    fn matches(types: &[Type]) -> bool;
+    fn from_vals(vals: Vec<Val>) -> Option<Self>;
+}
+
+macro_rules! scalar {
+    ($($rust:ty => $variant:ident),* $(,)?) => {$(
+        impl TypedVal for $rust {
+            fn matches(ty: &Type) -> bool {
+                matches!(ty, Type::$variant)
+            }
+
+            fn into_val(self) -> Val {
+                Val::$variant(self)
+            }
+
+            fn from_val(val: Val) -> Option<Self> {
+                match val {
+                    Val::$variant(v) => Some(v),
+                    _ => None,
+                }
+            }
+        }
+    )*};
+}
+
+scalar! {
+    bool => Bool,
+    i8 => S8,
+    u8 => U8,
+    i16 => S16,
+    u16 => U16,
+    i32 => S32,
+    u32 => U32,
+    i64 => S64,
+    u64 => U64,
+    f32 => Float32,
+    f64 => Float64,
+    char => Char,
+    String => String,
+}
+
+impl<T: TypedVal> TypedVal for Vec<T> {
+    fn matches(ty: &Type) -> bool {
+        matches!(ty, Type::List(list) if T::matches(&list.ty()))
+    }
+
+    fn into_val(self) -> Val {
+        Val::List(self.into_iter().map(T::into_val).collect())
+    }
+
+    fn from_val(val: Val) -> Option<Self> {
+        match val {
+            Val::List(items) => items.into_iter().map(T::from_val).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: TypedVal> TypedVal for Option<T> {
+    fn matches(ty: &Type) -> bool {
+        matches!(ty, Type::Option(opt) if T::matches(&opt.ty()))
+    }
+
+    fn into_val(self) -> Val {
+        Val::Option(self.map(|v| Box::new(v.into_val())))
+    }
+
+    fn from_val(val: Val) -> Option<Self> {
+        match val {
+            Val::Option(None) => Some(None),
+            Val::Option(Some(v)) => T::from_val(*v).map(Some),
+            _ => None,
+        }
+    }
+}
+
+impl IntoVals for () {
+    fn matches(types: &[Type]) -> bool {
+        types.is_empty()
+    }
+
+    fn into_vals(self) -> Vec<Val> {
+        Vec::new()
+    }
+}
+
+impl FromVals for () {
+    fn matches(types: &[Type]) -> bool {
+        types.is_empty()
+    }
+
+    fn from_vals(vals: Vec<Val>) -> Option<Self> {
+        vals.is_empty().then_some(())
+    }
+}
+
+impl<T: TypedVal> FromVals for T {
+    fn matches(types: &[Type]) -> bool {
+        matches!(types, [ty] if T::matches(ty))
+    }
+
+    fn from_vals(vals: Vec<Val>) -> Option<Self> {
+        let [val] = <[Val; 1]>::try_from(vals).ok()?;
+        T::from_val(val)
+    }
+}
+
+macro_rules! tuple {
+    ($len:literal; $($name:ident $var:ident $idx:tt),+) => {
+        impl<$($name: TypedVal),+> IntoVals for ($($name,)+) {
+            fn matches(types: &[Type]) -> bool {
+                types.len() == $len && $($name::matches(&types[$idx]))&&+
+            }
+
+            fn into_vals(self) -> Vec<Val> {
+                vec![$(self.$idx.into_val()),+]
+            }
+        }
+
+        impl<$($name: TypedVal),+> TypedVal for ($($name,)+) {
+            fn matches(ty: &Type) -> bool {
+                let Type::Tuple(tuple) = ty else { return false };
+                let types: Vec<Type> = tuple.types().collect();
+                <Self as IntoVals>::matches(&types)
+            }
+
+            fn into_val(self) -> Val {
+                Val::Tuple(self.into_vals())
+            }
+
+            fn from_val(val: Val) -> Option<Self> {
+                let Val::Tuple(vals) = val else { return None };
+                let [$($var),+] = <[Val; $len]>::try_from(vals).ok()?;
+                Some(($($name::from_val($var)?,)+))
+            }
+        }
+    };
+}
+
+tuple!(1; A a 0);
+tuple!(2; A a 0, B b 1);
+tuple!(3; A a 0, B b 1, C c 2);
+tuple!(4; A a 0, B b 1, C c 2, D d 3);
+tuple!(5; A a 0, B b 1, C c 2, D d 3, E e 4);
+tuple!(6; A a 0, B b 1, C c 2, D d 3, E e 4, F f 5);
+
+/// Renders a list of types the way WIT would, for error messages.
+pub(crate) fn describe(types: &[Type]) -> String {
+    let names: Vec<String> = types.iter().map(describe_one).collect();
+    format!("({})", names.join(", "))
+}
+
+fn describe_one(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".into(),
+        Type::S8 => "s8".into(),
+        Type::U8 => "u8".into(),
+        Type::S16 => "s16".into(),
+        Type::U16 => "u16".into(),
+        Type::S32 => "s32".into(),
+        Type::U32 => "u32".into(),
+        Type::S64 => "s64".into(),
+        Type::U64 => "u64".into(),
+        Type::Float32 => "f32".into(),
+        Type::Float64 => "f64".into(),
+        Type::Char => "char".into(),
+        Type::String => "string".into(),
+        Type::List(list) => format!("list<{}>", describe_one(&list.ty())),
+        Type::Option(opt) => format!("option<{}>", describe_one(&opt.ty())),
+        Type::Tuple(tuple) => {
+            let names: Vec<String> = tuple.types().map(|t| describe_one(&t)).collect();
+            format!("tuple<{}>", names.join(", "))
+        }
+        Type::Record(_) => "record".into(),
+        Type::Variant(_) => "variant".into(),
+        Type::Enum(_) => "enum".into(),
+        Type::Result(_) => "result".into(),
+        Type::Flags(_) => "flags".into(),
+        Type::Own(_) | Type::Borrow(_) => "resource".into(),
+        Type::Future(_) => "future".into(),
+        Type::Stream(_) => "stream".into(),
+        Type::ErrorContext => "error-context".into(),
+    }
+}
+
+/// Whether `val` has the outer shape of `ty`, for checking `Runtime::call`
+/// arguments before entering the guest. Nested values are left to wasmtime.
+pub(crate) fn shape_matches(ty: &Type, val: &Val) -> bool {
+    matches!(
+        (ty, val),
+        (Type::Bool, Val::Bool(_))
+            | (Type::S8, Val::S8(_))
+            | (Type::U8, Val::U8(_))
+            | (Type::S16, Val::S16(_))
+            | (Type::U16, Val::U16(_))
+            | (Type::S32, Val::S32(_))
+            | (Type::U32, Val::U32(_))
+            | (Type::S64, Val::S64(_))
+            | (Type::U64, Val::U64(_))
+            | (Type::Float32, Val::Float32(_))
+            | (Type::Float64, Val::Float64(_))
+            | (Type::Char, Val::Char(_))
+            | (Type::String, Val::String(_))
+            | (Type::List(_), Val::List(_))
+            | (Type::Record(_), Val::Record(_))
+            | (Type::Tuple(_), Val::Tuple(_))
+            | (Type::Variant(_), Val::Variant(..))
+            | (Type::Enum(_), Val::Enum(_))
+            | (Type::Option(_), Val::Option(_))
+            | (Type::Result(_), Val::Result(_))
+            | (Type::Flags(_), Val::Flags(_))
+            | (Type::Own(_) | Type::Borrow(_), Val::Resource(_))
+            | (Type::Future(_), Val::Future(_))
+            | (Type::Stream(_), Val::Stream(_))
+            | (Type::ErrorContext, Val::ErrorContext(_))
+    )
+}
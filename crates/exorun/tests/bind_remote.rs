@@ -0,0 +1,138 @@
+//! Regression tests for remote links decoding replies by the import's declared types.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use neorpc::ReplyOkEncoder;
+use neorpc::RpcFrame;
+use neorpc::encode_vals_to_bytes;
+use tokio::sync::{Mutex, Notify};
+use wasmtime::component::Val;
+
+use exorun::peer::{Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::{self, Transport};
+
+/// Answers every call with the same results, delivered in order.
+struct FixedReplyTransport {
+    results: Vec<Val>,
+    replies: Mutex<VecDeque<Vec<u8>>>,
+    ready: Notify,
+}
+
+impl FixedReplyTransport {
+    fn new(results: Vec<Val>) -> Self {
+        Self { results, replies: Mutex::new(VecDeque::new()), ready: Notify::new() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for FixedReplyTransport {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        let Ok(RpcFrame::Call(call)) = RpcFrame::decode(payload) else {
+            return Err(transport::Error::Io("Expected Call".into()));
+        };
+        let results = encode_vals_to_bytes(&self.results).map_err(|e| transport::Error::Io(e.to_string()))?;
+        let reply = ReplyOkEncoder::new(call.seq, &results)
+            .into_bytes()
+            .map_err(|e| transport::Error::Io(e.to_string()))?;
+        self.replies.lock().await.push_back(reply);
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(reply) = self.replies.lock().await.pop_front() {
+                return Ok(Some(reply));
+            }
+            self.ready.notified().await;
+        }
+    }
+}
+
+/// Instantiates `wat` with `interface` linked to a peer answering with `results`.
+async fn instantiate_with_remote(wat: &str, interface: &str, results: Vec<Val>) -> (Arc<Runtime>, exorun::InstanceId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let peer = Peer::new("remote", Box::new(FixedReplyTransport::new(results)), PeerConfig::default());
+    let peer_id = rt.add_peer(Arc::new(peer));
+
+    let component_id = rt.add_component_bytes(wat.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_remote(interface, peer_id.get_instance("target"))
+        .build()
+        .await
+        .expect("instantiate");
+    (rt, instance_id)
+}
+
+/// Imports `test:remote/api.neg() -> s32` and re-exports it as `run`.
+const NEGATIVE_S32_WAT: &str = r#"
+    (component
+        (import "test:remote/api" (instance $remote
+            (export "neg" (func (result s32)))))
+        (core func $neg (canon lower (func $remote "neg")))
+        (core module $m
+            (import "remote" "neg" (func $neg (result i32)))
+            (func (export "run") (result i32) (call $neg)))
+        (core instance $i (instantiate $m
+            (with "remote" (instance (export "neg" (func $neg))))))
+        (func $run (result s32) (canon lift (core func $i "run")))
+        (instance $api (export "run" (func $run)))
+        (export "test:local/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_remote_negative_s32_survives() {
+    let (rt, instance_id) = instantiate_with_remote(
+        NEGATIVE_S32_WAT,
+        "test:remote/api",
+        vec![Val::S32(-42)],
+    ).await;
+
+    let results = rt.call(instance_id, "test:local/api", "run", &[]).await.expect("call through remote link");
+    assert_eq!(results, vec![Val::S32(-42)]);
+}
+
+/// Imports `test:remote/api.triple() -> tuple<bool, string, s32>` and re-exports it as `run`.
+const TRIPLE_WAT: &str = r#"
+    (component
+        (import "test:remote/api" (instance $remote
+            (export "triple" (func (result (tuple bool string s32))))))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $triple (canon lower (func $remote "triple") (memory $memory) (realloc $realloc)))
+        (core module $m
+            (import "env" "memory" (memory 1))
+            (import "remote" "triple" (func $triple (param i32)))
+            (func (export "run") (result i32)
+                (call $triple (i32.const 512))
+                (i32.const 512)))
+        (core instance $i (instantiate $m
+            (with "env" (instance (export "memory" (memory $memory))))
+            (with "remote" (instance (export "triple" (func $triple))))))
+
+        (func $run (result (tuple bool string s32)) (canon lift (core func $i "run") (memory $memory)))
+        (instance $api (export "run" (func $run)))
+        (export "test:local/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_remote_mixed_results_keep_their_types() {
+    let reply = Val::Tuple(vec![Val::Bool(true), Val::String("hi".into()), Val::S32(-7)]);
+    let (rt, instance_id) = instantiate_with_remote(TRIPLE_WAT, "test:remote/api", vec![reply.clone()]).await;
+
+    let results = rt.call(instance_id, "test:local/api", "run", &[]).await.expect("call through remote link");
+    assert_eq!(results, vec![reply]);
+}
@@ -0,0 +1,110 @@
+//! Tests for exporting a runtime's components and importing them elsewhere.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::sync::Notify;
+use wasmtime::component::Val;
+
+use exorun::bootstrap::BootstrapBundle;
+use exorun::runtime::{self, Runtime};
+use exorun::transport::{self, Transport, TransportDescriptor};
+
+/// Stands in for the link back to the origin; nothing is sent in these tests.
+struct IdleTransport {
+    closed: Notify,
+}
+
+#[async_trait::async_trait]
+impl Transport for IdleTransport {
+    async fn send(&self, _payload: &[u8]) -> transport::Result<()> {
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        self.closed.notified().await;
+        Ok(None)
+    }
+}
+
+/// Exports `test:answer/api.get() -> s32` returning 42.
+const ANSWER_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "get") (result i32) (i32.const 42)))
+        (core instance $i (instantiate $m))
+        (func $get (result s32) (canon lift (core func $i "get")))
+        (instance $api (export "get" (func $get)))
+        (export "test:answer/api" (instance $api)))
+"#;
+
+fn origin() -> TransportDescriptor {
+    TransportDescriptor::Tcp("origin.example:7000".into())
+}
+
+/// Imports `bundle` into `rt`, counting how many times the origin is dialed.
+async fn import(rt: &Arc<Runtime>, bundle: BootstrapBundle, dials: &AtomicUsize) -> runtime::BootstrapImport {
+    rt.import_bootstrap(bundle, |desc| async move {
+        assert_eq!(desc, origin());
+        dials.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(IdleTransport { closed: Notify::new() }) as Box<dyn Transport>)
+    })
+    .await
+    .expect("import bundle")
+}
+
+#[tokio::test]
+async fn test_bootstrap_roundtrip_runs_origin_component() {
+    let a = Runtime::new().expect("runtime creation failed");
+    let component_a = a.add_component_bytes(ANSWER_WAT.as_bytes()).expect("add component");
+
+    let bundle = a.export_bootstrap().expect("export").with_origin(origin());
+    let wire = bundle.to_bytes().expect("encode bundle");
+    let decoded = BootstrapBundle::from_bytes(&wire).expect("decode bundle");
+    assert_eq!(decoded, bundle);
+
+    let b = Runtime::new().expect("runtime creation failed");
+    let dials = AtomicUsize::new(0);
+    let imported = import(&b, decoded, &dials).await;
+
+    let component_b = imported.components[&component_a];
+    let origin = imported.origin.expect("origin peer");
+    assert!(b.get_peer(origin).is_ok());
+
+    let instance = b.instantiate(component_b).build().await.expect("instantiate");
+    let results = b.call(instance, "test:answer/api", "get", &[]).await.expect("call");
+    assert_eq!(results, vec![Val::S32(42)]);
+}
+
+#[tokio::test]
+async fn test_bootstrap_reimport_is_idempotent() {
+    let a = Runtime::new().expect("runtime creation failed");
+    a.add_component_bytes(ANSWER_WAT.as_bytes()).expect("add component");
+    let bundle = a.export_bootstrap().expect("export").with_origin(origin());
+
+    let b = Runtime::new().expect("runtime creation failed");
+    let dials = AtomicUsize::new(0);
+    let first = import(&b, bundle.clone(), &dials).await;
+    let second = import(&b, bundle, &dials).await;
+
+    assert_eq!(first.components, second.components);
+    assert_eq!(first.origin, second.origin);
+    assert_eq!(dials.load(Ordering::SeqCst), 1);
+    assert_eq!(b.export_bootstrap().expect("export").components.len(), 1);
+}
+
+#[tokio::test]
+async fn test_bootstrap_rejects_tampered_component() {
+    let a = Runtime::new().expect("runtime creation failed");
+    a.add_component_bytes(ANSWER_WAT.as_bytes()).expect("add component");
+    let mut bundle = a.export_bootstrap().expect("export");
+    bundle.components[0].bytes.push(b' ');
+
+    let b = Runtime::new().expect("runtime creation failed");
+    let result = b.import_bootstrap(bundle, |_| async {
+        Err::<Box<dyn Transport>, _>(transport::Error::Io("no origin".into()))
+    }).await;
+
+    assert!(matches!(result, Err(runtime::Error::ContentMismatch(_))));
+    assert!(b.export_bootstrap().expect("export").components.is_empty());
+}
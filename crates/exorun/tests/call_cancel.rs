@@ -0,0 +1,85 @@
+//! Tests for cancelling calls with `Runtime::call_with_cancel`.
+
+use std::time::Duration;
+
+use exorun::CancellationToken;
+use exorun::runtime::{Error, Runtime};
+use wasmtime::component::Val;
+
+/// Exports `test:spin/api` with `spin`, which never returns, and `ping`, which returns 1.
+const SPIN_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "spin") (loop $l (br $l)))
+            (func (export "ping") (result i32) (i32.const 1)))
+        (core instance $i (instantiate $m))
+        (func $spin (canon lift (core func $i "spin")))
+        (func $ping (result u32) (canon lift (core func $i "ping")))
+        (instance $api
+            (export "spin" (func $spin))
+            (export "ping" (func $ping)))
+        (export "test:spin/api" (instance $api)))
+"#;
+
+const API: &str = "test:spin/api";
+
+#[tokio::test]
+async fn test_cancel_interrupts_running_call() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPIN_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let cancel = CancellationToken::new();
+    let call = tokio::spawn({
+        let rt = rt.clone();
+        let cancel = cancel.clone();
+        async move { rt.call_with_cancel(instance_id, API, "spin", &[], cancel).await }
+    });
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    cancel.cancel();
+
+    let outcome = tokio::time::timeout(Duration::from_secs(5), call)
+        .await
+        .expect("cancelled call returns")
+        .expect("call task");
+    assert!(matches!(outcome, Err(Error::Cancelled)), "got {:?}", outcome);
+}
+
+#[tokio::test]
+async fn test_cancelled_before_start_leaves_instance_usable() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPIN_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let outcome = rt.call_with_cancel(instance_id, API, "spin", &[], cancel).await;
+    assert!(matches!(outcome, Err(Error::Cancelled)));
+
+    // An uncancelled token doesn't get in the way
+    let results = rt.call_with_cancel(instance_id, API, "ping", &[], CancellationToken::new())
+        .await
+        .expect("ping");
+    assert_eq!(results, vec![Val::U32(1)]);
+}
+
+#[tokio::test]
+async fn test_interrupted_call_poisons_instance() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPIN_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let cancel = CancellationToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+    let outcome = rt.call_with_cancel(instance_id, API, "spin", &[], cancel).await;
+    assert!(matches!(outcome, Err(Error::Cancelled)));
+
+    // Wasmtime can't re-enter the interrupted instance, and the error says so
+    let after = rt.call(instance_id, API, "ping", &[]).await;
+    assert!(matches!(after, Err(Error::InstancePoisoned(id)) if id == instance_id), "got {:?}", after);
+}
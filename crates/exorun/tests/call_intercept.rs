@@ -0,0 +1,100 @@
+//! Tests for `CallInterceptor` hooks attached with `InstanceBuilder::intercept`.
+
+use std::sync::{Arc, Mutex};
+
+use exorun::CallInterceptor;
+use exorun::InstanceId;
+use exorun::peer::{self, Peer, PeerConfig};
+use exorun::runtime::{self, Runtime};
+use exorun::transport::LocalTransport;
+use neorpc::FailureReason;
+use wasmtime::component::{Type, Val};
+
+/// Exports `test:admin/api` with `read`, returning 7, and `admin`, which traps.
+const ADMIN_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "read") (result i32) (i32.const 7))
+            (func (export "admin") (result i32) unreachable))
+        (core instance $i (instantiate $m))
+        (func $read (result u32) (canon lift (core func $i "read")))
+        (func $admin (result u32) (canon lift (core func $i "admin")))
+        (instance $api
+            (export "read" (func $read))
+            (export "admin" (func $admin)))
+        (export "test:admin/api" (instance $api)))
+"#;
+
+const API: &str = "test:admin/api";
+
+/// Refuses `admin`, and logs every call it lets through.
+#[derive(Default)]
+struct Guard {
+    log: Mutex<Vec<String>>,
+}
+
+impl CallInterceptor for Guard {
+    fn before(&self, method: &str, _args: &[Val]) -> Result<(), FailureReason> {
+        match method {
+            "admin" => Err(FailureReason::DomainSpecific(403, "unauthorized".into())),
+            _ => Ok(()),
+        }
+    }
+
+    fn after(&self, method: &str, outcome: &runtime::Result<Vec<Val>>) {
+        self.log.lock().unwrap().push(format!("{} ok={}", method, outcome.is_ok()));
+    }
+}
+
+async fn guarded(rt: &Arc<Runtime>, guard: Arc<Guard>) -> InstanceId {
+    let component_id = rt.add_component_bytes(ADMIN_WAT.as_bytes()).expect("add component");
+    rt.instantiate(component_id)
+        .intercept(API, guard)
+        .build()
+        .await
+        .expect("instantiate")
+}
+
+#[tokio::test]
+async fn test_interceptor_blocks_admin_and_allows_read() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let guard = Arc::new(Guard::default());
+    let instance = guarded(&rt, Arc::clone(&guard)).await;
+
+    let err = rt.call(instance, API, "admin", &[]).await.expect_err("admin is blocked");
+    match err {
+        runtime::Error::Rejected(FailureReason::DomainSpecific(403, _)) => {}
+        other => panic!("Expected Rejected, got {:?}", other),
+    }
+
+    // Had the trapping guest run, the instance would now be poisoned
+    let results = rt.call(instance, API, "read", &[]).await.expect("read");
+    assert_eq!(results, vec![Val::U32(7)]);
+    assert_eq!(rt.instance_metrics(instance).await.expect("metrics").call_count, 1);
+    assert_eq!(*guard.log.lock().unwrap(), vec!["read ok=true".to_string()]);
+}
+
+#[tokio::test]
+async fn test_interceptor_rejection_is_served_as_domain_error() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let guard = Arc::new(Guard::default());
+    let instance = guarded(&rt, Arc::clone(&guard)).await;
+
+    let (ours, theirs) = LocalTransport::pair();
+    let peer_id = rt.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    rt.serve_peer(peer_id, instance).expect("serve");
+    let remote = Peer::new("server", Box::new(theirs), PeerConfig::default());
+
+    let err = remote.call(API, "admin", &[], vec![Type::U32]).await.expect_err("admin is blocked");
+    match err {
+        peer::Error::Remote(FailureReason::DomainSpecific(code, message)) => {
+            assert_eq!(code, 403);
+            assert_eq!(message, "unauthorized");
+        }
+        other => panic!("Expected a domain failure, got {:?}", other),
+    }
+
+    let results = remote.call(API, "read", &[], vec![Type::U32]).await.expect("read");
+    assert_eq!(results, vec![Val::U32(7)]);
+    assert_eq!(*guard.log.lock().unwrap(), vec!["read ok=true".to_string()]);
+}
@@ -0,0 +1,98 @@
+//! Tests for bounding calls with `Runtime::call_with_timeout`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use wasmtime::component::Linker;
+use wasmtime::component::Val;
+
+use exorun::context::ExorunCtx;
+use exorun::host::{self, SystemComponent};
+use exorun::runtime::{Error, Runtime};
+
+/// Links `test:hang/host`, whose `wait` never returns.
+struct Hang;
+
+impl SystemComponent for Hang {
+    fn interfaces(&self) -> &[&str] { &["test:hang/host"] }
+
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> host::Result<()> {
+        let mut instance = linker.instance("test:hang/host")?;
+        instance.func_new_async("wait", |_, _, _, _| Box::new(std::future::pending()))?;
+        Ok(())
+    }
+}
+
+/// Exports `test:hang/api` with `wait`, which calls the hanging import,
+/// `spin`, which loops forever, and `ping`, which returns 1.
+const HANG_WAT: &str = r#"
+    (component
+        (import "test:hang/host" (instance $host
+            (export "wait" (func))))
+        (core func $wait (canon lower (func $host "wait")))
+        (core module $m
+            (import "host" "wait" (func $wait))
+            (func (export "wait") (call $wait))
+            (func (export "spin") (loop $l (br $l)))
+            (func (export "ping") (result i32) (i32.const 1)))
+        (core instance $i (instantiate $m
+            (with "host" (instance (export "wait" (func $wait))))))
+        (func $wait_export (canon lift (core func $i "wait")))
+        (func $spin (canon lift (core func $i "spin")))
+        (func $ping (result u32) (canon lift (core func $i "ping")))
+        (instance $api
+            (export "wait" (func $wait_export))
+            (export "spin" (func $spin))
+            (export "ping" (func $ping)))
+        (export "test:hang/api" (instance $api)))
+"#;
+
+const API: &str = "test:hang/api";
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+async fn instance(rt: &Arc<Runtime>) -> exorun::InstanceId {
+    let component_id = rt.add_component_bytes(HANG_WAT.as_bytes()).expect("add component");
+    rt.instantiate(component_id)
+        .link_system_shared("test:hang/host", Arc::new(Hang))
+        .build()
+        .await
+        .expect("instantiate")
+}
+
+#[tokio::test]
+async fn test_timeout_fires_on_hanging_import() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let instance_id = instance(&rt).await;
+
+    let outcome = tokio::time::timeout(Duration::from_secs(5), rt.call_with_timeout(instance_id, API, "wait", &[], TIMEOUT))
+        .await
+        .expect("timed out call returns");
+    assert!(matches!(outcome, Err(Error::Timeout)), "got {:?}", outcome);
+
+    // The abandoned call left the instance mid-execution, so it is refused
+    let after = rt.call(instance_id, API, "ping", &[]).await;
+    assert!(matches!(after, Err(Error::InstancePoisoned(id)) if id == instance_id), "got {:?}", after);
+}
+
+#[tokio::test]
+async fn test_timeout_fires_on_spinning_guest() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let instance_id = instance(&rt).await;
+
+    let outcome = tokio::time::timeout(Duration::from_secs(5), rt.call_with_timeout(instance_id, API, "spin", &[], TIMEOUT))
+        .await
+        .expect("timed out call returns");
+    assert!(matches!(outcome, Err(Error::Timeout)), "got {:?}", outcome);
+    assert!(matches!(rt.call(instance_id, API, "ping", &[]).await, Err(Error::InstancePoisoned(_))));
+}
+
+#[tokio::test]
+async fn test_call_within_timeout_leaves_instance_usable() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let instance_id = instance(&rt).await;
+
+    let results = rt.call_with_timeout(instance_id, API, "ping", &[], TIMEOUT).await.expect("ping");
+    assert_eq!(results, vec![Val::U32(1)]);
+    let results = rt.call(instance_id, API, "ping", &[]).await.expect("ping after");
+    assert_eq!(results, vec![Val::U32(1)]);
+}
@@ -0,0 +1,64 @@
+//! Tests for telling trapped calls apart by `TrapKind`.
+
+use std::sync::Arc;
+
+use exorun::Runtime;
+use exorun::runtime::{Error, TrapKind};
+use wasmtime::component::Val;
+
+/// Exports `test:traps/api`, whose functions each trap a different way.
+const TRAPS_WAT: &str = r#"
+    (component
+        (core module $m
+            (memory 1)
+            (func (export "unreachable") unreachable)
+            (func (export "load") (result i32) (i32.load (i32.const 0x10000)))
+            (func (export "divide") (param i32) (result i32) (i32.div_u (i32.const 1) (local.get 0))))
+        (core instance $i (instantiate $m))
+        (func $unreachable (canon lift (core func $i "unreachable")))
+        (func $load (result u32) (canon lift (core func $i "load")))
+        (func $divide (param "by" u32) (result u32) (canon lift (core func $i "divide")))
+        (instance $api
+            (export "unreachable" (func $unreachable))
+            (export "load" (func $load))
+            (export "divide" (func $divide)))
+        (export "test:traps/api" (instance $api)))
+"#;
+
+/// Calls `function` on a fresh instance, since a trap poisons the one it hits.
+async fn trap(rt: &Arc<Runtime>, function: &str, args: &[Val]) -> Error {
+    let component_id = rt.add_component_bytes(TRAPS_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+    rt.call(instance_id, "test:traps/api", function, args).await.unwrap_err()
+}
+
+#[tokio::test]
+async fn test_unreachable_trap_kind() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let err = trap(&rt, "unreachable", &[]).await;
+    assert!(matches!(err, Error::Trap(TrapKind::Unreachable)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn test_trap_kinds_follow_trap_code() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let err = trap(&rt, "load", &[]).await;
+    assert!(matches!(err, Error::Trap(TrapKind::MemoryOutOfBounds)), "got {:?}", err);
+    let err = trap(&rt, "divide", &[Val::U32(0)]).await;
+    assert!(matches!(err, Error::Trap(TrapKind::IntegerDivisionByZero)), "got {:?}", err);
+}
+
+#[tokio::test]
+async fn test_bad_arguments_do_not_poison() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(TRAPS_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let err = rt.call(instance_id, "test:traps/api", "divide", &[]).await.unwrap_err();
+    assert!(matches!(err, Error::Signature { .. }), "got {:?}", err);
+    let err = rt.call(instance_id, "test:traps/api", "divide", &[Val::String("two".into())]).await.unwrap_err();
+    assert!(matches!(err, Error::Signature { .. }), "got {:?}", err);
+
+    let results = rt.call(instance_id, "test:traps/api", "divide", &[Val::U32(1)]).await.expect("call after bad args");
+    assert_eq!(results, vec![Val::U32(1)]);
+}
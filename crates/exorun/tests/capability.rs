@@ -0,0 +1,61 @@
+//! Tests for expiring capabilities checked by `Runtime::verify_capability`.
+
+use exorun::cap::{self, Capability};
+use exorun::host::Auth;
+use exorun::runtime::Runtime;
+
+const SCOPE: &str = "community:invite";
+
+#[test]
+fn test_valid_capability_verifies() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    rt.trust_capability_issuer(&issuer.public);
+
+    let cap = Capability::mint(&issuer.secret, SCOPE, cap::now() + 3600).expect("mint");
+    rt.verify_capability(&cap).expect("valid capability");
+}
+
+#[test]
+fn test_expired_capability_is_rejected() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    rt.trust_capability_issuer(&issuer.public);
+
+    let expires_at = cap::now() - 10;
+    let cap = Capability::mint(&issuer.secret, SCOPE, expires_at).expect("mint");
+    match rt.verify_capability(&cap) {
+        Err(cap::Error::Expired { expires_at: at, now }) => assert!(at == expires_at && now >= at),
+        res => panic!("Expected Expired, got {:?}", res),
+    }
+}
+
+#[test]
+fn test_tampered_capability_is_rejected() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    rt.trust_capability_issuer(&issuer.public);
+    let cap = Capability::mint(&issuer.secret, SCOPE, cap::now() + 3600).expect("mint");
+
+    let extended = Capability { expires_at: cap.expires_at + 3600, ..cap.clone() };
+    assert_eq!(rt.verify_capability(&extended), Err(cap::Error::BadSignature));
+
+    let widened = Capability { scope: "community:admin".into(), ..cap.clone() };
+    assert_eq!(rt.verify_capability(&widened), Err(cap::Error::BadSignature));
+
+    // Tampering wins over expiry, so a forged deadline in the past isn't reported as merely expired
+    let backdated = Capability { expires_at: 1, ..cap.clone() };
+    assert_eq!(rt.verify_capability(&backdated), Err(cap::Error::BadSignature));
+
+    let forged = Capability::mint(&Auth::generate().secret, SCOPE, cap.expires_at).expect("mint");
+    assert_eq!(rt.verify_capability(&forged), Err(cap::Error::BadSignature));
+}
+
+#[test]
+fn test_no_trusted_issuer() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    let cap = Capability::mint(&issuer.secret, SCOPE, cap::now() + 3600).expect("mint");
+    assert_eq!(rt.verify_capability(&cap), Err(cap::Error::NoIssuer));
+    assert!(matches!(Capability::mint(b"short", SCOPE, 0), Err(cap::Error::Key(_))));
+}
@@ -0,0 +1,67 @@
+//! Tests for content-addressed registration with `Runtime::add_component_dedup`.
+
+use exorun::Runtime;
+
+const NOOP_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "noop")))
+        (core instance $i (instantiate $m))
+        (func $noop (canon lift (core func $i "noop")))
+        (instance $api (export "noop" (func $noop)))
+        (export "test:dedup/api" (instance $api)))
+"#;
+
+const OTHER_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "other")))
+        (core instance $i (instantiate $m))
+        (func $other (canon lift (core func $i "other")))
+        (instance $api (export "other" (func $other)))
+        (export "test:dedup/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_dedup_returns_existing_id() {
+    let rt = Runtime::new().expect("runtime creation failed");
+
+    let (first, added) = rt.add_component_dedup(NOOP_WAT.as_bytes()).expect("first");
+    assert!(added);
+    let (second, added) = rt.add_component_dedup(NOOP_WAT.as_bytes()).expect("second");
+    assert!(!added);
+    assert_eq!(first, second);
+
+    let (other, added) = rt.add_component_dedup(OTHER_WAT.as_bytes()).expect("other");
+    assert!(added);
+    assert_ne!(other, first);
+    assert_eq!(rt.list_components(), vec![first, other]);
+
+    // Bytes registered without dedup are found by it too
+    let plain = rt.add_component_bytes(b"(component)").expect("plain");
+    assert_eq!(rt.add_component_dedup(b"(component)").expect("dedup"), (plain, false));
+}
+
+#[tokio::test]
+async fn test_dedup_index_follows_removal_and_update() {
+    let rt = Runtime::new().expect("runtime creation failed");
+
+    // Removing the indexed component hands its hash to a duplicate
+    let a = rt.add_component_bytes(NOOP_WAT.as_bytes()).expect("a");
+    let b = rt.add_component_bytes(NOOP_WAT.as_bytes()).expect("b");
+    rt.remove_component(a).expect("remove a");
+    assert_eq!(rt.add_component_dedup(NOOP_WAT.as_bytes()).expect("dedup"), (b, false));
+
+    // With no duplicate left, the bytes register afresh
+    rt.remove_component(b).expect("remove b");
+    let (c, added) = rt.add_component_dedup(NOOP_WAT.as_bytes()).expect("re-add");
+    assert!(added);
+    assert!(c != a && c != b);
+
+    // Updating a component re-keys it under its new bytes
+    rt.update_component(c, OTHER_WAT.as_bytes()).expect("update");
+    assert_eq!(rt.add_component_dedup(OTHER_WAT.as_bytes()).expect("other"), (c, false));
+    let (d, added) = rt.add_component_dedup(NOOP_WAT.as_bytes()).expect("noop again");
+    assert!(added);
+    assert_ne!(d, c);
+}
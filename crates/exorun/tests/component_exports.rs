@@ -87,7 +87,7 @@ async fn test_low_level_component_export_navigation() {
 
     // Create store with runtime context
     let ctx = ctx_builder.build(std::sync::Arc::clone(&rt));
-    let mut store = wasmtime::Store::new(rt.engine(), ctx);
+    let mut store = rt.new_store(ctx);
 
     // Instantiate the component
     let instance = linker
@@ -118,7 +118,7 @@ async fn test_low_level_component_export_navigation() {
 
     // Now get the actual function using the index
     let run_func = instance
-        .get_func(&mut store, &func_idx)
+        .get_func(&mut store, func_idx)
         .expect("Failed to get run function");
 
     // Type it and call it
@@ -0,0 +1,62 @@
+//! Tests for replacing a component's bytes in place.
+
+use exorun::ComponentId;
+use exorun::Runtime;
+use exorun::runtime::Error;
+use wasmtime::component::Val;
+
+/// A component whose `test:update/api.version` returns `n`.
+fn versioned(n: u32) -> String {
+    format!(r#"
+        (component
+            (core module $m
+                (func (export "version") (result i32) (i32.const {n})))
+            (core instance $i (instantiate $m))
+            (func $version (result u32) (canon lift (core func $i "version")))
+            (instance $api (export "version" (func $version)))
+            (export "test:update/api" (instance $api)))
+    "#)
+}
+
+#[tokio::test]
+async fn test_update_component_affects_only_new_instances() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let id = rt.add_component_bytes(versioned(1).as_bytes()).expect("add component");
+    assert_eq!(rt.component_version(id).unwrap(), 0);
+
+    let old = rt.instantiate(id).build().await.expect("instantiate v1");
+
+    rt.update_component(id, versioned(2).as_bytes()).expect("update component");
+    assert_eq!(rt.component_version(id).unwrap(), 1);
+
+    let new = rt.instantiate(id).build().await.expect("instantiate v2");
+    let results = rt.call(new, "test:update/api", "version", &[]).await.expect("call new");
+    assert_eq!(results, vec![Val::U32(2)]);
+
+    let results = rt.call(old, "test:update/api", "version", &[]).await.expect("call old");
+    assert_eq!(results, vec![Val::U32(1)], "live instance keeps the old component");
+
+    // The bundle ships the current bytes under the same id
+    let bundle = rt.export_bootstrap().expect("export");
+    assert_eq!(bundle.components.len(), 1);
+    assert_eq!(bundle.components[0].id, id);
+    assert_eq!(bundle.components[0].bytes, versioned(2).into_bytes());
+}
+
+#[tokio::test]
+async fn test_failed_update_keeps_old_version() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let id = rt.add_component_bytes(versioned(1).as_bytes()).expect("add component");
+
+    let bad = rt.update_component(id, b"not a component");
+    assert!(matches!(bad, Err(Error::Component(_))));
+    assert_eq!(rt.component_version(id).unwrap(), 0);
+
+    let instance = rt.instantiate(id).build().await.expect("instantiate");
+    let results = rt.call(instance, "test:update/api", "version", &[]).await.expect("call");
+    assert_eq!(results, vec![Val::U32(1)]);
+
+    let missing = rt.update_component(ComponentId(9999), versioned(2).as_bytes());
+    assert!(matches!(missing, Err(Error::ComponentNotFound(_))));
+    assert!(matches!(rt.component_version(ComponentId(9999)), Err(Error::ComponentNotFound(_))));
+}
@@ -0,0 +1,141 @@
+//! Tests for serving guest `result` errors as `FailureReason::DomainSpecific`.
+
+use std::sync::Arc;
+
+use exorun::ComponentId;
+use exorun::peer::{self, Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::{self, Transport};
+use neorpc::FailureReason;
+use tokio::sync::{Mutex, mpsc};
+use wasmtime::component::{Type, Val};
+
+/// Exports `test:errors/api` with `fail` returning `err("nope")`,
+/// `ok` returning `ok(5)`, `plain` returning `7`, and `reserved`
+/// returning `err(0xFFFF_0002)`, neorpc's unknown_target code.
+const ERRORS_WAT: &str = r#"
+    (component
+        (core module $m
+            (memory (export "mem") 1)
+            (data (i32.const 0) "\01\00\00\00\10\00\00\00\04\00\00\00")
+            (data (i32.const 16) "nope")
+            (data (i32.const 32) "\00\00\00\00\05\00\00\00")
+            (data (i32.const 48) "\01\00\00\00\02\00\ff\ff")
+            (func (export "fail") (result i32) (i32.const 0))
+            (func (export "ok") (result i32) (i32.const 32))
+            (func (export "plain") (result i32) (i32.const 7))
+            (func (export "reserved") (result i32) (i32.const 48)))
+        (core instance $i (instantiate $m))
+        (alias core export $i "mem" (core memory $mem))
+        (func $fail (result (result u32 (error string)))
+            (canon lift (core func $i "fail") (memory $mem)))
+        (func $ok (result (result u32 (error string)))
+            (canon lift (core func $i "ok") (memory $mem)))
+        (func $plain (result u32) (canon lift (core func $i "plain")))
+        (func $reserved (result (result u32 (error u32)))
+            (canon lift (core func $i "reserved") (memory $mem)))
+        (instance $api
+            (export "fail" (func $fail))
+            (export "ok" (func $ok))
+            (export "plain" (func $plain))
+            (export "reserved" (func $reserved)))
+        (export "test:errors/api" (instance $api)))
+"#;
+
+const API: &str = "test:errors/api";
+
+/// Hands every Call frame to a runtime's `serve_call` and queues its reply.
+struct Loopback {
+    server: Arc<Runtime>,
+    replies_tx: mpsc::UnboundedSender<Vec<u8>>,
+    replies_rx: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+#[async_trait::async_trait]
+impl Transport for Loopback {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        let reply = self.server.serve_call(payload).await
+            .map_err(|e| transport::Error::Io(e.to_string()))?;
+        self.replies_tx.send(reply).map_err(|e| transport::Error::Io(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        Ok(self.replies_rx.lock().await.recv().await)
+    }
+}
+
+/// Serves one instance of the errors component as `errors`, with or without mapping.
+async fn serve(mapped: bool) -> (Peer, Arc<Runtime>, ComponentId) {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component_id = server.add_component_bytes(ERRORS_WAT.as_bytes()).expect("add component");
+    let mut builder = server.instantiate(component_id);
+    if mapped {
+        builder = builder.with_error_mapping(API);
+    }
+    let instance_id = builder.build().await.expect("instantiate");
+    server.expose("errors", instance_id, API);
+
+    let (replies_tx, replies_rx) = mpsc::unbounded_channel();
+    let transport = Loopback { server: Arc::clone(&server), replies_tx, replies_rx: Mutex::new(replies_rx) };
+    let peer = Peer::new("errors-peer", Box::new(transport), PeerConfig::default());
+    (peer, server, component_id)
+}
+
+fn result_types(server: &Runtime, component_id: ComponentId, method: &str) -> Vec<Type> {
+    let ledger = server.get_ledger(component_id).expect("ledger");
+    ledger.exports[API].funcs[method].results.clone()
+}
+
+#[tokio::test]
+async fn test_err_result_becomes_domain_specific() {
+    let (peer, server, component_id) = serve(true).await;
+
+    let err = peer.call("errors", "fail", &[], result_types(&server, component_id, "fail"))
+        .await
+        .unwrap_err();
+    match err {
+        peer::Error::Remote(FailureReason::DomainSpecific(code, message)) => {
+            assert_eq!(code, 0);
+            assert_eq!(message, "nope");
+        }
+        other => panic!("expected DomainSpecific, got {:?}", other),
+    }
+
+    // Ok results and methods without a result type come back as values
+    let ok = peer.call("errors", "ok", &[], result_types(&server, component_id, "ok")).await.expect("ok");
+    assert_eq!(ok, vec![Val::Result(Ok(Some(Box::new(Val::U32(5)))))]);
+    let plain = peer.call("errors", "plain", &[], result_types(&server, component_id, "plain")).await.expect("plain");
+    assert_eq!(plain, vec![Val::U32(7)]);
+}
+
+#[tokio::test]
+async fn test_unmapped_err_is_a_value() {
+    let (peer, server, component_id) = serve(false).await;
+
+    let results = peer.call("errors", "fail", &[], result_types(&server, component_id, "fail"))
+        .await
+        .expect("unmapped errors are values");
+    assert_eq!(results, vec![Val::Result(Err(Some(Box::new(Val::String("nope".into())))))]);
+}
+
+#[tokio::test]
+async fn test_unknown_target_and_method() {
+    let (peer, server, component_id) = serve(true).await;
+    let types = result_types(&server, component_id, "plain");
+
+    let err = peer.call("missing", "plain", &[], types.clone()).await.unwrap_err();
+    assert!(matches!(err, peer::Error::Remote(reason) if reason == neorpc::unknown_target()));
+    let err = peer.call("errors", "missing", &[], types).await.unwrap_err();
+    assert!(matches!(err, peer::Error::Remote(FailureReason::MethodNotFound)));
+}
+
+#[tokio::test]
+async fn test_reserved_guest_code_is_remapped() {
+    let (peer, server, component_id) = serve(true).await;
+
+    let err = peer.call("errors", "reserved", &[], result_types(&server, component_id, "reserved"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, peer::Error::Remote(FailureReason::DomainSpecific(0, _))));
+}
@@ -0,0 +1,258 @@
+//! Integration tests for the Auth host component: ed25519 keys and XChaCha20Poly1305 encryption.
+
+use std::sync::Arc;
+
+use wasmtime::component::Val;
+
+use exorun::InstanceId;
+use exorun::host::Auth;
+use exorun::host::HostInstance;
+use exorun::runtime::Runtime;
+
+/// A guest re-exporting `exorun:auth/keys` `sign` and `verify` as `test:auth/api`.
+///
+/// Arguments are lifted into guest memory and handed straight to the host.
+const AUTH_GUEST_WAT: &str = r#"
+    (component
+        (import "exorun:auth/keys" (instance $keys
+            (export "sign" (func (param "secret" (list u8)) (param "msg" (list u8)) (result (list u8))))
+            (export "verify" (func (param "public" (list u8)) (param "msg" (list u8)) (param "sig" (list u8)) (result bool)))))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $sign (canon lower (func $keys "sign") (memory $memory) (realloc $realloc)))
+        (core func $verify (canon lower (func $keys "verify") (memory $memory)))
+
+        (core module $main
+            (import "keys" "sign" (func $sign (param i32 i32 i32 i32 i32)))
+            (import "keys" "verify" (func $verify (param i32 i32 i32 i32 i32 i32) (result i32)))
+            (func (export "sign") (param i32 i32 i32 i32) (result i32)
+                (call $sign (local.get 0) (local.get 1) (local.get 2) (local.get 3) (i32.const 512))
+                (i32.const 512))
+            (func (export "verify") (param i32 i32 i32 i32 i32 i32) (result i32)
+                (call $verify (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4) (local.get 5))))
+        (core instance $m (instantiate $main
+            (with "keys" (instance
+                (export "sign" (func $sign))
+                (export "verify" (func $verify))))))
+
+        (func $sign_export (param "secret" (list u8)) (param "msg" (list u8)) (result (list u8))
+            (canon lift (core func $m "sign") (memory $memory) (realloc $realloc)))
+        (func $verify_export (param "public" (list u8)) (param "msg" (list u8)) (param "sig" (list u8)) (result bool)
+            (canon lift (core func $m "verify") (memory $memory) (realloc $realloc)))
+        (instance $api
+            (export "sign" (func $sign_export))
+            (export "verify" (func $verify_export)))
+        (export "test:auth/api" (instance $api)))
+"#;
+
+fn bytes(data: &[u8]) -> Val {
+    Val::List(data.iter().map(|b| Val::U8(*b)).collect())
+}
+
+fn unbytes(val: &Val) -> Vec<u8> {
+    let Val::List(items) = val else { panic!("expected list, got {:?}", val) };
+    items.iter().map(|item| match item {
+        Val::U8(b) => *b,
+        other => panic!("expected u8, got {:?}", other),
+    }).collect()
+}
+
+async fn auth_guest() -> (Arc<Runtime>, InstanceId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(AUTH_GUEST_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_system("exorun:auth/keys", HostInstance::Auth(Auth::new()))
+        .build()
+        .await
+        .expect("instantiate");
+    (rt, instance_id)
+}
+
+async fn guest_sign(rt: &Runtime, instance: InstanceId, secret: &[u8], msg: &[u8]) -> Vec<u8> {
+    let results = rt.call(instance, "test:auth/api", "sign", &[bytes(secret), bytes(msg)])
+        .await
+        .expect("sign");
+    unbytes(&results[0])
+}
+
+async fn guest_verify(rt: &Runtime, instance: InstanceId, public: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+    let results = rt.call(instance, "test:auth/api", "verify", &[bytes(public), bytes(msg), bytes(sig)])
+        .await
+        .expect("verify never traps");
+    results == vec![Val::Bool(true)]
+}
+
+#[tokio::test]
+async fn test_auth_sign_then_verify_roundtrip() {
+    let (rt, instance) = auth_guest().await;
+    let keypair = Auth::generate();
+    assert_eq!(keypair.public.len(), 32);
+    assert_eq!(keypair.secret.len(), 32);
+
+    let sig = guest_sign(&rt, instance, &keypair.secret, b"hello").await;
+    assert_eq!(sig.len(), 64);
+    assert!(guest_verify(&rt, instance, &keypair.public, b"hello", &sig).await);
+    assert_eq!(Auth::sign(&keypair.secret, b"hello").unwrap(), sig);
+}
+
+#[tokio::test]
+async fn test_auth_detects_tampering() {
+    let (rt, instance) = auth_guest().await;
+    let keypair = Auth::generate();
+    let other = Auth::generate();
+    let sig = guest_sign(&rt, instance, &keypair.secret, b"hello").await;
+
+    let mut flipped = sig.clone();
+    flipped[0] ^= 0x01;
+
+    assert!(!guest_verify(&rt, instance, &keypair.public, b"hellp", &sig).await);
+    assert!(!guest_verify(&rt, instance, &keypair.public, b"hello", &flipped).await);
+    assert!(!guest_verify(&rt, instance, &other.public, b"hello", &sig).await);
+}
+
+#[tokio::test]
+async fn test_auth_verify_rejects_malformed_input() {
+    let (rt, instance) = auth_guest().await;
+    let keypair = Auth::generate();
+    let sig = Auth::sign(&keypair.secret, b"hello").unwrap();
+
+    assert!(!guest_verify(&rt, instance, &keypair.public[..3], b"hello", &sig).await);
+    assert!(!guest_verify(&rt, instance, &[], b"hello", &sig).await);
+    assert!(!guest_verify(&rt, instance, &keypair.public, b"hello", &sig[..63]).await);
+    assert!(!guest_verify(&rt, instance, &[0xff; 32], b"hello", &sig).await);
+
+    assert!(Auth::sign(&keypair.secret[..31], b"hello").is_err());
+}
+
+/// A guest re-exporting `exorun:auth/crypto` `seal` and `open` as `test:crypto/api`.
+const CRYPTO_GUEST_WAT: &str = r#"
+    (component
+        (type $sealed (result (list u8) (error string)))
+        (import "exorun:auth/crypto" (instance $crypto
+            (export "seal" (func (param "key" (list u8)) (param "nonce" (list u8)) (param "plaintext" (list u8)) (result $sealed)))
+            (export "open" (func (param "key" (list u8)) (param "nonce" (list u8)) (param "ciphertext" (list u8)) (result $sealed)))))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $seal (canon lower (func $crypto "seal") (memory $memory) (realloc $realloc)))
+        (core func $open (canon lower (func $crypto "open") (memory $memory) (realloc $realloc)))
+
+        (core module $main
+            (import "crypto" "seal" (func $seal (param i32 i32 i32 i32 i32 i32 i32)))
+            (import "crypto" "open" (func $open (param i32 i32 i32 i32 i32 i32 i32)))
+            (func (export "seal") (param i32 i32 i32 i32 i32 i32) (result i32)
+                (call $seal (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4) (local.get 5) (i32.const 512))
+                (i32.const 512))
+            (func (export "open") (param i32 i32 i32 i32 i32 i32) (result i32)
+                (call $open (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4) (local.get 5) (i32.const 512))
+                (i32.const 512)))
+        (core instance $m (instantiate $main
+            (with "crypto" (instance
+                (export "seal" (func $seal))
+                (export "open" (func $open))))))
+
+        (func $seal_export (param "key" (list u8)) (param "nonce" (list u8)) (param "plaintext" (list u8)) (result $sealed)
+            (canon lift (core func $m "seal") (memory $memory) (realloc $realloc)))
+        (func $open_export (param "key" (list u8)) (param "nonce" (list u8)) (param "ciphertext" (list u8)) (result $sealed)
+            (canon lift (core func $m "open") (memory $memory) (realloc $realloc)))
+        (instance $api
+            (export "seal" (func $seal_export))
+            (export "open" (func $open_export)))
+        (export "test:crypto/api" (instance $api)))
+"#;
+
+async fn crypto_guest() -> (Arc<Runtime>, InstanceId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(CRYPTO_GUEST_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_system("exorun:auth/crypto", HostInstance::Auth(Auth::new()))
+        .build()
+        .await
+        .expect("instantiate");
+    (rt, instance_id)
+}
+
+/// Calls `seal` or `open` in the guest, unpacking the `result<list<u8>, string>`.
+async fn guest_crypto(rt: &Runtime, instance: InstanceId, func: &str, key: &[u8], nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let results = rt.call(instance, "test:crypto/api", func, &[bytes(key), bytes(nonce), bytes(data)])
+        .await
+        .expect("crypto never traps");
+    match &results[0] {
+        Val::Result(Ok(Some(out))) => Ok(unbytes(out)),
+        Val::Result(Err(Some(msg))) => match msg.as_ref() {
+            Val::String(msg) => Err(msg.clone()),
+            other => panic!("expected string error, got {:?}", other),
+        },
+        other => panic!("expected result, got {:?}", other),
+    }
+}
+
+const KEY: [u8; 32] = [3; 32];
+const NONCE: [u8; 24] = [5; 24];
+
+#[tokio::test]
+async fn test_crypto_seal_open_roundtrip() {
+    let (rt, instance) = crypto_guest().await;
+
+    let sealed = guest_crypto(&rt, instance, "seal", &KEY, &NONCE, b"at rest").await.expect("seal");
+    assert_eq!(sealed.len(), b"at rest".len() + 16);
+    assert_ne!(&sealed[..7], b"at rest");
+
+    let opened = guest_crypto(&rt, instance, "open", &KEY, &NONCE, &sealed).await.expect("open");
+    assert_eq!(opened, b"at rest");
+}
+
+#[tokio::test]
+async fn test_crypto_open_with_wrong_key_fails() {
+    let (rt, instance) = crypto_guest().await;
+    let sealed = Auth::seal(&KEY, &NONCE, b"at rest").unwrap();
+
+    let result = guest_crypto(&rt, instance, "open", &[4; 32], &NONCE, &sealed).await;
+    assert_eq!(result, Err("auth tag mismatch".to_string()));
+
+    let mut tampered = sealed.clone();
+    tampered[0] ^= 0x01;
+    let result = guest_crypto(&rt, instance, "open", &KEY, &NONCE, &tampered).await;
+    assert_eq!(result, Err("auth tag mismatch".to_string()));
+}
+
+#[tokio::test]
+async fn test_crypto_open_truncated_ciphertext_fails() {
+    let (rt, instance) = crypto_guest().await;
+    let sealed = Auth::seal(&KEY, &NONCE, b"at rest").unwrap();
+
+    for len in [sealed.len() - 1, 16, 3, 0] {
+        let result = guest_crypto(&rt, instance, "open", &KEY, &NONCE, &sealed[..len]).await;
+        assert_eq!(result, Err("auth tag mismatch".to_string()), "truncated to {} bytes", len);
+    }
+}
+
+#[tokio::test]
+async fn test_crypto_rejects_bad_key_and_nonce_lengths() {
+    let (rt, instance) = crypto_guest().await;
+
+    assert!(guest_crypto(&rt, instance, "seal", &KEY[..31], &NONCE, b"x").await.is_err());
+    assert!(guest_crypto(&rt, instance, "seal", &KEY, &NONCE[..12], b"x").await.is_err());
+    assert!(guest_crypto(&rt, instance, "open", &[], &NONCE, b"x").await.is_err());
+}
@@ -0,0 +1,127 @@
+//! Tests for host functions that see their caller through `link_with_ctx`.
+
+use std::sync::Arc;
+
+use wasmtime::component::Linker;
+use wasmtime::component::Type;
+use wasmtime::component::Val;
+
+use exorun::context::ExorunCtx;
+use exorun::host::{self, SystemComponent, link_with_ctx};
+use exorun::peer::{Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::LocalTransport;
+
+/// Answers `test:whoami/id` with the calling instance's id, and the id of
+/// the peer whose call is running, or 0 for a local call.
+struct WhoAmI;
+
+impl SystemComponent for WhoAmI {
+    fn interfaces(&self) -> &[&str] { &["test:whoami/id"] }
+
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> host::Result<()> {
+        link_with_ctx(linker, "test:whoami/id", &["instance", "caller"], |ctx, method, _args| {
+            let id = match method {
+                "instance" => ctx.instance_id().map_or(0, |id| id.0),
+                _ => ctx.caller().map_or(0, |id| id.0),
+            };
+            Ok(vec![Val::U64(id)])
+        })
+    }
+}
+
+/// Re-exports `test:whoami/id` as `test:whoami/api`.
+const GUEST_WAT: &str = r#"
+    (component
+        (import "test:whoami/id" (instance $id
+            (export "instance" (func (result u64)))
+            (export "caller" (func (result u64)))))
+        (core func $instance (canon lower (func $id "instance")))
+        (core func $caller (canon lower (func $id "caller")))
+        (core module $m
+            (import "id" "instance" (func $instance (result i64)))
+            (import "id" "caller" (func $caller (result i64)))
+            (func (export "instance") (result i64) (call $instance))
+            (func (export "caller") (result i64) (call $caller)))
+        (core instance $i (instantiate $m
+            (with "id" (instance
+                (export "instance" (func $instance))
+                (export "caller" (func $caller))))))
+        (func $instance_export (result u64) (canon lift (core func $i "instance")))
+        (func $caller_export (result u64) (canon lift (core func $i "caller")))
+        (instance $api
+            (export "instance" (func $instance_export))
+            (export "caller" (func $caller_export)))
+        (export "test:whoami/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_host_function_sees_calling_instance() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component = rt.add_component_bytes(GUEST_WAT.as_bytes()).expect("add component");
+    let whoami: Arc<dyn SystemComponent> = Arc::new(WhoAmI);
+
+    let mut instances = Vec::new();
+    for _ in 0..2 {
+        let instance = rt.instantiate(component)
+            .link_system_shared("test:whoami/id", Arc::clone(&whoami))
+            .build()
+            .await
+            .expect("instantiate");
+        instances.push(instance);
+    }
+
+    for instance in instances {
+        let results = rt.call(instance, "test:whoami/api", "instance", &[]).await.expect("call");
+        assert_eq!(results, vec![Val::U64(instance.0)]);
+        let results = rt.call(instance, "test:whoami/api", "caller", &[]).await.expect("call");
+        assert_eq!(results, vec![Val::U64(0)], "a local call has no peer caller");
+    }
+}
+
+#[tokio::test]
+async fn test_host_function_sees_calling_peer() {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component = server.add_component_bytes(GUEST_WAT.as_bytes()).expect("add component");
+    let instance = server.instantiate(component)
+        .link_system_shared("test:whoami/id", Arc::new(WhoAmI))
+        .build()
+        .await
+        .expect("instantiate");
+
+    let (ours, theirs) = LocalTransport::pair();
+    let peer_id = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(peer_id, instance).expect("serve");
+    let client = Peer::new("server", Box::new(theirs), PeerConfig::default());
+
+    let results = client.call("test:whoami/api", "caller", &[], vec![Type::U64]).await.expect("remote call");
+    assert_eq!(results, vec![Val::U64(peer_id.0)]);
+
+    // The caller is cleared once the served call ends
+    let results = server.call(instance, "test:whoami/api", "caller", &[]).await.expect("local call");
+    assert_eq!(results, vec![Val::U64(0)]);
+}
+
+#[tokio::test]
+async fn test_handler_result_count_is_checked() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component = rt.add_component_bytes(GUEST_WAT.as_bytes()).expect("add component");
+    let instance = rt.instantiate(component)
+        .link_system_shared("test:whoami/id", Arc::new(Empty))
+        .build()
+        .await
+        .expect("instantiate");
+    let err = rt.call(instance, "test:whoami/api", "instance", &[]).await.unwrap_err();
+    assert!(format!("{:?}", err).contains("returned 0 results, expected 1"), "{:?}", err);
+}
+
+/// Links `test:whoami/id` with a handler that returns nothing.
+struct Empty;
+
+impl SystemComponent for Empty {
+    fn interfaces(&self) -> &[&str] { &["test:whoami/id"] }
+
+    fn install(&self, linker: &mut Linker<ExorunCtx>) -> host::Result<()> {
+        link_with_ctx(linker, "test:whoami/id", &["instance", "caller"], |_, _, _| Ok(vec![]))
+    }
+}
@@ -0,0 +1,220 @@
+//! Integration tests for the signed append-only log host component.
+
+use std::path::PathBuf;
+
+use ed25519_dalek::SigningKey;
+use wasmtime::component::Val;
+
+use exorun::host::Core;
+use exorun::host::HostInstance;
+use exorun::runtime::Runtime;
+
+/// A guest that appends "one", "two", "three" through `exorun:core/log`.
+///
+/// `run` returns the log length afterwards, and `missing` returns the
+/// option discriminant of `get(99)` (0 for `none`).
+const CORE_GUEST_WAT: &str = r#"
+    (component
+        (import "exorun:core/log" (instance $log
+            (export "append" (func (param "data" (list u8)) (result u64)))
+            (export "length" (func (result u64)))
+            (export "get" (func (param "index" u64) (result (option (list u8)))))))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr))
+            (data (i32.const 0) "onetwothree"))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $append (canon lower (func $log "append") (memory $memory)))
+        (core func $length (canon lower (func $log "length")))
+        (core func $get (canon lower (func $log "get") (memory $memory) (realloc $realloc)))
+
+        (core module $main
+            (import "env" "memory" (memory 1))
+            (import "log" "append" (func $append (param i32 i32) (result i64)))
+            (import "log" "length" (func $length (result i64)))
+            (import "log" "get" (func $get (param i64 i32)))
+            (func (export "run") (result i64)
+                (drop (call $append (i32.const 0) (i32.const 3)))
+                (drop (call $append (i32.const 3) (i32.const 3)))
+                (drop (call $append (i32.const 6) (i32.const 5)))
+                (call $length))
+            (func (export "missing") (result i32)
+                (call $get (i64.const 99) (i32.const 512))
+                (i32.load8_u (i32.const 512))))
+        (core instance $m (instantiate $main
+            (with "env" (instance (export "memory" (memory $memory))))
+            (with "log" (instance
+                (export "append" (func $append))
+                (export "length" (func $length))
+                (export "get" (func $get))))))
+
+        (func $run (result u64) (canon lift (core func $m "run")))
+        (func $missing (result u32) (canon lift (core func $m "missing")))
+        (instance $api
+            (export "run" (func $run))
+            (export "missing" (func $missing)))
+        (export "test:core/api" (instance $api)))
+"#;
+
+fn temp_log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("exorun-core-{}-{}.log", name, rand::random::<u64>()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_core_append_from_guest_and_verify() {
+    let path = temp_log_path("guest");
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let core = Core::open(&path, key.clone()).expect("open log");
+
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(CORE_GUEST_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_system("exorun:core/log", HostInstance::Core(core.clone()))
+        .build()
+        .await
+        .expect("instantiate");
+
+    let results = rt.call(instance_id, "test:core/api", "run", &[]).await.expect("run");
+    assert_eq!(results, vec![Val::U64(3)]);
+
+    // Reading past the end is a `none`, not a trap.
+    let results = rt.call(instance_id, "test:core/api", "missing", &[]).await.expect("missing");
+    assert_eq!(results, vec![Val::U32(0)]);
+
+    assert_eq!(core.get(0).await.unwrap().as_deref(), Some(&b"one"[..]));
+    assert_eq!(core.get(1).await.unwrap().as_deref(), Some(&b"two"[..]));
+    assert_eq!(core.get(2).await.unwrap().as_deref(), Some(&b"three"[..]));
+    assert_eq!(core.get(3).await.unwrap(), None);
+    core.verify().await.expect("signatures validate");
+
+    // Reopening replays and verifies the log from disk.
+    drop(rt);
+    drop(core);
+    let reopened = Core::open(&path, key).expect("reopen log");
+    assert_eq!(reopened.len().await, 3);
+    assert_eq!(reopened.append(b"four").await.unwrap(), 3);
+    assert_eq!(reopened.get(3).await.unwrap().as_deref(), Some(&b"four"[..]));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_core_rejects_tampered_or_foreign_log() {
+    let path = temp_log_path("tamper");
+    let key = SigningKey::from_bytes(&[9; 32]);
+    let core = Core::open(&path, key.clone()).expect("open log");
+    for entry in [&b"alpha"[..], b"beta", b"gamma"] {
+        core.append(entry).await.expect("append");
+    }
+    drop(core);
+
+    // A different key cannot vouch for the existing entries.
+    let other = SigningKey::from_bytes(&[10; 32]);
+    assert!(matches!(Core::open(&path, other), Err(exorun::host::Error::Corrupt(_))));
+
+    // Flip a byte inside the first entry's data.
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[4] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(matches!(Core::open(&path, key.clone()), Err(exorun::host::Error::Corrupt(_))));
+
+    // Chop the last signature in half.
+    bytes[4] ^= 0xff;
+    bytes.truncate(bytes.len() - 32);
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(matches!(Core::open(&path, key), Err(exorun::host::Error::Corrupt(_))));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_core_rejects_oversized_length_header() {
+    let path = temp_log_path("oversized");
+    let key = SigningKey::from_bytes(&[11; 32]);
+    let core = Core::open(&path, key.clone()).expect("open log");
+    core.append(b"alpha").await.expect("append");
+    drop(core);
+
+    // A hostile header claiming ~4 GiB is refused before anything is allocated for it
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    bytes.extend_from_slice(&[0; 8]);
+    std::fs::write(&path, &bytes).unwrap();
+    match Core::open(&path, key) {
+        Err(exorun::host::Error::Corrupt(msg)) => assert!(msg.contains("past the end"), "{}", msg),
+        other => panic!("expected Corrupt, got {:?}", other.map(|_| ())),
+    }
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_core_append_drops_torn_tail() {
+    let path = temp_log_path("torn");
+    let key = SigningKey::from_bytes(&[12; 32]);
+    let core = Core::open(&path, key.clone()).expect("open log");
+    core.append(b"alpha").await.expect("append");
+
+    // Stand in for a partial record left by a failed write
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[5, 0, 0, 0, b'b', b'e']).unwrap();
+    drop(file);
+
+    assert_eq!(core.append(b"beta").await.expect("append after torn write"), 1);
+    drop(core);
+
+    let reopened = Core::open(&path, key).expect("reopen log");
+    assert_eq!(reopened.len().await, 2);
+    assert_eq!(reopened.get(1).await.unwrap().as_deref(), Some(&b"beta"[..]));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_core_concurrent_appends_from_two_instances() {
+    let path = temp_log_path("concurrent");
+    let key = SigningKey::from_bytes(&[13; 32]);
+    let core = Core::open(&path, key.clone()).expect("open log");
+
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(CORE_GUEST_WAT.as_bytes()).expect("add component");
+    let mut instances = Vec::new();
+    for _ in 0..2 {
+        let instance_id = rt.instantiate(component_id)
+            .link_system("exorun:core/log", HostInstance::Core(core.clone()))
+            .build()
+            .await
+            .expect("instantiate");
+        instances.push(instance_id);
+    }
+
+    // Each waits for the other's appends instead of trapping on the shared log
+    let (a, b) = tokio::join!(
+        rt.call(instances[0], "test:core/api", "run", &[]),
+        rt.call(instances[1], "test:core/api", "run", &[]),
+    );
+    for results in [a.expect("first run"), b.expect("second run")] {
+        let [Val::U64(len)] = results.as_slice() else { panic!("Expected a length, got {:?}", results) };
+        assert!((3..=6).contains(len), "length {}", len);
+    }
+
+    assert_eq!(core.len().await, 6);
+    core.verify().await.expect("signatures validate");
+    drop(rt);
+    drop(core);
+    assert_eq!(Core::open(&path, key).expect("reopen log").len().await, 6);
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,119 @@
+//! Tests for system components shared between instances with `link_system_shared`.
+
+use std::sync::Arc;
+
+use wasmtime::component::Val;
+
+use exorun::InstanceId;
+use exorun::host::Kv;
+use exorun::host::SystemComponent;
+use exorun::local::builder::Error;
+use exorun::runtime::Runtime;
+
+/// A guest re-exporting `exorun:host/kv` `get` and `set` as `test:kv/api`.
+const KV_GUEST_WAT: &str = r#"
+    (component
+        (import "exorun:host/kv" (instance $kv
+            (export "get" (func (param "key" string) (result (option string))))
+            (export "set" (func (param "key" string) (param "val" string)))))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $get (canon lower (func $kv "get") (memory $memory) (realloc $realloc)))
+        (core func $set (canon lower (func $kv "set") (memory $memory) (realloc $realloc)))
+
+        (core module $main
+            (import "kv" "get" (func $get (param i32 i32 i32)))
+            (import "kv" "set" (func $set (param i32 i32 i32 i32)))
+            (func (export "get") (param i32 i32) (result i32)
+                (call $get (local.get 0) (local.get 1) (i32.const 512))
+                (i32.const 512))
+            (func (export "set") (param i32 i32 i32 i32)
+                (call $set (local.get 0) (local.get 1) (local.get 2) (local.get 3))))
+        (core instance $m (instantiate $main
+            (with "kv" (instance
+                (export "get" (func $get))
+                (export "set" (func $set))))))
+
+        (func $get_export (param "key" string) (result (option string))
+            (canon lift (core func $m "get") (memory $memory) (realloc $realloc)))
+        (func $set_export (param "key" string) (param "val" string)
+            (canon lift (core func $m "set") (memory $memory) (realloc $realloc)))
+        (instance $api
+            (export "get" (func $get_export))
+            (export "set" (func $set_export)))
+        (export "test:kv/api" (instance $api)))
+"#;
+
+async fn kv_set(rt: &Runtime, instance: InstanceId, key: &str, val: &str) {
+    rt.call(instance, "test:kv/api", "set", &[Val::String(key.into()), Val::String(val.into())])
+        .await
+        .expect("set");
+}
+
+async fn kv_get(rt: &Runtime, instance: InstanceId, key: &str) -> Option<String> {
+    let results = rt.call(instance, "test:kv/api", "get", &[Val::String(key.into())])
+        .await
+        .expect("get");
+    match &results[0] {
+        Val::Option(None) => None,
+        Val::Option(Some(val)) => match val.as_ref() {
+            Val::String(s) => Some(s.clone()),
+            other => panic!("expected string, got {:?}", other),
+        },
+        other => panic!("expected option, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_diamond_dependency_through_shared_link() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(KV_GUEST_WAT.as_bytes()).expect("add component");
+
+    let kv = Kv::new();
+    let shared: Arc<dyn SystemComponent> = Arc::new(kv.clone());
+
+    let a = rt.instantiate(component_id)
+        .link_system_shared("exorun:host/kv", Arc::clone(&shared))
+        .build()
+        .await
+        .expect("instantiate A");
+    let b = rt.instantiate(component_id)
+        .link_system_shared("exorun:host/kv", Arc::clone(&shared))
+        .build()
+        .await
+        .expect("instantiate B");
+
+    // Each instance sees what the other wrote
+    assert_eq!(kv_get(&rt, b, "owner").await, None);
+    kv_set(&rt, a, "owner", "a").await;
+    assert_eq!(kv_get(&rt, b, "owner").await.as_deref(), Some("a"));
+    kv_set(&rt, b, "owner", "b").await;
+    assert_eq!(kv_get(&rt, a, "owner").await.as_deref(), Some("b"));
+
+    // And so does the host, through its own handle
+    assert_eq!(kv.get_store().await["owner"], "b");
+}
+
+#[tokio::test]
+async fn test_shared_link_rejects_unprovided_interface() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(KV_GUEST_WAT.as_bytes()).expect("add component");
+
+    let err = rt.instantiate(component_id)
+        .link_system_shared("exorun:host/logging", Arc::new(Kv::new()))
+        .build()
+        .await
+        .expect_err("kv cannot provide logging");
+    assert!(matches!(err, Error::Host(_)), "unexpected error {:?}", err);
+}
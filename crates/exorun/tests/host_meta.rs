@@ -0,0 +1,245 @@
+//! Tests for the `Meta` host component, which lets guests spawn instances.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use exorun::Budget;
+use exorun::InstanceId;
+use exorun::VirtualClock;
+use exorun::cap::{self, Capability};
+use exorun::host::{Auth, HostInstance, Meta, meta};
+use exorun::local::builder;
+use exorun::runtime::Runtime;
+use wasmtime::component::Val;
+
+/// Exports `test:math/api` with `add`.
+const MATH_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (instance $api (export "add" (func $add)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+/// Imports `exorun:meta/spawn` and exports `test:spawner/api` with
+/// `spawn(bytes)`, registering the bytes and returning a new instance of them.
+const SPAWNER_WAT: &str = r#"
+    (component
+        (import "exorun:meta/spawn" (instance $meta
+            (export "register" (func (param "bytes" (list u8)) (result u64)))
+            (export "instantiate" (func (param "component-id" u64) (result u64)))))
+        (core module $mem
+            (memory (export "mem") 1)
+            (global $next (mut i32) (i32.const 8))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $next))
+                (global.set $next (i32.add (local.get $ptr) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mem (instantiate $mem))
+        (core func $register (canon lower (func $meta "register") (memory $mem "mem")))
+        (core func $instantiate (canon lower (func $meta "instantiate")))
+        (core module $m
+            (import "meta" "register" (func $register (param i32 i32) (result i64)))
+            (import "meta" "instantiate" (func $instantiate (param i64) (result i64)))
+            (func (export "spawn") (param i32 i32) (result i64)
+                (call $instantiate (call $register (local.get 0) (local.get 1)))))
+        (core instance $i (instantiate $m
+            (with "meta" (instance
+                (export "register" (func $register))
+                (export "instantiate" (func $instantiate))))))
+        (func $spawn (param "bytes" (list u8)) (result u64)
+            (canon lift (core func $i "spawn") (memory $mem "mem") (realloc (func $mem "realloc"))))
+        (instance $api (export "spawn" (func $spawn)))
+        (export "test:spawner/api" (instance $api)))
+"#;
+
+const META: &str = "exorun:meta/spawn";
+const SPAWNER: &str = "test:spawner/api";
+
+fn bytes_val(bytes: &[u8]) -> Val {
+    Val::List(bytes.iter().copied().map(Val::U8).collect())
+}
+
+async fn spawner(rt: &Arc<Runtime>) -> InstanceId {
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+    rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new()))
+        .allow_meta()
+        .build()
+        .await
+        .expect("instantiate spawner")
+}
+
+#[tokio::test]
+async fn test_guest_spawns_and_host_calls_instance() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let spawner = spawner(&rt).await;
+
+    let results = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect("spawn");
+    let [Val::U64(spawned)] = results.as_slice() else { panic!("Expected an instance id, got {:?}", results) };
+    let spawned = InstanceId(*spawned);
+
+    let results = rt.call(spawned, "test:math/api", "add", &[Val::U32(20), Val::U32(22)]).await.expect("add");
+    assert_eq!(results, vec![Val::U32(42)]);
+    assert_eq!(rt.list_components().len(), 2);
+    assert_eq!(rt.list_instances().len(), 2);
+}
+
+#[tokio::test]
+async fn test_invalid_bytes_trap() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let spawner = spawner(&rt).await;
+
+    rt.call(spawner, SPAWNER, "spawn", &[bytes_val(b"not a component")]).await.expect_err("register traps");
+    assert_eq!(rt.list_components().len(), 1);
+
+    // The failed import unwound the guest, so it can't be entered again
+    let after = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await;
+    assert!(matches!(after, Err(exorun::runtime::Error::InstancePoisoned(id)) if id == spawner), "got {:?}", after);
+}
+
+#[tokio::test]
+async fn test_meta_needs_allow_meta() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+
+    let err = rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new()))
+        .build()
+        .await
+        .expect_err("Meta is not allowed");
+    assert!(matches!(err, builder::Error::Host(_)), "got {:?}", err);
+    assert!(err.to_string().contains("allow_meta"), "got {}", err);
+    assert!(rt.list_instances().is_empty());
+}
+
+#[tokio::test]
+async fn test_spawning_needs_live_capability() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    rt.trust_capability_issuer(&issuer.public);
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+
+    let build = |cap: Option<Capability>| {
+        let mut builder = rt.instantiate(component_id)
+            .link_system(META, HostInstance::Meta(Meta::new()))
+            .allow_meta();
+        if let Some(cap) = cap {
+            builder = builder.with_capability(cap);
+        }
+        builder.build()
+    };
+    let live = Capability::mint(&issuer.secret, meta::SCOPE, cap::now() + 3600).expect("mint");
+    let expired = Capability::mint(&issuer.secret, meta::SCOPE, cap::now() - 10).expect("mint");
+    let other = Capability::mint(&issuer.secret, "test:other", cap::now() + 3600).expect("mint");
+
+    for (cap, why) in [(None, "no capability"), (Some(expired), "expired"), (Some(other), "capability is for")] {
+        let spawner = build(cap).await.expect("instantiate spawner");
+        let err = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect_err("spawn refused");
+        assert!(format!("{:?}", err).contains(why), "expected '{}', got {:?}", why, err);
+    }
+    assert_eq!(rt.list_components().len(), 1);
+
+    let spawner = build(Some(live)).await.expect("instantiate spawner");
+    rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect("spawn");
+    assert_eq!(rt.list_components().len(), 2);
+}
+
+#[tokio::test]
+async fn test_capability_expires_by_virtual_clock() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let issuer = Auth::generate();
+    rt.trust_capability_issuer(&issuer.public);
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+
+    // Long past by the wall clock, so only the virtual clock can keep it live
+    let start = 946_684_800;
+    let clock = Arc::new(VirtualClock::at(Duration::from_secs(start)));
+    let cap = Capability::mint(&issuer.secret, meta::SCOPE, start + 60).expect("mint");
+    let spawner = rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new()))
+        .allow_meta()
+        .with_capability(cap)
+        .with_virtual_clock(Arc::clone(&clock))
+        .build()
+        .await
+        .expect("instantiate spawner");
+
+    rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect("spawn before expiry");
+    assert_eq!(rt.list_components().len(), 2);
+
+    clock.advance(Duration::from_secs(120));
+    let err = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect_err("spawn after expiry");
+    assert!(format!("{:?}", err).contains("expired"), "got {:?}", err);
+    assert_eq!(rt.list_components().len(), 2);
+}
+
+/// Exports nothing, but needs two pages of memory to instantiate.
+const TWO_PAGES_WAT: &str = r#"
+    (component
+        (core module $m (memory 2))
+        (core instance $i (instantiate $m)))
+"#;
+
+#[tokio::test]
+async fn test_spawned_instances_inherit_budget() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+    let budgeted = rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new()))
+        .allow_meta()
+        .with_budget(Budget { fuel: 10_000_000, max_memory_bytes: 65536 })
+        .build()
+        .await
+        .expect("instantiate spawner");
+
+    let err = rt.call(budgeted, SPAWNER, "spawn", &[bytes_val(TWO_PAGES_WAT.as_bytes())]).await.expect_err("over budget");
+    assert!(format!("{:?}", err).contains("instantiate"), "got {:?}", err);
+    assert_eq!(rt.list_instances().len(), 1);
+
+    // Without a budget the same component spawns fine
+    let unbudgeted = spawner(&rt).await;
+    rt.call(unbudgeted, SPAWNER, "spawn", &[bytes_val(TWO_PAGES_WAT.as_bytes())]).await.expect("spawn");
+}
+
+#[tokio::test]
+async fn test_spawning_stops_at_max_spawns() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+    let spawner = rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new().with_max_spawns(2)))
+        .allow_meta()
+        .build()
+        .await
+        .expect("instantiate spawner");
+
+    for _ in 0..2 {
+        rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect("spawn");
+    }
+    let err = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect_err("limit reached");
+    assert!(format!("{:?}", err).contains("limit of 2"), "got {:?}", err);
+    assert_eq!(rt.list_instances().len(), 3);
+}
+
+#[tokio::test]
+async fn test_registering_stops_at_max_registrations() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SPAWNER_WAT.as_bytes()).expect("add component");
+    let spawner = rt.instantiate(component_id)
+        .link_system(META, HostInstance::Meta(Meta::new().with_max_registrations(2)))
+        .allow_meta()
+        .build()
+        .await
+        .expect("instantiate spawner");
+
+    for _ in 0..2 {
+        rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect("spawn");
+    }
+    let err = rt.call(spawner, SPAWNER, "spawn", &[bytes_val(MATH_WAT.as_bytes())]).await.expect_err("limit reached");
+    assert!(format!("{:?}", err).contains("limit of 2 components"), "got {:?}", err);
+    assert_eq!(rt.list_components().len(), 3);
+}
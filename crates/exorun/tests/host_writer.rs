@@ -0,0 +1,155 @@
+//! Integration tests for the Lamport-clock Writer host component.
+
+use std::sync::Arc;
+
+use wasmtime::component::Val;
+
+use exorun::InstanceId;
+use exorun::host::HostInstance;
+use exorun::host::Writer;
+use exorun::host::writer::Stamp;
+use exorun::runtime::Runtime;
+
+/// A guest re-exporting `exorun:writer/clock` `tick` and `merge` as `test:writer/api`.
+const WRITER_GUEST_WAT: &str = r#"
+    (component
+        (type $stamp (record (field "writer" string) (field "counter" u64)))
+        (import "exorun:writer/clock" (instance $clock
+            (export "stamp" (type $s (eq $stamp)))
+            (export "tick" (func (param "writer-id" string) (result $s)))
+            (export "merge" (func (param "a" $s) (param "b" $s) (result $s)))))
+        (alias export $clock "stamp" (type $imported))
+
+        (core module $mem
+            (memory (export "memory") 1)
+            (global $bump (mut i32) (i32.const 1024))
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr (global.get $bump))
+                (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+                (local.get $ptr)))
+        (core instance $mi (instantiate $mem))
+        (alias core export $mi "memory" (core memory $memory))
+        (alias core export $mi "realloc" (core func $realloc))
+
+        (core func $tick (canon lower (func $clock "tick") (memory $memory) (realloc $realloc)))
+        (core func $merge (canon lower (func $clock "merge") (memory $memory) (realloc $realloc)))
+
+        (core module $main
+            (import "clock" "tick" (func $tick (param i32 i32 i32)))
+            (import "clock" "merge" (func $merge (param i32 i32 i64 i32 i32 i64 i32)))
+            (func (export "tick") (param i32 i32) (result i32)
+                (call $tick (local.get 0) (local.get 1) (i32.const 512))
+                (i32.const 512))
+            (func (export "merge") (param i32 i32 i64 i32 i32 i64) (result i32)
+                (call $merge (local.get 0) (local.get 1) (local.get 2) (local.get 3) (local.get 4) (local.get 5) (i32.const 512))
+                (i32.const 512)))
+        (core instance $m (instantiate $main
+            (with "clock" (instance
+                (export "tick" (func $tick))
+                (export "merge" (func $merge))))))
+
+        (func $tick_export (param "writer-id" string) (result $imported)
+            (canon lift (core func $m "tick") (memory $memory) (realloc $realloc)))
+        (func $merge_export (param "a" $imported) (param "b" $imported) (result $imported)
+            (canon lift (core func $m "merge") (memory $memory) (realloc $realloc)))
+        (instance $api
+            (export "stamp" (type $imported))
+            (export "tick" (func $tick_export))
+            (export "merge" (func $merge_export)))
+        (export "test:writer/api" (instance $api)))
+"#;
+
+fn stamp_val(stamp: &Stamp) -> Val {
+    Val::Record(vec![
+        ("writer".into(), Val::String(stamp.writer.clone())),
+        ("counter".into(), Val::U64(stamp.counter)),
+    ])
+}
+
+fn val_stamp(val: &Val) -> Stamp {
+    let Val::Record(fields) = val else { panic!("expected record, got {:?}", val) };
+    match fields.as_slice() {
+        [(_, Val::String(writer)), (_, Val::U64(counter))] => Stamp { writer: writer.clone(), counter: *counter },
+        other => panic!("unexpected stamp fields {:?}", other),
+    }
+}
+
+fn stamp(writer: &str, counter: u64) -> Stamp {
+    Stamp { writer: writer.into(), counter }
+}
+
+async fn writer_guest(writer: Writer) -> (Arc<Runtime>, InstanceId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(WRITER_GUEST_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_system("exorun:writer/clock", HostInstance::Writer(writer))
+        .build()
+        .await
+        .expect("instantiate");
+    (rt, instance_id)
+}
+
+async fn guest_tick(rt: &Runtime, instance: InstanceId, writer: &str) -> Stamp {
+    let results = rt.call(instance, "test:writer/api", "tick", &[Val::String(writer.into())])
+        .await
+        .expect("tick");
+    val_stamp(&results[0])
+}
+
+async fn guest_merge(rt: &Runtime, instance: InstanceId, a: &Stamp, b: &Stamp) -> Stamp {
+    let results = rt.call(instance, "test:writer/api", "merge", &[stamp_val(a), stamp_val(b)])
+        .await
+        .expect("merge");
+    val_stamp(&results[0])
+}
+
+#[tokio::test]
+async fn test_writer_tick_is_monotonic_per_writer() {
+    let (rt, instance) = writer_guest(Writer::new()).await;
+
+    let mut last = std::collections::HashMap::new();
+    for writer in ["alice", "bob", "alice", "alice", "bob", "carol", "alice"] {
+        let stamp = guest_tick(&rt, instance, writer).await;
+        assert_eq!(stamp.writer, writer);
+        if let Some(prev) = last.insert(writer, stamp.counter) {
+            assert!(stamp.counter > prev, "{} went from {} to {}", writer, prev, stamp.counter);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_writer_merge_takes_max_of_concurrent_stamps() {
+    let writer = Writer::new();
+    let (rt, instance) = writer_guest(writer.clone()).await;
+
+    // Two replicas ticking independently produce concurrent stamps.
+    let a = stamp("alice", 3);
+    let b = stamp("bob", 7);
+    assert_eq!(guest_merge(&rt, instance, &a, &b).await, b);
+
+    // Equal counters break ties by writer, the same way on every replica.
+    let c = stamp("carol", 7);
+    assert_eq!(guest_merge(&rt, instance, &b, &c).await, c);
+
+    // Stamps issued after a merge follow everything merged.
+    let next = guest_tick(&rt, instance, "alice").await;
+    assert_eq!(next, stamp("alice", 8));
+    assert_eq!(writer.get_counters().await["bob"], 7);
+}
+
+#[tokio::test]
+async fn test_writer_merge_is_commutative_and_idempotent() {
+    let writer = Writer::new();
+    let stamps = [stamp("alice", 1), stamp("bob", 1), stamp("alice", 4), stamp("carol", 2)];
+
+    for a in &stamps {
+        assert_eq!(writer.merge(a.clone(), a.clone()).await, *a);
+        for b in &stamps {
+            let ab = writer.merge(a.clone(), b.clone()).await;
+            let ba = writer.merge(b.clone(), a.clone()).await;
+            assert_eq!(ab, ba);
+            assert_eq!(writer.merge(ab.clone(), b.clone()).await, ab);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Tests for per-instance resource metrics.
+
+use exorun::InstanceId;
+use exorun::InstanceMetrics;
+use exorun::Runtime;
+use exorun::runtime::Error;
+use wasmtime::component::Val;
+
+const PAGE: usize = 64 * 1024;
+
+/// A component exporting `test:metrics/api` with a `grow` that adds two pages.
+const METRICS_WAT: &str = r#"
+    (component
+        (core module $m
+            (memory (export "memory") 1)
+            (func (export "grow") (result i32) (memory.grow (i32.const 2))))
+        (core instance $i (instantiate $m))
+        (func $grow (result s32) (canon lift (core func $i "grow")))
+        (instance $api (export "grow" (func $grow)))
+        (export "test:metrics/api" (instance $api)))
+"#;
+
+/// A component with no memory at all.
+const EMPTY_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "noop")))
+        (core instance $i (instantiate $m))
+        (func $noop (canon lift (core func $i "noop")))
+        (instance $api (export "noop" (func $noop)))
+        (export "test:metrics/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_metrics_count_calls_and_fuel() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(METRICS_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let before = rt.instance_metrics(instance_id).await.expect("metrics");
+    assert_eq!(before.call_count, 0);
+    assert_eq!(before.fuel_consumed, 0);
+    assert_eq!(before.current_memory_bytes, PAGE);
+
+    let results = rt.call(instance_id, "test:metrics/api", "grow", &[]).await.expect("grow");
+    assert_eq!(results, vec![Val::S32(1)]);
+
+    let after = rt.instance_metrics(instance_id).await.expect("metrics");
+    assert_eq!(after.call_count, 1);
+    assert!(after.fuel_consumed > 0);
+    assert_eq!(after.current_memory_bytes, 3 * PAGE);
+    assert_eq!(after.peak_memory_bytes, 3 * PAGE);
+}
+
+#[tokio::test]
+async fn test_metrics_zeroed_for_idle_instance() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(EMPTY_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let metrics = rt.instance_metrics(instance_id).await.expect("idle instance has metrics");
+    assert_eq!(metrics, InstanceMetrics::default());
+
+    let missing = rt.instance_metrics(InstanceId(9999)).await;
+    assert!(matches!(missing, Err(Error::InstanceNotFound(_))));
+}
@@ -0,0 +1,112 @@
+//! Tests for pools of warm instances from `Runtime::create_pool`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use exorun::{InstanceId, PoolId};
+use exorun::local::LinkProfile;
+use exorun::runtime::{self, Runtime};
+use tokio::task::JoinSet;
+use wasmtime::component::Val;
+
+/// Exports `test:math/api` with `add`, and `boom`, which traps.
+const MATH_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1)))
+            (func (export "boom") unreachable))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (func $boom (canon lift (core func $i "boom")))
+        (instance $api
+            (export "add" (func $add))
+            (export "boom" (func $boom)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+const MATH: &str = "test:math/api";
+
+async fn math_pool(size: usize) -> (Arc<Runtime>, PoolId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let pool_id = rt.create_pool(component_id, size, LinkProfile::new()).await.expect("create pool");
+    (rt, pool_id)
+}
+
+async fn add(rt: &Runtime, instance_id: InstanceId, n: u32) -> (InstanceId, runtime::Result<Vec<Val>>) {
+    (instance_id, rt.call(instance_id, MATH, "add", &[Val::U32(n), Val::U32(1)]).await)
+}
+
+#[tokio::test]
+async fn test_pool_reuses_instances() {
+    let (rt, pool_id) = math_pool(2).await;
+    assert_eq!(rt.list_instances().len(), 2);
+
+    let mut used = HashSet::new();
+    for n in 0..10 {
+        let (instance_id, results) = rt.with_pooled_instance(pool_id, |id| add(&rt, id, n)).await.expect("checkout");
+        assert_eq!(results.expect("add"), vec![Val::U32(n + 1)]);
+        used.insert(instance_id);
+    }
+
+    assert_eq!(used.len(), 2);
+    assert_eq!(rt.list_instances().len(), 2);
+    for instance_id in used {
+        assert_eq!(rt.instance_metrics(instance_id).await.expect("metrics").call_count, 5);
+    }
+}
+
+#[tokio::test]
+async fn test_trapped_instance_is_replaced() {
+    let (rt, pool_id) = math_pool(1).await;
+
+    let rt_ref = &rt;
+    let (trapped, err) = rt.with_pooled_instance(pool_id, |id| async move {
+        (id, rt_ref.call(id, MATH, "boom", &[]).await)
+    }).await.expect("checkout");
+    err.expect_err("boom traps");
+
+    let (replacement, results) = rt.with_pooled_instance(pool_id, |id| add(&rt, id, 41)).await.expect("checkout");
+    assert_eq!(results.expect("add"), vec![Val::U32(42)]);
+    assert_ne!(replacement, trapped);
+    assert!(rt.instance_metrics(trapped).await.is_err());
+    assert_eq!(rt.list_instances().len(), 1);
+}
+
+#[tokio::test]
+async fn test_panicking_closure_replaces_instance() {
+    let (rt, pool_id) = math_pool(1).await;
+
+    let panicking = Arc::clone(&rt);
+    let handler = tokio::spawn(async move {
+        panicking.with_pooled_instance(pool_id, |_| async { panic!("handler failed") }).await
+    });
+    assert!(handler.await.expect_err("handler panics").is_panic());
+
+    let (_, results) = rt.with_pooled_instance(pool_id, |id| add(&rt, id, 1)).await.expect("checkout");
+    assert_eq!(results.expect("add"), vec![Val::U32(2)]);
+    assert_eq!(rt.list_instances().len(), 1);
+}
+
+#[tokio::test]
+async fn test_concurrent_checkouts_all_complete() {
+    let (rt, pool_id) = math_pool(2).await;
+
+    let mut handlers = JoinSet::new();
+    for n in 0..16 {
+        let rt = Arc::clone(&rt);
+        handlers.spawn(async move {
+            let (_, results) = rt.with_pooled_instance(pool_id, |id| add(&rt, id, n)).await.expect("checkout");
+            (n, results)
+        });
+    }
+    while let Some(joined) = handlers.join_next().await {
+        let (n, results) = joined.expect("handler");
+        assert_eq!(results.expect("add"), vec![Val::U32(n + 1)]);
+    }
+    assert_eq!(rt.list_instances().len(), 2);
+
+    let err = rt.with_pooled_instance(PoolId(99), |_| async {}).await.expect_err("no such pool");
+    assert!(matches!(err, exorun::pool::Error::PoolNotFound(PoolId(99))), "got {:?}", err);
+}
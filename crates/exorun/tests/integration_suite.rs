@@ -55,6 +55,36 @@ async fn test_peer_registration() {
     let _peer_id = runtime.add_peer(peer);
 }
 
+#[tokio::test]
+async fn test_remove_component_and_instance() {
+    use exorun::runtime::Error;
+
+    let rt = Runtime::new().expect("Failed to create runtime");
+    let app_id = rt.add_component_bytes(&wasm("app_logger")).expect("Failed to register app");
+
+    let instance_id = rt.instantiate(app_id)
+        .link_system("wasi:cli/environment", HostInstance::Wasi(Wasi::new()))
+        .link_system("exorun:host/logging", HostInstance::Logger(exorun::host::Logger::new()))
+        .build()
+        .await
+        .expect("Failed to instantiate");
+
+    rt.remove_component(app_id).expect("remove registered component");
+    assert!(matches!(rt.get_component(app_id), Err(Error::ComponentNotFound(_))));
+    assert!(matches!(rt.get_ledger(app_id), Err(Error::ComponentNotFound(_))));
+    assert!(matches!(rt.remove_component(app_id), Err(Error::ComponentNotFound(_))));
+
+    // Existing instances outlive their component's registration.
+    rt.call(instance_id, "exorun:test/runnable", "run", &[]).await.expect("call after component removal");
+
+    rt.remove_instance(instance_id).expect("remove registered instance");
+    assert!(matches!(
+        rt.call(instance_id, "exorun:test/runnable", "run", &[]).await,
+        Err(Error::InstanceNotFound(_))
+    ));
+    assert!(matches!(rt.remove_instance(instance_id), Err(Error::InstanceNotFound(_))));
+}
+
 // --- Test 4: System Integration (Logger) ---
 
 #[tokio::test]
@@ -194,12 +224,11 @@ impl MathServiceTransport {
 #[async_trait::async_trait]
 impl Transport for MathServiceTransport {
     async fn send(&self, payload: &[u8]) -> Result<(), exorun::transport::Error> {
-        use neopack::{Decoder, Encoder};
-        use neorpc::{RpcFrame, ReplyOkEncoder, encode_vals_to_bytes};
+        use neopack::Encoder;
+        use neorpc::{RpcFrame, ReplyOkEncoder, encode_vals_to_bytes, frame_bytes};
         use wasmtime::component::Val;
         
-        let mut dec = Decoder::new(payload);
-        let frame = RpcFrame::decode(&mut dec).map_err(|e| exorun::transport::Error::Io(e.to_string()))?;
+        let frame = RpcFrame::decode(payload).map_err(|e| exorun::transport::Error::Io(e.to_string()))?;
 
         match frame {
             RpcFrame::Call(mut c) => {
@@ -224,7 +253,7 @@ impl Transport for MathServiceTransport {
                 // Create reply
                 let mut enc = Encoder::new();
                 ReplyOkEncoder::new(c.seq, &result_bytes).encode(&mut enc).map_err(|e| exorun::transport::Error::Io(e.to_string()))?;
-                let response = enc.into_bytes().map_err(|e| exorun::transport::Error::Io(e.to_string()))?;
+                let response = frame_bytes(enc).map_err(|e| exorun::transport::Error::Io(e.to_string()))?;
                 
                 eprintln!("Mock transport sending response: seq={}, result={}, bytes={:?}", c.seq, result, response);
                 self.response_tx.send(response).map_err(|_| exorun::transport::Error::ConnectionLost("Channel closed".into()))?;
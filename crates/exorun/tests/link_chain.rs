@@ -0,0 +1,127 @@
+//! Tests for linking an interface to a fallback chain with `link_chain`.
+
+use std::sync::Arc;
+
+use exorun::InstanceId;
+use exorun::local::Linkable;
+use exorun::peer::{Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::LocalTransport;
+use wasmtime::component::Val;
+
+/// Exports `test:math/api` with `add`, and `boom`, which traps.
+const MATH_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1)))
+            (func (export "boom") unreachable))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (func $boom (canon lift (core func $i "boom")))
+        (instance $api
+            (export "add" (func $add))
+            (export "boom" (func $boom)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+/// Imports `test:math/api` and exports `test:client/api` with `run`,
+/// returning `add(20, 22)`, and `boom`, calling through to `boom`.
+const CLIENT_WAT: &str = r#"
+    (component
+        (import "test:math/api" (instance $math
+            (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+            (export "boom" (func))))
+        (core func $add (canon lower (func $math "add")))
+        (core func $boom (canon lower (func $math "boom")))
+        (core module $m
+            (import "math" "add" (func $add (param i32 i32) (result i32)))
+            (import "math" "boom" (func $boom))
+            (func (export "run") (result i32)
+                (call $add (i32.const 20) (i32.const 22)))
+            (func (export "boom") (call $boom)))
+        (core instance $i (instantiate $m
+            (with "math" (instance
+                (export "add" (func $add))
+                (export "boom" (func $boom))))))
+        (func $run (result u32) (canon lift (core func $i "run")))
+        (func $boom (canon lift (core func $i "boom")))
+        (instance $api
+            (export "run" (func $run))
+            (export "boom" (func $boom)))
+        (export "test:client/api" (instance $api)))
+"#;
+
+const MATH: &str = "test:math/api";
+const CLIENT: &str = "test:client/api";
+
+/// A runtime serving a math instance over a local channel, and the chain target reaching it.
+async fn remote_math(client: &Runtime) -> (Arc<Runtime>, InstanceId, Linkable) {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component_id = server.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let instance_id = server.instantiate(component_id).build().await.expect("instantiate");
+
+    let (ours, theirs) = LocalTransport::pair();
+    let peer_id = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(peer_id, instance_id).expect("serve");
+
+    let peer_id = client.add_peer(Arc::new(Peer::new("server", Box::new(theirs), PeerConfig::default())));
+    (server, instance_id, Linkable::Remote(peer_id.get_instance(MATH)))
+}
+
+async fn local_math(rt: &Arc<Runtime>) -> InstanceId {
+    let component_id = rt.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    rt.instantiate(component_id).build().await.expect("instantiate math")
+}
+
+async fn client(rt: &Arc<Runtime>, targets: Vec<Linkable>) -> InstanceId {
+    let component_id = rt.add_component_bytes(CLIENT_WAT.as_bytes()).expect("add component");
+    rt.instantiate(component_id)
+        .link_chain(MATH, targets)
+        .build()
+        .await
+        .expect("instantiate client")
+}
+
+#[tokio::test]
+async fn test_chain_falls_back_from_missing_local_to_remote() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let local = local_math(&rt).await;
+    rt.remove_instance(local).expect("remove local");
+    let (server, served, remote) = remote_math(&rt).await;
+
+    let instance = client(&rt, vec![Linkable::LocalInstance(local), remote]).await;
+    let results = rt.call(instance, CLIENT, "run", &[]).await.expect("run");
+    assert_eq!(results, vec![Val::U32(42)]);
+    assert_eq!(server.instance_metrics(served).await.expect("metrics").call_count, 1);
+}
+
+#[tokio::test]
+async fn test_chain_stops_at_first_answer() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let local = local_math(&rt).await;
+    let (server, served, remote) = remote_math(&rt).await;
+
+    let instance = client(&rt, vec![Linkable::LocalInstance(local), remote]).await;
+    let results = rt.call(instance, CLIENT, "run", &[]).await.expect("run");
+    assert_eq!(results, vec![Val::U32(42)]);
+    assert_eq!(rt.instance_metrics(local).await.expect("metrics").call_count, 1);
+    assert_eq!(server.instance_metrics(served).await.expect("metrics").call_count, 0);
+
+    // A trap is an answer too, not a reason to try the remote
+    rt.call(instance, CLIENT, "boom", &[]).await.expect_err("boom traps");
+    assert_eq!(server.instance_metrics(served).await.expect("metrics").call_count, 0);
+}
+
+#[tokio::test]
+async fn test_chain_returns_last_error_when_all_unreachable() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let first = local_math(&rt).await;
+    let second = local_math(&rt).await;
+    rt.remove_instance(first).expect("remove first");
+    rt.remove_instance(second).expect("remove second");
+
+    let instance = client(&rt, vec![Linkable::LocalInstance(first), Linkable::LocalInstance(second)]).await;
+    let err = rt.call(instance, CLIENT, "run", &[]).await.expect_err("nothing to call");
+    assert!(format!("{:?}", err).contains(&format!("instance not found: {}", second)), "got {:?}", err);
+}
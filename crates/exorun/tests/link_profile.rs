@@ -0,0 +1,59 @@
+//! Tests for applying a shared `LinkProfile` to several instances.
+
+use exorun::host::HostInstance;
+use exorun::host::Logger;
+use exorun::host::Wasi;
+use exorun::local::LinkProfile;
+use exorun::runtime::Runtime;
+
+fn wasm(name: &str) -> Vec<u8> {
+    let path = format!("tests/fixtures/{}.wasm", name);
+    std::fs::read(&path).unwrap_or_else(|_| panic!("Could not read wasm: {}", path))
+}
+
+#[tokio::test]
+async fn test_profile_applies_to_many_instances() {
+    let rt = Runtime::new().expect("Failed to create runtime");
+    let app_id = rt.add_component_bytes(&wasm("app_logger")).expect("Failed to register app");
+
+    let logger = Logger::new();
+    let profile = LinkProfile::new()
+        .link_system("wasi:cli/environment", HostInstance::Wasi(Wasi::new()))
+        .link_system("exorun:host/logging", HostInstance::Logger(logger.clone()));
+
+    for _ in 0..2 {
+        let instance_id = rt.instantiate(app_id)
+            .apply(&profile)
+            .build()
+            .await
+            .expect("Failed to instantiate from profile");
+        rt.call(instance_id, "exorun:test/runnable", "run", &[]).await.expect("run");
+    }
+
+    // Both instances log through the profile's shared logger
+    assert_eq!(logger.get_logs().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_later_link_overrides_profile() {
+    let rt = Runtime::new().expect("Failed to create runtime");
+    let app_id = rt.add_component_bytes(&wasm("app_logger")).expect("Failed to register app");
+
+    let shared = Logger::new();
+    let profile = LinkProfile::new()
+        .link_system("wasi:cli/environment", HostInstance::Wasi(Wasi::new()))
+        .link_system("exorun:host/logging", HostInstance::Logger(shared.clone()));
+
+    let own = Logger::new();
+    let instance_id = rt.instantiate(app_id)
+        .apply(&profile)
+        .link_system("exorun:host/logging", HostInstance::Logger(own.clone()))
+        .build()
+        .await
+        .expect("Failed to instantiate with override");
+    rt.call(instance_id, "exorun:test/runnable", "run", &[]).await.expect("run");
+
+    assert_eq!(own.get_logs().await, vec!["[INFO] Hello from Wasm!".to_string()]);
+    assert!(shared.get_logs().await.is_empty(), "the overridden logger sees nothing");
+    assert_eq!(profile.links().len(), 2, "applying leaves the profile untouched");
+}
@@ -0,0 +1,105 @@
+//! Tests for import/export compatibility checks on local links.
+
+use std::sync::Arc;
+
+use exorun::InstanceId;
+use exorun::Runtime;
+use exorun::ledger;
+use exorun::local::builder::Error;
+
+/// Imports `test:link/math` with `add` and `sub`, without calling either.
+const CONSUMER_WAT: &str = r#"
+    (component
+        (import "test:link/math" (instance
+            (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+            (export "sub" (func (param "a" u32) (param "b" u32) (result u32))))))
+"#;
+
+/// Exports `test:link/math` with `add` and `sub`.
+const FULL_PROVIDER_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32) (i32.add (local.get 0) (local.get 1)))
+            (func (export "sub") (param i32 i32) (result i32) (i32.sub (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (func $sub (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "sub")))
+        (instance $api
+            (export "add" (func $add))
+            (export "sub" (func $sub)))
+        (export "test:link/math" (instance $api)))
+"#;
+
+/// Exports `test:link/math` with only `add`.
+const MISSING_SUB_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32) (i32.add (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (instance $api (export "add" (func $add)))
+        (export "test:link/math" (instance $api)))
+"#;
+
+/// Exports `test:link/math` where `sub` takes one param instead of two.
+const UNARY_SUB_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32) (i32.add (local.get 0) (local.get 1)))
+            (func (export "sub") (param i32) (result i32) (i32.sub (i32.const 0) (local.get 0))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (func $sub (param "a" u32) (result u32) (canon lift (core func $i "sub")))
+        (instance $api
+            (export "add" (func $add))
+            (export "sub" (func $sub)))
+        (export "test:link/math" (instance $api)))
+"#;
+
+async fn provider(rt: &Arc<Runtime>, wat: &str) -> InstanceId {
+    let component_id = rt.add_component_bytes(wat.as_bytes()).expect("add provider");
+    rt.instantiate(component_id).build().await.expect("instantiate provider")
+}
+
+async fn link_consumer(rt: &Arc<Runtime>, target: InstanceId) -> Result<InstanceId, Error> {
+    let component_id = rt.add_component_bytes(CONSUMER_WAT.as_bytes()).expect("add consumer");
+    rt.instantiate(component_id)
+        .link_local("test:link/math", target)
+        .build()
+        .await
+}
+
+#[tokio::test]
+async fn test_link_accepts_matching_provider() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let target = provider(&rt, FULL_PROVIDER_WAT).await;
+
+    link_consumer(&rt, target).await.expect("compatible link");
+}
+
+#[tokio::test]
+async fn test_link_rejects_provider_missing_method() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let target = provider(&rt, MISSING_SUB_WAT).await;
+
+    let err = link_consumer(&rt, target).await.expect_err("provider lacks sub");
+    let Error::Ledger(ledger::Error::InvalidParameter { import_name, details }) = &err else {
+        panic!("expected an early ledger error, got {}", err);
+    };
+    assert_eq!(import_name, "test:link/math#sub");
+    assert!(details.contains("not found"), "details: {}", details);
+    assert!(err.to_string().contains("test:link/math#sub"), "message: {}", err);
+}
+
+#[tokio::test]
+async fn test_link_rejects_param_count_mismatch() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let target = provider(&rt, UNARY_SUB_WAT).await;
+
+    let err = link_consumer(&rt, target).await.expect_err("sub takes the wrong number of params");
+    let Error::Ledger(ledger::Error::InvalidParameter { import_name, details }) = &err else {
+        panic!("expected an early ledger error, got {}", err);
+    };
+    assert_eq!(import_name, "test:link/math#sub");
+    assert!(details.contains("import expects 2, export provides 1"), "details: {}", details);
+}
@@ -0,0 +1,108 @@
+//! Tests for the on-disk compiled component cache from `Runtime::enable_module_cache`.
+
+use std::path::{Path, PathBuf};
+
+use exorun::runtime::Runtime;
+use wasmtime::Engine;
+use wasmtime::component::{Component, Val};
+
+/// Exports `test:math/api` with `add`.
+const MATH_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (instance $api (export "add" (func $add)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+const MATH: &str = "test:math/api";
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("exorun-cache-{}-{}", name, rand::random::<u64>()))
+}
+
+fn artifacts(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .expect("read cache dir")
+        .map(|entry| entry.expect("cache entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cwasm"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[tokio::test]
+async fn test_artifact_loads_into_fresh_engine() {
+    let dir = temp_dir("fresh");
+    let first = Runtime::new().expect("runtime creation failed");
+    first.enable_module_cache(dir.clone()).expect("enable cache");
+    first.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    drop(first);
+
+    let [artifact] = artifacts(&dir).try_into().expect("one artifact");
+    let written = std::fs::metadata(&artifact).expect("metadata").modified().expect("mtime");
+
+    // The artifact alone is enough for a fresh engine with the same config
+    let rt = Runtime::new().expect("runtime creation failed");
+    let bytes = std::fs::read(&artifact).expect("read artifact");
+    let component = unsafe { Component::deserialize(rt.engine(), &bytes) }.expect("deserialize");
+    let component_id = rt.add_component(component).expect("add compiled");
+    let instance = rt.instantiate(component_id).build().await.expect("instantiate");
+    let results = rt.call(instance, MATH, "add", &[Val::U32(20), Val::U32(22)]).await.expect("add");
+    assert_eq!(results, vec![Val::U32(42)]);
+
+    // And a restarted runtime finds it instead of compiling again
+    let restarted = Runtime::new().expect("runtime creation failed");
+    restarted.enable_module_cache(dir.clone()).expect("enable cache");
+    let component_id = restarted.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let instance = restarted.instantiate(component_id).build().await.expect("instantiate");
+    let results = restarted.call(instance, MATH, "add", &[Val::U32(1), Val::U32(2)]).await.expect("add");
+    assert_eq!(results, vec![Val::U32(3)]);
+    assert_eq!(artifacts(&dir), vec![artifact.clone()]);
+    assert_eq!(std::fs::metadata(&artifact).expect("metadata").modified().expect("mtime"), written);
+
+    std::fs::remove_dir_all(&dir).expect("clean up");
+}
+
+#[tokio::test]
+async fn test_engine_config_changes_the_key() {
+    let dir = temp_dir("config");
+    let rt = Runtime::new().expect("runtime creation failed");
+    rt.enable_module_cache(dir.clone()).expect("enable cache");
+    rt.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    assert_eq!(artifacts(&dir).len(), 1);
+
+    // Without fuel metering, the compiled code differs
+    let mut config = wasmtime::Config::new();
+    config.async_support(true);
+    config.wasm_component_model(true);
+    let other = Runtime::with_engine(Engine::new(&config).expect("engine"));
+    other.enable_module_cache(dir.clone()).expect("enable cache");
+    other.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    assert_eq!(artifacts(&dir).len(), 2);
+
+    std::fs::remove_dir_all(&dir).expect("clean up");
+}
+
+#[tokio::test]
+async fn test_unloadable_artifact_is_recompiled() {
+    let dir = temp_dir("corrupt");
+    let rt = Runtime::new().expect("runtime creation failed");
+    rt.enable_module_cache(dir.clone()).expect("enable cache");
+    rt.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let [artifact] = artifacts(&dir).try_into().expect("one artifact");
+    std::fs::write(&artifact, b"not a compiled component").expect("corrupt artifact");
+
+    let restarted = Runtime::new().expect("runtime creation failed");
+    restarted.enable_module_cache(dir.clone()).expect("enable cache");
+    let component_id = restarted.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let instance = restarted.instantiate(component_id).build().await.expect("instantiate");
+    let results = restarted.call(instance, MATH, "add", &[Val::U32(2), Val::U32(3)]).await.expect("add");
+    assert_eq!(results, vec![Val::U32(5)]);
+    assert_ne!(std::fs::read(&artifact).expect("read artifact"), b"not a compiled component");
+
+    std::fs::remove_dir_all(&dir).expect("clean up");
+}
@@ -0,0 +1,67 @@
+//! Tests for seeding an instance's WASI randomness.
+
+use std::sync::Arc;
+
+use exorun::ComponentId;
+use exorun::InstanceId;
+use exorun::host::{HostInstance, Wasi};
+use exorun::runtime::Runtime;
+use wasmtime::component::Val;
+
+/// Exports `test:random/api.next`, which returns `wasi:random/random.get-random-u64`.
+const RANDOM_WAT: &str = r#"
+    (component
+        (import "wasi:random/random@0.2.0" (instance $random
+            (export "get-random-u64" (func (result u64)))))
+        (core func $get (canon lower (func $random "get-random-u64")))
+        (core module $m
+            (import "random" "u64" (func $get (result i64)))
+            (func (export "next") (result i64) (call $get)))
+        (core instance $i (instantiate $m
+            (with "random" (instance (export "u64" (func $get))))))
+        (func $next (result u64) (canon lift (core func $i "next")))
+        (instance $api (export "next" (func $next)))
+        (export "test:random/api" (instance $api)))
+"#;
+
+async fn instance(rt: &Arc<Runtime>, component_id: ComponentId, seed: Option<u64>) -> InstanceId {
+    let mut builder = rt.instantiate(component_id)
+        .link_system("wasi:random/random", HostInstance::Wasi(Wasi::new()));
+    if let Some(seed) = seed {
+        builder = builder.with_rng_seed(seed);
+    }
+    builder.build().await.expect("instantiate")
+}
+
+async fn draws(rt: &Arc<Runtime>, instance_id: InstanceId) -> Vec<Val> {
+    let mut draws = Vec::new();
+    for _ in 0..4 {
+        let mut results = rt.call(instance_id, "test:random/api", "next", &[]).await.expect("next");
+        draws.push(results.remove(0));
+    }
+    draws
+}
+
+#[tokio::test]
+async fn test_same_seed_same_bytes() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(RANDOM_WAT.as_bytes()).expect("add component");
+
+    let a = instance(&rt, component_id, Some(42)).await;
+    let b = instance(&rt, component_id, Some(42)).await;
+    let c = instance(&rt, component_id, Some(43)).await;
+
+    let first = draws(&rt, a).await;
+    assert_eq!(first, draws(&rt, b).await);
+    assert_ne!(first, draws(&rt, c).await);
+}
+
+#[tokio::test]
+async fn test_unseeded_instances_differ() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(RANDOM_WAT.as_bytes()).expect("add component");
+
+    let a = instance(&rt, component_id, None).await;
+    let b = instance(&rt, component_id, None).await;
+    assert_ne!(draws(&rt, a).await, draws(&rt, b).await);
+}
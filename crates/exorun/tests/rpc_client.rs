@@ -0,0 +1,113 @@
+//! Tests for typed peer clients generated from WIT by `#[rpc_client]`.
+#![cfg(feature = "macros")]
+
+use std::sync::{Arc, Mutex};
+
+use exorun::peer::{self, Peer, PeerConfig, rpc_client};
+use exorun::transport::{self, Transport};
+use neopack::Encoder;
+use neorpc::{FailureReason, ReplyErrEncoder, ReplyOkEncoder, RpcFrame, frame_bytes};
+use tokio::sync::mpsc;
+use wasmtime::component::{Type, Val};
+
+#[rpc_client(path = "tests/wit/calc", world = "calc-client", interface = "test:calc/api")]
+pub struct CalcClient;
+
+/// Each call the mock received: target, method, and decoded args.
+type CallLog = Arc<Mutex<Vec<(String, String, Vec<Val>)>>>;
+
+/// Answers `test:calc/api` calls in place of a remote runtime.
+struct MockCalc {
+    calls: CallLog,
+    replies: mpsc::UnboundedSender<Vec<u8>>,
+    inbox: tokio::sync::Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl MockCalc {
+    fn new() -> (Self, CallLog) {
+        let calls = CallLog::default();
+        let (replies, inbox) = mpsc::unbounded_channel();
+        let mock = Self { calls: Arc::clone(&calls), replies, inbox: tokio::sync::Mutex::new(inbox) };
+        (mock, calls)
+    }
+}
+
+fn io(e: impl std::fmt::Display) -> transport::Error {
+    transport::Error::Io(e.to_string())
+}
+
+#[async_trait::async_trait]
+impl Transport for MockCalc {
+    async fn send(&self, payload: &[u8]) -> transport::Result<()> {
+        let RpcFrame::Call(call) = RpcFrame::decode(payload).map_err(io)? else {
+            return Err(io("expected a Call frame"));
+        };
+        let param_types = match call.method {
+            "add" => vec![Type::U32, Type::U32],
+            "greet" => vec![Type::String],
+            _ => Vec::new(),
+        };
+        let args = neorpc::decode_vals(call.args, &param_types).map_err(io)?;
+        self.calls.lock().unwrap().push((call.target.to_string(), call.method.to_string(), args.clone()));
+
+        let outcome = match (call.method, args.as_slice()) {
+            ("add", [Val::U32(a), Val::U32(b)]) => Ok(vec![Val::U32(a + b)]),
+            ("greet", [Val::String(name)]) => Ok(vec![Val::String(format!("hello, {}", name))]),
+            ("reset", []) => Ok(Vec::new()),
+            _ => Err(FailureReason::MethodNotFound),
+        };
+        let mut enc = Encoder::new();
+        match outcome {
+            Ok(results) => {
+                let results = neorpc::encode_vals_to_bytes(&results).map_err(io)?;
+                ReplyOkEncoder::new(call.seq, &results).encode(&mut enc).map_err(io)?;
+            }
+            Err(reason) => ReplyErrEncoder::new(call.seq, reason).encode(&mut enc).map_err(io)?,
+        }
+        self.replies.send(frame_bytes(enc).map_err(io)?).map_err(io)
+    }
+
+    async fn recv(&self) -> transport::Result<Option<Vec<u8>>> {
+        Ok(self.inbox.lock().await.recv().await)
+    }
+}
+
+#[tokio::test]
+async fn test_generated_client_calls_through_peer() {
+    let (mock, calls) = MockCalc::new();
+    let peer = Arc::new(Peer::new("calc", Box::new(mock), PeerConfig::default()));
+    let calc = CalcClient::new(Arc::clone(&peer));
+
+    assert_eq!(calc.add(10, 5).await.expect("add"), 15);
+    assert_eq!(calc.greet("calc".to_string()).await.expect("greet"), "hello, calc");
+    calc.reset().await.expect("reset");
+
+    let calls = calls.lock().unwrap().clone();
+    assert_eq!(calls, vec![
+        ("test:calc/api".to_string(), "add".to_string(), vec![Val::U32(10), Val::U32(5)]),
+        ("test:calc/api".to_string(), "greet".to_string(), vec![Val::String("calc".into())]),
+        ("test:calc/api".to_string(), "reset".to_string(), vec![]),
+    ]);
+}
+
+#[tokio::test]
+async fn test_generated_client_uses_exposed_target() {
+    let (mock, calls) = MockCalc::new();
+    let peer = Arc::new(Peer::new("calc", Box::new(mock), PeerConfig::default()));
+    let calc = CalcClient::with_target(peer, "calculator");
+
+    assert_eq!(calc.add(1, 2).await.expect("add"), 3);
+    assert_eq!(calls.lock().unwrap()[0].0, "calculator");
+}
+
+#[tokio::test]
+async fn test_call_typed_rejects_mismatched_result_types() {
+    let (mock, calls) = MockCalc::new();
+    let peer = Peer::new("calc", Box::new(mock), PeerConfig::default());
+
+    let err = peer.call_typed::<(u32, u32), String>("test:calc/api", "add", (1, 2), vec![Type::U32])
+        .await
+        .expect_err("result types disagree with String");
+    assert!(matches!(err, peer::Error::NeoRpc(neorpc::Error::TypeMismatch { .. })), "got {:?}", err);
+    assert!(calls.lock().unwrap().is_empty());
+}
@@ -0,0 +1,175 @@
+//! Tests for the `rpc.call` and `rpc.serve` spans opened around remote calls.
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::field::{Field, Visit};
+use tracing::span;
+use tracing::{Event, Level, Metadata, Subscriber};
+use wasmtime::component::Val;
+
+use exorun::peer::{Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::LocalTransport;
+
+type Fields = HashMap<String, String>;
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+/// Keeps every span's fields and every event, for assertions.
+#[derive(Clone, Default)]
+struct Captured {
+    spans: Arc<Mutex<HashMap<u64, (String, Fields)>>>,
+    events: Arc<Mutex<Vec<(Level, Fields)>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Captured {
+    fn spans_named(&self, name: &str) -> Vec<Fields> {
+        let spans = self.spans.lock().unwrap();
+        let mut found: Vec<_> = spans.iter()
+            .filter(|(_, (span_name, _))| span_name == name)
+            .map(|(id, (_, fields))| (*id, fields.clone()))
+            .collect();
+        found.sort_by_key(|(id, _)| *id);
+        found.into_iter().map(|(_, fields)| fields).collect()
+    }
+}
+
+impl Subscriber for Captured {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut fields = Fields::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().insert(id, (attrs.metadata().name().to_string(), fields));
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events.lock().unwrap().push((*event.metadata().level(), fields));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// Exports `test:remote/api`, where `ok` returns 7 and `fail` traps.
+const SERVER_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "ok") (result i32) (i32.const 7))
+            (func (export "fail") (result i32) unreachable))
+        (core instance $i (instantiate $m))
+        (func $ok (result u32) (canon lift (core func $i "ok")))
+        (func $fail (result u32) (canon lift (core func $i "fail")))
+        (instance $api
+            (export "ok" (func $ok))
+            (export "fail" (func $fail)))
+        (export "test:remote/api" (instance $api)))
+"#;
+
+/// Imports `test:remote/api` with `ok` and `fail`, re-exporting both.
+const CALLER_WAT: &str = r#"
+    (component
+        (import "test:remote/api" (instance $remote
+            (export "ok" (func (result u32)))
+            (export "fail" (func (result u32)))))
+        (core func $ok (canon lower (func $remote "ok")))
+        (core func $fail (canon lower (func $remote "fail")))
+        (core module $m
+            (import "remote" "ok" (func $ok (result i32)))
+            (import "remote" "fail" (func $fail (result i32)))
+            (func (export "ok") (result i32) (call $ok))
+            (func (export "fail") (result i32) (call $fail)))
+        (core instance $i (instantiate $m
+            (with "remote" (instance
+                (export "ok" (func $ok))
+                (export "fail" (func $fail))))))
+        (func $run_ok (result u32) (canon lift (core func $i "ok")))
+        (func $run_fail (result u32) (canon lift (core func $i "fail")))
+        (instance $api
+            (export "ok" (func $run_ok))
+            (export "fail" (func $run_fail)))
+        (export "test:local/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_remote_calls_open_traced_spans() {
+    let captured = Captured::default();
+    let _guard = tracing::dispatcher::set_default(&tracing::Dispatch::new(captured.clone()));
+
+    let server = Runtime::new().expect("runtime creation failed");
+    let server_component = server.add_component_bytes(SERVER_WAT.as_bytes()).expect("add server component");
+    let served = server.instantiate(server_component).build().await.expect("instantiate server");
+
+    let (ours, theirs) = LocalTransport::pair();
+    let client_id = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(client_id, served).expect("serve");
+
+    let rt = Runtime::new().expect("runtime creation failed");
+    let peer_id = rt.add_peer(Arc::new(Peer::new("server", Box::new(theirs), PeerConfig::default())));
+    let component_id = rt.add_component_bytes(CALLER_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id)
+        .link_remote("test:remote/api", peer_id.get_instance("test:remote/api"))
+        .build()
+        .await
+        .expect("instantiate");
+
+    let results = rt.call(instance_id, "test:local/api", "ok", &[]).await.expect("ok call");
+    assert_eq!(results, vec![Val::U32(7)]);
+    rt.call(instance_id, "test:local/api", "fail", &[]).await.expect_err("fail call");
+
+    let calls = captured.spans_named("rpc.call");
+    assert_eq!(calls.len(), 2);
+    for (span, (method, outcome)) in calls.iter().zip([("ok", "ok"), ("fail", "remote")]) {
+        assert_eq!(span["target"], "test:remote/api");
+        assert_eq!(span["method"], method);
+        assert_eq!(span["outcome"], outcome);
+        assert!(span.contains_key("seq"));
+        assert!(span.contains_key("elapsed_us"));
+    }
+
+    // serve_peer opens the serving side's span with the trace id sent on the wire
+    let serves = captured.spans_named("rpc.serve");
+    assert_eq!(serves.len(), 2);
+    for (call, serve) in calls.iter().zip(&serves) {
+        assert!(call.contains_key("trace_id"));
+        assert_eq!(serve["trace_id"], call["trace_id"]);
+        assert_eq!(serve["method"], call["method"]);
+        assert_eq!(serve["seq"], call["seq"]);
+    }
+
+    // Only the failed call warns, naming the remote's reason
+    let events = captured.events.lock().unwrap();
+    let warnings: Vec<_> = events.iter().filter(|(level, _)| *level == Level::WARN).collect();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].1["reason"], "AppTrapped");
+}
@@ -0,0 +1,121 @@
+//! Tests for runtime lifecycle event subscriptions.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast::Receiver;
+use wasmtime::component::Val;
+
+use exorun::RuntimeEvent;
+use exorun::peer::{Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::Transport;
+
+/// Exports `test:events/api` with a `sum` that does some work and a `crash` that traps.
+const EVENTS_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "sum") (result i32)
+                (local $i i32) (local $acc i32)
+                (loop $l
+                    (local.set $acc (i32.add (local.get $acc) (local.get $i)))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br_if $l (i32.lt_u (local.get $i) (i32.const 1000))))
+                (local.get $acc))
+            (func (export "crash") unreachable))
+        (core instance $i (instantiate $m))
+        (func $sum (result s32) (canon lift (core func $i "sum")))
+        (func $crash (canon lift (core func $i "crash")))
+        (instance $api
+            (export "sum" (func $sum))
+            (export "crash" (func $crash)))
+        (export "test:events/api" (instance $api)))
+"#;
+
+struct IdleTransport;
+
+#[async_trait::async_trait]
+impl Transport for IdleTransport {
+    async fn send(&self, _payload: &[u8]) -> Result<(), exorun::transport::Error> {
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Option<Vec<u8>>, exorun::transport::Error> {
+        std::future::pending().await
+    }
+}
+
+async fn next_event(events: &mut Receiver<RuntimeEvent>) -> RuntimeEvent {
+    tokio::time::timeout(Duration::from_secs(1), events.recv())
+        .await
+        .expect("timed out waiting for event")
+        .expect("event channel closed")
+}
+
+#[tokio::test]
+async fn test_events_for_instance_lifecycle() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let mut events = rt.subscribe_events();
+
+    let component_id = rt.add_component_bytes(EVENTS_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+    match next_event(&mut events).await {
+        RuntimeEvent::InstanceStarted(id, component) => {
+            assert_eq!(id, instance_id);
+            assert_eq!(component, component_id);
+        }
+        other => panic!("Expected InstanceStarted, got {:?}", other),
+    }
+
+    let results = rt.call(instance_id, "test:events/api", "sum", &[]).await.expect("sum");
+    assert_eq!(results, vec![Val::S32(499500)]);
+    match next_event(&mut events).await {
+        RuntimeEvent::InstanceCallCompleted(id, elapsed) => {
+            assert_eq!(id, instance_id);
+            assert!(elapsed > Duration::ZERO);
+        }
+        other => panic!("Expected InstanceCallCompleted, got {:?}", other),
+    }
+
+    let crashed = rt.instantiate(component_id).build().await.expect("instantiate");
+    assert!(matches!(next_event(&mut events).await, RuntimeEvent::InstanceStarted(..)));
+    assert!(rt.call(crashed, "test:events/api", "crash", &[]).await.is_err());
+    match next_event(&mut events).await {
+        RuntimeEvent::InstanceTrapped(id, _) => assert_eq!(id, crashed),
+        other => panic!("Expected InstanceTrapped, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_events_for_peers() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let mut events = rt.subscribe_events();
+
+    let peer = Arc::new(Peer::new("idle", Box::new(IdleTransport), PeerConfig::default()));
+    let peer_id = rt.add_peer(peer);
+    assert!(matches!(next_event(&mut events).await, RuntimeEvent::PeerAdded(id) if id == peer_id));
+
+    rt.remove_peer(peer_id).await.expect("remove peer");
+    assert!(matches!(next_event(&mut events).await, RuntimeEvent::PeerRemoved(id) if id == peer_id));
+}
+
+#[tokio::test]
+async fn test_events_never_block_runtime() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(EVENTS_WAT.as_bytes()).expect("add component");
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    // A subscriber that never reads falls behind instead of stalling calls.
+    let mut lagging = rt.subscribe_events();
+    for _ in 0..300 {
+        rt.call(instance_id, "test:events/api", "sum", &[]).await.expect("sum");
+    }
+    assert!(matches!(
+        lagging.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(_))
+    ));
+
+    // With every receiver dropped, calls still go through.
+    drop(lagging);
+    rt.call(instance_id, "test:events/api", "sum", &[]).await.expect("sum");
+}
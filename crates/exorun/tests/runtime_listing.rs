@@ -0,0 +1,76 @@
+//! Tests for the `Runtime::list_*` introspection methods.
+
+use std::sync::Arc;
+
+use exorun::Runtime;
+use exorun::peer::{Peer, PeerConfig};
+use exorun::transport::LocalTransport;
+
+const EMPTY_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "noop")))
+        (core instance $i (instantiate $m))
+        (func $noop (canon lift (core func $i "noop")))
+        (instance $api (export "noop" (func $noop)))
+        (export "test:list/api" (instance $api)))
+"#;
+
+fn peer(name: &str) -> Arc<Peer> {
+    Arc::new(Peer::new(name, Box::new(LocalTransport::loopback()), PeerConfig::default()))
+}
+
+#[tokio::test]
+async fn test_lists_registered_items() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    assert!(rt.list_components().is_empty());
+    assert!(rt.list_peers().is_empty());
+    assert!(rt.list_instances().is_empty());
+
+    let a = rt.add_component_bytes(EMPTY_WAT.as_bytes()).expect("add a");
+    let b = rt.add_component_bytes(EMPTY_WAT.as_bytes()).expect("add b");
+    let alice = rt.add_peer(peer("alice"));
+    let bob = rt.add_peer(peer("bob"));
+    let carol = rt.add_peer(peer("carol"));
+    let instance = rt.instantiate(b).build().await.expect("instantiate");
+
+    assert_eq!(rt.list_components(), vec![a, b]);
+    assert_eq!(rt.list_peers(), vec![
+        (alice, "alice".to_string()),
+        (bob, "bob".to_string()),
+        (carol, "carol".to_string()),
+    ]);
+    assert_eq!(rt.list_instances(), vec![(instance, b)]);
+
+    rt.remove_peer(bob).await.expect("remove bob");
+    rt.remove_instance(instance).expect("remove instance");
+    rt.remove_component(a).expect("remove a");
+    assert_eq!(rt.list_components(), vec![b]);
+    assert_eq!(rt.list_peers().len(), 2);
+    assert!(rt.list_instances().is_empty());
+}
+
+#[tokio::test]
+async fn test_lists_under_concurrent_registration() {
+    let rt = Runtime::new().expect("runtime creation failed");
+
+    let tasks: Vec<_> = (0..16)
+        .map(|i| {
+            let rt = rt.clone();
+            tokio::spawn(async move {
+                rt.add_component_bytes(EMPTY_WAT.as_bytes()).expect("add component");
+                rt.add_peer(peer(&format!("peer-{}", i)));
+                // Every snapshot is internally whole: sorted, unique, and named
+                let peers = rt.list_peers();
+                assert!(peers.windows(2).all(|w| w[0].0.0 < w[1].0.0));
+                assert!(peers.iter().all(|(_, name)| name.starts_with("peer-")));
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(rt.list_components().len(), 16);
+    assert_eq!(rt.list_peers().len(), 16);
+}
@@ -0,0 +1,92 @@
+//! Tests for draining and aborting work in `Runtime::shutdown`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use exorun::ComponentId;
+use exorun::ShutdownReport;
+use exorun::local::builder;
+use exorun::runtime::{Error, Runtime};
+use wasmtime::component::Val;
+
+/// Exports `test:slow/api` with `count(n)`, which loops `n` times and returns `n`.
+const SLOW_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "count") (param $n i32) (result i32)
+                (local $i i32)
+                (block $done
+                    (loop $l
+                        (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+                        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                        (br $l)))
+                (local.get $i)))
+        (core instance $i (instantiate $m))
+        (func $count (param "n" u32) (result u32) (canon lift (core func $i "count")))
+        (instance $api (export "count" (func $count)))
+        (export "test:slow/api" (instance $api)))
+"#;
+
+const API: &str = "test:slow/api";
+
+async fn setup() -> (Arc<Runtime>, ComponentId) {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(SLOW_WAT.as_bytes()).expect("add component");
+    (rt, component_id)
+}
+
+/// Starts `count(n)` on a fresh instance and gives it a moment to get going.
+///
+/// Tests using this run on two workers, so one is free to drive timers
+/// while the call keeps the other busy.
+async fn start_count(rt: &Arc<Runtime>, component_id: ComponentId, n: u32) -> tokio::task::JoinHandle<exorun::runtime::Result<Vec<Val>>> {
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+    let call = tokio::spawn({
+        let rt = rt.clone();
+        async move { rt.call(instance_id, API, "count", &[Val::U32(n)]).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    call
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_shutdown_drains_slow_call() {
+    let (rt, component_id) = setup().await;
+    let call = start_count(&rt, component_id, 200_000_000).await;
+
+    let report = rt.clone().shutdown(Duration::from_secs(30)).await;
+    assert_eq!(report, ShutdownReport { completed: 1, aborted: 0 });
+    let results = call.await.expect("call task").expect("call completes");
+    assert_eq!(results, vec![Val::U32(200_000_000)]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_shutdown_aborts_after_timeout() {
+    let (rt, component_id) = setup().await;
+    let call = start_count(&rt, component_id, u32::MAX).await;
+
+    let report = rt.clone().shutdown(Duration::from_millis(20)).await;
+    assert_eq!(report, ShutdownReport { completed: 0, aborted: 1 });
+    let outcome = tokio::time::timeout(Duration::from_secs(5), call)
+        .await
+        .expect("aborted call returns")
+        .expect("call task");
+    assert!(matches!(outcome, Err(Error::Shutdown)), "got {:?}", outcome);
+}
+
+#[tokio::test]
+async fn test_shutdown_refuses_new_work_and_is_idempotent() {
+    let (rt, component_id) = setup().await;
+    let instance_id = rt.instantiate(component_id).build().await.expect("instantiate");
+
+    let report = rt.clone().shutdown(Duration::from_secs(1)).await;
+    assert_eq!(report, ShutdownReport::default());
+    assert!(rt.is_shut_down());
+
+    let err = rt.instantiate(component_id).build().await.unwrap_err();
+    assert!(matches!(err, builder::Error::Runtime(Error::Shutdown)), "got {:?}", err);
+    let err = rt.call(instance_id, API, "count", &[Val::U32(1)]).await.unwrap_err();
+    assert!(matches!(err, Error::Shutdown));
+
+    assert_eq!(rt.clone().shutdown(Duration::from_secs(1)).await, ShutdownReport::default());
+}
@@ -0,0 +1,199 @@
+//! Tests for serving a peer's inbound calls with `Runtime::serve_peer`.
+
+use std::sync::Arc;
+
+use exorun::InstanceId;
+use exorun::peer::{self, Peer, PeerConfig};
+use exorun::runtime::Runtime;
+use exorun::transport::LocalTransport;
+use neorpc::FailureReason;
+use tokio::task::JoinSet;
+use wasmtime::component::{Type, Val};
+
+/// Exports `test:math/api` with `add` and `boom`, which traps.
+const MATH_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1)))
+            (func (export "boom") unreachable))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (func $boom (canon lift (core func $i "boom")))
+        (instance $api
+            (export "add" (func $add))
+            (export "boom" (func $boom)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+/// Imports `test:math/api` and exports `test:client/api` `run`, returning `add(20, 22)`.
+const CLIENT_WAT: &str = r#"
+    (component
+        (import "test:math/api" (instance $math
+            (export "add" (func (param "a" u32) (param "b" u32) (result u32)))))
+        (core func $add (canon lower (func $math "add")))
+        (core module $m
+            (import "math" "add" (func $add (param i32 i32) (result i32)))
+            (func (export "run") (result i32)
+                (call $add (i32.const 20) (i32.const 22))))
+        (core instance $i (instantiate $m
+            (with "math" (instance (export "add" (func $add))))))
+        (func $run (result u32) (canon lift (core func $i "run")))
+        (instance $api (export "run" (func $run)))
+        (export "test:client/api" (instance $api)))
+"#;
+
+const MATH: &str = "test:math/api";
+
+/// A runtime serving a math instance to the returned peer.
+async fn math_server() -> (Arc<Runtime>, InstanceId, Peer) {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component_id = server.add_component_bytes(MATH_WAT.as_bytes()).expect("add component");
+    let instance_id = server.instantiate(component_id).build().await.expect("instantiate");
+
+    let (ours, theirs) = LocalTransport::pair();
+    let peer_id = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(peer_id, instance_id).expect("serve");
+
+    (server, instance_id, Peer::new("server", Box::new(theirs), PeerConfig::default()))
+}
+
+#[tokio::test]
+async fn test_serve_peer_runs_guest_calls_from_a_remote_guest() {
+    let (_server, _math, server_peer) = math_server().await;
+
+    let client = Runtime::new().expect("runtime creation failed");
+    let peer_id = client.add_peer(Arc::new(server_peer));
+    let component_id = client.add_component_bytes(CLIENT_WAT.as_bytes()).expect("add component");
+    let instance_id = client.instantiate(component_id)
+        .link_remote(MATH, peer_id.get_instance(MATH))
+        .build()
+        .await
+        .expect("instantiate client");
+
+    let results = client.call(instance_id, "test:client/api", "run", &[]).await.expect("run");
+    assert_eq!(results, vec![Val::U32(42)]);
+}
+
+#[tokio::test]
+async fn test_serve_peer_handles_concurrent_calls() {
+    let (_server, _math, peer) = math_server().await;
+    let peer = Arc::new(peer);
+
+    let mut calls = JoinSet::new();
+    for n in 0..32u32 {
+        let peer = Arc::clone(&peer);
+        calls.spawn(async move {
+            let results = peer.call(MATH, "add", &[Val::U32(n), Val::U32(1000)], vec![Type::U32]).await;
+            (n, results)
+        });
+    }
+    while let Some(joined) = calls.join_next().await {
+        let (n, results) = joined.expect("task");
+        assert_eq!(results.expect("add"), vec![Val::U32(n + 1000)]);
+    }
+}
+
+#[tokio::test]
+async fn test_serve_peer_maps_failures() {
+    let (_server, _math, peer) = math_server().await;
+
+    let err = peer.call(MATH, "boom", &[], vec![]).await.expect_err("boom traps");
+    assert!(matches!(err, peer::Error::Remote(FailureReason::AppTrapped)), "got {:?}", err);
+
+    let err = peer.call(MATH, "missing", &[], vec![]).await.expect_err("no such method");
+    assert!(matches!(err, peer::Error::Remote(FailureReason::MethodNotFound)), "got {:?}", err);
+
+    let err = peer.call("test:other/api", "add", &[], vec![]).await.expect_err("no such interface");
+    assert!(matches!(&err, peer::Error::Remote(reason) if *reason == neorpc::unknown_target()), "got {:?}", err);
+}
+
+/// Exports `add` under both `test:math/api` and `test:sum/api`.
+const TWO_TARGETS_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (instance $math (export "add" (func $add)))
+        (instance $sum (export "add" (func $add)))
+        (export "test:math/api" (instance $math))
+        (export "test:sum/api" (instance $sum)))
+"#;
+
+#[tokio::test]
+async fn test_advertised_targets_are_listed() {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component_id = server.add_component_bytes(TWO_TARGETS_WAT.as_bytes()).expect("add component");
+    let instance_id = server.instantiate(component_id).build().await.expect("instantiate");
+
+    let (ours, theirs) = LocalTransport::pair();
+    let served = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(served, instance_id).expect("serve");
+
+    let client = Runtime::new().expect("runtime creation failed");
+    let peer_id = client.add_peer(Arc::new(Peer::new("server", Box::new(theirs), PeerConfig::default())));
+    let peer = client.get_peer(peer_id).expect("peer");
+    assert!(peer.targets().is_empty());
+    assert!(!peer_id.get_instance(MATH).exists(&client));
+
+    server.advertise(served, instance_id).await.expect("advertise");
+    // Frames arrive in order, so the handshake is in once this reply is
+    let sum = peer.call("test:sum/api", "add", &[Val::U32(1), Val::U32(2)], vec![Type::U32]).await.expect("call");
+    assert_eq!(sum, vec![Val::U32(3)]);
+
+    assert_eq!(peer.targets(), vec![MATH, "test:sum/api"]);
+    assert!(peer_id.get_instance(MATH).exists(&client));
+    assert!(peer_id.get_instance("test:sum/api").exists(&client));
+    assert!(!peer_id.get_instance("test:other/api").exists(&client));
+
+    let err = peer.call("test:other/api", "add", &[Val::U32(1), Val::U32(2)], vec![Type::U32])
+        .await
+        .expect_err("not advertised");
+    assert!(matches!(&err, peer::Error::Remote(reason) if *reason == neorpc::unknown_target()), "got {:?}", err);
+}
+
+/// Exports `test:math/api` with just `add`, the interface `CLIENT_WAT` imports.
+const ADD_ONLY_WAT: &str = r#"
+    (component
+        (core module $m
+            (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1))))
+        (core instance $i (instantiate $m))
+        (func $add (param "a" u32) (param "b" u32) (result u32) (canon lift (core func $i "add")))
+        (instance $api (export "add" (func $add)))
+        (export "test:math/api" (instance $api)))
+"#;
+
+/// Serves `server_wat` to a client peer expecting `CLIENT_WAT`'s imports, and
+/// returns the outcome of a call made once the server's Handshake is in.
+async fn call_after_handshake(server_wat: &str) -> Result<Vec<Val>, peer::Error> {
+    let server = Runtime::new().expect("runtime creation failed");
+    let component_id = server.add_component_bytes(server_wat.as_bytes()).expect("add component");
+    let instance_id = server.instantiate(component_id).build().await.expect("instantiate");
+    let (ours, theirs) = LocalTransport::pair();
+    let served = server.add_peer(Arc::new(Peer::new("client", Box::new(ours), PeerConfig::default())));
+    server.serve_peer(served, instance_id).expect("serve");
+
+    let client = Runtime::new().expect("runtime creation failed");
+    let client_component = client.add_component_bytes(CLIENT_WAT.as_bytes()).expect("add client component");
+    let ledger = client.get_ledger(client_component).expect("ledger");
+    let config = PeerConfig { schema_fingerprint: Some(ledger.import_fingerprint()), ..Default::default() };
+    let peer = Peer::new("server", Box::new(theirs), config);
+
+    server.advertise(served, instance_id).await.expect("advertise");
+    // Frames arrive in order, so the handshake is in once this call is answered
+    let _ = peer.call(MATH, "add", &[Val::U32(0), Val::U32(0)], vec![Type::U32]).await;
+    peer.call(MATH, "add", &[Val::U32(20), Val::U32(22)], vec![Type::U32]).await
+}
+
+#[tokio::test]
+async fn test_handshake_checks_schema_fingerprint() {
+    let sum = call_after_handshake(ADD_ONLY_WAT).await.expect("schemas agree");
+    assert_eq!(sum, vec![Val::U32(42)]);
+
+    // MATH_WAT also exports `boom`, which the client's ledger doesn't know
+    let err = call_after_handshake(MATH_WAT).await.expect_err("schemas differ");
+    assert!(matches!(&err, peer::Error::Remote(reason) if *reason == neorpc::schema_mismatch()), "got {:?}", err);
+}
@@ -0,0 +1,133 @@
+//! Tests for host components generated from WIT by `#[system_component]`.
+#![cfg(feature = "macros")]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use exorun::context::ContextBuilder;
+use exorun::context::ExorunCtx;
+use exorun::host::{Logger, system_component};
+use exorun::runtime::Runtime;
+use wasmtime::Store;
+use wasmtime::component::{Component, Linker};
+
+/// Counts calls to `bump`, and adds.
+#[derive(Clone, Default)]
+struct Counter {
+    bumps: Arc<AtomicU32>,
+}
+
+#[system_component(path = "tests/wit", world = "counter-client", interface = "test:counter/api")]
+impl Counter {
+    fn add(&self, a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    fn bump(&self) {
+        self.bumps.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn total(&self) -> Option<u32> {
+        Some(self.bumps.load(Ordering::Relaxed))
+    }
+}
+
+/// Exports `run`, which bumps twice and returns `add(2, 3)`.
+const CLIENT_WAT: &str = r#"
+    (component
+        (import "test:counter/api" (instance $api
+            (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+            (export "bump" (func))))
+        (core func $add (canon lower (func $api "add")))
+        (core func $bump (canon lower (func $api "bump")))
+        (core module $m
+            (import "api" "add" (func $add (param i32 i32) (result i32)))
+            (import "api" "bump" (func $bump))
+            (func (export "run") (result i32)
+                (call $bump)
+                (call $bump)
+                (call $add (i32.const 2) (i32.const 3))))
+        (core instance $i (instantiate $m
+            (with "api" (instance
+                (export "add" (func $add))
+                (export "bump" (func $bump))))))
+        (func (export "run") (result u32) (canon lift (core func $i "run"))))
+"#;
+
+#[tokio::test]
+async fn test_generated_link_alongside_manual_link() {
+    let runtime = Runtime::new().expect("runtime creation failed");
+    let engine = runtime.engine().clone();
+    let component = Component::new(&engine, CLIENT_WAT).expect("compile");
+
+    let counter = Counter::default();
+    let mut linker = Linker::<ExorunCtx>::new(&engine);
+    counter.link(&mut linker).expect("generated link");
+    Logger::new().link(&mut linker).expect("manual link");
+
+    let ctx = ContextBuilder::new().build(Arc::clone(&runtime));
+    let mut store = Store::new(&engine, ctx);
+    store.set_fuel(1_000_000).expect("fuel");
+    store.set_epoch_deadline(u64::MAX / 2);
+    let instance = linker.instantiate_async(&mut store, &component).await.expect("instantiate");
+    let run = instance.get_typed_func::<(), (u32,)>(&mut store, "run").expect("run");
+
+    let (sum,) = run.call_async(&mut store, ()).await.expect("call");
+    assert_eq!(sum, 5);
+    assert_eq!(counter.total(), Some(2));
+}
+
+/// Serves WIT functions whose names are Rust keywords.
+#[derive(Clone, Default)]
+struct Keywords;
+
+#[system_component(path = "tests/wit", world = "keywords-client", interface = "test:counter/keywords")]
+impl Keywords {
+    fn r#type(&self) -> u32 {
+        7
+    }
+
+    fn r#match(&self, a: u32) -> bool {
+        a == 7
+    }
+}
+
+/// Exports `run`, returning `match(type())`.
+const KEYWORDS_WAT: &str = r#"
+    (component
+        (import "test:counter/keywords" (instance $api
+            (export "type" (func (result u32)))
+            (export "match" (func (param "a" u32) (result bool)))))
+        (core func $type (canon lower (func $api "type")))
+        (core func $match (canon lower (func $api "match")))
+        (core module $m
+            (import "api" "type" (func $type (result i32)))
+            (import "api" "match" (func $match (param i32) (result i32)))
+            (func (export "run") (result i32)
+                (call $match (call $type))))
+        (core instance $i (instantiate $m
+            (with "api" (instance
+                (export "type" (func $type))
+                (export "match" (func $match))))))
+        (func (export "run") (result bool) (canon lift (core func $i "run"))))
+"#;
+
+#[tokio::test]
+async fn test_keyword_named_functions_link() {
+    let runtime = Runtime::new().expect("runtime creation failed");
+    let engine = runtime.engine().clone();
+    let component = Component::new(&engine, KEYWORDS_WAT).expect("compile");
+
+    let mut linker = Linker::<ExorunCtx>::new(&engine);
+    Keywords.link(&mut linker).expect("generated link");
+
+    let ctx = ContextBuilder::new().build(Arc::clone(&runtime));
+    let mut store = Store::new(&engine, ctx);
+    store.set_fuel(1_000_000).expect("fuel");
+    store.set_epoch_deadline(u64::MAX / 2);
+    let instance = linker.instantiate_async(&mut store, &component).await.expect("instantiate");
+    let run = instance.get_typed_func::<(), (bool,)>(&mut store, "run").expect("run");
+
+    let (matched,) = run.call_async(&mut store, ()).await.expect("call");
+    assert!(matched);
+}
@@ -0,0 +1,56 @@
+//! Tests for per-instance execution budgets (fuel and memory caps).
+
+use exorun::Budget;
+use exorun::Runtime;
+use exorun::runtime::Error;
+use wasmtime::component::Val;
+
+/// A component exporting `test:budget/api` with a non-terminating `spin`
+/// and a `grow` that tries to add 100 pages (6.4 MB) of memory.
+const BUDGET_WAT: &str = r#"
+    (component
+        (core module $m
+            (memory (export "memory") 1)
+            (func (export "spin") (loop $l (br $l)))
+            (func (export "grow") (result i32) (memory.grow (i32.const 100))))
+        (core instance $i (instantiate $m))
+        (func $spin (canon lift (core func $i "spin")))
+        (func $grow (result s32) (canon lift (core func $i "grow")))
+        (instance $api
+            (export "spin" (func $spin))
+            (export "grow" (func $grow)))
+        (export "test:budget/api" (instance $api)))
+"#;
+
+#[tokio::test]
+async fn test_budget_out_of_fuel() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(BUDGET_WAT.as_bytes()).expect("add component");
+
+    let instance_id = rt.instantiate(component_id)
+        .with_budget(Budget { fuel: 10_000, max_memory_bytes: 1 << 20 })
+        .build()
+        .await
+        .expect("instantiate with budget");
+
+    let err = rt.call(instance_id, "test:budget/api", "spin", &[]).await.unwrap_err();
+    assert!(matches!(err, Error::OutOfFuel), "Expected OutOfFuel, got {:?}", err);
+}
+
+#[tokio::test]
+async fn test_budget_caps_memory() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(BUDGET_WAT.as_bytes()).expect("add component");
+
+    let capped = rt.instantiate(component_id)
+        .with_budget(Budget { fuel: 1_000_000, max_memory_bytes: 1 << 20 })
+        .build()
+        .await
+        .expect("instantiate with budget");
+    let results = rt.call(capped, "test:budget/api", "grow", &[]).await.expect("grow");
+    assert_eq!(results, vec![Val::S32(-1)]);
+
+    let unbounded = rt.instantiate(component_id).build().await.expect("instantiate");
+    let results = rt.call(unbounded, "test:budget/api", "grow", &[]).await.expect("grow");
+    assert_eq!(results, vec![Val::S32(1)]);
+}
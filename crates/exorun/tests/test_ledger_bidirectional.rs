@@ -2,6 +2,8 @@
 
 use exorun::Runtime;
 use exorun::host::{HostInstance, Wasi};
+use exorun::ledger::FuncKind;
+use wasmtime::component::Type;
 
 /// Helper to load Wasm fixtures.
 fn wasm(name: &str) -> Vec<u8> {
@@ -186,3 +188,27 @@ async fn test_ledger_imports_vs_exports() {
         "consumer should import an interface that provider exports"
     );
 }
+
+/// Test that a registered component's ledger describes its export signatures
+#[test]
+fn test_ledger_describes_provider_export() {
+    let runtime = Runtime::new().expect("runtime creation failed");
+    let provider_id = runtime.add_component_bytes(&wasm("app_provider")).expect("add provider");
+
+    let ledger = runtime.get_ledger(provider_id).expect("get provider ledger");
+    let math = ledger.exports.get("exorun:test/math").expect("math export");
+    let add = math.funcs.get("add").expect("add function");
+    assert!(matches!(add.params.as_slice(), [Type::U32, Type::U32]), "params {:?}", add.params);
+    assert!(matches!(add.results.as_slice(), [Type::U32]), "results {:?}", add.results);
+    assert_eq!(add.kind, FuncKind::Freestanding);
+}
+
+/// Test that a component with no wire-safe interfaces has an empty ledger, not an error
+#[test]
+fn test_ledger_empty_without_interfaces() {
+    let runtime = Runtime::new().expect("runtime creation failed");
+    let id = runtime.add_component_bytes(b"(component)").expect("add empty component");
+
+    let ledger = runtime.get_ledger(id).expect("get ledger");
+    assert!(ledger.imports.is_empty() && ledger.exports.is_empty());
+}
@@ -0,0 +1,62 @@
+//! Tests for describing transports and dialing them back from a descriptor.
+
+use neopack::Pack;
+use neopack::Unpack;
+
+use exorun::transport::{self, LocalTransport, TcpTransport, Transport, TransportDescriptor};
+
+#[test]
+fn test_descriptor_neopack_roundtrip() {
+    let descriptors = [
+        TransportDescriptor::Tcp("127.0.0.1:7000".into()),
+        TransportDescriptor::Quic { addr: "[::1]:7001".into(), server_name: "home.example".into() },
+        TransportDescriptor::WebSocket("ws://127.0.0.1:7002".into()),
+        TransportDescriptor::LocalChannel,
+    ];
+    for desc in descriptors {
+        let bytes = desc.pack_to_vec().expect("pack descriptor");
+        assert_eq!(TransportDescriptor::unpack_from_bytes(&bytes).expect("unpack descriptor"), desc);
+    }
+}
+
+#[tokio::test]
+async fn test_dial_local_channel_from_descriptor() {
+    let bytes = LocalTransport::loopback().descriptor().pack_to_vec().expect("pack descriptor");
+    let desc = TransportDescriptor::unpack_from_bytes(&bytes).expect("unpack descriptor");
+    assert_eq!(desc, TransportDescriptor::LocalChannel);
+
+    let dialed = transport::dial(&desc).await.expect("dial local channel");
+    assert_eq!(dialed.descriptor(), TransportDescriptor::LocalChannel);
+    dialed.send(b"ping").await.expect("send");
+    assert_eq!(dialed.recv().await.expect("recv"), Some(b"ping".to_vec()));
+}
+
+#[tokio::test]
+async fn test_local_pair_delivers_and_closes() {
+    let (a, b) = LocalTransport::pair();
+    a.send(b"hello").await.expect("send");
+    assert_eq!(b.recv().await.expect("recv"), Some(b"hello".to_vec()));
+
+    drop(a);
+    assert_eq!(b.recv().await.expect("recv after close"), None);
+    assert!(b.send(b"gone").await.is_err());
+}
+
+#[tokio::test]
+async fn test_tcp_descriptor_dials_back() {
+    let acceptor = TcpTransport::listen("127.0.0.1:0").await.expect("listen");
+    let addr = acceptor.local_addr().expect("local addr").to_string();
+    let server = tokio::spawn(async move {
+        let conn = acceptor.accept().await.expect("accept");
+        let msg = conn.recv().await.expect("recv").expect("message");
+        conn.send(&msg).await.expect("echo");
+    });
+
+    let desc = TransportDescriptor::Tcp(addr);
+    let dialed = transport::dial(&desc).await.expect("dial tcp");
+    assert_eq!(dialed.descriptor(), desc);
+    dialed.send(b"echo").await.expect("send");
+    assert_eq!(dialed.recv().await.expect("recv"), Some(b"echo".to_vec()));
+
+    server.await.unwrap();
+}
@@ -0,0 +1,107 @@
+//! Integration tests for the QUIC transport.
+#![cfg(feature = "quic")]
+
+use std::sync::Arc;
+
+use neorpc::ReplyOkEncoder;
+use neorpc::RpcFrame;
+use neorpc::encode_vals_to_bytes;
+use quinn::Endpoint;
+use quinn::rustls::RootCertStore;
+use quinn::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use wasmtime::component::Type;
+use wasmtime::component::Val;
+
+use exorun::peer::{Peer, PeerConfig};
+use exorun::transport::{QuicTransport, Transport, TransportDescriptor, quic};
+
+/// Starts a localhost server with a self-signed cert and a client endpoint trusting it.
+fn endpoints(max_concurrent_streams: u32) -> (Endpoint, Endpoint) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".into()]).expect("self-signed cert");
+    let cert = CertificateDer::from(certified.cert.der().to_vec());
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+    let server_config = quic::server_config(vec![cert.clone()], key, max_concurrent_streams).expect("server config");
+    let server = Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).expect("server endpoint");
+
+    let mut roots = RootCertStore::empty();
+    roots.add(cert).expect("trust cert");
+    let mut client = Endpoint::client("127.0.0.1:0".parse().unwrap()).expect("client endpoint");
+    client.set_default_client_config(quic::client_config(roots, max_concurrent_streams).expect("client config"));
+
+    (server, client)
+}
+
+/// Answers every `add(a, b)` call on `server` until the connection closes.
+async fn serve_adds(server: QuicTransport) {
+    while let Ok(Some(payload)) = server.recv().await {
+        let RpcFrame::Call(mut call) = RpcFrame::decode(&payload).expect("valid frame") else {
+            panic!("expected Call");
+        };
+        let mut args = call.args.list().expect("args list");
+        let a = args.next().expect("arg a").u32().expect("u32");
+        let b = args.next().expect("arg b").u32().expect("u32");
+
+        let results = encode_vals_to_bytes(&[Val::U32(a + b)]).expect("encode results");
+        let reply = ReplyOkEncoder::new(call.seq, &results).into_bytes().expect("encode reply");
+        server.send(&reply).await.expect("send reply");
+    }
+}
+
+#[tokio::test]
+async fn test_quic_call_roundtrip() {
+    let (server, client) = endpoints(16);
+    let addr = server.local_addr().expect("server addr");
+
+    let mut accepted = QuicTransport::accept_loop(server);
+    let serve = tokio::spawn(async move {
+        let conn = accepted.recv().await.expect("accepted connection");
+        serve_adds(conn).await;
+    });
+
+    let transport = QuicTransport::connect(&client, addr, "localhost").await.expect("connect");
+    assert_eq!(
+        transport.descriptor(),
+        TransportDescriptor::Quic { addr: addr.to_string(), server_name: "localhost".into() },
+    );
+    let peer = Peer::new("quic", Box::new(transport), PeerConfig::default());
+
+    let result = peer.call("math", "add", &[Val::U32(40), Val::U32(2)], vec![Type::U32])
+        .await
+        .expect("call over quic");
+    assert_eq!(result, vec![Val::U32(42)]);
+
+    peer.shutdown().await;
+    serve.abort();
+}
+
+#[tokio::test]
+async fn test_quic_concurrent_calls_within_stream_limit() {
+    let (server, client) = endpoints(2);
+    let addr = server.local_addr().expect("server addr");
+
+    let mut accepted = QuicTransport::accept_loop(server);
+    let serve = tokio::spawn(async move {
+        let conn = accepted.recv().await.expect("accepted connection");
+        serve_adds(conn).await;
+    });
+
+    let transport = QuicTransport::connect(&client, addr, "localhost").await.expect("connect");
+    let peer = Arc::new(Peer::new("quic", Box::new(transport), PeerConfig::default()));
+
+    // More calls than streams: the extras wait for a stream instead of failing.
+    let mut tasks = Vec::new();
+    for i in 0u32..8 {
+        let p = peer.clone();
+        tasks.push(tokio::spawn(async move {
+            p.call("math", "add", &[Val::U32(i), Val::U32(100)], vec![Type::U32]).await
+        }));
+    }
+    for (i, task) in tasks.into_iter().enumerate() {
+        let result = task.await.unwrap().expect("concurrent call");
+        assert_eq!(result, vec![Val::U32(i as u32 + 100)]);
+    }
+
+    peer.shutdown().await;
+    serve.abort();
+}
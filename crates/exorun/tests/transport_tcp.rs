@@ -0,0 +1,115 @@
+//! Integration tests for the length-prefixed TCP transport.
+
+use std::time::Duration;
+
+use neorpc::ReplyOkEncoder;
+use neorpc::RpcFrame;
+use neorpc::encode_vals_to_bytes;
+use wasmtime::component::Type;
+use wasmtime::component::Val;
+
+use exorun::peer::{Peer, PeerConfig, PeerState};
+use exorun::transport::{self, TcpTransport, Transport};
+
+/// Answers every `add(a, b)` call on `server` until the connection closes,
+/// or until `limit` calls have been answered.
+async fn serve_adds(server: TcpTransport, limit: usize) {
+    for _ in 0..limit {
+        let Ok(Some(payload)) = server.recv().await else { return };
+        let RpcFrame::Call(mut call) = RpcFrame::decode(&payload).expect("valid frame") else {
+            panic!("expected Call");
+        };
+        let mut args = call.args.list().expect("args list");
+        let a = args.next().expect("arg a").u32().expect("u32");
+        let b = args.next().expect("arg b").u32().expect("u32");
+
+        let results = encode_vals_to_bytes(&[Val::U32(a + b)]).expect("encode results");
+        let reply = ReplyOkEncoder::new(call.seq, &results).into_bytes().expect("encode reply");
+        server.send(&reply).await.expect("send reply");
+    }
+}
+
+#[tokio::test]
+async fn test_tcp_call_roundtrip() {
+    let acceptor = TcpTransport::listen("127.0.0.1:0").await.expect("listen");
+    let addr = acceptor.local_addr().expect("local addr").to_string();
+
+    let server = tokio::spawn(async move {
+        let conn = acceptor.accept().await.expect("accept");
+        serve_adds(conn, usize::MAX).await;
+    });
+
+    let client = TcpTransport::connect(&addr).await.expect("connect");
+    let peer = Peer::new("tcp", Box::new(client), PeerConfig::default());
+
+    for (a, b) in [(2, 3), (40, 2)] {
+        let result = peer.call("math", "add", &[Val::U32(a), Val::U32(b)], vec![Type::U32])
+            .await
+            .expect("call over tcp");
+        assert_eq!(result, vec![Val::U32(a + b)]);
+    }
+
+    peer.shutdown().await;
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_tcp_reconnect_after_connection_lost() {
+    let acceptor = TcpTransport::listen("127.0.0.1:0").await.expect("listen");
+    let addr = acceptor.local_addr().expect("local addr").to_string();
+
+    // Answer one call per connection, then hang up.
+    let server = tokio::spawn(async move {
+        loop {
+            let Ok(conn) = acceptor.accept().await else { return };
+            serve_adds(conn, 1).await;
+        }
+    });
+
+    let client = TcpTransport::connect(&addr).await.expect("connect");
+    let peer = Peer::new("tcp", Box::new(client), PeerConfig::default());
+
+    let result = peer.call("math", "add", &[Val::U32(1), Val::U32(1)], vec![Type::U32]).await.unwrap();
+    assert_eq!(result, vec![Val::U32(2)]);
+
+    // The server hangs up; the pump sees EOF and the peer disconnects.
+    for _ in 0..100 {
+        if peer.state() == PeerState::Disconnected { break; }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(peer.state(), PeerState::Disconnected);
+
+    let client = TcpTransport::connect(&addr).await.expect("reconnect");
+    peer.reconnect(Box::new(client)).await.expect("peer reconnect");
+
+    let result = peer.call("math", "add", &[Val::U32(2), Val::U32(2)], vec![Type::U32]).await.unwrap();
+    assert_eq!(result, vec![Val::U32(4)]);
+
+    peer.shutdown().await;
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_tcp_rejects_oversized_messages() {
+    let acceptor = TcpTransport::listen("127.0.0.1:0").await
+        .expect("listen")
+        .with_max_message_size(8);
+    let addr = acceptor.local_addr().expect("local addr").to_string();
+
+    let client = TcpTransport::connect(&addr).await.expect("connect");
+    let server = acceptor.accept().await.expect("accept");
+
+    // Within the limit: delivered intact.
+    client.send(b"tiny").await.expect("send small");
+    assert_eq!(server.recv().await.expect("recv").as_deref(), Some(&b"tiny"[..]));
+
+    // The sender's limit is larger, so the oversized header reaches the receiver.
+    client.send(&[0u8; 64]).await.expect("send large");
+    assert!(matches!(server.recv().await, Err(transport::Error::PayloadTooLarge)));
+
+    // After a failure the transport stays closed.
+    assert!(matches!(server.recv().await, Err(transport::Error::ConnectionLost(_))));
+
+    let small = TcpTransport::connect(&addr).await.expect("connect").with_max_message_size(8);
+    assert!(matches!(small.send(&[0u8; 64]).await, Err(transport::Error::PayloadTooLarge)));
+}
@@ -0,0 +1,115 @@
+//! Integration tests for the WebSocket transport.
+#![cfg(feature = "websocket")]
+
+use std::time::Duration;
+
+use futures_util::SinkExt;
+use futures_util::StreamExt;
+use neorpc::ReplyOkEncoder;
+use neorpc::RpcFrame;
+use neorpc::encode_vals_to_bytes;
+use tokio::net::TcpListener;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+use wasmtime::component::Type;
+use wasmtime::component::Val;
+
+use exorun::peer::{Peer, PeerConfig};
+use exorun::transport::{self, Transport, TransportDescriptor, WebSocketTransport};
+
+/// Binds a plain WebSocket server, returning its `ws://` URL and a task
+/// yielding the first connection once its handshake completes.
+async fn ws_server() -> (String, tokio::task::JoinHandle<WebSocketStream<tokio::net::TcpStream>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let url = format!("ws://{}", listener.local_addr().expect("local addr"));
+    let accept = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.expect("accept");
+        tokio_tungstenite::accept_async(stream).await.expect("handshake")
+    });
+    (url, accept)
+}
+
+#[tokio::test]
+async fn test_websocket_call_roundtrip() {
+    let (url, accept) = ws_server().await;
+    let server = tokio::spawn(async move {
+        let mut ws = accept.await.expect("server");
+        ws.send(Message::Ping(b"are you there".to_vec().into())).await.expect("ping");
+
+        let mut saw_pong = false;
+        let payload = loop {
+            match ws.next().await.expect("message").expect("valid message") {
+                Message::Pong(data) => saw_pong = &data[..] == b"are you there",
+                Message::Binary(payload) => break payload,
+                other => panic!("expected a binary call, got {:?}", other),
+            }
+        };
+
+        let RpcFrame::Call(mut call) = RpcFrame::decode(&payload).expect("valid frame") else {
+            panic!("expected Call");
+        };
+        let mut args = call.args.list().expect("args list");
+        let a = args.next().expect("arg a").u32().expect("u32");
+        let b = args.next().expect("arg b").u32().expect("u32");
+
+        let results = encode_vals_to_bytes(&[Val::U32(a + b)]).expect("encode results");
+        let reply = ReplyOkEncoder::new(call.seq, &results).into_bytes().expect("encode reply");
+        ws.send(Message::Binary(reply.into())).await.expect("send reply");
+
+        // The pong may go out after the call, with whichever read or write comes next
+        while !saw_pong {
+            match ws.next().await.expect("message").expect("valid message") {
+                Message::Pong(data) => saw_pong = &data[..] == b"are you there",
+                other => panic!("expected a pong, got {:?}", other),
+            }
+        }
+    });
+
+    let client = WebSocketTransport::connect(&url).await.expect("connect");
+    assert_eq!(client.descriptor(), TransportDescriptor::WebSocket(url));
+    let peer = Peer::new("ws", Box::new(client), PeerConfig::default());
+
+    let result = peer.call("math", "add", &[Val::U32(40), Val::U32(2)], vec![Type::U32])
+        .await
+        .expect("call over websocket");
+    assert_eq!(result, vec![Val::U32(42)]);
+    tokio::time::timeout(Duration::from_secs(5), server).await
+        .expect("ping was not answered")
+        .expect("server");
+    peer.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_websocket_close_is_connection_lost() {
+    let (url, accept) = ws_server().await;
+    let client = WebSocketTransport::connect(&url).await.expect("connect");
+    let mut ws = accept.await.expect("server");
+
+    ws.send(Message::Text("not binary".into())).await.expect("send text");
+    assert!(matches!(client.recv().await, Err(transport::Error::Io(_))));
+
+    ws.send(Message::Binary(b"still up".to_vec().into())).await.expect("send binary");
+    assert_eq!(client.recv().await.expect("recv"), Some(b"still up".to_vec()));
+
+    ws.close(None).await.expect("close");
+    assert!(matches!(client.recv().await, Err(transport::Error::ConnectionLost(_))));
+    assert!(matches!(client.recv().await, Err(transport::Error::ConnectionLost(_))));
+}
+
+#[tokio::test]
+async fn test_websocket_acceptor_pair() {
+    let acceptor = WebSocketTransport::listen("127.0.0.1:0").await.expect("listen");
+    let url = format!("ws://{}", acceptor.local_addr().expect("local addr"));
+    let server = tokio::spawn(async move {
+        let conn = acceptor.accept().await.expect("accept");
+        let msg = conn.recv().await.expect("recv").expect("message");
+        conn.send(&msg).await.expect("echo");
+    });
+
+    // Bytes that are not valid UTF-8 survive the trip untouched
+    let payload = vec![0xff, 0x00, 0xfe, 0x80];
+    let client = transport::dial(&TransportDescriptor::WebSocket(url)).await.expect("dial");
+    client.send(&payload).await.expect("send");
+    assert_eq!(client.recv().await.expect("recv"), Some(payload));
+    server.await.expect("server");
+}
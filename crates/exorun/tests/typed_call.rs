@@ -0,0 +1,91 @@
+//! Tests for `Runtime::call_typed`.
+
+use std::sync::Arc;
+
+use exorun::InstanceId;
+use exorun::Runtime;
+use exorun::host::HostInstance;
+use exorun::host::Wasi;
+use exorun::runtime::Error;
+
+fn wasm(name: &str) -> Vec<u8> {
+    let path = format!("tests/fixtures/{}.wasm", name);
+    std::fs::read(&path).unwrap_or_else(|_| panic!("Could not read wasm: {}", path))
+}
+
+/// A counter with a no-arg, no-result `bump` and a no-arg `count`.
+const COUNTER_WAT: &str = r#"
+    (component
+        (core module $m
+            (global $n (mut i32) (i32.const 0))
+            (func (export "bump") (global.set $n (i32.add (global.get $n) (i32.const 1))))
+            (func (export "count") (result i32) (global.get $n)))
+        (core instance $i (instantiate $m))
+        (func $bump (canon lift (core func $i "bump")))
+        (func $count (result u32) (canon lift (core func $i "count")))
+        (instance $api
+            (export "bump" (func $bump))
+            (export "count" (func $count)))
+        (export "test:typed/counter" (instance $api)))
+"#;
+
+async fn provider(rt: &Arc<Runtime>) -> InstanceId {
+    let component_id = rt.add_component_bytes(&wasm("app_provider")).expect("add provider");
+    rt.instantiate(component_id)
+        .link_system("wasi:cli/environment", HostInstance::Wasi(Wasi::new()))
+        .build()
+        .await
+        .expect("instantiate provider")
+}
+
+async fn counter(rt: &Arc<Runtime>) -> InstanceId {
+    let component_id = rt.add_component_bytes(COUNTER_WAT.as_bytes()).expect("add counter");
+    rt.instantiate(component_id).build().await.expect("instantiate counter")
+}
+
+#[tokio::test]
+async fn test_call_typed_add() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let id = provider(&rt).await;
+
+    let sum = rt.call_typed::<(u32, u32), u32>(id, "exorun:test/math", "add", (10, 5))
+        .await
+        .expect("typed add");
+    assert_eq!(sum, 15);
+}
+
+#[tokio::test]
+async fn test_call_typed_zero_args_and_zero_results() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let id = counter(&rt).await;
+
+    rt.call_typed::<(), ()>(id, "test:typed/counter", "bump", ()).await.expect("bump");
+    rt.call_typed::<(), ()>(id, "test:typed/counter", "bump", ()).await.expect("bump");
+
+    let count = rt.call_typed::<(), u32>(id, "test:typed/counter", "count", ())
+        .await
+        .expect("count");
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_call_typed_rejects_mismatch_before_calling() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let id = provider(&rt).await;
+
+    let arity = rt.call_typed::<(u32,), u32>(id, "exorun:test/math", "add", (10,)).await;
+    assert!(matches!(arity, Err(Error::Signature { .. })), "one arg for two params: {:?}", arity);
+
+    let types = rt.call_typed::<(u32, String), u32>(id, "exorun:test/math", "add", (10, "5".into())).await;
+    assert!(matches!(types, Err(Error::Signature { .. })), "string for u32: {:?}", types);
+
+    let results = rt.call_typed::<(u32, u32), ()>(id, "exorun:test/math", "add", (10, 5)).await;
+    assert!(matches!(results, Err(Error::Signature { .. })), "dropped result: {:?}", results);
+
+    let metrics = rt.instance_metrics(id).await.expect("metrics");
+    assert_eq!(metrics.call_count, 0, "mismatched calls never reach the guest");
+
+    let counter_id = counter(&rt).await;
+    let extra = rt.call_typed::<(u32,), u32>(counter_id, "test:typed/counter", "count", (1,)).await;
+    assert!(matches!(extra, Err(Error::Signature { .. })), "arg for zero params: {:?}", extra);
+}
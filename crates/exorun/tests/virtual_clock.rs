@@ -0,0 +1,92 @@
+//! Tests for reading WASI clocks from a `VirtualClock`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use exorun::ComponentId;
+use exorun::InstanceId;
+use exorun::VirtualClock;
+use exorun::host::{HostInstance, Wasi};
+use exorun::runtime::Runtime;
+use wasmtime::component::Val;
+
+/// Exports `test:clock/api` with `wall`, the seconds of
+/// `wasi:clocks/wall-clock.now`, and `monotonic`, `wasi:clocks/monotonic-clock.now`.
+const CLOCK_WAT: &str = r#"
+    (component
+        (import "wasi:clocks/wall-clock@0.2.0" (instance $wall
+            (type $datetime (record (field "seconds" u64) (field "nanoseconds" u32)))
+            (export "datetime" (type $dt (eq $datetime)))
+            (export "now" (func (result $dt)))))
+        (import "wasi:clocks/monotonic-clock@0.2.0" (instance $mono
+            (export "now" (func (result u64)))))
+        (core module $mem (memory (export "memory") 1))
+        (core instance $mem (instantiate $mem))
+        (alias core export $mem "memory" (core memory $memory))
+        (core func $wall_now (canon lower (func $wall "now") (memory $memory)))
+        (core func $mono_now (canon lower (func $mono "now")))
+        (core module $m
+            (import "env" "memory" (memory 1))
+            (import "env" "wall" (func $wall (param i32)))
+            (import "env" "mono" (func $mono (result i64)))
+            (func (export "wall") (result i64) (call $wall (i32.const 0)) (i64.load (i32.const 0)))
+            (func (export "monotonic") (result i64) (call $mono)))
+        (core instance $i (instantiate $m
+            (with "env" (instance
+                (export "memory" (memory $memory))
+                (export "wall" (func $wall_now))
+                (export "mono" (func $mono_now))))))
+        (func $wall_export (result u64) (canon lift (core func $i "wall")))
+        (func $monotonic_export (result u64) (canon lift (core func $i "monotonic")))
+        (instance $api
+            (export "wall" (func $wall_export))
+            (export "monotonic" (func $monotonic_export)))
+        (export "test:clock/api" (instance $api)))
+"#;
+
+const API: &str = "test:clock/api";
+
+async fn instance(rt: &Arc<Runtime>, component_id: ComponentId, clock: &Arc<VirtualClock>) -> InstanceId {
+    rt.instantiate(component_id)
+        .link_system("wasi:clocks/wall-clock", HostInstance::Wasi(Wasi::new()))
+        .with_virtual_clock(Arc::clone(clock))
+        .build()
+        .await
+        .expect("instantiate")
+}
+
+async fn read(rt: &Arc<Runtime>, instance_id: InstanceId, function: &str) -> u64 {
+    match rt.call(instance_id, API, function, &[]).await.expect("read clock").as_slice() {
+        [Val::U64(value)] => *value,
+        other => panic!("expected one u64, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_advance_moves_wall_clock() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(CLOCK_WAT.as_bytes()).expect("add component");
+    let clock = Arc::new(VirtualClock::at(Duration::from_secs(1_700_000_000)));
+    let instance_id = instance(&rt, component_id, &clock).await;
+
+    let before = read(&rt, instance_id, "wall").await;
+    assert_eq!(before, 1_700_000_000);
+    assert_eq!(read(&rt, instance_id, "wall").await, before, "virtual time stands still");
+
+    clock.advance(Duration::from_secs(3600));
+    assert_eq!(read(&rt, instance_id, "wall").await - before, 3600);
+}
+
+#[tokio::test]
+async fn test_clocks_share_one_source() {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let component_id = rt.add_component_bytes(CLOCK_WAT.as_bytes()).expect("add component");
+    let clock = Arc::new(VirtualClock::at(Duration::ZERO));
+    let a = instance(&rt, component_id, &clock).await;
+    let b = instance(&rt, component_id, &clock).await;
+
+    clock.advance(Duration::from_millis(1500));
+    assert_eq!(read(&rt, a, "monotonic").await, 1_500_000_000);
+    assert_eq!(read(&rt, b, "monotonic").await, 1_500_000_000);
+    assert_eq!(read(&rt, a, "wall").await, 1);
+}
@@ -0,0 +1,119 @@
+//! Tests for capability-scoped WASI filesystem mounts.
+//!
+//! These drive the same `wasi:filesystem` host calls a guest's `open-at`
+//! lands on, against a context configured through `Wasi::link`.
+
+use std::path::PathBuf;
+
+use wasmtime::component::Linker;
+use wasmtime::component::Resource;
+use wasmtime_wasi::filesystem::WasiFilesystemView;
+use wasmtime_wasi::p2::bindings::filesystem::preopens::Host as _;
+use wasmtime_wasi::p2::bindings::filesystem::types::{
+    Descriptor, DescriptorFlags, ErrorCode, HostDescriptor, OpenFlags, PathFlags,
+};
+
+use exorun::context::{ContextBuilder, ExorunCtx};
+use exorun::host::{HostInstance, Wasi};
+use exorun::runtime::Runtime;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("exorun-wasi-{}-{}", name, rand::random::<u64>()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+/// Builds a store context with `wasi` linked in, as `InstanceBuilder` does.
+fn context_for(wasi: Wasi) -> ExorunCtx {
+    let rt = Runtime::new().expect("runtime creation failed");
+    let mut linker = Linker::new(rt.engine());
+    let mut ctx_builder = ContextBuilder::new();
+    HostInstance::Wasi(wasi).link(&mut linker, &mut ctx_builder).expect("link wasi");
+    ctx_builder.build(rt)
+}
+
+/// Looks up the preopened directory mounted at `guest`.
+fn preopen(ctx: &mut ExorunCtx, guest: &str) -> Option<Resource<Descriptor>> {
+    let dirs = ctx.filesystem().get_directories().expect("list preopens");
+    dirs.into_iter().find(|(_, path)| path == guest).map(|(dir, _)| dir)
+}
+
+/// Creates `path` for writing under the mount `dir`, returning the WASI error code on failure.
+async fn create(ctx: &mut ExorunCtx, dir: &Resource<Descriptor>, path: &str) -> Result<(), ErrorCode> {
+    ctx.filesystem()
+        .open_at(
+            Resource::new_borrow(dir.rep()),
+            PathFlags::empty(),
+            path.to_string(),
+            OpenFlags::CREATE,
+            DescriptorFlags::WRITE,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| e.downcast().expect("WASI error code"))
+}
+
+async fn read(ctx: &mut ExorunCtx, dir: &Resource<Descriptor>, path: &str) -> Result<(), ErrorCode> {
+    ctx.filesystem()
+        .open_at(
+            Resource::new_borrow(dir.rep()),
+            PathFlags::empty(),
+            path.to_string(),
+            OpenFlags::empty(),
+            DescriptorFlags::READ,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| e.downcast().expect("WASI error code"))
+}
+
+#[tokio::test]
+async fn test_readonly_mount_rejects_writes() {
+    let host = temp_dir("ro");
+    std::fs::write(host.join("existing.txt"), b"hello").unwrap();
+
+    let mut ctx = context_for(Wasi::builder().preopen_ro(&host, "/data").build());
+    let dir = preopen(&mut ctx, "/data").expect("mount is preopened");
+
+    read(&mut ctx, &dir, "existing.txt").await.expect("reads are allowed");
+    assert_eq!(create(&mut ctx, &dir, "new.txt").await, Err(ErrorCode::NotPermitted));
+    assert!(!host.join("new.txt").exists());
+
+    let _ = std::fs::remove_dir_all(&host);
+}
+
+#[tokio::test]
+async fn test_readwrite_mount_allows_writes() {
+    let host = temp_dir("rw");
+
+    let mut ctx = context_for(Wasi::builder().preopen_rw(&host, "/scratch").build());
+    let dir = preopen(&mut ctx, "/scratch").expect("mount is preopened");
+
+    create(&mut ctx, &dir, "new.txt").await.expect("writes are allowed");
+    assert!(host.join("new.txt").exists());
+
+    let _ = std::fs::remove_dir_all(&host);
+}
+
+#[tokio::test]
+async fn test_mounts_are_the_only_capabilities() {
+    let outer = temp_dir("outer");
+    let inner = outer.join("inner");
+    std::fs::create_dir_all(&inner).unwrap();
+    std::fs::write(outer.join("secret.txt"), b"nope").unwrap();
+
+    let mut ctx = context_for(Wasi::builder().preopen_rw(&inner, "/app").build());
+    let dir = preopen(&mut ctx, "/app").expect("mount is preopened");
+
+    // Only the declared mount is visible.
+    assert_eq!(ctx.filesystem().get_directories().unwrap().len(), 1);
+    assert!(preopen(&mut context_for(Wasi::new()), "/app").is_none());
+
+    // `..` cannot climb out of the mount, for reads or writes.
+    assert_eq!(read(&mut ctx, &dir, "../secret.txt").await, Err(ErrorCode::NotPermitted));
+    assert_eq!(create(&mut ctx, &dir, "../escaped.txt").await, Err(ErrorCode::NotPermitted));
+    assert_eq!(read(&mut ctx, &dir, "/etc/passwd").await, Err(ErrorCode::NotPermitted));
+    assert!(!outer.join("escaped.txt").exists());
+
+    let _ = std::fs::remove_dir_all(&outer);
+}
@@ -0,0 +1,50 @@
+//! Tests for capturing guest stdout and stderr through `Wasi`.
+
+use exorun::host::{HostInstance, Wasi};
+use exorun::runtime::Runtime;
+use wasmtime::component::Val;
+
+fn wasm(name: &str) -> Vec<u8> {
+    let path = format!("tests/fixtures/{}.wasm", name);
+    std::fs::read(&path).unwrap_or_else(|_| panic!("Could not read wasm: {}", path))
+}
+
+#[tokio::test]
+async fn test_capture_stdio_keeps_streams_apart() {
+    let rt = Runtime::new().expect("Failed to create runtime");
+    let app_id = rt.add_component_bytes(&wasm("app_stdio")).expect("Failed to register app");
+
+    let wasi = Wasi::builder().capture_stdio().build();
+    let instance_id = rt.instantiate(app_id)
+        .link_system("wasi:cli/stdout", HostInstance::Wasi(wasi.clone()))
+        .build()
+        .await
+        .expect("Failed to instantiate");
+
+    let results = rt.call(instance_id, "exorun:test/runnable", "run", &[]).await.expect("run");
+    assert_eq!(results, vec![Val::String("Done".into())]);
+
+    // Output outlives the instance that wrote it
+    rt.remove_instance(instance_id).expect("remove instance");
+
+    assert_eq!(wasi.take_stdout(), "Hello from stdout!\n");
+    assert_eq!(wasi.take_stderr(), "Hello from stderr!\n");
+    assert_eq!(wasi.take_stdout(), "", "a take drains what it returned");
+}
+
+#[tokio::test]
+async fn test_uncaptured_stdio_is_discarded() {
+    let rt = Runtime::new().expect("Failed to create runtime");
+    let app_id = rt.add_component_bytes(&wasm("app_stdio")).expect("Failed to register app");
+
+    let wasi = Wasi::new();
+    let instance_id = rt.instantiate(app_id)
+        .link_system("wasi:cli/stdout", HostInstance::Wasi(wasi.clone()))
+        .build()
+        .await
+        .expect("Failed to instantiate");
+    rt.call(instance_id, "exorun:test/runnable", "run", &[]).await.expect("run");
+
+    assert_eq!(wasi.take_stdout(), "");
+    assert_eq!(wasi.take_stderr(), "");
+}
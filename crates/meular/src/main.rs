@@ -2,5 +2,5 @@ use exorun::runtime::Runtime;
 
 #[tokio::main]
 async fn main() {
-    let rt = Runtime::new();
+    let _rt = Runtime::new();
 }
@@ -30,6 +30,12 @@ extern crate self as neopack;
 #[cfg(test)]
 mod tests;
 
+mod stream;
+pub use stream::StreamEncoder;
+
+mod schema;
+pub use schema::{validate_against, Schema};
+
 /// Neopack serialization and deserialization errors.
 #[derive(Debug, Clone)]
 pub enum Error {
@@ -55,6 +61,37 @@ pub enum Error {
     EmptyAdt(Scope),
     /// Structural Violation: Attempted to write a non-Variant directly into a Map.
     InvalidMapEntry,
+    /// Well-formed, but not in canonical form (see [`validate_canonical`]).
+    NonCanonical(&'static str),
+    /// Nesting exceeded the limit set by [`Decoder::with_max_depth`].
+    DepthExceeded,
+    /// A raw fragment held more than one item; carries the number of extra bytes.
+    TrailingBytes(usize),
+    /// The sink of a [`StreamEncoder`] failed.
+    Io(String),
+    /// A container of unknown size was opened on an unseekable [`StreamEncoder`].
+    UnsizedScope,
+    /// A sized container's body did not match the length declared up front.
+    SizeMismatch { declared: u32, actual: usize },
+    /// Timestamp nanoseconds outside `0..=999_999_999`.
+    InvalidTimestamp,
+    /// A list read by [`Decoder::scalar_list`] held an item of another type.
+    HeterogeneousList { index: usize, tag: u8 },
+    /// An [`EncoderMark`] was taken in a scope that has since closed or been rolled back into.
+    StaleMark,
+    /// The CRC trailer checked by [`Decoder::verify_crc`] doesn't match the payload.
+    ChecksumMismatch { stored: u32, computed: u32 },
+    /// Bytes were asked of an [`Encoder::dry_run`] encoder, which only counts them.
+    DryRun,
+    /// A map closed by [`Encoder::map_sorted_end`] held this key more than once.
+    DuplicateKey(String),
+    /// Top-level item `index` of a buffer checked by [`validate`] is malformed.
+    BadItem { index: usize, cause: Box<Error> },
+    /// The value at `path` doesn't match the [`Schema`] passed to [`validate_against`].
+    SchemaMismatch { path: String, why: String },
+    /// The value at this path has the wrong type; returned in place of
+    /// `InvalidTag` by decoders made with [`Decoder::with_path_tracking`].
+    TypeMismatchAt(String),
 }
 
 impl std::fmt::Display for Error {
@@ -66,6 +103,27 @@ impl std::fmt::Display for Error {
             }
             Error::TooManyItems(s) => write!(f, "Too many items in scope {:?}; expected exactly 1", s),
             Error::EmptyAdt(s) => write!(f, "Empty ADT scope {:?}; expected exactly 1 item", s),
+            Error::NonCanonical(why) => write!(f, "Not canonical: {}", why),
+            Error::DepthExceeded => write!(f, "Nesting depth limit exceeded"),
+            Error::TrailingBytes(n) => write!(f, "{} trailing bytes after item", n),
+            Error::Io(msg) => write!(f, "I/O error: {}", msg),
+            Error::UnsizedScope => write!(f, "Container length unknown on an unseekable sink; open it sized"),
+            Error::SizeMismatch { declared, actual } => {
+                write!(f, "Container declared {} body bytes but wrote {}", declared, actual)
+            }
+            Error::InvalidTimestamp => write!(f, "Timestamp nanos must be below one second"),
+            Error::HeterogeneousList { index, tag } => {
+                write!(f, "List item {} has tag {:#04x}, unlike the items before it", index, tag)
+            }
+            Error::StaleMark => write!(f, "Mark was taken in a scope that has since closed or been rolled back into"),
+            Error::DryRun => write!(f, "Dry-run encoder holds no bytes; read its size with len()"),
+            Error::DuplicateKey(key) => write!(f, "Duplicate map key {:?}", key),
+            Error::BadItem { index, cause } => write!(f, "Item {} is malformed: {}", index, cause),
+            Error::SchemaMismatch { path, why } => write!(f, "Schema mismatch at {}: {}", path, why),
+            Error::TypeMismatchAt(path) => write!(f, "Type mismatch at {}", path),
+            Error::ChecksumMismatch { stored, computed } => {
+                write!(f, "Checksum mismatch: trailer says {:#010x}, payload hashes to {:#010x}", stored, computed)
+            }
             _ => write!(f, "{:?}", self),
         }
     }
@@ -73,12 +131,155 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// The payload-free [`ErrorCode`] for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::BufferFull => ErrorCode::BufferFull,
+            Error::InvalidTag(_) => ErrorCode::InvalidTag,
+            Error::InvalidUtf8 => ErrorCode::InvalidUtf8,
+            Error::ScopeMismatch { .. } => ErrorCode::ScopeMismatch,
+            Error::ScopeUnderflow => ErrorCode::ScopeUnderflow,
+            Error::ScopeStillOpen => ErrorCode::ScopeStillOpen,
+            Error::UnexpectedEnd => ErrorCode::UnexpectedEnd,
+            Error::BlobTooLarge(_) => ErrorCode::BlobTooLarge,
+            Error::TooManyItems(_) => ErrorCode::TooManyItems,
+            Error::EmptyAdt(_) => ErrorCode::EmptyAdt,
+            Error::InvalidMapEntry => ErrorCode::InvalidMapEntry,
+            Error::NonCanonical(_) => ErrorCode::NonCanonical,
+            Error::DepthExceeded => ErrorCode::DepthExceeded,
+            Error::TrailingBytes(_) => ErrorCode::TrailingBytes,
+            Error::Io(_) => ErrorCode::Io,
+            Error::UnsizedScope => ErrorCode::UnsizedScope,
+            Error::SizeMismatch { .. } => ErrorCode::SizeMismatch,
+            Error::InvalidTimestamp => ErrorCode::InvalidTimestamp,
+            Error::HeterogeneousList { .. } => ErrorCode::HeterogeneousList,
+            Error::StaleMark => ErrorCode::StaleMark,
+            Error::ChecksumMismatch { .. } => ErrorCode::ChecksumMismatch,
+            Error::DryRun => ErrorCode::DryRun,
+            Error::DuplicateKey(_) => ErrorCode::DuplicateKey,
+            Error::BadItem { .. } => ErrorCode::BadItem,
+            Error::SchemaMismatch { .. } => ErrorCode::SchemaMismatch,
+            Error::TypeMismatchAt(_) => ErrorCode::TypeMismatchAt,
+        }
+    }
+}
+
+/// Discriminant of an [`Error`], without its payload.
+///
+/// Cheap to match on and one byte on the wire. Codes are stable:
+/// new variants get new codes, and existing codes are never reused.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    BufferFull = 0x01,
+    InvalidTag = 0x02,
+    InvalidUtf8 = 0x03,
+    ScopeMismatch = 0x04,
+    ScopeUnderflow = 0x05,
+    ScopeStillOpen = 0x06,
+    UnexpectedEnd = 0x07,
+    BlobTooLarge = 0x08,
+    TooManyItems = 0x09,
+    EmptyAdt = 0x0A,
+    InvalidMapEntry = 0x0B,
+    NonCanonical = 0x0C,
+    DepthExceeded = 0x0D,
+    TrailingBytes = 0x0E,
+    Io = 0x0F,
+    UnsizedScope = 0x10,
+    SizeMismatch = 0x11,
+    InvalidTimestamp = 0x12,
+    HeterogeneousList = 0x13,
+    StaleMark = 0x14,
+    ChecksumMismatch = 0x15,
+    DryRun = 0x16,
+    DuplicateKey = 0x17,
+    BadItem = 0x18,
+    SchemaMismatch = 0x19,
+    TypeMismatchAt = 0x1A,
+}
+
+impl ErrorCode {
+    /// Returns the ErrorCode for a given byte, or `None` if unknown.
+    pub fn from_u8(b: u8) -> Option<Self> {
+        match b {
+            0x01 => Some(ErrorCode::BufferFull),
+            0x02 => Some(ErrorCode::InvalidTag),
+            0x03 => Some(ErrorCode::InvalidUtf8),
+            0x04 => Some(ErrorCode::ScopeMismatch),
+            0x05 => Some(ErrorCode::ScopeUnderflow),
+            0x06 => Some(ErrorCode::ScopeStillOpen),
+            0x07 => Some(ErrorCode::UnexpectedEnd),
+            0x08 => Some(ErrorCode::BlobTooLarge),
+            0x09 => Some(ErrorCode::TooManyItems),
+            0x0A => Some(ErrorCode::EmptyAdt),
+            0x0B => Some(ErrorCode::InvalidMapEntry),
+            0x0C => Some(ErrorCode::NonCanonical),
+            0x0D => Some(ErrorCode::DepthExceeded),
+            0x0E => Some(ErrorCode::TrailingBytes),
+            0x0F => Some(ErrorCode::Io),
+            0x10 => Some(ErrorCode::UnsizedScope),
+            0x11 => Some(ErrorCode::SizeMismatch),
+            0x12 => Some(ErrorCode::InvalidTimestamp),
+            0x13 => Some(ErrorCode::HeterogeneousList),
+            0x14 => Some(ErrorCode::StaleMark),
+            0x15 => Some(ErrorCode::ChecksumMismatch),
+            0x16 => Some(ErrorCode::DryRun),
+            0x17 => Some(ErrorCode::DuplicateKey),
+            0x18 => Some(ErrorCode::BadItem),
+            0x19 => Some(ErrorCode::SchemaMismatch),
+            0x1A => Some(ErrorCode::TypeMismatchAt),
+            _ => None,
+        }
+    }
+}
+
 /// Specialized `Result` for Neopack operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The largest nanos value a timestamp may carry.
+const MAX_TIMESTAMP_NANOS: u32 = 999_999_999;
+
+/// Body length of a [`Tag::Decimal128`]: a 16-byte mantissa and a 1-byte scale.
+pub const DECIMAL_LEN: usize = 17;
+
+/// Length of the trailer written by [`Encoder::into_bytes_with_crc`]:
+/// a little-endian CRC32C (Castagnoli) of every byte before it.
+pub const CRC_TRAILER_LEN: usize = 4;
+
+/// Lookup table for [`crc32c`], built from the reflected Castagnoli polynomial.
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0x82F6_3B78 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC32C (Castagnoli) of `bytes`, as used by iSCSI and ext4.
+fn crc32c(bytes: &[u8]) -> u32 {
+    let crc = bytes.iter().fold(!0u32, |crc, &b| {
+        CRC32C_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    });
+    !crc
+}
+
 /// Identifies the type of the encoded value.
 ///
 /// Used for schema evolution and safe skipping of unknown fields.
+///
+/// Scalars have a fixed width, implied by their tag, and carry no length.
+/// Everything else carries a length, `Decimal128` included, so
+/// [`Decoder::skip`] passes over it by that length without reading the body.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tag {
@@ -107,6 +308,8 @@ pub enum Tag {
     // Blobs (Tag + u32 Len + Bytes)
     String = 0x10,
     Bytes = 0x11,
+    /// A byte blob with a u64 length, for payloads past `u32::MAX`.
+    BigBytes = 0x12,
 
     // Containers (Tag + u32 Len + Body)
     List = 0x20,
@@ -117,6 +320,16 @@ pub enum Tag {
     ResultOk = 0x31,
     ResultErr = 0x32,
     Variant = 0x33,
+
+    // Wide scalars
+    /// Fixed-point decimal (Tag + u32 Len + Body): a 17-byte body holding an
+    /// i128 mantissa (LE) followed by an i8 scale. The length lets a decoder
+    /// skip it like a blob.
+    Decimal128 = 0x40,
+    /// Wall-clock instant, 12 bytes: i64 seconds since the unix epoch (LE) followed by u32 nanos (LE).
+    Timestamp = 0x41,
+    U128 = 0x42,
+    S128 = 0x43,
 }
 
 impl Tag {
@@ -141,17 +354,30 @@ impl Tag {
             0x0F => Some(Tag::OptionNone),
             0x10 => Some(Tag::String),
             0x11 => Some(Tag::Bytes),
+            0x12 => Some(Tag::BigBytes),
             0x20 => Some(Tag::List),
             0x21 => Some(Tag::Map),
             0x30 => Some(Tag::OptionSome),
             0x31 => Some(Tag::ResultOk),
             0x32 => Some(Tag::ResultErr),
             0x33 => Some(Tag::Variant),
+            0x40 => Some(Tag::Decimal128),
+            0x41 => Some(Tag::Timestamp),
+            0x42 => Some(Tag::U128),
+            0x43 => Some(Tag::S128),
             _ => None,
         }
     }
 }
 
+/// The tag and length header of an item, read by [`Decoder::peek_header`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub tag: Tag,
+    /// Body length for blobs and containers; `None` for scalars and unit-like tags.
+    pub byte_len: Option<u64>,
+}
+
 /// Internal state tracking for the `Encoder` stack.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Scope {
@@ -176,6 +402,49 @@ struct Frame {
     start: usize,
     scope: Scope,
     count: usize,
+    /// Numbers the scope among those its encoder opened, so a scope reopened
+    /// at the same offset after a rollback is told apart. Unused when streaming.
+    serial: usize,
+    /// The encoder's rollback count when this scope was opened or last rolled
+    /// back into; marks taken under an older one are stale. Unused when streaming.
+    generation: usize,
+}
+
+impl Frame {
+    /// Checks that an item starting with `tag` may be written into this scope.
+    fn check_write(&self, tag: Tag) -> Result<()> {
+        match self.scope {
+            Scope::Root | Scope::List => Ok(()),
+            Scope::Map => {
+                if tag != Tag::Variant {
+                    Err(Error::InvalidMapEntry)
+                } else {
+                    Ok(())
+                }
+            },
+            Scope::Option | Scope::Result | Scope::Variant => {
+                if self.count >= 1 {
+                    Err(Error::TooManyItems(self.scope))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Checks that this scope is the `expected` one and holds what it must.
+    fn check_close(&self, expected: Scope) -> Result<()> {
+        if self.scope != expected {
+            return Err(Error::ScopeMismatch { expected, actual: self.scope });
+        }
+
+        match self.scope {
+            Scope::Option | Scope::Result | Scope::Variant if self.count == 0 => {
+                Err(Error::EmptyAdt(self.scope))
+            },
+            _ => Ok(()),
+        }
+    }
 }
 
 /// A bounded, state-machine driven encoder.
@@ -196,38 +465,172 @@ pub struct Encoder {
     buf: Vec<u8>,
     /// Bottom is always `Scope::Root`.
     stack: Vec<Frame>,
+    /// When set, writes only advance `counted` and `buf` stays empty.
+    dry_run: bool,
+    counted: usize,
+    /// Scopes opened so far, numbering each `Frame`.
+    opened: usize,
+    /// Rollbacks done so far, stamping each `Frame` they touch.
+    rollbacks: usize,
+}
+
+/// A position in an [`Encoder`], taken by [`Encoder::mark`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderMark {
+    len: usize,
+    depth: usize,
+    /// Serial of the scope the mark was taken in.
+    serial: usize,
+    /// Generation of that scope when the mark was taken.
+    generation: usize,
+    count: usize,
 }
 
 impl Encoder {
     /// Creates a new encoder with default capacity.
     pub fn new() -> Self {
+        Self::with_buf(Vec::with_capacity(1024), false)
+    }
+
+    /// Creates an encoder that only counts bytes.
+    ///
+    /// All structural checks still run, but nothing is buffered;
+    /// use [`Encoder::len`] to read the final size. Asking it for bytes
+    /// fails with `Error::DryRun`, so a size probe can't pass for output.
+    /// See [`encoded_len`] for the common case.
+    pub fn dry_run() -> Self {
+        Self::with_buf(Vec::new(), true)
+    }
+
+    fn with_buf(buf: Vec<u8>, dry_run: bool) -> Self {
         let mut enc = Self {
-            buf: Vec::with_capacity(1024),
+            buf,
             stack: Vec::with_capacity(8),
+            dry_run,
+            counted: 0,
+            opened: 0,
+            rollbacks: 0,
         };
-        enc.stack.push(Frame { start: 0, scope: Scope::Root, count: 0 });
+        enc.stack.push(Frame { start: 0, scope: Scope::Root, count: 0, serial: 0, generation: 0 });
         enc
     }
 
+    /// Reserves capacity for at least `bytes` more bytes.
+    ///
+    /// Pair with [`encoded_len`] to size the buffer in a single allocation.
+    /// Does nothing for a dry-run encoder.
+    pub fn reserve_hint(&mut self, bytes: usize) {
+        if !self.dry_run {
+            self.buf.reserve(bytes);
+        }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        if self.dry_run { self.counted } else { self.buf.len() }
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Captures the current position, for a later [`Encoder::rollback`].
+    pub fn mark(&self) -> EncoderMark {
+        let frame = self.stack.last().unwrap();
+        EncoderMark {
+            len: self.len(),
+            depth: self.stack.len(),
+            serial: frame.serial,
+            generation: frame.generation,
+            count: frame.count,
+        }
+    }
+
+    /// Discards everything written since `mark`, including scopes opened since.
+    ///
+    /// The scope the mark was taken in gets its item count back, so rolling
+    /// back inside an open List leaves it as if the dropped items were never written.
+    ///
+    /// A rollback spends every mark taken in that scope before it, `mark`
+    /// included; take a fresh one to roll back there again.
+    ///
+    /// # Errors
+    /// Returns `Error::StaleMark` if that scope has since been closed or rolled
+    /// back into. The encoder is unchanged.
+    pub fn rollback(&mut self, mark: EncoderMark) -> Result<()> {
+        let live = self.stack.get(mark.depth - 1)
+            .is_some_and(|frame| frame.serial == mark.serial && frame.generation == mark.generation);
+        if !live {
+            return Err(Error::StaleMark);
+        }
+
+        self.stack.truncate(mark.depth);
+        self.rollbacks += 1;
+        let generation = self.rollbacks;
+        let frame = self.current_frame();
+        frame.count = mark.count;
+        frame.generation = generation;
+        if self.dry_run {
+            self.counted = mark.len;
+        } else {
+            self.buf.truncate(mark.len);
+        }
+        Ok(())
+    }
+
     /// Consumes the encoder and returns the final byte vector.
     ///
     /// # Errors
-    /// Returns `Error::ScopeStillOpen` if the stack depth > 1.
+    /// Returns `Error::ScopeStillOpen` if the stack depth > 1,
+    /// or `Error::DryRun` for a dry-run encoder.
     pub fn into_bytes(self) -> Result<Vec<u8>> {
-        if self.stack.len() > 1 {
-            return Err(Error::ScopeStillOpen);
-        }
+        self.as_bytes()?;
         Ok(self.buf)
     }
 
-    /// Returns a view of the current buffer.
+    /// Consumes the encoder and returns the final bytes followed by a CRC trailer.
+    ///
+    /// The trailer is [`CRC_TRAILER_LEN`] bytes outside the TLV structure, so
+    /// check and strip it with [`Decoder::verify_crc`] before decoding.
     ///
     /// # Errors
     /// Returns `Error::ScopeStillOpen` if the stack depth > 1.
+    pub fn into_bytes_with_crc(self) -> Result<Vec<u8>> {
+        let mut bytes = self.into_bytes()?;
+        let crc = crc32c(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        Ok(bytes)
+    }
+
+    /// Consumes the encoder and returns the bytes in canonical form.
+    ///
+    /// Map entries are sorted by key (bytewise) at every nesting level
+    /// and `Tag::Pad` bytes are dropped, so equal values always produce equal bytes.
+    /// The result passes [`validate_canonical`].
+    ///
+    /// # Errors
+    /// Returns `Error::ScopeStillOpen` if the stack depth > 1,
+    /// or `Error::NonCanonical` if a map contains the same key twice.
+    pub fn into_canonical_bytes(self) -> Result<Vec<u8>> {
+        let bytes = self.into_bytes()?;
+        let mut out = Vec::with_capacity(bytes.len());
+        canonicalize_items(Decoder::new(&bytes), &mut out, false)?;
+        Ok(out)
+    }
+
+    /// Returns a view of the current buffer.
+    ///
+    /// # Errors
+    /// Returns `Error::ScopeStillOpen` if the stack depth > 1,
+    /// or `Error::DryRun` for a dry-run encoder.
     pub fn as_bytes(&self) -> Result<&[u8]> {
         if self.stack.len() > 1 {
             return Err(Error::ScopeStillOpen);
         }
+        if self.dry_run {
+            return Err(Error::DryRun);
+        }
         Ok(&self.buf)
     }
 
@@ -236,24 +639,7 @@ impl Encoder {
     }
 
     fn check_write(&mut self, tag: Tag) -> Result<()> {
-        let frame = self.current_frame();
-        match frame.scope {
-            Scope::Root | Scope::List => Ok(()),
-            Scope::Map => {
-                if tag != Tag::Variant {
-                    Err(Error::InvalidMapEntry)
-                } else {
-                    Ok(())
-                }
-            },
-            Scope::Option | Scope::Result | Scope::Variant => {
-                if frame.count >= 1 {
-                    Err(Error::TooManyItems(frame.scope))
-                } else {
-                    Ok(())
-                }
-            }
-        }
+        self.current_frame().check_write(tag)
     }
 
     fn on_item_written(&mut self) {
@@ -261,26 +647,37 @@ impl Encoder {
         frame.count += 1;
     }
 
+    fn put(&mut self, bytes: &[u8]) {
+        if self.dry_run {
+            self.counted += bytes.len();
+        } else {
+            self.buf.extend_from_slice(bytes);
+        }
+    }
+
     fn write_tag(&mut self, tag: Tag) -> Result<()> {
         self.check_write(tag)?;
-        self.buf.push(tag as u8);
+        self.put(&[tag as u8]);
         Ok(())
     }
 
     fn write_u32_raw(&mut self, v: u32) {
-        self.buf.extend_from_slice(&v.to_le_bytes());
+        self.put(&v.to_le_bytes());
     }
 
     fn begin_scope(&mut self, tag: Tag, scope: Scope) -> Result<()> {
         self.check_write(tag)?;
 
-        self.buf.push(tag as u8);
-        self.buf.extend_from_slice(&[0, 0, 0, 0]); // Length placeholder
+        self.put(&[tag as u8]);
+        self.put(&[0, 0, 0, 0]); // Length placeholder
 
+        self.opened += 1;
         self.stack.push(Frame {
-            start: self.buf.len(), // Body starts after Length
+            start: self.len(), // Body starts after Length
             scope,
             count: 0,
+            serial: self.opened,
+            generation: self.rollbacks,
         });
         Ok(())
     }
@@ -290,33 +687,21 @@ impl Encoder {
             return Err(Error::ScopeUnderflow);
         }
 
-        { // Validate Scope State
-            let frame = self.current_frame();
-            if frame.scope != expected {
-                return Err(Error::ScopeMismatch { expected, actual: frame.scope });
-            }
-
-            match frame.scope {
-                Scope::Option | Scope::Result | Scope::Variant => {
-                    if frame.count == 0 {
-                        return Err(Error::EmptyAdt(frame.scope));
-                    }
-                },
-                _ => {}
-            }
-        }
+        self.current_frame().check_close(expected)?;
 
         // Pop and Patch
         let frame = self.stack.pop().unwrap();
-        let body_len = self.buf.len() - frame.start;
+        let body_len = self.len() - frame.start;
 
         if body_len > u32::MAX as usize {
             return Err(Error::BlobTooLarge(body_len));
         }
 
-        let len_bytes = (body_len as u32).to_le_bytes();
-        let len_pos = frame.start - 4;
-        self.buf[len_pos..frame.start].copy_from_slice(&len_bytes);
+        if !self.dry_run {
+            let len_bytes = (body_len as u32).to_le_bytes();
+            let len_pos = frame.start - 4;
+            self.buf[len_pos..frame.start].copy_from_slice(&len_bytes);
+        }
 
         self.on_item_written();
 
@@ -331,32 +716,67 @@ impl Encoder {
     }
 
     /// Encodes an unsigned 8-bit integer.
-    pub fn u8(&mut self, v: u8) -> Result<()> { self.write_tag(Tag::U8)?; self.buf.push(v); self.on_item_written(); Ok(()) }
+    pub fn u8(&mut self, v: u8) -> Result<()> { self.write_tag(Tag::U8)?; self.put(&[v]); self.on_item_written(); Ok(()) }
     /// Encodes a signed 8-bit integer.
-    pub fn s8(&mut self, v: i8) -> Result<()> { self.write_tag(Tag::S8)?; self.buf.push(v as u8); self.on_item_written(); Ok(()) }
+    pub fn s8(&mut self, v: i8) -> Result<()> { self.write_tag(Tag::S8)?; self.put(&[v as u8]); self.on_item_written(); Ok(()) }
 
     /// Encodes an unsigned 16-bit integer (LE).
-    pub fn u16(&mut self, v: u16) -> Result<()> { self.write_tag(Tag::U16)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn u16(&mut self, v: u16) -> Result<()> { self.write_tag(Tag::U16)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
     /// Encodes a signed 16-bit integer (LE).
-    pub fn s16(&mut self, v: i16) -> Result<()> { self.write_tag(Tag::S16)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn s16(&mut self, v: i16) -> Result<()> { self.write_tag(Tag::S16)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
 
     /// Encodes an unsigned 32-bit integer (LE).
-    pub fn u32(&mut self, v: u32) -> Result<()> { self.write_tag(Tag::U32)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn u32(&mut self, v: u32) -> Result<()> { self.write_tag(Tag::U32)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
     /// Encodes a signed 32-bit integer (LE).
-    pub fn s32(&mut self, v: i32) -> Result<()> { self.write_tag(Tag::S32)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn s32(&mut self, v: i32) -> Result<()> { self.write_tag(Tag::S32)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
 
     /// Encodes an unsigned 64-bit integer (LE).
-    pub fn u64(&mut self, v: u64) -> Result<()> { self.write_tag(Tag::U64)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn u64(&mut self, v: u64) -> Result<()> { self.write_tag(Tag::U64)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
     /// Encodes a signed 64-bit integer (LE).
-    pub fn s64(&mut self, v: i64) -> Result<()> { self.write_tag(Tag::S64)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn s64(&mut self, v: i64) -> Result<()> { self.write_tag(Tag::S64)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+
+    /// Encodes an unsigned 128-bit integer (LE).
+    pub fn u128(&mut self, v: u128) -> Result<()> { self.write_tag(Tag::U128)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    /// Encodes a signed 128-bit integer (LE).
+    pub fn s128(&mut self, v: i128) -> Result<()> { self.write_tag(Tag::S128)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
 
     /// Encodes a 32-bit float (LE).
-    pub fn f32(&mut self, v: f32) -> Result<()> { self.write_tag(Tag::F32)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn f32(&mut self, v: f32) -> Result<()> { self.write_tag(Tag::F32)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
     /// Encodes a 64-bit float (LE).
-    pub fn f64(&mut self, v: f64) -> Result<()> { self.write_tag(Tag::F64)?; self.buf.extend_from_slice(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn f64(&mut self, v: f64) -> Result<()> { self.write_tag(Tag::F64)?; self.put(&v.to_le_bytes()); self.on_item_written(); Ok(()) }
 
     /// Encodes a char as u32 (LE).
-    pub fn char(&mut self, v: char) -> Result<()> { self.write_tag(Tag::Char)?; self.buf.extend_from_slice(&(v as u32).to_le_bytes()); self.on_item_written(); Ok(()) }
+    pub fn char(&mut self, v: char) -> Result<()> { self.write_tag(Tag::Char)?; self.put(&(v as u32).to_le_bytes()); self.on_item_written(); Ok(()) }
+
+    /// Encodes a fixed-point decimal with value `mantissa * 10^-scale`.
+    ///
+    /// Written length-prefixed, as a body of [`DECIMAL_LEN`] bytes: the mantissa
+    /// as i128 (LE), followed by the scale as a single byte.
+    pub fn decimal(&mut self, mantissa: i128, scale: i8) -> Result<()> {
+        self.write_tag(Tag::Decimal128)?;
+        self.write_u32_raw(DECIMAL_LEN as u32);
+        self.put(&mantissa.to_le_bytes());
+        self.put(&[scale as u8]);
+        self.on_item_written();
+        Ok(())
+    }
+
+    /// Encodes a wall-clock instant as seconds since the unix epoch plus nanos.
+    ///
+    /// Instants before the epoch have negative `secs`; `nanos` always counts forward.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTimestamp` if `nanos` is a full second or more.
+    pub fn timestamp(&mut self, secs: i64, nanos: u32) -> Result<()> {
+        if nanos > MAX_TIMESTAMP_NANOS {
+            return Err(Error::InvalidTimestamp);
+        }
+        self.write_tag(Tag::Timestamp)?;
+        self.put(&secs.to_le_bytes());
+        self.put(&nanos.to_le_bytes());
+        self.on_item_written();
+        Ok(())
+    }
 
     /// Encodes Unit `()`.
     pub fn unit(&mut self) -> Result<()> { self.write_tag(Tag::Unit)?; self.on_item_written(); Ok(()) }
@@ -369,7 +789,7 @@ impl Encoder {
         if len > u32::MAX as usize { return Err(Error::BlobTooLarge(len)); }
         self.write_tag(Tag::String)?;
         self.write_u32_raw(len as u32);
-        self.buf.extend_from_slice(v.as_bytes());
+        self.put(v.as_bytes());
         self.on_item_written();
         Ok(())
     }
@@ -380,18 +800,41 @@ impl Encoder {
         if len > u32::MAX as usize { return Err(Error::BlobTooLarge(len)); }
         self.write_tag(Tag::Bytes)?;
         self.write_u32_raw(len as u32);
-        self.buf.extend_from_slice(v);
+        self.put(v);
+        self.on_item_written();
+        Ok(())
+    }
+
+    /// Encodes a byte blob with a u64 length, lifting the `u32::MAX` limit of [`Encoder::bytes`].
+    ///
+    /// Containers still frame their bodies with a u32, so a blob this large
+    /// must be written at the top level.
+    pub fn big_bytes(&mut self, v: &[u8]) -> Result<()> {
+        self.write_tag(Tag::BigBytes)?;
+        self.put(&(v.len() as u64).to_le_bytes());
+        self.put(v);
         self.on_item_written();
         Ok(())
     }
 
-    /// Appends pre-encoded neopack bytes directly to the buffer.
+    /// Appends a pre-encoded neopack item directly to the buffer.
     ///
     /// This is used to inject already-encoded data (like a pre-encoded list of values)
-    /// into the stream without re-encoding. The caller must ensure the bytes are valid
-    /// neopack-encoded data.
+    /// into the stream without re-encoding.
+    ///
+    /// # Errors
+    /// The fragment must be exactly one complete item. Returns `Error::UnexpectedEnd`
+    /// if it is empty or truncated, and `Error::TrailingBytes` if anything follows the item.
+    /// The item's leading tag is checked against the current scope like any other write.
     pub fn append_raw(&mut self, v: &[u8]) -> Result<()> {
-        self.buf.extend_from_slice(v);
+        let mut probe = Decoder::new(v);
+        let tag = probe.peek_tag()?;
+        probe.skip()?;
+        if probe.remaining() != 0 {
+            return Err(Error::TrailingBytes(probe.remaining()));
+        }
+        self.check_write(tag)?;
+        self.put(v);
         self.on_item_written();
         Ok(())
     }
@@ -414,6 +857,44 @@ impl Encoder {
     /// Ends a Map container.
     pub fn map_end(&mut self) -> Result<()> { self.end_scope(Scope::Map) }
 
+    /// Begins a Map container whose entries are sorted when it ends.
+    ///
+    /// # Invariants
+    /// - Must be closed via `map_sorted_end()`.
+    /// - **Strict:** Only `variant_begin()` (Key/Value pair) is allowed as a direct child.
+    pub fn map_sorted_begin(&mut self) -> Result<()> { self.map_begin() }
+
+    /// Ends a Map container, sorting its entries by key (bytewise).
+    ///
+    /// Entries are sorted by the same routine [`Encoder::into_canonical_bytes`] uses,
+    /// so the output doesn't depend on insertion order. Nested maps are left as written.
+    /// A dry-run encoder keeps no entries to compare, so it can't catch duplicates.
+    ///
+    /// # Errors
+    /// Returns `Error::DuplicateKey` if two entries share a key; the map stays open.
+    pub fn map_sorted_end(&mut self) -> Result<()> {
+        if self.stack.len() <= 1 {
+            return Err(Error::ScopeUnderflow);
+        }
+        self.current_frame().check_close(Scope::Map)?;
+
+        if !self.dry_run {
+            let start = self.current_frame().start;
+            let body = self.buf.split_off(start);
+            let sorted = collect_items(Decoder::new(&body))
+                .and_then(sort_map_entries)
+                .map(|entries| entries.concat());
+            match sorted {
+                Ok(sorted) => self.buf.extend_from_slice(&sorted),
+                Err(e) => {
+                    self.buf.extend_from_slice(&body);
+                    return Err(e);
+                }
+            }
+        }
+        self.end_scope(Scope::Map)
+    }
+
     /// Begins an `Option::Some` container.
     ///
     /// # Invariants
@@ -460,6 +941,12 @@ impl Encoder {
     pub fn variant_end(&mut self) -> Result<()> { self.end_scope(Scope::Variant) }
 }
 
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// A zero-copy, bounds-checked cursor over a byte slice.
 ///
 /// Decoders are immutable views. Reading advances the internal cursor.
@@ -470,12 +957,62 @@ impl Encoder {
 #[derive(Debug, Clone)]
 pub struct Decoder<'a> {
     buf: &'a [u8],
+    depth: Option<usize>,
+    /// Offset of `buf` within the buffer the outermost decoder was made over.
+    pos: usize,
+    /// Where the most recently read item began, for [`Decoder::value_span`].
+    last_start: Option<usize>,
+    /// Path to this view's items, if [`Decoder::with_path_tracking`] turned tracking on.
+    path: Option<std::sync::Arc<str>>,
 }
 
 impl<'a> Decoder<'a> {
     /// Creates a decoder over the slice.
     pub fn new(buf: &'a [u8]) -> Self {
-        Self { buf }
+        Self { buf, depth: None, pos: 0, last_start: None, path: None }
+    }
+
+    /// Creates a decoder that refuses to nest deeper than `max_depth` containers.
+    ///
+    /// Each container entered (list, map, option, result, variant) uses one level;
+    /// the sub-decoder it returns carries the remaining budget.
+    /// Use this when decoding untrusted input with recursive `Unpack` impls.
+    pub fn with_max_depth(buf: &'a [u8], max_depth: usize) -> Self {
+        Self { buf, depth: Some(max_depth), pos: 0, last_start: None, path: None }
+    }
+
+    /// Turns on path tracking, so type mismatches report where they happened.
+    ///
+    /// Containers entered from this decoder extend the path like a JSON pointer:
+    /// list items by index, map keys and variant cases by name, and Result
+    /// payloads as `ok` or `err`. Reading the wrong type then fails with
+    /// `Error::TypeMismatchAt("/metadata/version")` instead of `Error::InvalidTag`.
+    /// Decoders without tracking never build paths.
+    pub fn with_path_tracking(mut self) -> Self {
+        self.path = Some("".into());
+        self
+    }
+
+    /// Checks the CRC trailer written by [`Encoder::into_bytes_with_crc`],
+    /// returning the payload before it.
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEnd` if `bytes` is shorter than the trailer,
+    /// or `Error::ChecksumMismatch` if the payload or trailer was altered.
+    pub fn verify_crc(bytes: &[u8]) -> Result<&[u8]> {
+        let split = bytes.len().checked_sub(CRC_TRAILER_LEN).ok_or(Error::UnexpectedEnd)?;
+        let (payload, trailer) = bytes.split_at(split);
+        let stored = u32::from_le_bytes(trailer.try_into().expect("trailer is 4 bytes"));
+        let computed = crc32c(payload);
+        if stored != computed {
+            return Err(Error::ChecksumMismatch { stored, computed });
+        }
+        Ok(payload)
+    }
+
+    /// Returns how many more containers may be entered, or `None` if unlimited.
+    pub fn remaining_depth(&self) -> Option<usize> {
+        self.depth
     }
 
     /// Returns the remaining bytes in the view.
@@ -483,15 +1020,61 @@ impl<'a> Decoder<'a> {
         self.buf.len()
     }
 
+    /// Returns the cursor's offset within the buffer the outermost decoder was made over.
+    ///
+    /// Sub-decoders for container bodies count from the same origin.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the `[start, end)` offsets of the item most recently read, or `None` before any.
+    ///
+    /// Offsets count from the same origin as [`Decoder::position`], and a
+    /// container's span covers the whole container, header and body.
+    /// Reads include [`Decoder::skip`] and [`Decoder::next_item_bytes`].
+    /// After a failed read the span is meaningless.
+    pub fn value_span(&self) -> Option<std::ops::Range<usize>> {
+        self.last_start.map(|start| start..self.pos)
+    }
+
+    fn start_item(&mut self) {
+        self.last_start = Some(self.pos);
+    }
+
     /// Peeks the next Tag without advancing.
     pub fn peek_tag(&self) -> Result<Tag> {
         if self.buf.is_empty() { return Err(Error::UnexpectedEnd); }
         Tag::from_u8(self.buf[0]).ok_or(Error::InvalidTag(self.buf[0]))
     }
 
+    /// Peeks the next item's tag and, for blobs and containers, its body length.
+    ///
+    /// The cursor doesn't move, so a message can be routed on its header
+    /// and then decoded as usual.
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEnd` if the header itself is truncated;
+    /// a truncated body isn't checked.
+    pub fn peek_header(&self) -> Result<Header> {
+        let tag = self.peek_tag()?;
+        let mut probe = self.clone();
+        probe.consume(1)?;
+        let byte_len = match tag {
+            Tag::String | Tag::Bytes | Tag::Decimal128 |
+            Tag::List | Tag::Map |
+            Tag::OptionSome | Tag::ResultOk | Tag::ResultErr | Tag::Variant => {
+                Some(u32::from_le_bytes(probe.read_bytes(4)?.try_into().unwrap()) as u64)
+            }
+            Tag::BigBytes => Some(u64::from_le_bytes(probe.read_bytes(8)?.try_into().unwrap())),
+            _ => None,
+        };
+        Ok(Header { tag, byte_len })
+    }
+
     fn consume(&mut self, n: usize) -> Result<()> {
         if n > self.buf.len() { return Err(Error::UnexpectedEnd); }
         self.buf = &self.buf[n..];
+        self.pos += n;
         Ok(())
     }
 
@@ -499,6 +1082,7 @@ impl<'a> Decoder<'a> {
         if self.buf.is_empty() { return Err(Error::UnexpectedEnd); }
         let b = self.buf[0];
         self.buf = &self.buf[1..];
+        self.pos += 1;
         Ok(b)
     }
 
@@ -506,27 +1090,68 @@ impl<'a> Decoder<'a> {
         if n > self.buf.len() { return Err(Error::UnexpectedEnd); }
         let (head, tail) = self.buf.split_at(n);
         self.buf = tail;
+        self.pos += n;
         Ok(head)
     }
 
     fn read_slice(&mut self, n: usize) -> Result<Decoder<'a>> {
+        let pos = self.pos;
         let bytes = self.read_bytes(n)?;
-        Ok(Decoder::new(bytes))
+        Ok(Decoder { buf: bytes, depth: self.depth, pos, last_start: None, path: self.path.clone() })
+    }
+
+    /// Appends `segment` to the path, if it is being tracked.
+    fn push_segment(&mut self, segment: impl std::fmt::Display) {
+        if let Some(path) = &self.path {
+            self.path = Some(format!("{}/{}", path, segment).into());
+        }
+    }
+
+    /// The error for finding `tag` where another type was expected.
+    fn mismatch(&self, tag: Tag) -> Error {
+        match self.path.as_deref() {
+            Some("") => Error::TypeMismatchAt("/".into()),
+            Some(path) => Error::TypeMismatchAt(path.into()),
+            None => Error::InvalidTag(tag as u8),
+        }
+    }
+
+    /// Splits off the raw bytes of the next item.
+    fn next_item(&mut self) -> Result<&'a [u8]> {
+        let mut probe = self.clone();
+        probe.skip()?;
+        let len = self.remaining() - probe.remaining();
+        self.start_item();
+        self.read_bytes(len)
+    }
+
+    /// Splits off the raw bytes of the next complete item, or `None` at the end.
+    ///
+    /// Walks a buffer holding several top-level items, one at a time.
+    /// A buffer that ends partway through an item fails with
+    /// `Error::UnexpectedEnd` and leaves the decoder where it was.
+    pub fn next_item_bytes(&mut self) -> Result<Option<&'a [u8]>> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        self.next_item().map(Some)
     }
 
     fn check_tag(&mut self, expected: Tag) -> Result<()> {
         let tag = self.peek_tag()?;
         if tag == expected {
+            self.start_item();
             self.consume(1)?;
             Ok(())
         } else {
-            Err(Error::InvalidTag(tag as u8))
+            Err(self.mismatch(tag))
         }
     }
 
     /// Skips the next item and its nested children.
     pub fn skip(&mut self) -> Result<()> {
         let tag = self.peek_tag()?;
+        self.start_item();
         self.consume(1)?; // Consume Tag
 
         match tag {
@@ -538,16 +1163,23 @@ impl<'a> Decoder<'a> {
             Tag::U16 | Tag::S16 => { self.consume(2)?; },
             Tag::U32 | Tag::S32 | Tag::F32 | Tag::Char => { self.consume(4)?; },
             Tag::U64 | Tag::S64 | Tag::F64 => { self.consume(8)?; },
+            Tag::U128 | Tag::S128 => { self.consume(16)?; },
+            Tag::Timestamp => { self.consume(12)?; },
 
             // Variable length (Blob or Scoped)
             // Structure: [Length: u32] [Body: Length]
-            Tag::String | Tag::Bytes |
+            Tag::String | Tag::Bytes | Tag::Decimal128 |
             Tag::List | Tag::Map |
             Tag::OptionSome | Tag::ResultOk | Tag::ResultErr | Tag::Variant => {
                 let len_bytes = self.read_bytes(4)?;
                 let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
                 self.consume(len)?;
             }
+            // Structure: [Length: u64] [Body: Length]
+            Tag::BigBytes => {
+                let len = self.read_big_len()?;
+                self.consume(len)?;
+            }
         }
         Ok(())
     }
@@ -556,9 +1188,9 @@ impl<'a> Decoder<'a> {
     pub fn bool(&mut self) -> Result<bool> {
         let tag = self.peek_tag()?;
         match tag {
-            Tag::BoolTrue => { self.consume(1)?; Ok(true) },
-            Tag::BoolFalse => { self.consume(1)?; Ok(false) },
-            _ => Err(Error::InvalidTag(tag as u8))
+            Tag::BoolTrue => { self.start_item(); self.consume(1)?; Ok(true) },
+            Tag::BoolFalse => { self.start_item(); self.consume(1)?; Ok(false) },
+            _ => Err(self.mismatch(tag))
         }
     }
 
@@ -582,6 +1214,11 @@ impl<'a> Decoder<'a> {
     /// Decodes s64 (LE).
     pub fn s64(&mut self) -> Result<i64> { self.check_tag(Tag::S64)?; Ok(i64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap())) }
 
+    /// Decodes u128 (LE).
+    pub fn u128(&mut self) -> Result<u128> { self.check_tag(Tag::U128)?; Ok(u128::from_le_bytes(self.read_bytes(16)?.try_into().unwrap())) }
+    /// Decodes s128 (LE).
+    pub fn s128(&mut self) -> Result<i128> { self.check_tag(Tag::S128)?; Ok(i128::from_le_bytes(self.read_bytes(16)?.try_into().unwrap())) }
+
     /// Decodes f32 (LE).
     pub fn f32(&mut self) -> Result<f32> { self.check_tag(Tag::F32)?; Ok(f32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap())) }
     /// Decodes f64 (LE).
@@ -594,6 +1231,35 @@ impl<'a> Decoder<'a> {
         std::char::from_u32(val).ok_or(Error::InvalidUtf8)
     }
 
+    /// Decodes a fixed-point decimal as `(mantissa, scale)`.
+    ///
+    /// # Errors
+    /// Returns `Error::SizeMismatch` if the declared length isn't [`DECIMAL_LEN`].
+    pub fn decimal(&mut self) -> Result<(i128, i8)> {
+        self.check_tag(Tag::Decimal128)?;
+        let declared = u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap());
+        if declared as usize != DECIMAL_LEN {
+            return Err(Error::SizeMismatch { declared, actual: DECIMAL_LEN });
+        }
+        let mantissa = i128::from_le_bytes(self.read_bytes(16)?.try_into().unwrap());
+        let scale = self.read_u8()? as i8;
+        Ok((mantissa, scale))
+    }
+
+    /// Decodes a wall-clock instant as `(secs, nanos)` since the unix epoch.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTimestamp` if the encoded nanos are a full second or more.
+    pub fn timestamp(&mut self) -> Result<(i64, u32)> {
+        self.check_tag(Tag::Timestamp)?;
+        let secs = i64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap());
+        let nanos = u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap());
+        if nanos > MAX_TIMESTAMP_NANOS {
+            return Err(Error::InvalidTimestamp);
+        }
+        Ok((secs, nanos))
+    }
+
     /// Decodes Unit `()`.
     pub fn unit(&mut self) -> Result<()> { self.check_tag(Tag::Unit) }
     /// Decodes `Option::None`.
@@ -614,15 +1280,83 @@ impl<'a> Decoder<'a> {
         self.read_bytes(len)
     }
 
+    /// Decodes a byte slice written by [`Encoder::big_bytes`].
+    pub fn big_bytes(&mut self) -> Result<&'a [u8]> {
+        self.check_tag(Tag::BigBytes)?;
+        let len = self.read_big_len()?;
+        self.read_bytes(len)
+    }
+
+    /// Reads a u64 blob length; one past the address space can't be in the buffer either.
+    fn read_big_len(&mut self) -> Result<usize> {
+        let len = u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap());
+        usize::try_from(len).map_err(|_| Error::UnexpectedEnd)
+    }
+
+    fn descend(&self) -> Result<Option<usize>> {
+        match self.depth {
+            Some(0) => Err(Error::DepthExceeded),
+            depth => Ok(depth.map(|d| d - 1)),
+        }
+    }
+
     fn enter_container(&mut self, expected: Tag) -> Result<Decoder<'a>> {
         self.check_tag(expected)?;
+        let depth = self.descend()?;
         let len = u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()) as usize;
-        self.read_slice(len)
+        let mut body = self.read_slice(len)?;
+        body.depth = depth;
+        Ok(body)
     }
 
     /// Decodes a List into an iterator.
     pub fn list(&mut self) -> Result<ListIter<'a>> {
-        Ok(ListIter { dec: self.enter_container(Tag::List)? })
+        Ok(ListIter { dec: self.enter_container(Tag::List)?, index: 0 })
+    }
+
+    /// Decodes a List whose items are all the scalar `T`.
+    ///
+    /// Every item's tag is checked up front in one pass over the body,
+    /// so the iterator then reads values without looking at tags again.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTag` if the first item isn't a `T`,
+    /// `Error::HeterogeneousList` if a later one isn't,
+    /// and `Error::UnexpectedEnd` if the body ends partway through an item.
+    pub fn scalar_list<T: Scalar>(&mut self) -> Result<ScalarListIter<'a, T>> {
+        let body = self.enter_container(Tag::List)?.buf;
+        let stride = 1 + T::WIDTH;
+        for (index, item) in body.chunks(stride).enumerate() {
+            if item[0] != T::TAG as u8 {
+                return Err(match index {
+                    0 => Error::InvalidTag(item[0]),
+                    _ => Error::HeterogeneousList { index, tag: item[0] },
+                });
+            }
+            if item.len() < stride {
+                return Err(Error::UnexpectedEnd);
+            }
+        }
+        Ok(ScalarListIter { items: body.chunks_exact(stride), _scalar: std::marker::PhantomData })
+    }
+
+    /// Decodes a List whose body may still be arriving.
+    ///
+    /// Unlike [`Decoder::list`], this does not require the whole body to be present.
+    /// The returned iterator yields complete items as they become available and
+    /// reports `Error::UnexpectedEnd` at the first truncated one,
+    /// so a partial list is never mistaken for a finished one.
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEnd` if the tag or length header is truncated.
+    pub fn try_list(&mut self) -> Result<PartialListIter<'a>> {
+        self.check_tag(Tag::List)?;
+        let depth = self.descend()?;
+        let len = u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()) as usize;
+        let available = len.min(self.remaining());
+        let mut body = self.read_slice(available)?;
+        body.depth = depth;
+        Ok(PartialListIter { dec: body, pending: len, index: 0 })
     }
 
     /// Decodes a Map into an iterator.
@@ -637,13 +1371,14 @@ impl<'a> Decoder<'a> {
         let tag = self.peek_tag()?;
         match tag {
             Tag::OptionNone => {
+                self.start_item();
                 self.consume(1)?;
                 Ok(None)
             }
             Tag::OptionSome => {
                 Ok(Some(self.enter_container(Tag::OptionSome)?))
             }
-            _ => Err(Error::InvalidTag(tag as u8))
+            _ => Err(self.mismatch(tag))
         }
     }
 
@@ -653,9 +1388,17 @@ impl<'a> Decoder<'a> {
     pub fn result(&mut self) -> Result<std::result::Result<Decoder<'a>, Decoder<'a>>> {
         let tag = self.peek_tag()?;
         match tag {
-            Tag::ResultOk => Ok(Ok(self.enter_container(Tag::ResultOk)?)),
-            Tag::ResultErr => Ok(Err(self.enter_container(Tag::ResultErr)?)),
-            _ => Err(Error::InvalidTag(tag as u8))
+            Tag::ResultOk => {
+                let mut payload = self.enter_container(Tag::ResultOk)?;
+                payload.push_segment("ok");
+                Ok(Ok(payload))
+            }
+            Tag::ResultErr => {
+                let mut payload = self.enter_container(Tag::ResultErr)?;
+                payload.push_segment("err");
+                Ok(Err(payload))
+            }
+            _ => Err(self.mismatch(tag))
         }
     }
 
@@ -665,6 +1408,7 @@ impl<'a> Decoder<'a> {
     pub fn variant(&mut self) -> Result<(&'a str, Decoder<'a>)> {
         let mut inner = self.enter_container(Tag::Variant)?;
         let name = inner.str()?;
+        inner.push_segment(name);
         Ok((name, inner))
     }
 }
@@ -673,11 +1417,18 @@ impl<'a> Decoder<'a> {
 #[derive(Debug)]
 pub struct ListIter<'a> {
     dec: Decoder<'a>,
+    /// Index of the next item, for path tracking.
+    index: usize,
 }
 
-impl<'a> ListIter<'a> {
+impl<'a> Iterator for ListIter<'a> {
+    type Item = Decoder<'a>;
+
     /// Returns a Decoder for the next item, or `None`.
-    pub fn next(&mut self) -> Option<Decoder<'a>> {
+    ///
+    /// Iteration stops at the end of the list or at the first malformed item,
+    /// and stays stopped.
+    fn next(&mut self) -> Option<Decoder<'a>> {
         if self.dec.remaining() == 0 {
             return None;
         }
@@ -686,7 +1437,112 @@ impl<'a> ListIter<'a> {
             return None;
         }
         let len = self.dec.remaining() - probe.remaining();
-        self.dec.read_slice(len).ok()
+        let mut item = self.dec.read_slice(len).ok()?;
+        item.push_segment(self.index);
+        self.index += 1;
+        Some(item)
+    }
+}
+
+impl std::iter::FusedIterator for ListIter<'_> {}
+
+/// A fixed-width scalar that [`Decoder::scalar_list`] can read in bulk.
+///
+/// Implemented for the integer and float types; it is sealed,
+/// so the tag and width always match the encoder's.
+pub trait Scalar: Copy + sealed::Sealed {
+    const TAG: Tag;
+    /// Bytes after the tag.
+    const WIDTH: usize;
+    /// Reads the value from exactly `WIDTH` little-endian bytes.
+    fn from_le(bytes: &[u8]) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_scalar {
+    ($($ty:ty => $tag:ident),* $(,)?) => {$(
+        impl sealed::Sealed for $ty {}
+        impl Scalar for $ty {
+            const TAG: Tag = Tag::$tag;
+            const WIDTH: usize = std::mem::size_of::<$ty>();
+            fn from_le(bytes: &[u8]) -> Self { <$ty>::from_le_bytes(bytes.try_into().unwrap()) }
+        }
+    )*};
+}
+
+impl_scalar! {
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64, u128 => U128,
+    i8 => S8, i16 => S16, i32 => S32, i64 => S64, i128 => S128,
+    f32 => F32, f64 => F64,
+}
+
+/// Iterator over a List of one scalar type, created by [`Decoder::scalar_list`].
+///
+/// Tags were checked when the list was opened, so items are infallible.
+#[derive(Debug, Clone)]
+pub struct ScalarListIter<'a, T> {
+    items: std::slice::ChunksExact<'a, u8>,
+    _scalar: std::marker::PhantomData<T>,
+}
+
+impl<T: Scalar> Iterator for ScalarListIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.items.next().map(|item| T::from_le(&item[1..]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<T: Scalar> ExactSizeIterator for ScalarListIter<'_, T> {}
+
+/// Iterator for items within a List that may be truncated.
+///
+/// Created by [`Decoder::try_list`].
+#[derive(Debug)]
+pub struct PartialListIter<'a> {
+    dec: Decoder<'a>,
+    /// Declared body bytes not yet yielded, including bytes not yet received.
+    pending: usize,
+    /// Index of the next item, for path tracking.
+    index: usize,
+}
+
+impl<'a> PartialListIter<'a> {
+    /// Returns a Decoder for the next item, or `None` at the end of the list.
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEnd` if the next item has not fully arrived.
+    /// The iterator is left unchanged, so the error can be treated as "pending".
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<Decoder<'a>>> {
+        if self.pending == 0 {
+            return Ok(None);
+        }
+        let mut probe = self.dec.clone();
+        probe.skip()?;
+        let len = self.dec.remaining() - probe.remaining();
+        self.pending -= len;
+        let mut item = self.dec.read_slice(len)?;
+        item.push_segment(self.index);
+        self.index += 1;
+        Ok(Some(item))
+    }
+
+    /// Returns the number of declared body bytes that have not arrived yet.
+    pub fn missing(&self) -> usize {
+        self.pending - self.dec.remaining()
+    }
+
+    /// Returns `true` if the whole list body is present.
+    pub fn is_complete(&self) -> bool {
+        self.missing() == 0
     }
 }
 
@@ -698,16 +1554,285 @@ pub struct MapIter<'a> {
 
 impl<'a> MapIter<'a> {
     /// Returns `(Key, ValueDecoder)` for the next item, or `None`.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<(&'a str, Decoder<'a>)>> {
         if self.dec.remaining() == 0 {
             return Ok(None);
         }
-        if self.dec.peek_tag()? != Tag::Variant {
-             return Err(Error::InvalidTag(self.dec.peek_tag()? as u8));
+        let tag = self.dec.peek_tag()?;
+        if tag != Tag::Variant {
+             return Err(self.dec.mismatch(tag));
         }
         let (name, val) = self.dec.variant()?;
         Ok(Some((name, val)))
     }
+
+    /// Reads the rest of the map once, indexing its entries by key.
+    ///
+    /// If a key appears more than once, the last entry wins.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidTag` if an entry isn't a variant, as [`MapIter::next`] does.
+    pub fn index(mut self) -> Result<MapIndex<'a>> {
+        let mut entries = Vec::new();
+        while let Some(entry) = self.next()? {
+            entries.push(entry);
+        }
+        // The sort is stable, so reversing first puts the last of equal keys first
+        entries.reverse();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.dedup_by(|later, earlier| later.0 == earlier.0);
+        Ok(MapIndex { entries })
+    }
+}
+
+/// A Map's entries by key, created by [`MapIter::index`].
+///
+/// Borrows the encoded map; values are decoded only when fetched.
+#[derive(Debug, Clone)]
+pub struct MapIndex<'a> {
+    /// Sorted by key, one entry per key.
+    entries: Vec<(&'a str, Decoder<'a>)>,
+}
+
+impl<'a> MapIndex<'a> {
+    /// Returns a Decoder for the value under `key`, or `None` if the map has no such key.
+    pub fn get(&self, key: &str) -> Option<Decoder<'a>> {
+        let i = self.entries.binary_search_by(|(k, _)| (*k).cmp(key)).ok()?;
+        Some(self.entries[i].1.clone())
+    }
+
+    /// Returns the number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map had no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for MapIter<'a> {
+    type Item = Result<(&'a str, Decoder<'a>)>;
+    type IntoIter = MapEntries<'a>;
+
+    fn into_iter(self) -> MapEntries<'a> {
+        MapEntries { inner: self, failed: false }
+    }
+}
+
+/// A [`MapIter`] as a standard `Iterator`, created by `MapIter::into_iter`.
+///
+/// A malformed entry is yielded once as `Err`, after which iteration ends.
+#[derive(Debug)]
+pub struct MapEntries<'a> {
+    inner: MapIter<'a>,
+    failed: bool,
+}
+
+impl<'a> Iterator for MapEntries<'a> {
+    type Item = Result<(&'a str, Decoder<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let entry = self.inner.next().transpose();
+        self.failed = matches!(entry, Some(Err(_)));
+        entry
+    }
+}
+
+impl std::iter::FusedIterator for MapEntries<'_> {}
+
+/// Re-encodes a sequence of items, dropping padding and optionally sorting map entries.
+fn canonicalize_items(dec: Decoder<'_>, out: &mut Vec<u8>, sort: bool) -> Result<()> {
+    let mut items = collect_items(dec)?;
+
+    if sort {
+        items = sort_map_entries(items).map_err(|e| match e {
+            Error::DuplicateKey(_) => Error::NonCanonical("duplicate map key"),
+            e => e,
+        })?;
+    }
+
+    for item in items {
+        canonicalize_item(item, out)?;
+    }
+    Ok(())
+}
+
+/// Splits a sequence into its items, dropping `Tag::Pad` bytes.
+fn collect_items(mut dec: Decoder<'_>) -> Result<Vec<&[u8]>> {
+    let mut items = Vec::new();
+    while dec.remaining() > 0 {
+        if dec.peek_tag()? == Tag::Pad {
+            dec.consume(1)?;
+            continue;
+        }
+        items.push(dec.next_item()?);
+    }
+    Ok(items)
+}
+
+/// Sorts map entries by key (bytewise), the one ordering canonical maps use.
+///
+/// # Errors
+/// Returns `Error::DuplicateKey` if two entries share a key.
+fn sort_map_entries(items: Vec<&[u8]>) -> Result<Vec<&[u8]>> {
+    let mut keyed = Vec::with_capacity(items.len());
+    for item in items {
+        keyed.push((Decoder::new(item).variant()?.0, item));
+    }
+    keyed.sort_by(|a, b| a.0.cmp(b.0));
+    if let Some(w) = keyed.windows(2).find(|w| w[0].0 == w[1].0) {
+        return Err(Error::DuplicateKey(w[0].0.to_string()));
+    }
+    Ok(keyed.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Re-encodes a single item, recursing into containers.
+fn canonicalize_item(item: &[u8], out: &mut Vec<u8>) -> Result<()> {
+    let mut dec = Decoder::new(item);
+    let tag = dec.peek_tag()?;
+    let sort = match tag {
+        Tag::Map => true,
+        Tag::List | Tag::OptionSome | Tag::ResultOk | Tag::ResultErr | Tag::Variant => false,
+        _ => {
+            out.extend_from_slice(item);
+            return Ok(());
+        }
+    };
+
+    let mut body = dec.enter_container(tag)?;
+    out.push(tag as u8);
+    let len_pos = out.len();
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    if tag == Tag::Variant {
+        // The name is metadata, not an item; copy it verbatim
+        out.extend_from_slice(body.next_item()?);
+    }
+    canonicalize_items(body, out, sort)?;
+
+    let body_len = out.len() - len_pos - 4;
+    if body_len > u32::MAX as usize {
+        return Err(Error::BlobTooLarge(body_len));
+    }
+    out[len_pos..len_pos + 4].copy_from_slice(&(body_len as u32).to_le_bytes());
+    Ok(())
+}
+
+/// One open container while walking a buffer in [`validate`] or [`validate_canonical`].
+struct CanonLevel<'a> {
+    dec: Decoder<'a>,
+    scope: Scope,
+    count: usize,
+    last_key: Option<&'a str>,
+}
+
+/// Checks that `bytes` is a sequence of items in canonical form.
+///
+/// Canonical form is what [`Encoder::into_canonical_bytes`] produces:
+///
+/// 1.  No `Tag::Pad` bytes anywhere.
+/// 2.  Map entries are variants, sorted by key with no duplicates.
+/// 3.  Every length header is exact: Option, Result, and Variant bodies hold exactly one item.
+/// 4.  Strings are valid UTF-8 and chars are valid scalar values.
+///
+/// The walk is iterative, so deeply nested input cannot overflow the stack.
+pub fn validate_canonical(bytes: &[u8]) -> Result<()> {
+    check_items(Decoder::new(bytes), true)
+}
+
+/// Checks that `bytes` is a sequence of well-formed items, returning how many there are.
+///
+/// Well-formed is what [`Encoder`] enforces as it writes: valid tags and exact
+/// length headers, one item in every Option, Result, and Variant body, only
+/// variants in maps, and valid UTF-8 and chars. Unlike [`validate_canonical`],
+/// padding and unsorted map keys are accepted. Use it before trusting a buffer
+/// read from disk.
+///
+/// # Errors
+/// Returns `Error::BadItem` for the first malformed item, carrying its index
+/// and the cause: `Error::UnexpectedEnd` if the buffer ends partway through
+/// it, and otherwise what was wrong with it, such as `Error::InvalidTag`.
+pub fn validate(bytes: &[u8]) -> Result<usize> {
+    let mut dec = Decoder::new(bytes);
+    let mut count = 0;
+    while dec.remaining() > 0 {
+        if matches!(dec.peek_tag(), Ok(Tag::Pad)) {
+            dec.consume(1)?;
+            continue;
+        }
+        dec.next_item()
+            .and_then(|item| check_items(Decoder::new(item), false))
+            .map_err(|cause| Error::BadItem { index: count, cause: Box::new(cause) })?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Walks the items in `dec` and everything nested in them, iteratively.
+///
+/// Checks the rules both [`validate`] and [`validate_canonical`] share,
+/// and if `canonical`, rejects padding and unsorted map keys too.
+fn check_items(dec: Decoder<'_>, canonical: bool) -> Result<()> {
+    let mut stack = vec![CanonLevel { dec, scope: Scope::Root, count: 0, last_key: None }];
+
+    while let Some(level) = stack.last_mut() {
+        if level.dec.remaining() == 0 {
+            if matches!(level.scope, Scope::Option | Scope::Result | Scope::Variant) && level.count == 0 {
+                return Err(Error::EmptyAdt(level.scope));
+            }
+            stack.pop();
+            continue;
+        }
+
+        if !canonical && level.dec.peek_tag()? == Tag::Pad {
+            level.dec.consume(1)?;
+            continue;
+        }
+
+        if matches!(level.scope, Scope::Option | Scope::Result | Scope::Variant) && level.count >= 1 {
+            return Err(Error::TooManyItems(level.scope));
+        }
+
+        let tag = level.dec.peek_tag()?;
+        if level.scope == Scope::Map && tag != Tag::Variant {
+            return Err(Error::InvalidMapEntry);
+        }
+        level.count += 1;
+
+        let child = match tag {
+            Tag::Pad => return Err(Error::NonCanonical("padding byte")),
+            Tag::String => { level.dec.str()?; None }
+            Tag::Char => { level.dec.char()?; None }
+            Tag::Decimal128 => { level.dec.decimal()?; None }
+            Tag::Timestamp => { level.dec.timestamp()?; None }
+            Tag::List => Some((level.dec.enter_container(tag)?, Scope::List)),
+            Tag::Map => Some((level.dec.enter_container(tag)?, Scope::Map)),
+            Tag::OptionSome => Some((level.dec.enter_container(tag)?, Scope::Option)),
+            Tag::ResultOk | Tag::ResultErr => Some((level.dec.enter_container(tag)?, Scope::Result)),
+            Tag::Variant => {
+                let mut body = level.dec.enter_container(tag)?;
+                let key = body.str()?;
+                if canonical && level.scope == Scope::Map {
+                    if level.last_key.is_some_and(|last| last >= key) {
+                        return Err(Error::NonCanonical("map keys not sorted"));
+                    }
+                    level.last_key = Some(key);
+                }
+                Some((body, Scope::Variant))
+            }
+            _ => { level.dec.skip()?; None }
+        };
+
+        if let Some((dec, scope)) = child {
+            stack.push(CanonLevel { dec, scope, count: 0, last_key: None });
+        }
+    }
+    Ok(())
 }
 
 /// Encode a value into a neopack byte stream.
@@ -721,6 +1846,19 @@ pub trait Pack {
     }
 }
 
+/// Returns the exact number of bytes `value` packs to, without buffering them.
+///
+/// Counts tags, length headers, and variant names the same way the real encoder would,
+/// so `encoded_len(&v)? == v.pack_to_vec()?.len()`.
+pub fn encoded_len<T: Pack + ?Sized>(value: &T) -> Result<usize> {
+    let mut enc = Encoder::dry_run();
+    value.pack(&mut enc)?;
+    if enc.stack.len() > 1 {
+        return Err(Error::ScopeStillOpen);
+    }
+    Ok(enc.len())
+}
+
 /// Decode a value from a neopack byte stream.
 pub trait Unpack: Sized {
     fn unpack(dec: &mut Decoder<'_>) -> Result<Self>;
@@ -772,6 +1910,13 @@ impl Unpack for u64 {
     fn unpack(dec: &mut Decoder<'_>) -> Result<Self> { dec.u64() }
 }
 
+impl Pack for u128 {
+    fn pack(&self, enc: &mut Encoder) -> Result<()> { enc.u128(*self) }
+}
+impl Unpack for u128 {
+    fn unpack(dec: &mut Decoder<'_>) -> Result<Self> { dec.u128() }
+}
+
 impl Pack for i8 {
     fn pack(&self, enc: &mut Encoder) -> Result<()> { enc.s8(*self) }
 }
@@ -800,6 +1945,13 @@ impl Unpack for i64 {
     fn unpack(dec: &mut Decoder<'_>) -> Result<Self> { dec.s64() }
 }
 
+impl Pack for i128 {
+    fn pack(&self, enc: &mut Encoder) -> Result<()> { enc.s128(*self) }
+}
+impl Unpack for i128 {
+    fn unpack(dec: &mut Decoder<'_>) -> Result<Self> { dec.s128() }
+}
+
 impl Pack for f32 {
     fn pack(&self, enc: &mut Encoder) -> Result<()> { enc.f32(*self) }
 }
@@ -868,12 +2020,7 @@ impl<T: Pack> Pack for Vec<T> {
 }
 impl<T: Unpack> Unpack for Vec<T> {
     fn unpack(dec: &mut Decoder<'_>) -> Result<Self> {
-        let mut list = dec.list()?;
-        let mut items = Vec::new();
-        while let Some(mut item) = list.next() {
-            items.push(T::unpack(&mut item)?);
-        }
-        Ok(items)
+        dec.list()?.map(|mut item| T::unpack(&mut item)).collect()
     }
 }
 
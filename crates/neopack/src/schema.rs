@@ -0,0 +1,156 @@
+//! Checking encoded bytes against a shape described at runtime.
+//!
+//! A [`Schema`] describes what a value should look like without a Rust type
+//! to decode it into, so [`validate_against`] can vet untrusted input, such as
+//! a config file or a message at an API boundary, before anything reads it.
+//!
+//! Mismatches fail with `Error::SchemaMismatch`, whose path points at the
+//! offending value like a JSON pointer: map keys and variant cases by name,
+//! list items by index, and Result payloads as `ok` or `err`. Options add no
+//! segment. Maps may hold keys the schema doesn't mention, as they do when a
+//! newer writer adds fields.
+
+use crate::Decoder;
+use crate::Error;
+use crate::Result;
+use crate::Scope;
+use crate::Tag;
+
+/// The expected shape of one encoded value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Any single well-formed item.
+    Any,
+    /// A scalar or blob with this tag. `Tag::BoolTrue` and `Tag::BoolFalse` each accept either bool.
+    Scalar(Tag),
+    /// A List whose items all match the schema.
+    List(Box<Schema>),
+    /// A Map holding at least these keys, each with a value matching its schema.
+    Map(Vec<(String, Schema)>),
+    /// An Option whose payload, if present, matches the schema.
+    Option(Box<Schema>),
+    /// A Result whose Ok and Err payloads match the respective schemas.
+    Result(Box<Schema>, Box<Schema>),
+    /// A Variant named after one of these cases, with a payload matching that case's schema.
+    Variant(Vec<(String, Schema)>),
+}
+
+/// Checks that `bytes` holds exactly one item, matching `schema` throughout.
+///
+/// # Errors
+/// Returns `Error::SchemaMismatch` naming the path of the first value that
+/// doesn't match, `Error::TrailingBytes` if more follows the item, or the
+/// decoding error for malformed input.
+pub fn validate_against(bytes: &[u8], schema: &Schema) -> Result<()> {
+    let mut dec = Decoder::new(bytes);
+    check(&mut dec, schema, &mut String::new())?;
+    if dec.remaining() != 0 {
+        return Err(Error::TrailingBytes(dec.remaining()));
+    }
+    Ok(())
+}
+
+/// Checks the next item in `dec` against `schema`; `path` leads to it.
+fn check(dec: &mut Decoder<'_>, schema: &Schema, path: &mut String) -> Result<()> {
+    let tag = dec.peek_tag()?;
+    match schema {
+        Schema::Any => {
+            let item = dec.next_item()?;
+            crate::check_items(Decoder::new(item), false)
+        }
+        Schema::Scalar(Tag::BoolTrue | Tag::BoolFalse) => {
+            expect_tag(tag, &[Tag::BoolTrue, Tag::BoolFalse], path)?;
+            dec.skip()
+        }
+        Schema::Scalar(expected) => {
+            expect_tag(tag, &[*expected], path)?;
+            match expected {
+                Tag::String => dec.str().map(drop),
+                Tag::Char => dec.char().map(drop),
+                Tag::Decimal128 => dec.decimal().map(drop),
+                Tag::Timestamp => dec.timestamp().map(drop),
+                Tag::List | Tag::Map | Tag::OptionSome | Tag::ResultOk | Tag::ResultErr | Tag::Variant => {
+                    Err(mismatch(path, format!("{:?} is not a scalar; use its own schema", expected)))
+                }
+                _ => dec.skip(),
+            }
+        }
+        Schema::List(item) => {
+            expect_tag(tag, &[Tag::List], path)?;
+            let mut body = dec.enter_container(Tag::List)?;
+            let mut index = 0;
+            while body.remaining() > 0 {
+                nested(path, &index.to_string(), |path| check(&mut body, item, path))?;
+                index += 1;
+            }
+            Ok(())
+        }
+        Schema::Map(fields) => {
+            expect_tag(tag, &[Tag::Map], path)?;
+            let index = dec.map()?.index()?;
+            for (key, field) in fields {
+                let Some(mut value) = index.get(key) else {
+                    return Err(mismatch(path, format!("missing key {:?}", key)));
+                };
+                nested(path, key, |path| check_payload(&mut value, Scope::Variant, field, path))?;
+            }
+            Ok(())
+        }
+        Schema::Option(some) => {
+            expect_tag(tag, &[Tag::OptionSome, Tag::OptionNone], path)?;
+            match dec.option()? {
+                Some(mut payload) => check_payload(&mut payload, Scope::Option, some, path),
+                None => Ok(()),
+            }
+        }
+        Schema::Result(ok, err) => {
+            expect_tag(tag, &[Tag::ResultOk, Tag::ResultErr], path)?;
+            match dec.result()? {
+                Ok(mut payload) => nested(path, "ok", |path| check_payload(&mut payload, Scope::Result, ok, path)),
+                Err(mut payload) => nested(path, "err", |path| check_payload(&mut payload, Scope::Result, err, path)),
+            }
+        }
+        Schema::Variant(cases) => {
+            expect_tag(tag, &[Tag::Variant], path)?;
+            let (name, mut payload) = dec.variant()?;
+            let Some((_, case)) = cases.iter().find(|(case, _)| case == name) else {
+                return Err(mismatch(path, format!("unexpected case {:?}", name)));
+            };
+            nested(path, name, |path| check_payload(&mut payload, Scope::Variant, case, path))
+        }
+    }
+}
+
+/// Checks the one item in a strict scope's body against `schema`.
+fn check_payload(body: &mut Decoder<'_>, scope: Scope, schema: &Schema, path: &mut String) -> Result<()> {
+    if body.remaining() == 0 {
+        return Err(Error::EmptyAdt(scope));
+    }
+    check(body, schema, path)?;
+    if body.remaining() != 0 {
+        return Err(Error::TooManyItems(scope));
+    }
+    Ok(())
+}
+
+/// Runs `f` with `segment` appended to `path`, then restores it.
+fn nested(path: &mut String, segment: &str, f: impl FnOnce(&mut String) -> Result<()>) -> Result<()> {
+    let len = path.len();
+    path.push('/');
+    path.push_str(segment);
+    let result = f(path);
+    path.truncate(len);
+    result
+}
+
+fn expect_tag(found: Tag, expected: &[Tag], path: &str) -> Result<()> {
+    if expected.contains(&found) {
+        return Ok(());
+    }
+    Err(mismatch(path, format!("expected {:?}, found {:?}", expected[0], found)))
+}
+
+fn mismatch(path: &str, why: String) -> Error {
+    let path = if path.is_empty() { "/".to_string() } else { path.to_string() };
+    Error::SchemaMismatch { path, why }
+}
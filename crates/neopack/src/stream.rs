@@ -0,0 +1,365 @@
+//! Encoding straight into an [`std::io::Write`] sink.
+//!
+//! [`StreamEncoder`] has the same structural rules as [`Encoder`],
+//! but hands each tag and value to the sink as soon as it is written,
+//! so a large message never has to sit in memory whole.
+//!
+//! The catch is container length headers, which precede a body whose size
+//! isn't known until the container closes:
+//!
+//! - On a seekable sink ([`StreamEncoder::new`]) a placeholder is written
+//!   and back-patched on close, exactly like [`Encoder`] does in its buffer.
+//! - On an unseekable sink ([`StreamEncoder::unseekable`]) nothing can be
+//!   patched, so containers must be opened with a declared body length via
+//!   the `*_begin_sized` calls, such as [`StreamEncoder::list_begin_sized`] or
+//!   [`StreamEncoder::variant_begin_sized`] (use [`encoded_len`] to compute it),
+//!   or written whole via [`StreamEncoder::pack`], which buffers just that one item.
+
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+use crate::Encoder;
+use crate::Error;
+use crate::Frame;
+use crate::Pack;
+use crate::Result;
+use crate::Scope;
+use crate::Tag;
+
+/// An open scope, plus the body length promised up front, if any.
+struct StreamFrame {
+    frame: Frame,
+    declared: Option<u32>,
+}
+
+/// Rewrites bytes already handed to a seekable sink.
+struct Patcher<W> {
+    /// Sink position of the first byte this encoder wrote.
+    base: u64,
+    write_at: fn(&mut W, u64, &[u8]) -> std::io::Result<()>,
+}
+
+/// An encoder that writes into `W` as it goes.
+///
+/// See the [module docs](self) for how container lengths are handled.
+pub struct StreamEncoder<W: Write> {
+    sink: W,
+    /// Bottom is always `Scope::Root`.
+    stack: Vec<StreamFrame>,
+    written: usize,
+    /// `None` for unseekable sinks.
+    patcher: Option<Patcher<W>>,
+}
+
+impl<W: Write + Seek> StreamEncoder<W> {
+    /// Creates an encoder that back-patches container lengths in `sink`.
+    ///
+    /// Output starts at the sink's current position.
+    pub fn new(mut sink: W) -> Result<Self> {
+        let base = sink.stream_position().map_err(io_error)?;
+        let patcher = Patcher { base, write_at: write_at::<W> };
+        Ok(Self::with_patcher(sink, Some(patcher)))
+    }
+}
+
+impl<W: Write> StreamEncoder<W> {
+    /// Creates an encoder for a sink that cannot seek.
+    ///
+    /// Only sized containers may be opened; the unsized `*_begin` calls
+    /// fail with `Error::UnsizedScope`.
+    pub fn unseekable(sink: W) -> Self {
+        Self::with_patcher(sink, None)
+    }
+
+    fn with_patcher(sink: W, patcher: Option<Patcher<W>>) -> Self {
+        let mut stack = Vec::with_capacity(8);
+        stack.push(StreamFrame {
+            frame: Frame { start: 0, scope: Scope::Root, count: 0, serial: 0, generation: 0 },
+            declared: None,
+        });
+        Self { sink, stack, written: 0, patcher }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.written
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.written == 0
+    }
+
+    /// Flushes and returns the sink.
+    ///
+    /// # Errors
+    /// Returns `Error::ScopeStillOpen` if the stack depth > 1.
+    pub fn finish(mut self) -> Result<W> {
+        if self.stack.len() > 1 {
+            return Err(Error::ScopeStillOpen);
+        }
+        self.sink.flush().map_err(io_error)?;
+        Ok(self.sink)
+    }
+
+    fn current_frame(&mut self) -> &mut Frame {
+        &mut self.stack.last_mut().unwrap().frame
+    }
+
+    fn check_write(&mut self, tag: Tag) -> Result<()> {
+        self.current_frame().check_write(tag)
+    }
+
+    fn on_item_written(&mut self) {
+        self.current_frame().count += 1;
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> Result<()> {
+        self.sink.write_all(bytes).map_err(io_error)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    fn scalar(&mut self, tag: Tag, bytes: &[u8]) -> Result<()> {
+        self.check_write(tag)?;
+        self.put(&[tag as u8])?;
+        self.put(bytes)?;
+        self.on_item_written();
+        Ok(())
+    }
+
+    fn blob(&mut self, tag: Tag, v: &[u8]) -> Result<()> {
+        let len = v.len();
+        if len > u32::MAX as usize { return Err(Error::BlobTooLarge(len)); }
+        self.check_write(tag)?;
+        self.put(&[tag as u8])?;
+        self.put(&(len as u32).to_le_bytes())?;
+        self.put(v)?;
+        self.on_item_written();
+        Ok(())
+    }
+
+    fn begin_scope(&mut self, tag: Tag, scope: Scope, declared: Option<u32>) -> Result<()> {
+        if declared.is_none() && self.patcher.is_none() {
+            return Err(Error::UnsizedScope);
+        }
+        self.check_write(tag)?;
+
+        self.put(&[tag as u8])?;
+        self.put(&declared.unwrap_or(0).to_le_bytes())?;
+
+        self.stack.push(StreamFrame {
+            frame: Frame { start: self.written, scope, count: 0, serial: 0, generation: 0 },
+            declared,
+        });
+        Ok(())
+    }
+
+    fn end_scope(&mut self, expected: Scope) -> Result<()> {
+        if self.stack.len() <= 1 {
+            return Err(Error::ScopeUnderflow);
+        }
+        self.current_frame().check_close(expected)?;
+
+        let StreamFrame { frame, declared } = self.stack.pop().unwrap();
+        let body_len = self.written - frame.start;
+
+        match declared {
+            Some(declared) if declared as usize != body_len => {
+                return Err(Error::SizeMismatch { declared, actual: body_len });
+            }
+            Some(_) => {}
+            None => {
+                if body_len > u32::MAX as usize {
+                    return Err(Error::BlobTooLarge(body_len));
+                }
+                let patcher = self.patcher.as_ref().ok_or(Error::UnsizedScope)?;
+                let len_pos = patcher.base + (frame.start - 4) as u64;
+                (patcher.write_at)(&mut self.sink, len_pos, &(body_len as u32).to_le_bytes())
+                    .map_err(io_error)?;
+            }
+        }
+
+        self.on_item_written();
+        Ok(())
+    }
+
+    /// Encodes a boolean value.
+    pub fn bool(&mut self, v: bool) -> Result<()> {
+        self.scalar(if v { Tag::BoolTrue } else { Tag::BoolFalse }, &[])
+    }
+
+    /// Encodes an unsigned 8-bit integer.
+    pub fn u8(&mut self, v: u8) -> Result<()> { self.scalar(Tag::U8, &[v]) }
+    /// Encodes a signed 8-bit integer.
+    pub fn s8(&mut self, v: i8) -> Result<()> { self.scalar(Tag::S8, &[v as u8]) }
+    /// Encodes an unsigned 16-bit integer (LE).
+    pub fn u16(&mut self, v: u16) -> Result<()> { self.scalar(Tag::U16, &v.to_le_bytes()) }
+    /// Encodes a signed 16-bit integer (LE).
+    pub fn s16(&mut self, v: i16) -> Result<()> { self.scalar(Tag::S16, &v.to_le_bytes()) }
+    /// Encodes an unsigned 32-bit integer (LE).
+    pub fn u32(&mut self, v: u32) -> Result<()> { self.scalar(Tag::U32, &v.to_le_bytes()) }
+    /// Encodes a signed 32-bit integer (LE).
+    pub fn s32(&mut self, v: i32) -> Result<()> { self.scalar(Tag::S32, &v.to_le_bytes()) }
+    /// Encodes an unsigned 64-bit integer (LE).
+    pub fn u64(&mut self, v: u64) -> Result<()> { self.scalar(Tag::U64, &v.to_le_bytes()) }
+    /// Encodes a signed 64-bit integer (LE).
+    pub fn s64(&mut self, v: i64) -> Result<()> { self.scalar(Tag::S64, &v.to_le_bytes()) }
+    /// Encodes an unsigned 128-bit integer (LE).
+    pub fn u128(&mut self, v: u128) -> Result<()> { self.scalar(Tag::U128, &v.to_le_bytes()) }
+    /// Encodes a signed 128-bit integer (LE).
+    pub fn s128(&mut self, v: i128) -> Result<()> { self.scalar(Tag::S128, &v.to_le_bytes()) }
+    /// Encodes a 32-bit float (LE).
+    pub fn f32(&mut self, v: f32) -> Result<()> { self.scalar(Tag::F32, &v.to_le_bytes()) }
+    /// Encodes a 64-bit float (LE).
+    pub fn f64(&mut self, v: f64) -> Result<()> { self.scalar(Tag::F64, &v.to_le_bytes()) }
+    /// Encodes a Unicode scalar value as u32 (LE).
+    pub fn char(&mut self, v: char) -> Result<()> { self.scalar(Tag::Char, &(v as u32).to_le_bytes()) }
+
+    /// Encodes a fixed-point decimal: `mantissa × 10^(-scale)`.
+    pub fn decimal(&mut self, mantissa: i128, scale: i8) -> Result<()> {
+        let mut body = [0u8; crate::DECIMAL_LEN];
+        body[..16].copy_from_slice(&mantissa.to_le_bytes());
+        body[16] = scale as u8;
+        self.blob(Tag::Decimal128, &body)
+    }
+
+    /// Encodes a wall-clock instant: seconds since the unix epoch plus nanos.
+    pub fn timestamp(&mut self, secs: i64, nanos: u32) -> Result<()> {
+        if nanos > crate::MAX_TIMESTAMP_NANOS {
+            return Err(Error::InvalidTimestamp);
+        }
+        let mut body = [0u8; 12];
+        body[..8].copy_from_slice(&secs.to_le_bytes());
+        body[8..].copy_from_slice(&nanos.to_le_bytes());
+        self.scalar(Tag::Timestamp, &body)
+    }
+
+    /// Encodes Unit `()`.
+    pub fn unit(&mut self) -> Result<()> { self.scalar(Tag::Unit, &[]) }
+    /// Encodes `Option::None`.
+    pub fn option_none(&mut self) -> Result<()> { self.scalar(Tag::OptionNone, &[]) }
+
+    /// Encodes a UTF-8 string blob.
+    pub fn str(&mut self, v: &str) -> Result<()> { self.blob(Tag::String, v.as_bytes()) }
+    /// Encodes a raw byte blob.
+    pub fn bytes(&mut self, v: &[u8]) -> Result<()> { self.blob(Tag::Bytes, v) }
+
+    /// Encodes a byte blob with a u64 length, as [`Encoder::big_bytes`] does.
+    pub fn big_bytes(&mut self, v: &[u8]) -> Result<()> {
+        self.scalar(Tag::BigBytes, &(v.len() as u64).to_le_bytes())?;
+        self.put(v)
+    }
+
+    /// Writes a pre-encoded neopack item, as [`Encoder::append_raw`] does.
+    pub fn append_raw(&mut self, v: &[u8]) -> Result<()> {
+        let mut probe = crate::Decoder::new(v);
+        let tag = probe.peek_tag()?;
+        probe.skip()?;
+        if probe.remaining() != 0 {
+            return Err(Error::TrailingBytes(probe.remaining()));
+        }
+        self.check_write(tag)?;
+        self.put(v)?;
+        self.on_item_written();
+        Ok(())
+    }
+
+    /// Encodes `value` in memory, then writes it as one item.
+    ///
+    /// Only this item is buffered, so this is how nested values of unknown
+    /// size go into an unseekable sink.
+    pub fn pack<T: Pack + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let mut enc = Encoder::new();
+        value.pack(&mut enc)?;
+        self.append_raw(&enc.into_bytes()?)
+    }
+
+    /// Begins a List container, back-patching its length on close.
+    pub fn list_begin(&mut self) -> Result<()> { self.begin_scope(Tag::List, Scope::List, None) }
+    /// Begins a List container whose body will be exactly `byte_len` bytes.
+    ///
+    /// Works on any sink. `list_end` fails with `Error::SizeMismatch` if the
+    /// body written differs from `byte_len`.
+    pub fn list_begin_sized(&mut self, byte_len: u32) -> Result<()> { self.begin_scope(Tag::List, Scope::List, Some(byte_len)) }
+    /// Ends a List container.
+    pub fn list_end(&mut self) -> Result<()> { self.end_scope(Scope::List) }
+
+    /// Begins a Map container, back-patching its length on close.
+    pub fn map_begin(&mut self) -> Result<()> { self.begin_scope(Tag::Map, Scope::Map, None) }
+    /// Begins a Map container whose body will be exactly `byte_len` bytes.
+    pub fn map_begin_sized(&mut self, byte_len: u32) -> Result<()> { self.begin_scope(Tag::Map, Scope::Map, Some(byte_len)) }
+    /// Ends a Map container.
+    pub fn map_end(&mut self) -> Result<()> { self.end_scope(Scope::Map) }
+
+    /// Encodes a Map whose entries `write` puts into an in-memory [`Encoder`],
+    /// sorted by key as [`Encoder::map_sorted_end`] sorts them.
+    ///
+    /// Sorting needs every entry at once, so like [`StreamEncoder::pack`],
+    /// this buffers just the one map. Works on any sink.
+    ///
+    /// # Errors
+    /// Returns `Error::DuplicateKey` if two entries share a key; nothing is written.
+    pub fn map_sorted(&mut self, write: impl FnOnce(&mut Encoder) -> Result<()>) -> Result<()> {
+        let mut enc = Encoder::new();
+        enc.map_sorted_begin()?;
+        write(&mut enc)?;
+        enc.map_sorted_end()?;
+        self.append_raw(&enc.into_bytes()?)
+    }
+
+    /// Begins an `Option::Some` container.
+    pub fn option_some_begin(&mut self) -> Result<()> { self.begin_scope(Tag::OptionSome, Scope::Option, None) }
+    /// Begins an `Option::Some` container whose value will be exactly `byte_len` bytes.
+    pub fn option_some_begin_sized(&mut self, byte_len: u32) -> Result<()> { self.begin_scope(Tag::OptionSome, Scope::Option, Some(byte_len)) }
+    /// Ends an `Option::Some` container.
+    pub fn option_some_end(&mut self) -> Result<()> { self.end_scope(Scope::Option) }
+
+    /// Begins a `Result::Ok` container.
+    pub fn result_ok_begin(&mut self) -> Result<()> { self.begin_scope(Tag::ResultOk, Scope::Result, None) }
+    /// Begins a `Result::Ok` container whose value will be exactly `byte_len` bytes.
+    pub fn result_ok_begin_sized(&mut self, byte_len: u32) -> Result<()> { self.begin_scope(Tag::ResultOk, Scope::Result, Some(byte_len)) }
+    /// Ends a `Result::Ok` container.
+    pub fn result_ok_end(&mut self) -> Result<()> { self.end_scope(Scope::Result) }
+
+    /// Begins a `Result::Err` container.
+    pub fn result_err_begin(&mut self) -> Result<()> { self.begin_scope(Tag::ResultErr, Scope::Result, None) }
+    /// Begins a `Result::Err` container whose value will be exactly `byte_len` bytes.
+    pub fn result_err_begin_sized(&mut self, byte_len: u32) -> Result<()> { self.begin_scope(Tag::ResultErr, Scope::Result, Some(byte_len)) }
+    /// Ends a `Result::Err` container.
+    pub fn result_err_end(&mut self) -> Result<()> { self.end_scope(Scope::Result) }
+
+    /// Begins a Variant (Named Payload); see [`Encoder::variant_begin`].
+    pub fn variant_begin(&mut self, name: &str) -> Result<()> {
+        self.begin_scope(Tag::Variant, Scope::Variant, None)?;
+        self.str(name)?;
+        self.current_frame().count = 0;
+        Ok(())
+    }
+    /// Begins a Variant whose payload will be exactly `byte_len` bytes.
+    ///
+    /// `byte_len` covers the payload only; the name's length is added here.
+    pub fn variant_begin_sized(&mut self, name: &str, byte_len: u32) -> Result<()> {
+        let body_len = 1 + 4 + name.len() + byte_len as usize;
+        let body_len = u32::try_from(body_len).map_err(|_| Error::BlobTooLarge(body_len))?;
+        self.begin_scope(Tag::Variant, Scope::Variant, Some(body_len))?;
+        self.str(name)?;
+        self.current_frame().count = 0;
+        Ok(())
+    }
+    /// Ends a Variant.
+    pub fn variant_end(&mut self) -> Result<()> { self.end_scope(Scope::Variant) }
+}
+
+fn write_at<W: Write + Seek>(sink: &mut W, pos: u64, bytes: &[u8]) -> std::io::Result<()> {
+    let here = sink.stream_position()?;
+    sink.seek(SeekFrom::Start(pos))?;
+    sink.write_all(bytes)?;
+    sink.seek(SeekFrom::Start(here))?;
+    Ok(())
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::Io(e.to_string())
+}
@@ -14,8 +14,8 @@ fn test_bool_roundtrip() -> Result<()> {
     let bytes = enc.into_bytes()?;
     let mut dec = Decoder::new(&bytes);
 
-    assert_eq!(dec.bool()?, true);
-    assert_eq!(dec.bool()?, false);
+    assert!(dec.bool()?);
+    assert!(!dec.bool()?);
     assert_eq!(dec.remaining(), 0);
     Ok(())
 }
@@ -93,6 +93,7 @@ fn test_u64_s64_roundtrip() -> Result<()> {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_floats_roundtrip() -> Result<()> {
     let mut enc = Encoder::new();
     enc.f32(0.0)?;
@@ -124,6 +125,167 @@ fn test_char_roundtrip() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_decimal_roundtrip() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.decimal(i128::MIN, -128)?;
+    enc.decimal(i128::MAX, 127)?;
+    enc.decimal(12345, 2)?;
+
+    let bytes = enc.into_bytes()?;
+    assert_eq!(bytes.len(), 3 * (1 + 4 + DECIMAL_LEN));
+    let mut dec = Decoder::new(&bytes);
+
+    assert_eq!(dec.decimal()?, (i128::MIN, -128));
+    assert_eq!(dec.decimal()?, (i128::MAX, 127));
+    assert_eq!(dec.decimal()?, (12345, 2));
+    assert_eq!(dec.remaining(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_decimal_skip() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.decimal(-1, 4)?;
+    enc.u8(7)?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    dec.skip()?;
+    assert_eq!(dec.u8()?, 7);
+
+    // The length header is what a skipping decoder relies on
+    assert_eq!(bytes[1..5], (DECIMAL_LEN as u32).to_le_bytes());
+    Ok(())
+}
+
+#[test]
+fn test_decimal_wrong_length_rejected() {
+    let mut bytes = vec![Tag::Decimal128 as u8];
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&[0; 16]);
+    assert!(matches!(
+        Decoder::new(&bytes).decimal(),
+        Err(Error::SizeMismatch { declared: 16, actual: DECIMAL_LEN })
+    ));
+}
+
+#[test]
+fn test_128_bit_roundtrip() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.u128(u128::MAX)?;
+    enc.s128(i128::MIN)?;
+    enc.u8(7)?;
+
+    let bytes = enc.into_bytes()?;
+    assert_eq!(bytes.len(), 2 * 17 + 2);
+    let mut dec = Decoder::new(&bytes);
+
+    assert_eq!(dec.u128()?, u128::MAX);
+    assert_eq!(dec.s128()?, i128::MIN);
+    assert_eq!(dec.u8()?, 7);
+
+    let mut dec = Decoder::new(&bytes);
+    dec.skip()?;
+    dec.skip()?;
+    assert_eq!(dec.u8()?, 7);
+    Ok(())
+}
+
+#[test]
+fn test_next_item_bytes_frames_values() -> Result<()> {
+    let mut first = Encoder::new();
+    first.u32(7)?;
+    let mut second = Encoder::new();
+    second.list_begin()?;
+    second.str("a")?;
+    second.map_begin()?;
+    second.map_end()?;
+    second.list_end()?;
+    let mut third = Encoder::new();
+    third.str("tail")?;
+    let parts = [first.into_bytes()?, second.into_bytes()?, third.into_bytes()?];
+    let bytes = parts.concat();
+
+    let mut dec = Decoder::new(&bytes);
+    for part in &parts {
+        assert_eq!(dec.next_item_bytes()?, Some(&part[..]));
+    }
+    assert_eq!(dec.next_item_bytes()?, None);
+    assert_eq!(dec.next_item_bytes()?, None);
+
+    // Each slice decodes on its own
+    assert_eq!(Decoder::new(&parts[2]).str()?, "tail");
+
+    // Truncation is an error, not a clean end, and leaves the decoder in place
+    let truncated = &bytes[..bytes.len() - 2];
+    let mut dec = Decoder::new(truncated);
+    dec.next_item_bytes()?;
+    dec.next_item_bytes()?;
+    let before = dec.remaining();
+    assert!(matches!(dec.next_item_bytes(), Err(Error::UnexpectedEnd)));
+    assert_eq!(dec.remaining(), before);
+    Ok(())
+}
+
+#[test]
+fn test_timestamp_roundtrip() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.timestamp(1_700_000_000, 123_456_789)?;
+    enc.timestamp(-1, 999_999_999)?;
+    enc.timestamp(i64::MIN, 0)?;
+
+    let bytes = enc.into_bytes()?;
+    assert_eq!(bytes.len(), 3 * 13);
+    let mut dec = Decoder::new(&bytes);
+
+    assert_eq!(dec.timestamp()?, (1_700_000_000, 123_456_789));
+    assert_eq!(dec.timestamp()?, (-1, 999_999_999));
+    assert_eq!(dec.timestamp()?, (i64::MIN, 0));
+    assert_eq!(dec.remaining(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_timestamp_skip() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.timestamp(-86_400, 5)?;
+    enc.u8(7)?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    dec.skip()?;
+    assert_eq!(dec.u8()?, 7);
+    Ok(())
+}
+
+#[test]
+fn test_err_timestamp_nanos_out_of_range() -> Result<()> {
+    let mut enc = Encoder::new();
+    assert!(matches!(enc.timestamp(0, 1_000_000_000), Err(Error::InvalidTimestamp)));
+    enc.u8(1)?;
+    assert_eq!(enc.into_bytes()?.len(), 2, "rejected timestamp wrote nothing");
+
+    // Hand-built bytes with too many nanos are refused on decode too
+    let mut bytes = vec![Tag::Timestamp as u8];
+    bytes.extend_from_slice(&0i64.to_le_bytes());
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(Decoder::new(&bytes).timestamp(), Err(Error::InvalidTimestamp)));
+    Ok(())
+}
+
+#[test]
+fn test_tag_bytes_are_unique() {
+    let mut seen = std::collections::HashSet::new();
+    for b in 0..=u8::MAX {
+        if let Some(tag) = Tag::from_u8(b) {
+            assert_eq!(tag as u8, b);
+            assert!(seen.insert(tag as u8));
+        }
+    }
+    assert_eq!(Tag::from_u8(Tag::Decimal128 as u8), Some(Tag::Decimal128));
+}
+
 #[test]
 fn test_unit_and_none() -> Result<()> {
     let mut enc = Encoder::new();
@@ -173,6 +335,36 @@ fn test_bytes() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_big_bytes() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.big_bytes(&[1, 2, 3])?;
+    enc.big_bytes(&[])?;
+    enc.u8(9)?;
+
+    let bytes = enc.into_bytes()?;
+    assert_eq!(&bytes[..9], &[Tag::BigBytes as u8, 3, 0, 0, 0, 0, 0, 0, 0]);
+    let mut dec = Decoder::new(&bytes);
+
+    assert_eq!(dec.big_bytes()?, &[1, 2, 3]);
+    dec.skip()?;
+    assert_eq!(dec.u8()?, 9);
+    assert!(matches!(Decoder::new(&bytes).bytes(), Err(Error::InvalidTag(0x12))));
+    Ok(())
+}
+
+#[test]
+fn test_big_bytes_truncated_body() {
+    // A header claiming a body past u32::MAX, with only a few bytes behind it
+    let mut bytes = vec![Tag::BigBytes as u8];
+    bytes.extend_from_slice(&5_000_000_000u64.to_le_bytes());
+    bytes.extend_from_slice(&[1, 2, 3]);
+
+    assert!(matches!(Decoder::new(&bytes).skip(), Err(Error::UnexpectedEnd)));
+    assert!(matches!(Decoder::new(&bytes).big_bytes(), Err(Error::UnexpectedEnd)));
+    assert!(matches!(Decoder::new(&bytes[..5]).skip(), Err(Error::UnexpectedEnd)));
+}
+
 // ============================================================================
 //  CONTAINER TESTS (Happy Path)
 // ============================================================================
@@ -260,6 +452,176 @@ fn test_map_empty() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_list_iterator_adapters() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    for n in [3, 1, 4, 1, 5] {
+        enc.u32(n)?;
+    }
+    enc.list_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    let nums = dec.list()?.map(|mut d| d.u32().unwrap()).collect::<Vec<_>>();
+    assert_eq!(nums, vec![3, 1, 4, 1, 5]);
+
+    let mut dec = Decoder::new(&bytes);
+    let mut total = 0;
+    for mut item in dec.list()? {
+        total += item.u32()?;
+    }
+    assert_eq!(total, 14);
+    Ok(())
+}
+
+#[test]
+fn test_list_iterator_stops_at_malformed_item() -> Result<()> {
+    // A whole u32, then a u32 cut short
+    let bytes = [Tag::List as u8, 7, 0, 0, 0, Tag::U32 as u8, 9, 0, 0, 0, Tag::U32 as u8, 2];
+    let mut dec = Decoder::new(&bytes);
+    let mut list = dec.list()?;
+
+    // Items borrow the input rather than copying it
+    let first = list.next().unwrap();
+    assert_eq!(first.remaining(), 5);
+    assert!(std::ptr::eq(first.clone().next_item_bytes()?.unwrap().as_ptr(), &bytes[5]));
+
+    assert!(list.next().is_none());
+    assert!(list.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_map_index_lookup() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    for i in 0..50u32 {
+        enc.variant_begin(&format!("key{}", i))?;
+        enc.u32(i * 10)?;
+        enc.variant_end()?;
+    }
+    enc.map_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let index = Decoder::new(&bytes).map()?.index()?;
+    assert_eq!(index.len(), 50);
+    assert_eq!(index.get("key0").unwrap().u32()?, 0);
+    assert_eq!(index.get("key37").unwrap().u32()?, 370);
+    assert!(index.get("key50").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_map_index_last_duplicate_wins() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    for (key, n) in [("a", 1), ("b", 2), ("a", 3), ("a", 4)] {
+        enc.variant_begin(key)?;
+        enc.u32(n)?;
+        enc.variant_end()?;
+    }
+    enc.map_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let index = Decoder::new(&bytes).map()?.index()?;
+    assert_eq!(index.len(), 2);
+    assert_eq!(index.get("a").unwrap().u32()?, 4);
+    assert_eq!(index.get("b").unwrap().u32()?, 2);
+
+    // A list re-tagged as a map, so its entry isn't a variant
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(7)?;
+    enc.list_end()?;
+    let mut bytes = enc.into_bytes()?;
+    bytes[0] = Tag::Map as u8;
+    assert!(matches!(Decoder::new(&bytes).map()?.index(), Err(Error::InvalidTag(t)) if t == Tag::U32 as u8));
+    Ok(())
+}
+
+#[test]
+fn test_map_entries_iterator() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    for (key, n) in [("a", 1), ("b", 2)] {
+        enc.variant_begin(key)?;
+        enc.u32(n)?;
+        enc.variant_end()?;
+    }
+    enc.map_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    let entries = dec.map()?
+        .into_iter()
+        .map(|entry| entry.map(|(key, mut val)| (key, val.u32().unwrap())))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(entries, vec![("a", 1), ("b", 2)]);
+
+    // A list re-tagged as a map, so its second entry isn't a variant
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+        enc.variant_begin("a")?;
+            enc.u32(1)?;
+        enc.variant_end()?;
+        enc.u32(7)?;
+    enc.list_end()?;
+    let mut bytes = enc.into_bytes()?;
+    bytes[0] = Tag::Map as u8;
+
+    // The bad entry is reported once, then iteration ends
+    let mut dec = Decoder::new(&bytes);
+    let mut entries = dec.map()?.into_iter();
+    assert_eq!(entries.next().unwrap()?.0, "a");
+    assert!(matches!(entries.next(), Some(Err(Error::InvalidTag(_)))));
+    assert!(entries.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_crc_trailer_roundtrip() -> Result<()> {
+    let encode = || -> Result<Encoder> {
+        let mut enc = Encoder::new();
+        enc.map_begin()?;
+        enc.variant_begin("name")?;
+        enc.str("neopack")?;
+        enc.variant_end()?;
+        enc.map_end()?;
+        Ok(enc)
+    };
+    let plain = encode()?.into_bytes()?;
+    let bytes = encode()?.into_bytes_with_crc()?;
+    assert_eq!(bytes.len(), plain.len() + CRC_TRAILER_LEN);
+
+    let payload = Decoder::verify_crc(&bytes)?;
+    assert_eq!(payload, plain.as_slice());
+    let mut map = Decoder::new(payload).map()?;
+    assert_eq!(map.next()?.map(|(k, mut v)| (k, v.str().unwrap())), Some(("name", "neopack")));
+
+    // The check value for CRC32C, so the trailer matches other implementations
+    assert_eq!(crate::crc32c(b"123456789"), 0xE306_9283);
+    Ok(())
+}
+
+#[test]
+fn test_crc_detects_flipped_byte() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(7)?;
+    enc.str("payload")?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes_with_crc()?;
+
+    for i in 0..bytes.len() {
+        let mut corrupt = bytes.clone();
+        corrupt[i] ^= 0x01;
+        assert!(matches!(Decoder::verify_crc(&corrupt), Err(Error::ChecksumMismatch { .. })), "byte {}", i);
+    }
+    assert!(matches!(Decoder::verify_crc(&bytes[..3]), Err(Error::UnexpectedEnd)));
+    Ok(())
+}
+
 #[test]
 fn test_option_some_workflow() -> Result<()> {
     let mut enc = Encoder::new();
@@ -326,6 +688,69 @@ fn test_variant_workflow() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_peek_header_leaves_cursor() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(1)?;
+    enc.u32(2)?;
+    enc.list_end()?;
+    enc.u32(3)?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+
+    assert_eq!(dec.peek_header()?, Header { tag: Tag::List, byte_len: Some(10) });
+    let mut list = dec.list()?;
+    assert_eq!(list.next().unwrap().u32()?, 1);
+    assert_eq!(list.next().unwrap().u32()?, 2);
+
+    assert_eq!(dec.peek_header()?, Header { tag: Tag::U32, byte_len: None });
+    assert_eq!(dec.u32()?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_value_spans_are_contiguous() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.u32(7)?;
+    enc.str("hey")?;
+    enc.list_begin()?;
+    enc.u8(1)?;
+    enc.u8(2)?;
+    enc.list_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    assert_eq!(dec.value_span(), None);
+
+    dec.u32()?;
+    assert_eq!(dec.value_span(), Some(0..5));
+    dec.str()?;
+    assert_eq!(dec.value_span(), Some(5..13));
+    let mut list = dec.list()?;
+    assert_eq!(dec.value_span(), Some(13..bytes.len()));
+
+    // Items inside a container count from the same origin
+    list.next().unwrap();
+    let mut second = list.next().unwrap();
+    assert_eq!(second.position(), 20);
+    second.u8()?;
+    assert_eq!(second.value_span(), Some(20..22));
+    Ok(())
+}
+
+#[test]
+fn test_peek_header_truncated() {
+    let bytes = [Tag::List as u8, 10, 0];
+    assert!(matches!(Decoder::new(&bytes).peek_header(), Err(Error::UnexpectedEnd)));
+    assert!(matches!(Decoder::new(&[]).peek_header(), Err(Error::UnexpectedEnd)));
+
+    // Only the header needs to be present
+    let bytes = [Tag::String as u8, 10, 0, 0, 0, b'a'];
+    assert_eq!(Decoder::new(&bytes).peek_header().unwrap().byte_len, Some(10));
+}
+
 // ============================================================================
 //  COMPLEX INTEGRATION
 // ============================================================================
@@ -392,52 +817,721 @@ fn test_deeply_nested_mixed() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_append_raw_variant_into_map() -> Result<()> {
+    let mut frag = Encoder::new();
+    frag.variant_begin("b")?;
+    frag.list_begin()?;
+    frag.u8(1)?;
+    frag.u8(2)?;
+    frag.list_end()?;
+    frag.variant_end()?;
+    let frag = frag.into_bytes()?;
+
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    enc.variant_begin("a")?;
+    enc.str("x")?;
+    enc.variant_end()?;
+    enc.append_raw(&frag)?;
+    enc.map_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut dec = Decoder::new(&bytes);
+    let mut map = dec.map()?;
+    let (k, mut v) = map.next()?.unwrap();
+    assert_eq!((k, v.str()?), ("a", "x"));
+    let (k, mut v) = map.next()?.unwrap();
+    assert_eq!(k, "b");
+    let mut list = v.list()?;
+    assert_eq!(list.next().unwrap().u8()?, 1);
+    assert_eq!(list.next().unwrap().u8()?, 2);
+    assert!(map.next()?.is_none());
+    Ok(())
+}
+
 // ============================================================================
-//  ENCODER STRICTNESS FAILURE MODES
+//  STREAMING DECODE
 // ============================================================================
 
 #[test]
-fn test_strict_result_empty() {
-    let mut enc = Encoder::new();
-    enc.result_ok_begin().unwrap();
-    // Error: Closed without writing a value
-    match enc.result_ok_end() {
-        Err(Error::EmptyAdt(Scope::Result)) => {},
-        _ => panic!("Expected EmptyAdt error"),
+fn test_try_list_complete() -> Result<()> {
+    let bytes = vec![1u32, 2, 3].pack_to_vec()?;
+    let mut dec = Decoder::new(&bytes);
+    let mut list = dec.try_list()?;
+    assert!(list.is_complete());
+
+    let mut seen = Vec::new();
+    while let Some(mut item) = list.next()? {
+        seen.push(item.u32()?);
     }
+    assert_eq!(seen, vec![1, 2, 3]);
+    assert_eq!(dec.remaining(), 0);
+    Ok(())
 }
 
 #[test]
-fn test_strict_result_too_many() {
-    let mut enc = Encoder::new();
-    enc.result_ok_begin().unwrap();
-    enc.u32(1).unwrap();
-    // Error: Trying to write second value
-    match enc.u32(2) {
-        Err(Error::TooManyItems(Scope::Result)) => {},
-        _ => panic!("Expected TooManyItems error"),
+fn test_try_list_byte_by_byte() -> Result<()> {
+    let items = vec!["a".to_string(), "bc".to_string(), "def".to_string()];
+    let bytes = items.pack_to_vec()?;
+
+    let mut last_count = 0;
+    for end in 0..=bytes.len() {
+        let mut dec = Decoder::new(&bytes[..end]);
+        let mut list = match dec.try_list() {
+            Ok(list) => list,
+            Err(Error::UnexpectedEnd) => { assert!(end < 5); continue; }
+            Err(e) => return Err(e),
+        };
+
+        let mut count = 0;
+        let finished = loop {
+            match list.next() {
+                Ok(Some(mut item)) => { assert_eq!(item.str()?, items[count]); count += 1; }
+                Ok(None) => break true,
+                Err(Error::UnexpectedEnd) => break false,
+                Err(e) => return Err(e),
+            }
+        };
+
+        // Only the complete buffer may report the end of the list
+        assert_eq!(finished, end == bytes.len());
+        assert_eq!(list.is_complete(), end == bytes.len());
+        assert!(count >= last_count);
+        last_count = count;
     }
+    assert_eq!(last_count, items.len());
+    Ok(())
 }
 
 #[test]
-fn test_strict_option_empty() {
-    let mut enc = Encoder::new();
-    enc.option_some_begin().unwrap();
-    match enc.option_some_end() {
-        Err(Error::EmptyAdt(Scope::Option)) => {},
-        _ => panic!("Expected EmptyAdt"),
+fn test_try_list_pending_is_retryable() -> Result<()> {
+    let bytes = vec![10u32, 20].pack_to_vec()?;
+    let truncated = &bytes[..bytes.len() - 2];
+    let mut dec = Decoder::new(truncated);
+    let mut list = dec.try_list()?;
+
+    assert_eq!(list.next()?.unwrap().u32()?, 10);
+    assert!(matches!(list.next(), Err(Error::UnexpectedEnd)));
+    assert!(matches!(list.next(), Err(Error::UnexpectedEnd)));
+    assert_eq!(list.missing(), 2);
+    Ok(())
+}
+
+// ============================================================================
+//  CANONICAL FORM
+// ============================================================================
+
+fn encode_string_map(enc: &mut Encoder, map: &std::collections::HashMap<String, u32>) -> Result<()> {
+    enc.map_begin()?;
+    for (k, v) in map {
+        enc.variant_begin(k)?;
+        enc.u32(*v)?;
+        enc.variant_end()?;
     }
+    enc.map_end()
 }
 
 #[test]
-fn test_strict_option_too_many() {
-    let mut enc = Encoder::new();
-    enc.option_some_begin().unwrap();
-    enc.u32(1).unwrap();
-    match enc.str("no") {
-        Err(Error::TooManyItems(Scope::Option)) => {},
-        _ => panic!("Expected TooManyItems"),
-    }
+fn test_canonical_hashmap_insertion_order() -> Result<()> {
+    let keys = ["delta", "alpha", "charlie", "bravo", "echo", "foxtrot", "golf"];
+
+    let mut forward = std::collections::HashMap::new();
+    for (i, k) in keys.iter().enumerate() { forward.insert(k.to_string(), i as u32); }
+    let mut backward = std::collections::HashMap::new();
+    for (i, k) in keys.iter().enumerate().rev() { backward.insert(k.to_string(), i as u32); }
+
+    let mut a = Encoder::new();
+    encode_string_map(&mut a, &forward)?;
+    let mut b = Encoder::new();
+    encode_string_map(&mut b, &backward)?;
+
+    let a = a.into_canonical_bytes()?;
+    let b = b.into_canonical_bytes()?;
+    assert_eq!(a, b);
+    validate_canonical(&a)?;
+
+    let mut map = Decoder::new(&a).map()?;
+    let mut seen = Vec::new();
+    while let Some((key, _)) = map.next()? { seen.push(key); }
+    let mut sorted = keys.to_vec();
+    sorted.sort();
+    assert_eq!(seen, sorted);
+    Ok(())
+}
+
+#[test]
+fn test_canonical_nested_maps_in_list() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+        enc.map_begin()?;
+            enc.variant_begin("z")?; enc.u8(1)?; enc.variant_end()?;
+            enc.variant_begin("a")?;
+                enc.map_begin()?;
+                    enc.variant_begin("y")?; enc.unit()?; enc.variant_end()?;
+                    enc.variant_begin("x")?; enc.unit()?; enc.variant_end()?;
+                enc.map_end()?;
+            enc.variant_end()?;
+        enc.map_end()?;
+    enc.list_end()?;
+
+    assert!(matches!(validate_canonical(enc.as_bytes()?), Err(Error::NonCanonical(_))));
+
+    let bytes = enc.into_canonical_bytes()?;
+    validate_canonical(&bytes)?;
+
+    let mut list = Decoder::new(&bytes).list()?;
+    let mut outer = list.next().unwrap().map()?;
+    let (k, mut v) = outer.next()?.unwrap();
+    assert_eq!(k, "a");
+    let mut inner = v.map()?;
+    assert_eq!(inner.next()?.unwrap().0, "x");
+    assert_eq!(inner.next()?.unwrap().0, "y");
+    assert_eq!(outer.next()?.unwrap().0, "z");
+    Ok(())
+}
+
+#[test]
+fn test_canonical_drops_padding() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.append_raw(&[Tag::Pad as u8])?;
+    enc.u8(7)?;
+    enc.list_end()?;
+
+    let padded = enc.as_bytes()?.to_vec();
+    assert!(matches!(validate_canonical(&padded), Err(Error::NonCanonical(_))));
+
+    let bytes = enc.into_canonical_bytes()?;
+    validate_canonical(&bytes)?;
+    assert_eq!(bytes, vec![7u8].pack_to_vec()?);
+    Ok(())
+}
+
+#[test]
+fn test_canonical_rejects_duplicate_keys() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    enc.variant_begin("k")?; enc.u8(1)?; enc.variant_end()?;
+    enc.variant_begin("k")?; enc.u8(2)?; enc.variant_end()?;
+    enc.map_end()?;
+
+    let bytes = enc.as_bytes()?.to_vec();
+    assert!(matches!(validate_canonical(&bytes), Err(Error::NonCanonical(_))));
+    assert!(matches!(enc.into_canonical_bytes(), Err(Error::NonCanonical(_))));
+    Ok(())
+}
+
+#[test]
+fn test_map_sorted_orders_entries() -> Result<()> {
+    let keys = ["delta", "alpha", "Charlie", "bravo", "é", "echo"];
+
+    let mut enc = Encoder::new();
+    enc.map_sorted_begin()?;
+    for (i, k) in keys.iter().enumerate() {
+        enc.variant_begin(k)?; enc.u32(i as u32)?; enc.variant_end()?;
+    }
+    enc.map_sorted_end()?;
+    let bytes = enc.into_bytes()?;
+    validate_canonical(&bytes)?;
+
+    let mut map = Decoder::new(&bytes).map()?;
+    let mut seen = Vec::new();
+    while let Some((key, mut val)) = map.next()? { seen.push((key, val.u32()?)); }
+    assert_eq!(seen, vec![("Charlie", 2), ("alpha", 1), ("bravo", 3), ("delta", 0), ("echo", 5), ("é", 4)]);
+    Ok(())
+}
+
+#[test]
+fn test_map_sorted_rejects_duplicate_keys() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_sorted_begin()?;
+    enc.variant_begin("k")?; enc.u8(1)?; enc.variant_end()?;
+    enc.variant_begin("j")?; enc.u8(2)?; enc.variant_end()?;
+    enc.variant_begin("k")?; enc.u8(3)?; enc.variant_end()?;
+
+    assert!(matches!(enc.map_sorted_end(), Err(Error::DuplicateKey(key)) if key == "k"));
+    assert!(matches!(enc.as_bytes(), Err(Error::ScopeStillOpen)));
+    Ok(())
+}
+
+#[test]
+fn test_canonical_rejects_loose_length() {
+    // Option::Some whose length header covers two items
+    let bytes = [Tag::OptionSome as u8, 2, 0, 0, 0, Tag::Unit as u8, Tag::Unit as u8];
+    assert!(matches!(validate_canonical(&bytes), Err(Error::TooManyItems(Scope::Option))));
+}
+
+// ============================================================================
+//  VALIDATION
+// ============================================================================
+
+/// Three top-level items: a u32, an unsorted map, and a string, with padding between.
+fn loose_buffer() -> Result<Vec<u8>> {
+    let mut enc = Encoder::new();
+    enc.u32(1)?;
+    enc.map_begin()?;
+    enc.variant_begin("b")?; enc.unit()?; enc.variant_end()?;
+    enc.variant_begin("a")?; enc.unit()?; enc.variant_end()?;
+    enc.map_end()?;
+    enc.str("last")?;
+    let mut bytes = enc.into_bytes()?;
+    bytes.insert(5, Tag::Pad as u8);
+    Ok(bytes)
+}
+
+#[test]
+fn test_validate_counts_items() -> Result<()> {
+    let bytes = loose_buffer()?;
+    assert_eq!(validate(&bytes)?, 3);
+    assert!(validate_canonical(&bytes).is_err());
+    assert_eq!(validate(&[])?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_validate_truncated() -> Result<()> {
+    let bytes = loose_buffer()?;
+    match validate(&bytes[..bytes.len() - 1]) {
+        Err(Error::BadItem { index: 2, cause }) => assert!(matches!(*cause, Error::UnexpectedEnd)),
+        other => panic!("expected item 2 truncated, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_invalid_tag_mid_stream() -> Result<()> {
+    let mut bytes = loose_buffer()?;
+    bytes[6] = 0xFF;
+    match validate(&bytes) {
+        Err(Error::BadItem { index: 1, cause }) => assert!(matches!(*cause, Error::InvalidTag(0xFF))),
+        other => panic!("expected item 1 invalid, got {:?}", other),
+    }
+
+    // Nested problems count against the top-level item holding them
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.option_some_begin()?;
+    enc.u8(1)?;
+    enc.option_some_end()?;
+    enc.list_end()?;
+    let mut bytes = enc.into_bytes()?;
+    bytes[5] = Tag::Map as u8;
+    match validate(&bytes) {
+        Err(Error::BadItem { index: 0, cause }) => assert!(matches!(*cause, Error::InvalidMapEntry)),
+        other => panic!("expected item 0 malformed, got {:?}", other),
+    }
+    Ok(())
+}
+
+// ============================================================================
+//  SCHEMA VALIDATION
+// ============================================================================
+
+/// `{ name: "demo", metadata: { version: 3, tags: ["a", "b"] }, status: Ok(true) }`
+fn config_bytes(version: impl FnOnce(&mut Encoder) -> Result<()>) -> Result<Vec<u8>> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    enc.variant_begin("name")?; enc.str("demo")?; enc.variant_end()?;
+    enc.variant_begin("metadata")?;
+    enc.map_begin()?;
+    enc.variant_begin("version")?; version(&mut enc)?; enc.variant_end()?;
+    enc.variant_begin("tags")?;
+    enc.list_begin()?; enc.str("a")?; enc.str("b")?; enc.list_end()?;
+    enc.variant_end()?;
+    enc.map_end()?;
+    enc.variant_end()?;
+    enc.variant_begin("status")?;
+    enc.result_ok_begin()?; enc.bool(true)?; enc.result_ok_end()?;
+    enc.variant_end()?;
+    enc.map_end()?;
+    enc.into_bytes()
+}
+
+fn config_schema() -> Schema {
+    Schema::Map(vec![
+        ("name".into(), Schema::Scalar(Tag::String)),
+        ("metadata".into(), Schema::Map(vec![
+            ("version".into(), Schema::Scalar(Tag::U32)),
+            ("tags".into(), Schema::List(Box::new(Schema::Scalar(Tag::String)))),
+        ])),
+        ("status".into(), Schema::Result(
+            Box::new(Schema::Scalar(Tag::BoolTrue)),
+            Box::new(Schema::Scalar(Tag::String)),
+        )),
+    ])
+}
+
+fn assert_mismatch_at(result: Result<()>, expected: &str) {
+    match result {
+        Err(Error::SchemaMismatch { path, .. }) => assert_eq!(path, expected),
+        other => panic!("expected a mismatch at {}, got {:?}", expected, other),
+    }
+}
+
+#[test]
+fn test_schema_accepts_matching_value() -> Result<()> {
+    let bytes = config_bytes(|enc| enc.u32(3))?;
+    validate_against(&bytes, &config_schema())?;
+    validate_against(&bytes, &Schema::Any)?;
+
+    // Keys the schema doesn't name are allowed
+    let loose = Schema::Map(vec![("name".into(), Schema::Scalar(Tag::String))]);
+    validate_against(&bytes, &loose)?;
+    Ok(())
+}
+
+#[test]
+fn test_schema_wrong_type_names_path() -> Result<()> {
+    let bytes = config_bytes(|enc| enc.str("3"))?;
+    let result = validate_against(&bytes, &config_schema());
+    assert_mismatch_at(result, "/metadata/version");
+
+    let bytes = config_bytes(|enc| enc.u32(3))?;
+    let schema = Schema::Map(vec![(
+        "metadata".into(),
+        Schema::Map(vec![("tags".into(), Schema::List(Box::new(Schema::Scalar(Tag::U8))))]),
+    )]);
+    assert_mismatch_at(validate_against(&bytes, &schema), "/metadata/tags/0");
+
+    let schema = Schema::Map(vec![("status".into(), Schema::Result(
+        Box::new(Schema::Scalar(Tag::Unit)),
+        Box::new(Schema::Any),
+    ))]);
+    assert_mismatch_at(validate_against(&bytes, &schema), "/status/ok");
+
+    assert_mismatch_at(validate_against(&bytes, &Schema::Scalar(Tag::U32)), "/");
+    Ok(())
+}
+
+#[test]
+fn test_schema_missing_key_names_parent() -> Result<()> {
+    let bytes = config_bytes(|enc| enc.u32(3))?;
+    let schema = Schema::Map(vec![(
+        "metadata".into(),
+        Schema::Map(vec![("author".into(), Schema::Scalar(Tag::String))]),
+    )]);
+    match validate_against(&bytes, &schema) {
+        Err(Error::SchemaMismatch { path, why }) => {
+            assert_eq!(path, "/metadata");
+            assert!(why.contains("author"));
+        }
+        other => panic!("expected a missing key, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_schema_variant_cases() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.variant_begin("Circle")?; enc.f64(1.0)?; enc.variant_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let shape = |radius: Tag| Schema::Variant(vec![
+        ("Circle".into(), Schema::Scalar(radius)),
+        ("Empty".into(), Schema::Scalar(Tag::Unit)),
+    ]);
+    validate_against(&bytes, &shape(Tag::F64))?;
+    assert_mismatch_at(validate_against(&bytes, &shape(Tag::F32)), "/Circle");
+
+    let square = Schema::Variant(vec![("Square".into(), Schema::Scalar(Tag::F64))]);
+    assert_mismatch_at(validate_against(&bytes, &square), "/");
+
+    // Structural errors still surface as themselves, not as mismatches
+    assert!(matches!(validate_against(&bytes[..bytes.len() - 1], &shape(Tag::F64)), Err(Error::UnexpectedEnd)));
+    let mut trailing = bytes.clone();
+    trailing.push(Tag::Unit as u8);
+    assert!(matches!(validate_against(&trailing, &shape(Tag::F64)), Err(Error::TrailingBytes(1))));
+    Ok(())
+}
+
+// ============================================================================
+//  PATH TRACKING
+// ============================================================================
+
+/// Reads `config_bytes` the way a hand-written `Unpack` would, expecting a u32 version.
+fn read_config(mut dec: Decoder<'_>) -> Result<u32> {
+    let mut version = 0;
+    let mut fields = dec.map()?;
+    while let Some((key, mut value)) = fields.next()? {
+        match key {
+            "metadata" => {
+                let mut metadata = value.map()?;
+                while let Some((key, mut value)) = metadata.next()? {
+                    match key {
+                        "version" => version = value.u32()?,
+                        "tags" => for mut tag in value.list()? { tag.str()?; },
+                        _ => value.skip()?,
+                    }
+                }
+            }
+            "status" => { value.result()?.map_err(|_| Error::InvalidMapEntry)?.bool()?; }
+            _ => value.skip()?,
+        }
+    }
+    Ok(version)
+}
+
+#[test]
+fn test_path_tracking_names_wrong_leaf() -> Result<()> {
+    let bytes = config_bytes(|enc| enc.str("3"))?;
+    match read_config(Decoder::new(&bytes).with_path_tracking()) {
+        Err(Error::TypeMismatchAt(path)) => assert_eq!(path, "/metadata/version"),
+        other => panic!("expected a mismatch at /metadata/version, got {:?}", other),
+    }
+
+    // Without tracking the same mismatch is a bare InvalidTag
+    assert!(matches!(read_config(Decoder::new(&bytes)), Err(Error::InvalidTag(t)) if t == Tag::String as u8));
+
+    let bytes = config_bytes(|enc| enc.u32(3))?;
+    assert_eq!(read_config(Decoder::new(&bytes).with_path_tracking())?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_path_tracking_list_indices_and_results() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u8(1)?;
+    enc.result_ok_begin()?; enc.str("x")?; enc.result_ok_end()?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut items = Decoder::new(&bytes).with_path_tracking().list()?;
+    items.next().unwrap().u8()?;
+    let mut payload = items.next().unwrap().result()?.unwrap();
+    match payload.u64() {
+        Err(Error::TypeMismatchAt(path)) => assert_eq!(path, "/1/ok"),
+        other => panic!("expected a mismatch at /1/ok, got {:?}", other),
+    }
+
+    match Decoder::new(&bytes).with_path_tracking().map() {
+        Err(Error::TypeMismatchAt(path)) => assert_eq!(path, "/"),
+        other => panic!("expected a mismatch at the root, got {:?}", other),
+    }
+    Ok(())
+}
+
+// ============================================================================
+//  SIZE ESTIMATION
+// ============================================================================
+
+/// Writes one of everything, so dry-run and real encoders can be compared.
+fn write_everything(enc: &mut Encoder) -> Result<()> {
+    enc.bool(true)?;
+    enc.u8(1)?; enc.s8(-1)?;
+    enc.u16(2)?; enc.s16(-2)?;
+    enc.u32(3)?; enc.s32(-3)?;
+    enc.u64(4)?; enc.s64(-4)?;
+    enc.f32(5.0)?; enc.f64(6.0)?;
+    enc.char('🦀')?;
+    enc.unit()?;
+    enc.option_none()?;
+    enc.str("hello")?;
+    enc.bytes(&[1, 2, 3])?;
+    enc.list_begin()?;
+        enc.map_begin()?;
+            enc.variant_begin("key")?;
+                enc.option_some_begin()?;
+                    enc.result_err_begin()?;
+                        enc.str("nested")?;
+                    enc.result_err_end()?;
+                enc.option_some_end()?;
+            enc.variant_end()?;
+        enc.map_end()?;
+        enc.result_ok_begin()?;
+            enc.list_begin()?;
+            enc.list_end()?;
+        enc.result_ok_end()?;
+    enc.list_end()?;
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_matches_real_encoding() -> Result<()> {
+    let mut real = Encoder::new();
+    write_everything(&mut real)?;
+    let bytes = real.into_bytes()?;
+
+    let mut dry = Encoder::dry_run();
+    write_everything(&mut dry)?;
+    assert_eq!(dry.len(), bytes.len());
+
+    // A size probe never hands back bytes that could be mistaken for the encoding
+    assert!(matches!(dry.as_bytes(), Err(Error::DryRun)));
+    assert!(matches!(dry.into_bytes(), Err(Error::DryRun)));
+    Ok(())
+}
+
+#[test]
+fn test_encoded_len_scalars() -> Result<()> {
+    assert_eq!(encoded_len(&true)?, true.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&7u8)?, 7u8.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&7u16)?, 7u16.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&7u32)?, 7u32.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&7u64)?, 7u64.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&-7i8)?, (-7i8).pack_to_vec()?.len());
+    assert_eq!(encoded_len(&-7i16)?, (-7i16).pack_to_vec()?.len());
+    assert_eq!(encoded_len(&-7i32)?, (-7i32).pack_to_vec()?.len());
+    assert_eq!(encoded_len(&-7i64)?, (-7i64).pack_to_vec()?.len());
+    assert_eq!(encoded_len(&1.5f32)?, 1.5f32.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&1.5f64)?, 1.5f64.pack_to_vec()?.len());
+    assert_eq!(encoded_len(&())?, ().pack_to_vec()?.len());
+    assert_eq!(encoded_len("abc")?, 1 + 4 + 3);
+    Ok(())
+}
+
+#[test]
+fn test_encoded_len_containers() -> Result<()> {
+    let items: Vec<u32> = (0..10_000).collect();
+    let len = encoded_len(&items)?;
+    assert_eq!(len, 1 + 4 + 10_000 * 5);
+
+    let mut enc = Encoder::new();
+    enc.reserve_hint(len);
+    items.pack(&mut enc)?;
+    assert_eq!(enc.len(), len);
+
+    let nested: Vec<Option<std::result::Result<String, u8>>> = vec![Some(Ok("x".into())), None, Some(Err(1))];
+    assert_eq!(encoded_len(&nested)?, nested.pack_to_vec()?.len());
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_still_enforces_structure() {
+    let mut enc = Encoder::dry_run();
+    enc.map_begin().unwrap();
+    assert!(matches!(enc.u32(1), Err(Error::InvalidMapEntry)));
+    assert!(matches!(enc.option_some_end(), Err(Error::ScopeMismatch { .. })));
+}
+
+// ============================================================================
+//  DEPTH LIMITS
+// ============================================================================
+
+fn nested_lists(depth: usize) -> Result<Vec<u8>> {
+    let mut enc = Encoder::new();
+    for _ in 0..depth { enc.list_begin()?; }
+    enc.unit()?;
+    for _ in 0..depth { enc.list_end()?; }
+    enc.into_bytes()
+}
+
+/// Descends through nested single-item lists, returning how many were entered.
+fn descend_lists(mut cur: Decoder) -> Result<usize> {
+    let mut entered = 0;
+    while cur.peek_tag()? == Tag::List {
+        let mut items = cur.list()?;
+        entered += 1;
+        cur = items.next().ok_or(Error::UnexpectedEnd)?;
+    }
+    cur.unit()?;
+    Ok(entered)
+}
+
+#[test]
+fn test_depth_unlimited_by_default() -> Result<()> {
+    let bytes = nested_lists(100)?;
+    let dec = Decoder::new(&bytes);
+    assert_eq!(dec.remaining_depth(), None);
+    assert_eq!(descend_lists(dec)?, 100);
+    Ok(())
+}
+
+#[test]
+fn test_depth_limit_rejects_deep_nesting() -> Result<()> {
+    let bytes = nested_lists(100)?;
+    let dec = Decoder::with_max_depth(&bytes, 64);
+    assert!(matches!(descend_lists(dec), Err(Error::DepthExceeded)));
+
+    let bytes = nested_lists(64)?;
+    let dec = Decoder::with_max_depth(&bytes, 64);
+    assert_eq!(descend_lists(dec)?, 64);
+    Ok(())
+}
+
+#[test]
+fn test_depth_inherited_by_sub_decoders() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.map_begin()?;
+    enc.variant_begin("k")?;
+    enc.option_some_begin()?;
+    enc.u8(1)?;
+    enc.option_some_end()?;
+    enc.variant_end()?;
+    enc.map_end()?;
+    let bytes = enc.into_bytes()?;
+
+    // map -> variant -> option uses three levels.
+    let mut dec = Decoder::with_max_depth(&bytes, 3);
+    let mut map = dec.map()?;
+    let (_, mut val) = map.next()?.unwrap();
+    assert_eq!(val.remaining_depth(), Some(1));
+    let mut inner = val.option()?.unwrap();
+    assert_eq!(inner.remaining_depth(), Some(0));
+    assert_eq!(inner.u8()?, 1);
+
+    let mut dec = Decoder::with_max_depth(&bytes, 2);
+    let mut map = dec.map()?;
+    let (_, mut val) = map.next()?.unwrap();
+    assert!(matches!(val.option(), Err(Error::DepthExceeded)));
+    Ok(())
+}
+
+#[test]
+fn test_depth_limit_applies_to_try_list() -> Result<()> {
+    let bytes = nested_lists(1)?;
+    let mut dec = Decoder::with_max_depth(&bytes, 0);
+    assert!(matches!(dec.try_list(), Err(Error::DepthExceeded)));
+    Ok(())
+}
+
+// ============================================================================
+//  ENCODER STRICTNESS FAILURE MODES
+// ============================================================================
+
+#[test]
+fn test_strict_result_empty() {
+    let mut enc = Encoder::new();
+    enc.result_ok_begin().unwrap();
+    // Error: Closed without writing a value
+    match enc.result_ok_end() {
+        Err(Error::EmptyAdt(Scope::Result)) => {},
+        _ => panic!("Expected EmptyAdt error"),
+    }
+}
+
+#[test]
+fn test_strict_result_too_many() {
+    let mut enc = Encoder::new();
+    enc.result_ok_begin().unwrap();
+    enc.u32(1).unwrap();
+    // Error: Trying to write second value
+    match enc.u32(2) {
+        Err(Error::TooManyItems(Scope::Result)) => {},
+        _ => panic!("Expected TooManyItems error"),
+    }
+}
+
+#[test]
+fn test_strict_option_empty() {
+    let mut enc = Encoder::new();
+    enc.option_some_begin().unwrap();
+    match enc.option_some_end() {
+        Err(Error::EmptyAdt(Scope::Option)) => {},
+        _ => panic!("Expected EmptyAdt"),
+    }
+}
+
+#[test]
+fn test_strict_option_too_many() {
+    let mut enc = Encoder::new();
+    enc.option_some_begin().unwrap();
+    enc.u32(1).unwrap();
+    match enc.str("no") {
+        Err(Error::TooManyItems(Scope::Option)) => {},
+        _ => panic!("Expected TooManyItems"),
+    }
 }
 
 #[test]
@@ -484,6 +1578,47 @@ fn test_strict_map_entry_invalid_container() {
     }
 }
 
+#[test]
+fn test_strict_append_raw_non_variant_in_map() {
+    let frag = 5u32.pack_to_vec().unwrap();
+    let mut enc = Encoder::new();
+    enc.map_begin().unwrap();
+    match enc.append_raw(&frag) {
+        Err(Error::InvalidMapEntry) => {},
+        _ => panic!("Expected InvalidMapEntry"),
+    }
+}
+
+#[test]
+fn test_strict_append_raw_malformed_fragment() {
+    let mut enc = Encoder::new();
+    enc.list_begin().unwrap();
+
+    let mut two = 1u8.pack_to_vec().unwrap();
+    two.extend(2u8.pack_to_vec().unwrap());
+    assert!(matches!(enc.append_raw(&two), Err(Error::TrailingBytes(2))));
+
+    let truncated = &"hello".pack_to_vec().unwrap()[..4];
+    assert!(matches!(enc.append_raw(truncated), Err(Error::UnexpectedEnd)));
+    assert!(matches!(enc.append_raw(&[]), Err(Error::UnexpectedEnd)));
+
+    // Rejected fragments leave the scope untouched.
+    enc.list_end().unwrap();
+    assert_eq!(enc.into_bytes().unwrap(), Vec::<u8>::new().pack_to_vec().unwrap());
+}
+
+#[test]
+fn test_strict_append_raw_too_many() {
+    let frag = 1u8.pack_to_vec().unwrap();
+    let mut enc = Encoder::new();
+    enc.option_some_begin().unwrap();
+    enc.append_raw(&frag).unwrap();
+    match enc.append_raw(&frag) {
+        Err(Error::TooManyItems(Scope::Option)) => {},
+        _ => panic!("Expected TooManyItems"),
+    }
+}
+
 // ============================================================================
 //  ENCODER STATE ERRORS
 // ============================================================================
@@ -520,6 +1655,220 @@ fn test_scope_still_open() {
     }
 }
 
+// ============================================================================
+//  STREAM ENCODER
+// ============================================================================
+
+/// Writes the same nested document through any encoder-shaped API.
+macro_rules! write_nested_doc {
+    ($enc:expr) => {{
+        let enc = $enc;
+        enc.map_begin().unwrap();
+        enc.variant_begin("name").unwrap();
+        enc.str("stream").unwrap();
+        enc.variant_end().unwrap();
+        enc.variant_begin("rows").unwrap();
+        enc.list_begin().unwrap();
+        for i in 0..3u32 {
+            enc.list_begin().unwrap();
+            enc.u32(i).unwrap();
+            enc.option_some_begin().unwrap();
+            enc.bytes(&[i as u8; 5]).unwrap();
+            enc.option_some_end().unwrap();
+            enc.list_end().unwrap();
+        }
+        enc.list_end().unwrap();
+        enc.variant_end().unwrap();
+        enc.map_end().unwrap();
+    }};
+}
+
+#[test]
+fn test_stream_encoder_backpatches_nested_scopes() {
+    let mut buffered = Encoder::new();
+    write_nested_doc!(&mut buffered);
+    let expected = buffered.into_bytes().unwrap();
+
+    let mut stream = StreamEncoder::new(std::io::Cursor::new(Vec::new())).unwrap();
+    write_nested_doc!(&mut stream);
+    assert_eq!(stream.len(), expected.len());
+    let bytes = stream.finish().unwrap().into_inner();
+    assert_eq!(bytes, expected);
+
+    let mut dec = Decoder::new(&bytes);
+    let mut map = dec.map().unwrap();
+    let (key, mut val) = map.next().unwrap().unwrap();
+    assert_eq!((key, val.str().unwrap()), ("name", "stream"));
+    let (key, mut val) = map.next().unwrap().unwrap();
+    assert_eq!(key, "rows");
+    let mut rows = val.list().unwrap();
+    for i in 0..3u32 {
+        let mut row = rows.next().unwrap().list().unwrap();
+        assert_eq!(row.next().unwrap().u32().unwrap(), i);
+        let mut some = row.next().unwrap().option().unwrap().unwrap();
+        assert_eq!(some.bytes().unwrap(), &[i as u8; 5]);
+    }
+    assert!(rows.next().is_none());
+}
+
+#[test]
+fn test_stream_encoder_starts_at_sink_position() {
+    let mut cursor = std::io::Cursor::new(b"HEADER".to_vec());
+    cursor.set_position(6);
+
+    let mut stream = StreamEncoder::new(cursor).unwrap();
+    stream.list_begin().unwrap();
+    stream.u64(7).unwrap();
+    stream.list_end().unwrap();
+    let bytes = stream.finish().unwrap().into_inner();
+
+    assert_eq!(&bytes[..6], b"HEADER");
+    let mut dec = Decoder::new(&bytes[6..]);
+    assert_eq!(dec.list().unwrap().next().unwrap().u64().unwrap(), 7);
+}
+
+#[test]
+fn test_stream_encoder_unseekable_needs_sizes() {
+    let items = [1u32, 2, 3];
+    let body_len: usize = items.iter().map(|v| encoded_len(v).unwrap()).sum();
+
+    let mut stream = StreamEncoder::unseekable(Vec::new());
+    assert!(matches!(stream.list_begin(), Err(Error::UnsizedScope)));
+    stream.list_begin_sized(body_len as u32).unwrap();
+    for item in &items {
+        stream.pack(item).unwrap();
+    }
+    stream.list_end().unwrap();
+    let bytes = stream.finish().unwrap();
+
+    let mut dec = Decoder::new(&bytes);
+    let mut list = dec.list().unwrap();
+    for item in &items {
+        assert_eq!(list.next().unwrap().u32().unwrap(), *item);
+    }
+    assert!(list.next().is_none());
+
+    let mut short = StreamEncoder::unseekable(Vec::new());
+    short.list_begin_sized(100).unwrap();
+    short.u32(1).unwrap();
+    assert!(matches!(short.list_end(), Err(Error::SizeMismatch { declared: 100, actual: 5 })));
+}
+
+#[test]
+fn test_stream_encoder_unseekable_sized_adts() {
+    let mut buffered = Encoder::new();
+    buffered.list_begin().unwrap();
+    buffered.option_some_begin().unwrap();
+    buffered.u32(1).unwrap();
+    buffered.option_some_end().unwrap();
+    buffered.result_ok_begin().unwrap();
+    buffered.str("ok").unwrap();
+    buffered.result_ok_end().unwrap();
+    buffered.result_err_begin().unwrap();
+    buffered.u8(2).unwrap();
+    buffered.result_err_end().unwrap();
+    buffered.variant_begin("point").unwrap();
+    buffered.s64(-3).unwrap();
+    buffered.variant_end().unwrap();
+    buffered.list_end().unwrap();
+    let expected = buffered.into_bytes().unwrap();
+
+    let len = |v: &dyn Fn(&mut Encoder) -> Result<()>| {
+        let mut enc = Encoder::dry_run();
+        v(&mut enc).unwrap();
+        enc.len() as u32
+    };
+    let mut stream = StreamEncoder::unseekable(Vec::new());
+    stream.list_begin_sized(expected.len() as u32 - 5).unwrap();
+    assert!(matches!(stream.option_some_begin(), Err(Error::UnsizedScope)));
+    stream.option_some_begin_sized(len(&|e| e.u32(1))).unwrap();
+    stream.u32(1).unwrap();
+    stream.option_some_end().unwrap();
+    stream.result_ok_begin_sized(len(&|e| e.str("ok"))).unwrap();
+    stream.str("ok").unwrap();
+    stream.result_ok_end().unwrap();
+    stream.result_err_begin_sized(len(&|e| e.u8(2))).unwrap();
+    stream.u8(2).unwrap();
+    stream.result_err_end().unwrap();
+    assert!(matches!(stream.variant_begin("point"), Err(Error::UnsizedScope)));
+    stream.variant_begin_sized("point", len(&|e| e.s64(-3))).unwrap();
+    stream.s64(-3).unwrap();
+    stream.variant_end().unwrap();
+    stream.list_end().unwrap();
+    assert_eq!(stream.finish().unwrap(), expected);
+
+    let mut short = StreamEncoder::unseekable(Vec::new());
+    short.variant_begin_sized("v", 9).unwrap();
+    short.u32(1).unwrap();
+    assert!(matches!(short.variant_end(), Err(Error::SizeMismatch { declared: 15, actual: 11 })));
+}
+
+#[test]
+fn test_stream_encoder_map_sorted_matches_encoder() {
+    let entries = |enc: &mut Encoder| -> Result<()> {
+        for (k, v) in [("delta", 0u32), ("alpha", 1), ("charlie", 2)] {
+            enc.variant_begin(k)?; enc.u32(v)?; enc.variant_end()?;
+        }
+        Ok(())
+    };
+    let mut buffered = Encoder::new();
+    buffered.map_sorted_begin().unwrap();
+    entries(&mut buffered).unwrap();
+    buffered.map_sorted_end().unwrap();
+    let expected = buffered.into_bytes().unwrap();
+
+    let mut stream = StreamEncoder::unseekable(Vec::new());
+    stream.map_sorted(entries).unwrap();
+    assert_eq!(stream.finish().unwrap(), expected);
+
+    let mut stream = StreamEncoder::unseekable(Vec::new());
+    let dup = stream.map_sorted(|enc| {
+        enc.variant_begin("k")?; enc.unit()?; enc.variant_end()?;
+        enc.variant_begin("k")?; enc.unit()?; enc.variant_end()
+    });
+    assert!(matches!(dup, Err(Error::DuplicateKey(key)) if key == "k"));
+    assert!(stream.is_empty());
+}
+
+/// Writes one of every scalar and blob through any encoder-shaped API.
+macro_rules! write_every_scalar {
+    ($enc:expr) => {{
+        let enc = $enc;
+        enc.bool(true).unwrap();
+        enc.u8(1).unwrap();
+        enc.s8(-1).unwrap();
+        enc.u16(2).unwrap();
+        enc.s16(-2).unwrap();
+        enc.u32(3).unwrap();
+        enc.s32(-3).unwrap();
+        enc.u64(4).unwrap();
+        enc.s64(-4).unwrap();
+        enc.u128(5).unwrap();
+        enc.s128(-5).unwrap();
+        enc.f32(1.5).unwrap();
+        enc.f64(-2.5).unwrap();
+        enc.char('λ').unwrap();
+        enc.decimal(-12345, 2).unwrap();
+        enc.timestamp(1_700_000_000, 42).unwrap();
+        enc.unit().unwrap();
+        enc.option_none().unwrap();
+        enc.str("text").unwrap();
+        enc.bytes(&[1, 2, 3]).unwrap();
+        enc.big_bytes(&[4, 5, 6, 7]).unwrap();
+    }};
+}
+
+#[test]
+fn test_stream_encoder_matches_encoder_for_every_scalar() {
+    let mut buffered = Encoder::new();
+    write_every_scalar!(&mut buffered);
+    let expected = buffered.into_bytes().unwrap();
+
+    let mut stream = StreamEncoder::unseekable(Vec::new());
+    write_every_scalar!(&mut stream);
+    assert_eq!(stream.finish().unwrap(), expected);
+}
+
 // ============================================================================
 //  DECODER FAILURE MODES
 // ============================================================================
@@ -637,6 +1986,240 @@ fn test_trailing_data_in_item_decoder() -> Result<()> {
     Ok(())
 }
 
+// ============================================================================
+//  SCALAR LISTS
+// ============================================================================
+
+#[test]
+fn test_scalar_list_matches_list_iter() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    for i in 0..100_000u32 {
+        enc.u32(i.wrapping_mul(2_654_435_761))?;
+    }
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut slow = Vec::new();
+    for mut item in Decoder::new(&bytes).list()? {
+        slow.push(item.u32()?);
+    }
+
+    let fast = Decoder::new(&bytes).scalar_list::<u32>()?;
+    assert_eq!(fast.len(), 100_000);
+    assert_eq!(fast.collect::<Vec<_>>(), slow);
+    Ok(())
+}
+
+#[test]
+fn test_scalar_list_128_bit() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u128(u128::MAX)?;
+    enc.u128(1)?;
+    enc.list_end()?;
+    enc.list_begin()?;
+    enc.s128(i128::MIN)?;
+    enc.s128(-1)?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut dec = Decoder::new(&bytes);
+    assert_eq!(dec.scalar_list::<u128>()?.collect::<Vec<_>>(), vec![u128::MAX, 1]);
+    assert_eq!(dec.scalar_list::<i128>()?.collect::<Vec<_>>(), vec![i128::MIN, -1]);
+    Ok(())
+}
+
+#[test]
+fn test_scalar_list_floats_and_empty() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.f64(PI)?;
+    enc.f64(-0.5)?;
+    enc.list_end()?;
+    enc.list_begin()?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut dec = Decoder::new(&bytes);
+    assert_eq!(dec.scalar_list::<f64>()?.collect::<Vec<_>>(), vec![PI, -0.5]);
+    assert_eq!(dec.scalar_list::<i8>()?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_scalar_list_rejects_mixed_items() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(1)?;
+    enc.u32(2)?;
+    enc.u64(3)?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    match Decoder::new(&bytes).scalar_list::<u32>() {
+        Err(Error::HeterogeneousList { index: 2, tag }) => assert_eq!(tag, Tag::U64 as u8),
+        res => panic!("Expected HeterogeneousList at index 2, got {:?}", res.map(|it| it.count())),
+    }
+    match Decoder::new(&bytes).scalar_list::<u64>() {
+        Err(Error::InvalidTag(tag)) => assert_eq!(tag, Tag::U32 as u8),
+        res => panic!("Expected InvalidTag, got {:?}", res.map(|it| it.count())),
+    }
+    Ok(())
+}
+
+// ============================================================================
+//  ENCODER MARKS AND ROLLBACK
+// ============================================================================
+
+#[test]
+fn test_rollback_inside_open_list() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(1)?;
+    enc.u32(2)?;
+    let mark = enc.mark();
+    enc.u32(3)?;
+    enc.rollback(mark)?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut items = Vec::new();
+    for mut item in Decoder::new(&bytes).list()? {
+        items.push(item.u32()?);
+    }
+    assert_eq!(items, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_rollback_drops_scopes_opened_after_mark() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    enc.u32(1)?;
+    let mark = enc.mark();
+    enc.map_begin()?;
+    enc.variant_begin("half")?;
+    enc.rollback(mark)?;
+    enc.u32(2)?;
+    enc.list_end()?;
+    let bytes = enc.into_bytes()?;
+
+    let mut expected = Encoder::new();
+    expected.list_begin()?;
+    expected.u32(1)?;
+    expected.u32(2)?;
+    expected.list_end()?;
+    assert_eq!(bytes, expected.into_bytes()?);
+    Ok(())
+}
+
+#[test]
+fn test_rollback_rejects_stale_mark() -> Result<()> {
+    let mut enc = Encoder::new();
+    enc.list_begin()?;
+    let mark = enc.mark();
+    enc.u32(1)?;
+    enc.list_end()?;
+    assert!(matches!(enc.rollback(mark), Err(Error::StaleMark)));
+
+    // A scope reopened at the same offset is a different scope
+    let outer = enc.mark();
+    enc.list_begin()?;
+    let inner = enc.mark();
+    enc.rollback(outer)?;
+    enc.list_begin()?;
+    assert!(matches!(enc.rollback(inner), Err(Error::StaleMark)));
+    enc.u32(2)?;
+    enc.list_end()?;
+
+    // A mark past a rollback is stale, even once the buffer grows back over it
+    enc.list_begin()?;
+    let m1 = enc.mark();
+    enc.str("aaaaaaaa")?;
+    let m2 = enc.mark();
+    enc.str("bbbbbbbb")?;
+    enc.rollback(m1)?;
+    enc.str("cccccccccccccccccccccccc")?;
+    assert!(matches!(enc.rollback(m2), Err(Error::StaleMark)));
+    assert!(matches!(enc.rollback(m1), Err(Error::StaleMark)));
+    enc.list_end()?;
+
+    let bytes = enc.into_bytes()?;
+    let mut dec = Decoder::new(&bytes);
+    assert_eq!(dec.list()?.next().unwrap().u32()?, 1);
+    assert_eq!(dec.list()?.next().unwrap().u32()?, 2);
+    assert_eq!(dec.list()?.next().unwrap().str()?, "cccccccccccccccccccccccc");
+    Ok(())
+}
+
+#[test]
+fn test_rollback_dry_run() -> Result<()> {
+    let mut enc = Encoder::dry_run();
+    enc.list_begin()?;
+    enc.str("kept")?;
+    let mark = enc.mark();
+    enc.str("dropped")?;
+    enc.rollback(mark)?;
+    enc.list_end()?;
+
+    let mut real = Encoder::new();
+    real.list_begin()?;
+    real.str("kept")?;
+    real.list_end()?;
+    assert_eq!(enc.len(), real.into_bytes()?.len());
+    Ok(())
+}
+
+// ============================================================================
+//  ERROR CODES
+// ============================================================================
+
+#[test]
+fn test_error_codes_are_distinct_and_stable() {
+    let errors = [
+        Error::BufferFull,
+        Error::InvalidTag(0xFF),
+        Error::InvalidUtf8,
+        Error::ScopeMismatch { expected: Scope::List, actual: Scope::Map },
+        Error::ScopeUnderflow,
+        Error::ScopeStillOpen,
+        Error::UnexpectedEnd,
+        Error::BlobTooLarge(1),
+        Error::TooManyItems(Scope::Option),
+        Error::EmptyAdt(Scope::Variant),
+        Error::InvalidMapEntry,
+        Error::NonCanonical("test"),
+        Error::DepthExceeded,
+        Error::TrailingBytes(1),
+        Error::Io("test".into()),
+        Error::UnsizedScope,
+        Error::SizeMismatch { declared: 1, actual: 2 },
+        Error::InvalidTimestamp,
+        Error::HeterogeneousList { index: 1, tag: 0 },
+        Error::StaleMark,
+        Error::ChecksumMismatch { stored: 0, computed: 1 },
+        Error::DryRun,
+        Error::DuplicateKey("k".into()),
+        Error::BadItem { index: 0, cause: Box::new(Error::UnexpectedEnd) },
+        Error::SchemaMismatch { path: "/".into(), why: "test".into() },
+        Error::TypeMismatchAt("/".into()),
+    ];
+
+    let codes: std::collections::HashSet<_> = errors.iter().map(Error::code).collect();
+    assert_eq!(codes.len(), errors.len());
+    for error in &errors {
+        let code = error.code();
+        assert_eq!(ErrorCode::from_u8(code as u8), Some(code));
+    }
+    assert_eq!(ErrorCode::from_u8(0), None);
+
+    // Codes go over the wire, so they must never change
+    assert_eq!(Error::BufferFull.code() as u8, 0x01);
+    assert_eq!(Error::UnexpectedEnd.code() as u8, 0x07);
+    assert_eq!(Error::HeterogeneousList { index: 0, tag: 0 }.code() as u8, 0x13);
+}
+
 // ── Derive macro tests ──
 
 #[derive(Debug, PartialEq, Pack, Unpack)]
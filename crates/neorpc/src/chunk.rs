@@ -0,0 +1,109 @@
+//! # Chunk Reassembly
+//!
+//! Rebuilds a results payload from the `ReplyChunk` frames of a single call.
+//!
+//! ## Invariants
+//! - **Per-Call**: A reassembler only accepts chunks for the `seq` it was created with.
+//! - **Gap Detection**: The payload is released only once every index up to the
+//!   final chunk has arrived exactly once; anything else is a `ProtocolViolation`.
+//! - **Bounded Buffering**: At most `max_chunks` chunks and `max_bytes` payload
+//!   bytes are held; a chunk past either limit is a `ProtocolViolation`.
+
+use std::collections::BTreeMap;
+
+use crate::error::Result;
+use crate::error::Error;
+use crate::frame::ReplyChunkDecoder;
+
+/// Chunks a reassembler buffers, unless set with `ChunkReassembler::with_limits`.
+pub const DEFAULT_MAX_CHUNKS: usize = 4096;
+
+/// Payload bytes a reassembler buffers, unless set with `ChunkReassembler::with_limits`.
+pub const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Collects the chunks of one reply, in any arrival order.
+pub struct ChunkReassembler {
+    seq: u64,
+    chunks: BTreeMap<u32, Vec<u8>>,
+    last: Option<u32>,
+    bytes: usize,
+    max_chunks: usize,
+    max_bytes: usize,
+}
+
+impl ChunkReassembler {
+    pub fn new(seq: u64) -> Self {
+        Self::with_limits(seq, DEFAULT_MAX_CHUNKS, DEFAULT_MAX_BYTES)
+    }
+
+    /// Creates a reassembler holding at most `max_chunks` chunks of `max_bytes` in total.
+    pub fn with_limits(seq: u64, max_chunks: usize, max_bytes: usize) -> Self {
+        Self { seq, chunks: BTreeMap::new(), last: None, bytes: 0, max_chunks, max_bytes }
+    }
+
+    /// The call this reassembler collects chunks for.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Buffers a chunk.
+    ///
+    /// Fails on a foreign `seq`, a repeated index, a second final chunk,
+    /// an index past the final chunk, or a chunk over either limit.
+    pub fn push(&mut self, chunk: &ReplyChunkDecoder) -> Result<()> {
+        if chunk.seq != self.seq {
+            return Err(violation(format!("Chunk for seq {} sent to reassembler for seq {}", chunk.seq, self.seq)));
+        }
+        if self.chunks.contains_key(&chunk.index) {
+            return Err(violation(format!("Duplicate chunk index {}", chunk.index)));
+        }
+        if self.chunks.len() >= self.max_chunks {
+            return Err(violation(format!("More than {} chunks for seq {}", self.max_chunks, self.seq)));
+        }
+        if self.bytes + chunk.payload.len() > self.max_bytes {
+            return Err(violation(format!("More than {} chunk bytes for seq {}", self.max_bytes, self.seq)));
+        }
+        if let Some(last) = self.last {
+            if chunk.last {
+                return Err(violation(format!("Second final chunk for seq {}", self.seq)));
+            }
+            if chunk.index > last {
+                return Err(violation(format!("Chunk index {} past final index {}", chunk.index, last)));
+            }
+        } else if chunk.last {
+            if let Some(max) = self.chunks.keys().next_back().copied().filter(|&max| max > chunk.index) {
+                return Err(violation(format!("Chunk index {} past final index {}", max, chunk.index)));
+            }
+            self.last = Some(chunk.index);
+        }
+        self.bytes += chunk.payload.len();
+        self.chunks.insert(chunk.index, chunk.payload.to_vec());
+        Ok(())
+    }
+
+    /// Returns true once the final chunk and every index before it have arrived.
+    pub fn is_complete(&self) -> bool {
+        self.last.is_some_and(|last| self.chunks.len() as u64 == last as u64 + 1)
+    }
+
+    /// Concatenates the chunks into the results payload.
+    ///
+    /// The returned bytes are a pre-encoded results list, suitable for `decode_vals`.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        let Some(last) = self.last else {
+            return Err(violation(format!("Missing final chunk for seq {}", self.seq)));
+        };
+        let mut out = Vec::new();
+        for (expected, (index, payload)) in (0..=last).zip(self.chunks) {
+            if index != expected {
+                return Err(violation(format!("Gap in chunk indices for seq {}: missing {}", self.seq, expected)));
+            }
+            out.extend_from_slice(&payload);
+        }
+        Ok(out)
+    }
+}
+
+fn violation(msg: String) -> Error {
+    Error::ProtocolViolation(msg)
+}
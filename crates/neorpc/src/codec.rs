@@ -12,6 +12,7 @@ use crate::error::Error;
 use neopack::Decoder;
 use neopack::Encoder;
 
+use wasmtime::component::ResourceAny;
 use wasmtime::component::Type;
 use wasmtime::component::Val;
 
@@ -23,7 +24,52 @@ const MAX_RECURSION_DEPTH: usize = 64;
 /// # Errors
 /// Returns `RpcError::RecursionLimitExceeded` if the value is too deeply nested.
 pub fn encode_val(enc: &mut Encoder, val: &Val) -> Result<()> {
-    encode_val_impl(enc, val, 0)
+    encode_val_impl(enc, val, 0, None)
+}
+
+/// Opt-in codec extensions beyond the default wire-safe subset.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CodecOptions {
+    /// Marshal resource handles as opaque `u32` tokens instead of rejecting them.
+    pub allow_resource_tokens: bool,
+}
+
+/// Maps resource handles to the opaque tokens sent over the wire.
+///
+/// Tokens are only meaningful to the side that issued them; the remote can hold
+/// them and pass them back, but never dereference them.
+#[derive(Debug, Default)]
+pub struct ResourceTokens {
+    handles: Vec<ResourceAny>,
+}
+
+impl ResourceTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the token for `handle`, issuing a new one on first sight.
+    pub fn token_for(&mut self, handle: ResourceAny) -> u32 {
+        if let Some(idx) = self.handles.iter().position(|h| *h == handle) {
+            return idx as u32;
+        }
+        self.handles.push(handle);
+        (self.handles.len() - 1) as u32
+    }
+
+    /// Returns the handle a token was issued for.
+    pub fn resolve(&self, token: u32) -> Option<ResourceAny> {
+        self.handles.get(token as usize).copied()
+    }
+}
+
+/// Encodes a value like `encode_val`, with the extensions enabled in `opts`.
+///
+/// With `allow_resource_tokens`, `Val::Resource` is written as `variant "ResourceToken" { u32 }`
+/// using a token issued by `tokens`.
+pub fn encode_val_with(enc: &mut Encoder, val: &Val, opts: &CodecOptions, tokens: &mut ResourceTokens) -> Result<()> {
+    let tokens = opts.allow_resource_tokens.then_some(tokens);
+    encode_val_impl(enc, val, 0, tokens)
 }
 
 /// Encodes a list of `wasmtime::component::Val` into a byte vector.
@@ -40,7 +86,7 @@ pub fn encode_vals_to_bytes(vals: &[Val]) -> Result<Vec<u8>> {
     enc.into_bytes().map_err(Error::from)
 }
 
-fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
+fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize, mut tokens: Option<&mut ResourceTokens>) -> Result<()> {
     if depth > MAX_RECURSION_DEPTH {
         return Err(Error::RecursionLimitExceeded);
     }
@@ -62,7 +108,7 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
         Val::List(items) => {
             enc.list_begin()?;
             for item in items {
-                encode_val_impl(enc, item, depth + 1)?;
+                encode_val_impl(enc, item, depth + 1, tokens.as_deref_mut())?;
             }
             enc.list_end()?;
         },
@@ -70,7 +116,7 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
             enc.map_begin()?;
             for (name, value) in fields {
                 enc.variant_begin(name)?;
-                encode_val_impl(enc, value, depth + 1)?;
+                encode_val_impl(enc, value, depth + 1, tokens.as_deref_mut())?;
                 enc.variant_end()?;
             }
             enc.map_end()?;
@@ -78,14 +124,14 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
         Val::Tuple(items) => {
             enc.list_begin()?;
             for item in items {
-                encode_val_impl(enc, item, depth + 1)?;
+                encode_val_impl(enc, item, depth + 1, tokens.as_deref_mut())?;
             }
             enc.list_end()?;
         },
         Val::Variant(name, value) => {
             enc.variant_begin(name)?;
             match value {
-                Some(v) => encode_val_impl(enc, v, depth + 1)?,
+                Some(v) => encode_val_impl(enc, v, depth + 1, tokens.as_deref_mut())?,
                 None => enc.unit()?,
             }
             enc.variant_end()?;
@@ -99,7 +145,7 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
             match opt {
                 Some(v) => {
                     enc.option_some_begin()?;
-                    encode_val_impl(enc, v, depth + 1)?;
+                    encode_val_impl(enc, v, depth + 1, tokens.as_deref_mut())?;
                     enc.option_some_end()?;
                 },
                 None => enc.option_none()?,
@@ -109,7 +155,7 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
             match res {
                 Ok(Some(v)) => {
                     enc.result_ok_begin()?;
-                    encode_val_impl(enc, v, depth + 1)?;
+                    encode_val_impl(enc, v, depth + 1, tokens.as_deref_mut())?;
                     enc.result_ok_end()?;
                 },
                 Ok(None) => {
@@ -119,7 +165,7 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
                 }
                 Err(Some(v)) => {
                     enc.result_err_begin()?;
-                    encode_val_impl(enc, v, depth + 1)?;
+                    encode_val_impl(enc, v, depth + 1, tokens.as_deref_mut())?;
                     enc.result_err_end()?;
                 },
                 Err(None) => {
@@ -136,7 +182,15 @@ fn encode_val_impl(enc: &mut Encoder, val: &Val, depth: usize) -> Result<()> {
             }
             enc.list_end()?;
         },
-        Val::Resource(_) | Val::Future(_) | Val::Stream(_) | Val::ErrorContext(_) => {
+        Val::Resource(handle) => {
+            let Some(tokens) = tokens else {
+                return Err(Error::UnsupportedType(val_desc(val).into()));
+            };
+            enc.variant_begin("ResourceToken")?;
+            enc.u32(tokens.token_for(*handle))?;
+            enc.variant_end()?;
+        },
+        Val::Future(_) | Val::Stream(_) | Val::ErrorContext(_) => {
             return Err(Error::UnsupportedType(val_desc(val).into()));
         }
     }
@@ -152,7 +206,7 @@ pub fn decode_vals(mut list_decoder: Decoder, types: &[Type]) -> Result<Vec<Val>
 
     for ty in types {
         if let Some(mut item_dec) = list_iter.next() {
-            vals.push(decode_val_impl(&mut item_dec, ty, 0)?);
+            vals.push(decode_val_impl(&mut item_dec, ty, 0, &mut DecodeState::strict())?);
         } else {
             return Err(Error::ProtocolViolation("Fewer args than types".into()));
         }
@@ -167,10 +221,65 @@ pub fn decode_vals(mut list_decoder: Decoder, types: &[Type]) -> Result<Vec<Val>
 
 /// Decodes a single Value based on the expected Wasmtime Type.
 pub fn decode_val(dec: &mut Decoder, ty: &Type) -> Result<Val> {
-    decode_val_impl(dec, ty, 0)
+    decode_val_impl(dec, ty, 0, &mut DecodeState::strict())
+}
+
+/// Decodes a value like `decode_val`, with the extensions enabled in `opts`.
+///
+/// With `allow_resource_tokens`, `own`/`borrow` types accept a `ResourceToken`
+/// previously issued by `tokens`.
+pub fn decode_val_with(dec: &mut Decoder, ty: &Type, opts: &CodecOptions, tokens: &ResourceTokens) -> Result<Val> {
+    let mut state = DecodeState { tokens: opts.allow_resource_tokens.then_some(tokens), ..DecodeState::strict() };
+    decode_val_impl(dec, ty, 0, &mut state)
 }
 
-fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
+/// How the decoder treats enum cases and flag names missing from the expected type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownPolicy {
+    /// Fail with `Error::UnknownVariant` (the behavior of `decode_val`).
+    #[default]
+    Error,
+    /// Drop unknown flags and replace unknown enum cases with the first declared case.
+    Skip,
+}
+
+/// Decodes a single Value, tolerating unknown enum cases and flags per `policy`.
+///
+/// Intended for rolling upgrades, where a newer peer may send names this side
+/// does not know yet. Returns the value along with the number of unknown names
+/// that were dropped or substituted, so callers can log or count them.
+pub fn decode_val_lenient(dec: &mut Decoder, ty: &Type, policy: UnknownPolicy) -> Result<(Val, usize)> {
+    let mut state = DecodeState { policy, ..DecodeState::strict() };
+    let val = decode_val_impl(dec, ty, 0, &mut state)?;
+    Ok((val, state.warnings))
+}
+
+/// Options and tallies threaded through a single decode.
+struct DecodeState<'t> {
+    policy: UnknownPolicy,
+    warnings: usize,
+    /// Present only when resource tokens are enabled.
+    tokens: Option<&'t ResourceTokens>,
+}
+
+impl DecodeState<'_> {
+    fn strict() -> Self {
+        Self { policy: UnknownPolicy::Error, warnings: 0, tokens: None }
+    }
+
+    /// Records an unknown name, or rejects it under the strict policy.
+    fn tolerate(&mut self, name: &str) -> Result<()> {
+        match self.policy {
+            UnknownPolicy::Error => Err(Error::UnknownVariant(name.to_string())),
+            UnknownPolicy::Skip => {
+                self.warnings += 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize, state: &mut DecodeState<'_>) -> Result<Val> {
     if depth > MAX_RECURSION_DEPTH {
         return Err(Error::RecursionLimitExceeded);
     }
@@ -192,10 +301,9 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
 
         Type::List(handle) => {
             let inner_ty = handle.ty();
-            let mut iter = dec.list()?;
             let mut list = Vec::new();
-            while let Some(mut item_dec) = iter.next() {
-                list.push(decode_val_impl(&mut item_dec, &inner_ty, depth + 1)?);
+            for mut item_dec in dec.list()? {
+                list.push(decode_val_impl(&mut item_dec, &inner_ty, depth + 1, state)?);
             }
             Ok(Val::List(list))
         },
@@ -205,7 +313,7 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
             let mut list = Vec::new();
             for ty in handle.types() {
                 let mut item = iter.next().ok_or(Error::ProtocolViolation("Tuple too short".into()))?;
-                list.push(decode_val_impl(&mut item, &ty, depth + 1)?);
+                list.push(decode_val_impl(&mut item, &ty, depth + 1, state)?);
             }
             Ok(Val::Tuple(list))
         },
@@ -223,7 +331,7 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
             while let Some((k, mut v)) = iter.next()? {
                 if let Some(idx) = fields.iter().position(|f| f.name == k) {
                     let field = &fields[idx];
-                    let val = decode_val_impl(&mut v, &field.ty, depth + 1)?;
+                    let val = decode_val_impl(&mut v, &field.ty, depth + 1, state)?;
                     record_vals[idx] = Some((field.name.to_string(), val));
                 } else {
                     v.skip()?;
@@ -245,7 +353,7 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
             let (name, mut val_dec) = dec.variant()?;
             if let Some(case) = handle.cases().find(|c| c.name == name) {
                  let payload = if let Some(ty) = &case.ty {
-                    Some(Box::new(decode_val_impl(&mut val_dec, ty, depth + 1)?))
+                    Some(Box::new(decode_val_impl(&mut val_dec, ty, depth + 1, state)?))
                 } else {
                     val_dec.unit()?;
                     None
@@ -262,14 +370,16 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
             if handle.names().any(|n| n == name) {
                 Ok(Val::Enum(name.to_string()))
             } else {
-                Err(Error::UnknownVariant(name.to_string()))
+                state.tolerate(name)?;
+                let fallback = handle.names().next().ok_or_else(|| Error::UnknownVariant(name.to_string()))?;
+                Ok(Val::Enum(fallback.to_string()))
             }
         },
 
         Type::Option(handle) => {
             let inner_ty = handle.ty();
             if let Some(mut opt_dec) = dec.option()? {
-                let val = decode_val_impl(&mut opt_dec, &inner_ty, depth + 1)?;
+                let val = decode_val_impl(&mut opt_dec, &inner_ty, depth + 1, state)?;
                 Ok(Val::Option(Some(Box::new(val))))
             } else {
                 Ok(Val::Option(None))
@@ -280,7 +390,7 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
             match dec.result()? {
                 Ok(mut d) => {
                     let val = if let Some(ty) = handle.ok() {
-                        Some(Box::new(decode_val_impl(&mut d, &ty, depth + 1)?))
+                        Some(Box::new(decode_val_impl(&mut d, &ty, depth + 1, state)?))
                     } else {
                         d.unit()?; None
                     };
@@ -288,7 +398,7 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
                 },
                 Err(mut d) => {
                     let val = if let Some(ty) = handle.err() {
-                        Some(Box::new(decode_val_impl(&mut d, &ty, depth + 1)?))
+                        Some(Box::new(decode_val_impl(&mut d, &ty, depth + 1, state)?))
                     } else {
                         d.unit()?; None
                     };
@@ -298,19 +408,32 @@ fn decode_val_impl(dec: &mut Decoder, ty: &Type, depth: usize) -> Result<Val> {
         },
 
         Type::Flags(handle) => {
-            let mut iter = dec.list()?;
             let mut active = Vec::new();
-            while let Some(mut item) = iter.next() {
+            for mut item in dec.list()? {
                 let f = item.str()?;
                 if handle.names().any(|n| n == f) {
                     active.push(f.to_string());
                 } else {
-                    return Err(Error::UnknownVariant(f.to_string()));
+                    state.tolerate(f)?;
                 }
             }
             Ok(Val::Flags(active))
         },
 
+        Type::Own(res_ty) | Type::Borrow(res_ty) if state.tokens.is_some() => {
+            let (name, mut body) = dec.variant()?;
+            if name != "ResourceToken" {
+                return Err(Error::UnknownVariant(name.to_string()));
+            }
+            let token = body.u32()?;
+            let handle = state.tokens.and_then(|t| t.resolve(token))
+                .ok_or_else(|| Error::ProtocolViolation(format!("Unknown resource token {}", token)))?;
+            if handle.ty() != *res_ty {
+                return Err(Error::TypeMismatch { expected: format!("{:?}", res_ty), found: format!("{:?}", handle.ty()) });
+            }
+            Ok(Val::Resource(handle))
+        },
+
         Type::Own(_) | Type::Borrow(_) | Type::Future(_) | Type::Stream(_) | Type::ErrorContext => {
             Err(Error::UnsupportedType("RPC does not support resources or handles".into()))
         },
@@ -61,7 +61,12 @@ pub enum FailureReason {
     ProtocolViolation(String),
     /// Application-specific domain error (e.g., auth failure, business logic violation).
     /// Contains (error_code, description) for programmatic handling.
+    /// Codes from [`crate::RESERVED_CODES`] up are neorpc's own.
     DomainSpecific(u32, String),
+    /// The remote is too busy to take the call; retry after the given delay.
+    Overloaded { retry_after_ms: u32 },
+    /// The call's deadline passed before the remote finished it.
+    DeadlineExceeded,
 }
 
 impl FailureReason {
@@ -76,6 +81,8 @@ impl FailureReason {
             Self::BadArgumentCount => "BadArgs",
             Self::ProtocolViolation(_) => "ProtoVio",
             Self::DomainSpecific(_, _) => "Domain",
+            Self::Overloaded { .. } => "Overloaded",
+            Self::DeadlineExceeded => "Deadline",
         }
     }
 
@@ -90,6 +97,8 @@ impl FailureReason {
             "BadArgs" => Ok(Self::BadArgumentCount),
             "ProtoVio" => Ok(Self::ProtocolViolation("Remote protocol violation".into())),
             "Domain" => Ok(Self::DomainSpecific(0, "Domain error".into())),
+            "Overloaded" => Ok(Self::Overloaded { retry_after_ms: 0 }),
+            "Deadline" => Ok(Self::DeadlineExceeded),
             other => Err(Error::UnknownVariant(format!("FailureReason: {}", other))),
         }
     }
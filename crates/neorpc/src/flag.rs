@@ -33,7 +33,7 @@ pub fn encode_flags_bitmap(enc: &mut Encoder, active: &[String], flags_handle: &
     };
     let all_names: Vec<&str> = flags_ty.names().collect();
     let num_flags = all_names.len();
-    let num_bytes = (num_flags + 7) / 8;
+    let num_bytes = num_flags.div_ceil(8);
 
     let mut bitmap = vec![0u8; num_bytes];
 
@@ -70,10 +70,8 @@ pub fn decode_flags_bitmap(dec: &mut Decoder, flags_handle: &Type) -> Result<Vec
         let byte_idx = idx / 8;
         let bit_idx = idx % 8;
 
-        if byte_idx < bitmap.len() {
-            if (bitmap[byte_idx] & (1 << bit_idx)) != 0 {
-                active.push(name.to_string());
-            }
+        if byte_idx < bitmap.len() && (bitmap[byte_idx] & (1 << bit_idx)) != 0 {
+            active.push(name.to_string());
         }
     }
 
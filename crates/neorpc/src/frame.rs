@@ -1,8 +1,25 @@
 //! # Protocol Frames
 //!
-//! Defines the structure of the RPC envelope (Call vs Reply).
+//! Defines the structure of the RPC envelope (Call vs Reply vs Cancel).
+//! A Notify frame is a call that wants no reply, so it carries no seq.
+//! Large successful replies may instead be split across several ReplyChunk frames.
+//! Batch and ReplyBatch frames carry several Calls or Replies in one message.
+//! Ping and Pong frames check liveness; they carry a nonce instead of a seq,
+//! so they never collide with call correlation.
+//! A Handshake frame carries a schema fingerprint, checked before any calls,
+//! and may list the call targets its sender serves.
+//!
+//! Calls also have a compact form, `CallC`, for links where both sides share
+//! the schema: a positional list `[seq, target, method, args]` instead of a keyed map.
+//! The top-level variant name tells the two forms apart, so they can be mixed on one stream.
+//!
+//! Every frame starts with a `PROTO_VERSION` byte, outside the neopack
+//! structure, ahead of its variant. The `encode` methods write the variant
+//! alone, and `into_bytes` or `frame_bytes` put the byte in front, so entries
+//! inside a Batch or ReplyBatch have none of their own; the batch's byte covers them.
 //!
 //! ## Invariants
+//! - **Version Checked**: A frame whose first byte isn't `PROTO_VERSION` is refused before anything else is read.
 //! - **Panic Safety**: All decoding paths return `Result`, never panicking on unknown data.
 //! - **Forward Compatibility**: Unknown header fields are safely skipped.
 
@@ -12,6 +29,10 @@ use crate::error::Error;
 
 use neopack::Decoder;
 use neopack::Encoder;
+use neopack::PartialListIter;
+
+/// Wire format version, written as the first byte of every frame.
+pub const PROTO_VERSION: u8 = 1;
 
 /// Encodes an outbound Call frame.
 ///
@@ -23,11 +44,21 @@ pub struct CallEncoder<'a> {
     pub method: &'a str,
     /// Pre-encoded arguments list (including list headers).
     pub args_payload: &'a [u8],
+    /// Absolute deadline in unix milliseconds, after which the caller has given up.
+    pub deadline_ms: Option<u64>,
+    /// Correlates this call with the caller's trace, across peers.
+    pub trace_id: Option<u128>,
 }
 
 impl<'a> CallEncoder<'a> {
-    pub fn new(seq: u64, target: &'a str, method: &'a str, args_payload: &'a [u8]) -> Self {
-        Self { seq, target, method, args_payload }
+    pub fn new(seq: u64, target: &'a str, method: &'a str, args_payload: &'a [u8], deadline_ms: Option<u64>) -> Self {
+        Self { seq, target, method, args_payload, deadline_ms, trace_id: None }
+    }
+
+    /// Attaches a distributed trace id to this call.
+    pub fn with_trace_id(mut self, trace_id: u128) -> Self {
+        self.trace_id = Some(trace_id);
+        self
     }
 
     /// Encode this call into the encoder.
@@ -38,6 +69,14 @@ impl<'a> CallEncoder<'a> {
         write_map_u64(enc, "seq", self.seq)?;
         write_map_str(enc, "target", self.target)?;
         write_map_str(enc, "method", self.method)?;
+        if let Some(deadline) = self.deadline_ms {
+            write_map_u64(enc, "deadline", deadline)?;
+        }
+        if let Some(trace_id) = self.trace_id {
+            enc.variant_begin("trace")?;
+            enc.bytes(&trace_id.to_le_bytes())?;
+            enc.variant_end()?;
+        }
 
         enc.variant_begin("args")?;
         enc.append_raw(self.args_payload)?;
@@ -52,7 +91,34 @@ impl<'a> CallEncoder<'a> {
     pub fn into_bytes(self) -> Result<Vec<u8>> {
         let mut enc = Encoder::new();
         self.encode(&mut enc)?;
-        enc.into_bytes().map_err(Error::from)
+        frame_bytes(enc)
+    }
+
+    /// Encode this call as a compact `CallC` frame, without map keys.
+    ///
+    /// The compact form has no room for a deadline or trace id,
+    /// so calls carrying either are rejected rather than silently stripped.
+    pub fn encode_compact(&self, enc: &mut Encoder) -> Result<()> {
+        if self.deadline_ms.is_some() || self.trace_id.is_some() {
+            return Err(Error::ProtocolViolation("Compact calls carry no deadline or trace id".into()));
+        }
+
+        enc.variant_begin("CallC")?;
+        enc.list_begin()?;
+        enc.u64(self.seq)?;
+        enc.str(self.target)?;
+        enc.str(self.method)?;
+        enc.append_raw(self.args_payload)?;
+        enc.list_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this call as a compact `CallC` frame and return the bytes directly.
+    pub fn into_compact_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode_compact(&mut enc)?;
+        frame_bytes(enc)
     }
 }
 
@@ -65,6 +131,10 @@ pub struct CallDecoder<'a> {
     pub method: &'a str,
     /// Use `decode_vals` with this decoder and the method signature.
     pub args: Decoder<'a>,
+    /// Absolute deadline in unix milliseconds, if the caller set one.
+    pub deadline_ms: Option<u64>,
+    /// The caller's distributed trace id, if it sent one.
+    pub trace_id: Option<u128>,
 }
 
 impl<'a> CallDecoder<'a> {
@@ -75,12 +145,20 @@ impl<'a> CallDecoder<'a> {
         let mut target = None;
         let mut method = None;
         let mut args_dec = None;
+        let mut deadline_ms = None;
+        let mut trace_id = None;
 
         while let Some((key, mut val)) = map.next()? {
             match key {
                 "seq" => seq = Some(val.u64()?),
                 "target" => target = Some(val.str()?),
                 "method" => method = Some(val.str()?),
+                "deadline" => deadline_ms = Some(val.u64()?),
+                "trace" => {
+                    let bytes: [u8; 16] = val.bytes()?.try_into()
+                        .map_err(|_| Error::ProtocolViolation("Trace id must be 16 bytes".into()))?;
+                    trace_id = Some(u128::from_le_bytes(bytes));
+                }
                 "args" => args_dec = Some(val),
                 _ => val.skip()?,
             }
@@ -91,6 +169,101 @@ impl<'a> CallDecoder<'a> {
             target: target.ok_or(Error::ProtocolViolation("Missing target".into()))?,
             method: method.ok_or(Error::ProtocolViolation("Missing method".into()))?,
             args: args_dec.ok_or(Error::ProtocolViolation("Missing args".into()))?,
+            deadline_ms,
+            trace_id,
+        })
+    }
+
+    /// Decode the body of a compact `CallC` frame.
+    ///
+    /// Extra trailing items are skipped, like unknown keys in the verbose form.
+    pub fn decode_compact(mut dec: Decoder<'a>) -> Result<Self> {
+        let mut items = dec.list()?;
+        let mut next = |field: &str| items.next()
+            .ok_or_else(|| Error::ProtocolViolation(format!("Missing {}", field)));
+
+        Ok(CallDecoder {
+            seq: next("seq")?.u64()?,
+            target: next("target")?.str()?,
+            method: next("method")?.str()?,
+            args: next("args")?,
+            deadline_ms: None,
+            trace_id: None,
+        })
+    }
+}
+
+/// Encodes an outbound Notify frame: a call with no seq, answered by nothing.
+///
+/// The `args_payload` is a pre-encoded neopack list of values, as for `CallEncoder`.
+pub struct NotifyEncoder<'a> {
+    pub target: &'a str,
+    pub method: &'a str,
+    /// Pre-encoded arguments list (including list headers).
+    pub args_payload: &'a [u8],
+}
+
+impl<'a> NotifyEncoder<'a> {
+    pub fn new(target: &'a str, method: &'a str, args_payload: &'a [u8]) -> Self {
+        Self { target, method, args_payload }
+    }
+
+    /// Encode this notification into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("Notify")?;
+        enc.map_begin()?;
+
+        write_map_str(enc, "target", self.target)?;
+        write_map_str(enc, "method", self.method)?;
+
+        enc.variant_begin("args")?;
+        enc.append_raw(self.args_payload)?;
+        enc.variant_end()?;
+
+        enc.map_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this notification and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Notify frame.
+///
+/// **Invariant**: The `args` decoder points to a List container containing the arguments.
+pub struct NotifyDecoder<'a> {
+    pub target: &'a str,
+    pub method: &'a str,
+    /// Use `decode_vals` with this decoder and the method signature.
+    pub args: Decoder<'a>,
+}
+
+impl<'a> NotifyDecoder<'a> {
+    /// Decode a Notify frame from the decoder.
+    pub fn decode(mut dec: Decoder<'a>) -> Result<Self> {
+        let mut map = dec.map()?;
+        let mut target = None;
+        let mut method = None;
+        let mut args_dec = None;
+
+        while let Some((key, mut val)) = map.next()? {
+            match key {
+                "target" => target = Some(val.str()?),
+                "method" => method = Some(val.str()?),
+                "args" => args_dec = Some(val),
+                _ => val.skip()?,
+            }
+        }
+
+        Ok(NotifyDecoder {
+            target: target.ok_or(Error::ProtocolViolation("Missing target".into()))?,
+            method: method.ok_or(Error::ProtocolViolation("Missing method".into()))?,
+            args: args_dec.ok_or(Error::ProtocolViolation("Missing args".into()))?,
         })
     }
 }
@@ -132,7 +305,7 @@ impl<'a> ReplyOkEncoder<'a> {
     pub fn into_bytes(self) -> Result<Vec<u8>> {
         let mut enc = Encoder::new();
         self.encode(&mut enc)?;
-        enc.into_bytes().map_err(Error::from)
+        frame_bytes(enc)
     }
 }
 
@@ -169,7 +342,7 @@ impl ReplyErrEncoder {
     pub fn into_bytes(self) -> Result<Vec<u8>> {
         let mut enc = Encoder::new();
         self.encode(&mut enc)?;
-        enc.into_bytes().map_err(Error::from)
+        frame_bytes(enc)
     }
 }
 
@@ -233,19 +406,536 @@ impl<'a> ReplyDecoder<'a> {
     }
 }
 
+/// Encodes an outbound Cancel frame.
+///
+/// Tells the remote that the caller no longer wants the result of call `seq`.
+/// The remote may abort the in-flight call; no Reply is expected either way.
+pub struct CancelEncoder {
+    pub seq: u64,
+}
+
+impl CancelEncoder {
+    pub fn new(seq: u64) -> Self {
+        Self { seq }
+    }
+
+    /// Encode this cancellation into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("Cancel")?;
+        enc.map_begin()?;
+
+        write_map_u64(enc, "seq", self.seq)?;
+
+        enc.map_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this cancellation and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Cancel frame.
+pub struct CancelDecoder {
+    pub seq: u64,
+}
+
+impl CancelDecoder {
+    /// Decode a Cancel frame from the decoder.
+    pub fn decode(mut dec: Decoder) -> Result<Self> {
+        let mut map = dec.map()?;
+        let mut seq = None;
+
+        while let Some((key, mut val)) = map.next()? {
+            match key {
+                "seq" => seq = Some(val.u64()?),
+                _ => val.skip()?,
+            }
+        }
+
+        Ok(CancelDecoder {
+            seq: seq.ok_or(Error::ProtocolViolation("Missing seq".into()))?,
+        })
+    }
+}
+
+/// Encodes one piece of a chunked success reply.
+///
+/// The concatenated payloads of all chunks for a `seq`, in index order, form the
+/// same pre-encoded results list a `ReplyOkEncoder` would carry.
+/// Small replies should keep using `ReplyOkEncoder`.
+pub struct ReplyChunkEncoder<'a> {
+    pub seq: u64,
+    pub index: u32,
+    /// Set on the final chunk of the reply.
+    pub last: bool,
+    /// A slice of the pre-encoded results list.
+    pub payload: &'a [u8],
+}
+
+impl<'a> ReplyChunkEncoder<'a> {
+    pub fn new(seq: u64, index: u32, last: bool, payload: &'a [u8]) -> Self {
+        Self { seq, index, last, payload }
+    }
+
+    /// Encode this chunk into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("ReplyChunk")?;
+        enc.map_begin()?;
+
+        write_map_u64(enc, "seq", self.seq)?;
+
+        enc.variant_begin("index")?;
+        enc.u32(self.index)?;
+        enc.variant_end()?;
+
+        enc.variant_begin("last")?;
+        enc.bool(self.last)?;
+        enc.variant_end()?;
+
+        enc.variant_begin("payload")?;
+        enc.bytes(self.payload)?;
+        enc.variant_end()?;
+
+        enc.map_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this chunk and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound ReplyChunk frame.
+///
+/// **Invariant**: `payload` is a raw fragment, not a standalone neopack item.
+/// Feed chunks to a `ChunkReassembler` to recover the results list.
+pub struct ReplyChunkDecoder<'a> {
+    pub seq: u64,
+    pub index: u32,
+    pub last: bool,
+    pub payload: &'a [u8],
+}
+
+impl<'a> ReplyChunkDecoder<'a> {
+    /// Decode a ReplyChunk frame from the decoder.
+    pub fn decode(mut dec: Decoder<'a>) -> Result<Self> {
+        let mut map = dec.map()?;
+        let mut seq = None;
+        let mut index = None;
+        let mut last = None;
+        let mut payload = None;
+
+        while let Some((key, mut val)) = map.next()? {
+            match key {
+                "seq" => seq = Some(val.u64()?),
+                "index" => index = Some(val.u32()?),
+                "last" => last = Some(val.bool()?),
+                "payload" => payload = Some(val.bytes()?),
+                _ => val.skip()?,
+            }
+        }
+
+        Ok(ReplyChunkDecoder {
+            seq: seq.ok_or(Error::ProtocolViolation("Missing seq".into()))?,
+            index: index.ok_or(Error::ProtocolViolation("Missing index".into()))?,
+            last: last.ok_or(Error::ProtocolViolation("Missing last".into()))?,
+            payload: payload.ok_or(Error::ProtocolViolation("Missing payload".into()))?,
+        })
+    }
+}
+
+/// Encodes an outbound Ping frame, asking the remote to answer with a Pong.
+pub struct PingEncoder {
+    pub nonce: u64,
+}
+
+impl PingEncoder {
+    pub fn new(nonce: u64) -> Self {
+        Self { nonce }
+    }
+
+    /// Encode this ping into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        encode_probe(enc, "Ping", self.nonce)
+    }
+
+    /// Encode this ping and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Ping frame.
+pub struct PingDecoder {
+    pub nonce: u64,
+}
+
+impl PingDecoder {
+    /// Decode a Ping frame from the decoder.
+    pub fn decode(dec: Decoder) -> Result<Self> {
+        Ok(Self { nonce: decode_probe(dec)? })
+    }
+}
+
+/// Encodes an outbound Pong frame, echoing the nonce of the Ping it answers.
+pub struct PongEncoder {
+    pub nonce: u64,
+}
+
+impl PongEncoder {
+    pub fn new(nonce: u64) -> Self {
+        Self { nonce }
+    }
+
+    /// Encode this pong into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        encode_probe(enc, "Pong", self.nonce)
+    }
+
+    /// Encode this pong and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Pong frame.
+pub struct PongDecoder {
+    pub nonce: u64,
+}
+
+impl PongDecoder {
+    /// Decode a Pong frame from the decoder.
+    pub fn decode(dec: Decoder) -> Result<Self> {
+        Ok(Self { nonce: decode_probe(dec)? })
+    }
+}
+
+/// Encodes an outbound Handshake frame, announcing the sender's schema fingerprint.
+///
+/// The fingerprint comes from `crate::schema::fingerprint()`. The sender may
+/// also list the call targets it serves, so the remote knows what it can reach.
+pub struct HandshakeEncoder {
+    pub fingerprint: u64,
+    /// Call targets the sender serves; omitted from the frame when empty.
+    pub targets: Vec<String>,
+}
+
+impl HandshakeEncoder {
+    pub fn new(fingerprint: u64) -> Self {
+        Self { fingerprint, targets: Vec::new() }
+    }
+
+    /// Advertises the call targets the sender serves.
+    pub fn with_targets(mut self, targets: Vec<String>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Encode this handshake into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("Handshake")?;
+        enc.map_begin()?;
+        write_map_u64(enc, "fingerprint", self.fingerprint)?;
+        if !self.targets.is_empty() {
+            enc.variant_begin("targets")?;
+            enc.list_begin()?;
+            for target in &self.targets {
+                enc.str(target)?;
+            }
+            enc.list_end()?;
+            enc.variant_end()?;
+        }
+        enc.map_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this handshake and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Handshake frame.
+pub struct HandshakeDecoder {
+    pub fingerprint: u64,
+    /// Call targets the sender serves, empty if it listed none.
+    pub targets: Vec<String>,
+}
+
+impl HandshakeDecoder {
+    /// Decode a Handshake frame from the decoder.
+    pub fn decode(mut dec: Decoder) -> Result<Self> {
+        let mut map = dec.map()?;
+        let mut fingerprint = None;
+        let mut targets = Vec::new();
+
+        while let Some((key, mut val)) = map.next()? {
+            match key {
+                "fingerprint" => fingerprint = Some(val.u64()?),
+                "targets" => {
+                    for mut target in val.list()? {
+                        targets.push(target.str()?.to_string());
+                    }
+                }
+                _ => val.skip()?,
+            }
+        }
+
+        Ok(Self {
+            fingerprint: fingerprint.ok_or(Error::ProtocolViolation("Missing fingerprint".into()))?,
+            targets,
+        })
+    }
+
+    /// Checks the remote's fingerprint against our own.
+    ///
+    /// A mismatch is the failure to reply with, `crate::schema::schema_mismatch()`.
+    pub fn verify(&self, local: u64) -> std::result::Result<(), FailureReason> {
+        if self.fingerprint == local {
+            Ok(())
+        } else {
+            Err(crate::schema::schema_mismatch())
+        }
+    }
+}
+
+/// Encodes several Calls into one Batch frame, to save round trips.
+///
+/// Each entry is a complete Call frame, so a remote can answer them
+/// one by one or gather the replies into a `ReplyBatchEncoder`.
+#[derive(Default)]
+pub struct BatchCallEncoder<'a> {
+    pub calls: Vec<CallEncoder<'a>>,
+}
+
+impl<'a> BatchCallEncoder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a call to the batch.
+    pub fn push(&mut self, call: CallEncoder<'a>) {
+        self.calls.push(call);
+    }
+
+    /// Encode this batch into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("Batch")?;
+        enc.list_begin()?;
+        for call in &self.calls {
+            call.encode(enc)?;
+        }
+        enc.list_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this batch and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound Batch frame, one Call at a time.
+///
+/// **Invariant**: Entries are length-delimited, so a malformed Call fails
+/// only its own entry; iteration carries on with the next. Only an entry
+/// whose framing is broken ends the batch, since nothing after it can be found.
+pub struct BatchDecoder<'a> {
+    entries: BatchEntries<'a>,
+}
+
+impl<'a> BatchDecoder<'a> {
+    /// Decode a Batch frame from the decoder.
+    pub fn decode(dec: Decoder<'a>) -> Result<Self> {
+        Ok(Self { entries: BatchEntries::decode(dec)? })
+    }
+}
+
+impl<'a> Iterator for BatchDecoder<'a> {
+    /// A Call, or a `ProtocolViolation` naming the index of the bad entry.
+    type Item = Result<CallDecoder<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next_frame("Call", CallDecoder::decode)
+    }
+}
+
+/// A Reply queued in a `ReplyBatchEncoder`.
+enum ReplyEntry<'a> {
+    Ok(ReplyOkEncoder<'a>),
+    Err(ReplyErrEncoder),
+}
+
+/// Encodes several Replies into one ReplyBatch frame.
+///
+/// Replies may be in any order and mix successes with failures;
+/// the caller pairs them up by `seq` as usual.
+#[derive(Default)]
+pub struct ReplyBatchEncoder<'a> {
+    replies: Vec<ReplyEntry<'a>>,
+}
+
+impl<'a> ReplyBatchEncoder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a success reply with a pre-encoded results list.
+    pub fn push_ok(&mut self, seq: u64, results_payload: &'a [u8]) {
+        self.replies.push(ReplyEntry::Ok(ReplyOkEncoder::new(seq, results_payload)));
+    }
+
+    /// Appends a failure reply.
+    pub fn push_err(&mut self, seq: u64, reason: FailureReason) {
+        self.replies.push(ReplyEntry::Err(ReplyErrEncoder::new(seq, reason)));
+    }
+
+    /// Encode this batch into the encoder.
+    pub fn encode(&self, enc: &mut Encoder) -> Result<()> {
+        enc.variant_begin("ReplyBatch")?;
+        enc.list_begin()?;
+        for reply in &self.replies {
+            match reply {
+                ReplyEntry::Ok(ok) => ok.encode(enc)?,
+                ReplyEntry::Err(err) => err.encode(enc)?,
+            }
+        }
+        enc.list_end()?;
+        enc.variant_end()?;
+        Ok(())
+    }
+
+    /// Encode this batch and return the bytes directly.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        let mut enc = Encoder::new();
+        self.encode(&mut enc)?;
+        frame_bytes(enc)
+    }
+}
+
+/// Decodes an inbound ReplyBatch frame, one Reply at a time.
+///
+/// Entries fail independently, as in `BatchDecoder`.
+pub struct ReplyBatchDecoder<'a> {
+    entries: BatchEntries<'a>,
+}
+
+impl<'a> ReplyBatchDecoder<'a> {
+    /// Decode a ReplyBatch frame from the decoder.
+    pub fn decode(dec: Decoder<'a>) -> Result<Self> {
+        Ok(Self { entries: BatchEntries::decode(dec)? })
+    }
+}
+
+impl<'a> Iterator for ReplyBatchDecoder<'a> {
+    /// A Reply, or a `ProtocolViolation` naming the index of the bad entry.
+    type Item = Result<ReplyDecoder<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next_frame("Reply", ReplyDecoder::decode)
+    }
+}
+
+/// The list of frames inside a Batch or ReplyBatch.
+struct BatchEntries<'a> {
+    list: PartialListIter<'a>,
+    index: usize,
+    broken: bool,
+}
+
+impl<'a> BatchEntries<'a> {
+    fn decode(mut dec: Decoder<'a>) -> Result<Self> {
+        let list = dec.try_list()?;
+        if !list.is_complete() {
+            return Err(Error::ProtocolViolation("Truncated batch".into()));
+        }
+        Ok(Self { list, index: 0, broken: false })
+    }
+
+    /// Decodes the next entry, which must be a `tag` frame.
+    fn next_frame<T>(
+        &mut self,
+        tag: &str,
+        decode: impl FnOnce(Decoder<'a>) -> Result<T>,
+    ) -> Option<Result<T>> {
+        if self.broken {
+            return None;
+        }
+        let index = self.index;
+        let violation = |e: &dyn std::fmt::Display| {
+            Error::ProtocolViolation(format!("Batch entry {}: {}", index, e))
+        };
+
+        let mut entry = match self.list.next() {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return None,
+            Err(e) => {
+                self.broken = true;
+                return Some(Err(violation(&e)));
+            }
+        };
+        self.index += 1;
+
+        let frame = entry.variant().map_err(Error::from).and_then(|(found, body)| {
+            if found != tag {
+                return Err(Error::UnknownVariant(format!("expected {}, found {}", tag, found)));
+            }
+            decode(body)
+        });
+        Some(frame.map_err(|e| violation(&e)))
+    }
+}
+
 /// Top-level frame decoder.
 pub enum RpcFrame<'a> {
+    /// Either a verbose `Call` or a compact `CallC`.
     Call(CallDecoder<'a>),
+    Notify(NotifyDecoder<'a>),
     Reply(ReplyDecoder<'a>),
+    Cancel(CancelDecoder),
+    ReplyChunk(ReplyChunkDecoder<'a>),
+    Batch(BatchDecoder<'a>),
+    ReplyBatch(ReplyBatchDecoder<'a>),
+    Ping(PingDecoder),
+    Pong(PongDecoder),
+    Handshake(HandshakeDecoder),
 }
 
 impl<'a> RpcFrame<'a> {
-    /// Decode an RPC frame from the decoder.
-    pub fn decode(dec: &mut Decoder<'a>) -> Result<Self> {
-        let (msg_type, body) = dec.variant()?;
+    /// Decode an RPC frame, starting with its version byte.
+    pub fn decode(frame: &'a [u8]) -> Result<Self> {
+        let (msg_type, body) = Decoder::new(frame_body(frame)?).variant()?;
         match msg_type {
             "Call" => Ok(RpcFrame::Call(CallDecoder::decode(body)?)),
+            "CallC" => Ok(RpcFrame::Call(CallDecoder::decode_compact(body)?)),
+            "Notify" => Ok(RpcFrame::Notify(NotifyDecoder::decode(body)?)),
             "Reply" => Ok(RpcFrame::Reply(ReplyDecoder::decode(body)?)),
+            "Cancel" => Ok(RpcFrame::Cancel(CancelDecoder::decode(body)?)),
+            "ReplyChunk" => Ok(RpcFrame::ReplyChunk(ReplyChunkDecoder::decode(body)?)),
+            "Batch" => Ok(RpcFrame::Batch(BatchDecoder::decode(body)?)),
+            "ReplyBatch" => Ok(RpcFrame::ReplyBatch(ReplyBatchDecoder::decode(body)?)),
+            "Ping" => Ok(RpcFrame::Ping(PingDecoder::decode(body)?)),
+            "Pong" => Ok(RpcFrame::Pong(PongDecoder::decode(body)?)),
+            "Handshake" => Ok(RpcFrame::Handshake(HandshakeDecoder::decode(body)?)),
             _ => Err(Error::UnknownVariant(format!("Top-level frame: {}", msg_type))),
         }
     }
@@ -253,15 +943,20 @@ impl<'a> RpcFrame<'a> {
 
 /// Decodes just the sequence number from a raw frame.
 /// This is useful for routing replies when the full decoding might fail.
+/// Batch frames carry one seq per entry, and Notify/Ping/Pong/Handshake carry none, so they are rejected here.
 pub fn decode_seq(bytes: &[u8]) -> Result<u64> {
-    let mut dec = Decoder::new(bytes);
-    let (msg_type, mut body) = dec.variant()?;
+    let (msg_type, mut body) = Decoder::new(frame_body(bytes)?).variant()?;
     let mut map = match msg_type {
-        "Call" => body.map()?,
+        "CallC" => return CallDecoder::decode_compact(body).map(|call| call.seq),
+        "Call" | "Cancel" | "ReplyChunk" => body.map()?,
         "Reply" => match body.result()? {
             Ok(mut ok_body) => ok_body.map()?,
             Err(mut err_body) => err_body.map()?,
         },
+        "Batch" | "ReplyBatch" => return Err(Error::ProtocolViolation("Batch frames have no single seq".into())),
+        "Notify" => return Err(Error::ProtocolViolation("Notify frames have no seq".into())),
+        "Ping" | "Pong" => return Err(Error::ProtocolViolation("Ping frames have no seq".into())),
+        "Handshake" => return Err(Error::ProtocolViolation("Handshake frames have no seq".into())),
         _ => return Err(Error::UnknownVariant(format!("Top-level frame: {}", msg_type))),
     };
 
@@ -276,8 +971,24 @@ pub fn decode_seq(bytes: &[u8]) -> Result<u64> {
     Err(Error::ProtocolViolation("Missing seq".into()))
 }
 
+/// Finishes a frame written into `enc` by an `encode` method, putting the version byte in front.
+pub fn frame_bytes(enc: Encoder) -> Result<Vec<u8>> {
+    let mut bytes = enc.into_bytes()?;
+    bytes.insert(0, PROTO_VERSION);
+    Ok(bytes)
+}
+
 // Helper functions
 
+/// Checks a frame's version byte and returns what follows it.
+fn frame_body(frame: &[u8]) -> Result<&[u8]> {
+    match frame.split_first() {
+        Some((&PROTO_VERSION, body)) => Ok(body),
+        Some((other, _)) => Err(Error::ProtocolViolation(format!("version mismatch: got {}", other))),
+        None => Err(neopack::Error::UnexpectedEnd.into()),
+    }
+}
+
 fn write_map_u64(enc: &mut Encoder, key: &str, val: u64) -> Result<()> {
     enc.variant_begin(key)?;
     enc.u64(val)?;
@@ -292,6 +1003,31 @@ fn write_map_str(enc: &mut Encoder, key: &str, val: &str) -> Result<()> {
     Ok(())
 }
 
+/// Encode a Ping or Pong, which differ only in tag.
+fn encode_probe(enc: &mut Encoder, tag: &str, nonce: u64) -> Result<()> {
+    enc.variant_begin(tag)?;
+    enc.map_begin()?;
+    write_map_u64(enc, "nonce", nonce)?;
+    enc.map_end()?;
+    enc.variant_end()?;
+    Ok(())
+}
+
+/// Decode the body of a Ping or Pong.
+fn decode_probe(mut dec: Decoder) -> Result<u64> {
+    let mut map = dec.map()?;
+    let mut nonce = None;
+
+    while let Some((key, mut val)) = map.next()? {
+        match key {
+            "nonce" => nonce = Some(val.u64()?),
+            _ => val.skip()?,
+        }
+    }
+
+    nonce.ok_or(Error::ProtocolViolation("Missing nonce".into()))
+}
+
 /// Encode a unit variant (variant with no payload).
 fn encode_unit_variant(enc: &mut Encoder, tag: &str) -> Result<()> {
     enc.variant_begin(tag)?;
@@ -300,14 +1036,7 @@ fn encode_unit_variant(enc: &mut Encoder, tag: &str) -> Result<()> {
     Ok(())
 }
 
-/// Decode a unit variant and return its tag.
-fn decode_unit_variant<'a>(dec: &mut Decoder<'a>) -> Result<&'a str> {
-    let (tag, mut body) = dec.variant()?;
-    body.unit()?;
-    Ok(tag)
-}
-
-/// Encode a FailureReason, including any payload for DomainSpecific.
+/// Encode a FailureReason, including any payload for DomainSpecific and Overloaded.
 fn encode_failure_reason(enc: &mut Encoder, reason: &FailureReason) -> Result<()> {
     match reason {
         FailureReason::DomainSpecific(code, msg) => {
@@ -318,6 +1047,11 @@ fn encode_failure_reason(enc: &mut Encoder, reason: &FailureReason) -> Result<()
             enc.list_end()?;
             enc.variant_end()?;
         }
+        FailureReason::Overloaded { retry_after_ms } => {
+            enc.variant_begin("Overloaded")?;
+            enc.u32(*retry_after_ms)?;
+            enc.variant_end()?;
+        }
         _ => {
             encode_unit_variant(enc, reason.as_tag())?;
         }
@@ -325,7 +1059,7 @@ fn encode_failure_reason(enc: &mut Encoder, reason: &FailureReason) -> Result<()
     Ok(())
 }
 
-/// Decode a FailureReason, including any payload for DomainSpecific.
+/// Decode a FailureReason, including any payload for DomainSpecific and Overloaded.
 fn decode_failure_reason(dec: &mut Decoder) -> Result<FailureReason> {
     let (tag, mut body) = dec.variant()?;
     match tag {
@@ -337,6 +1071,10 @@ fn decode_failure_reason(dec: &mut Decoder) -> Result<FailureReason> {
             let msg = msg_dec.str()?.to_string();
             Ok(FailureReason::DomainSpecific(code, msg))
         }
+        "Overloaded" => {
+            let retry_after_ms = body.u32()?;
+            Ok(FailureReason::Overloaded { retry_after_ms })
+        }
         _ => {
             body.unit()?;
             FailureReason::from_tag(tag)
@@ -11,7 +11,9 @@
 mod error;
 mod codec;
 mod frame;
+mod chunk;
 mod flag;
+mod schema;
 
 #[cfg(test)]
 mod tests;
@@ -20,15 +22,49 @@ pub use error::Error;
 pub use error::FailureReason;
 pub use error::Result;
 pub use frame::RpcFrame;
+pub use frame::PROTO_VERSION;
+pub use frame::frame_bytes;
 pub use frame::CallEncoder;
 pub use frame::CallDecoder;
+pub use frame::NotifyEncoder;
+pub use frame::NotifyDecoder;
 pub use frame::ReplyOkEncoder;
 pub use frame::ReplyErrEncoder;
 pub use frame::ReplyDecoder;
+pub use frame::CancelEncoder;
+pub use frame::CancelDecoder;
+pub use frame::ReplyChunkEncoder;
+pub use frame::ReplyChunkDecoder;
+pub use frame::BatchCallEncoder;
+pub use frame::BatchDecoder;
+pub use frame::ReplyBatchEncoder;
+pub use frame::ReplyBatchDecoder;
+pub use frame::PingEncoder;
+pub use frame::PingDecoder;
+pub use frame::PongEncoder;
+pub use frame::PongDecoder;
+pub use frame::HandshakeEncoder;
+pub use frame::HandshakeDecoder;
 pub use frame::decode_seq;
+pub use chunk::ChunkReassembler;
+pub use chunk::DEFAULT_MAX_CHUNKS;
+pub use chunk::DEFAULT_MAX_BYTES;
+pub use schema::fingerprint;
+pub use schema::schema_mismatch;
+pub use schema::MethodSchema;
+pub use schema::RESERVED_CODES;
+pub use schema::SCHEMA_MISMATCH;
+pub use schema::unknown_target;
+pub use schema::UNKNOWN_TARGET;
 pub use codec::encode_val;
 pub use codec::encode_vals_to_bytes;
+pub use codec::encode_val_with;
 pub use codec::decode_val;
 pub use codec::decode_vals;
+pub use codec::decode_val_lenient;
+pub use codec::UnknownPolicy;
+pub use codec::decode_val_with;
+pub use codec::CodecOptions;
+pub use codec::ResourceTokens;
 pub use flag::encode_flags_bitmap;
 pub use flag::decode_flags_bitmap;
@@ -0,0 +1,166 @@
+//! # Schema Fingerprints
+//!
+//! Hashes a set of method signatures into one 64-bit fingerprint,
+//! which peers exchange in a Handshake frame before making calls.
+//! Two sides with equal fingerprints agree on every interface, method,
+//! and type, so values decode the way they were encoded.
+//!
+//! ## Invariants
+//! - **Deterministic**: The hash is FNV-1a over a canonical rendering, never `std`'s seeded hasher.
+//! - **Order-Free**: Methods are sorted first, so the order they are listed in doesn't matter.
+
+use wasmtime::component::Type;
+
+use crate::error::FailureReason;
+
+/// Domain error codes from here up are reserved for neorpc's own failures,
+/// like [`SCHEMA_MISMATCH`] and [`UNKNOWN_TARGET`]; applications can't use them.
+pub const RESERVED_CODES: u32 = 0xFFFF_0000;
+
+/// Domain error code for a Reply refused because the peers' schemas differ.
+pub const SCHEMA_MISMATCH: u32 = 0xFFFF_0001;
+
+/// The failure a peer answers with when the caller's fingerprint doesn't match its own.
+pub fn schema_mismatch() -> FailureReason {
+    FailureReason::DomainSpecific(SCHEMA_MISMATCH, "schema mismatch".into())
+}
+
+/// Domain error code for a Call naming a target its receiver doesn't serve.
+pub const UNKNOWN_TARGET: u32 = 0xFFFF_0002;
+
+/// The failure a peer answers with when a Call's target isn't one it serves.
+pub fn unknown_target() -> FailureReason {
+    FailureReason::DomainSpecific(UNKNOWN_TARGET, "unknown target".into())
+}
+
+/// One method's signature, as it goes into a fingerprint.
+#[derive(Clone, Copy, Debug)]
+pub struct MethodSchema<'a> {
+    pub interface: &'a str,
+    pub method: &'a str,
+    pub params: &'a [Type],
+    pub results: &'a [Type],
+}
+
+/// Computes the fingerprint of a set of methods, in any order.
+pub fn fingerprint<'a>(methods: impl IntoIterator<Item = MethodSchema<'a>>) -> u64 {
+    let mut methods: Vec<_> = methods.into_iter().collect();
+    methods.sort_by(|a, b| (a.interface, a.method).cmp(&(b.interface, b.method)));
+
+    let mut canonical = String::new();
+    for method in methods {
+        canonical.push_str(method.interface);
+        canonical.push('#');
+        canonical.push_str(method.method);
+        write_types(&mut canonical, method.params);
+        canonical.push_str("->");
+        write_types(&mut canonical, method.results);
+        canonical.push(';');
+    }
+    fnv1a(canonical.as_bytes())
+}
+
+/// 64-bit FNV-1a, which is stable across runs and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME))
+}
+
+fn write_types(out: &mut String, types: &[Type]) {
+    out.push('(');
+    for (i, ty) in types.iter().enumerate() {
+        if i > 0 { out.push(','); }
+        write_type(out, ty);
+    }
+    out.push(')');
+}
+
+/// Renders a type with every name that affects its wire encoding.
+fn write_type(out: &mut String, ty: &Type) {
+    match ty {
+        Type::Bool => out.push_str("bool"),
+        Type::S8 => out.push_str("s8"),
+        Type::U8 => out.push_str("u8"),
+        Type::S16 => out.push_str("s16"),
+        Type::U16 => out.push_str("u16"),
+        Type::S32 => out.push_str("s32"),
+        Type::U32 => out.push_str("u32"),
+        Type::S64 => out.push_str("s64"),
+        Type::U64 => out.push_str("u64"),
+        Type::Float32 => out.push_str("f32"),
+        Type::Float64 => out.push_str("f64"),
+        Type::Char => out.push_str("char"),
+        Type::String => out.push_str("string"),
+        Type::List(h) => {
+            out.push_str("list<");
+            write_type(out, &h.ty());
+            out.push('>');
+        }
+        Type::Option(h) => {
+            out.push_str("option<");
+            write_type(out, &h.ty());
+            out.push('>');
+        }
+        Type::Tuple(h) => {
+            out.push_str("tuple");
+            write_types(out, &h.types().collect::<Vec<_>>());
+        }
+        Type::Result(h) => {
+            out.push_str("result<");
+            write_optional(out, h.ok());
+            out.push(',');
+            write_optional(out, h.err());
+            out.push('>');
+        }
+        Type::Record(h) => {
+            out.push_str("record{");
+            for field in h.fields() {
+                out.push_str(field.name);
+                out.push(':');
+                write_type(out, &field.ty);
+                out.push(',');
+            }
+            out.push('}');
+        }
+        Type::Variant(h) => {
+            out.push_str("variant{");
+            for case in h.cases() {
+                out.push_str(case.name);
+                if let Some(ty) = &case.ty {
+                    out.push(':');
+                    write_type(out, ty);
+                }
+                out.push(',');
+            }
+            out.push('}');
+        }
+        Type::Enum(h) => {
+            out.push_str("enum{");
+            for name in h.names() {
+                out.push_str(name);
+                out.push(',');
+            }
+            out.push('}');
+        }
+        Type::Flags(h) => {
+            out.push_str("flags{");
+            for name in h.names() {
+                out.push_str(name);
+                out.push(',');
+            }
+            out.push('}');
+        }
+        Type::Own(_) | Type::Borrow(_) => out.push_str("resource"),
+        Type::Future(_) => out.push_str("future"),
+        Type::Stream(_) => out.push_str("stream"),
+        Type::ErrorContext => out.push_str("error-context"),
+    }
+}
+
+fn write_optional(out: &mut String, ty: Option<Type>) {
+    match ty {
+        Some(ty) => write_type(out, &ty),
+        None => out.push('_'),
+    }
+}
@@ -225,15 +225,15 @@ fn test_rpc_call_roundtrip() {
 
     let mut enc = Encoder::new();
     let args_bytes = encode_vals_to_bytes(&args).unwrap();
-    CallEncoder::new(1, "svc", "method", &args_bytes).encode(&mut enc).unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    CallEncoder::new(1, "svc", "method", &args_bytes, None).encode(&mut enc).unwrap();
+    let bytes = frame_bytes(enc).unwrap();
 
-    let mut dec = Decoder::new(&bytes);
-    match RpcFrame::decode(&mut dec).unwrap() {
+    match RpcFrame::decode(&bytes).unwrap() {
         RpcFrame::Call(c) => {
             assert_eq!(c.seq, 1);
             assert_eq!(c.target, "svc");
             assert_eq!(c.method, "method");
+            assert_eq!(c.deadline_ms, None);
             // c.args is now a Decoder, not c.args_decoder
             let d_args = decode_vals(c.args, &arg_types).unwrap();
             assert_eq!(format!("{:?}", args), format!("{:?}", d_args));
@@ -251,10 +251,9 @@ fn test_rpc_reply_success_roundtrip() {
     let mut enc = Encoder::new();
     let results_bytes = encode_vals_to_bytes(&results).unwrap();
     ReplyOkEncoder::new(2, &results_bytes).encode(&mut enc).unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
 
-    let mut dec = Decoder::new(&bytes);
-    match RpcFrame::decode(&mut dec).unwrap() {
+    match RpcFrame::decode(&bytes).unwrap() {
         RpcFrame::Reply(r) => {
             assert_eq!(r.seq, 2);
             let val_dec = r.status.expect("Expected Success");
@@ -270,10 +269,9 @@ fn test_rpc_reply_failure_roundtrip() {
     let mut enc = Encoder::new();
     // ReplyErrEncoder takes failure reason by value
     ReplyErrEncoder::new(3, FailureReason::OutOfFuel).encode(&mut enc).unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
 
-    let mut dec = Decoder::new(&bytes);
-    match RpcFrame::decode(&mut dec).unwrap() {
+    match RpcFrame::decode(&bytes).unwrap() {
         RpcFrame::Reply(r) => {
             assert_eq!(r.seq, 3);
             match r.status {
@@ -286,17 +284,486 @@ fn test_rpc_reply_failure_roundtrip() {
 }
 
 #[test]
-fn test_rpc_sequence_skippable() {
+fn test_rpc_reply_overloaded_roundtrip() {
+    let reason = FailureReason::Overloaded { retry_after_ms: 250 };
+    let bytes = ReplyErrEncoder::new(4, reason.clone()).into_bytes().unwrap();
+
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Reply(r) => {
+            assert_eq!(r.seq, 4);
+            assert_eq!(r.status.err(), Some(reason));
+        }
+        _ => panic!("Expected Reply"),
+    }
+}
+
+#[test]
+fn test_rpc_reply_deadline_exceeded_roundtrip() {
+    let bytes = ReplyErrEncoder::new(9, FailureReason::DeadlineExceeded).into_bytes().unwrap();
+
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Reply(r) => {
+            assert_eq!(r.seq, 9);
+            assert_eq!(r.status.err(), Some(FailureReason::DeadlineExceeded));
+        }
+        _ => panic!("Expected Reply"),
+    }
+    assert_eq!(FailureReason::from_tag(FailureReason::DeadlineExceeded.as_tag()).unwrap(), FailureReason::DeadlineExceeded);
+    // A peer predating a tag rejects it cleanly
+    assert!(matches!(FailureReason::from_tag("Expired"), Err(Error::UnknownVariant(_))));
+}
+
+#[test]
+fn test_err_unknown_failure_reason_with_payload() {
+    // A reason added by a newer peer, carrying a payload this side cannot interpret.
     let mut enc = Encoder::new();
+    enc.variant_begin("Reply").unwrap();
+    enc.result_err_begin().unwrap();
+    enc.map_begin().unwrap();
+    write_map_u64(&mut enc, "seq", 5).unwrap();
+    enc.variant_begin("reason").unwrap();
+    enc.variant_begin("Throttled").unwrap();
+    enc.u32(250).unwrap();
+    enc.variant_end().unwrap();
+    enc.variant_end().unwrap();
+    enc.map_end().unwrap();
+    enc.result_err_end().unwrap();
+    enc.variant_end().unwrap();
+
+    let bytes = frame_bytes(enc).unwrap();
+    assert!(RpcFrame::decode(&bytes).is_err());
+    assert_eq!(decode_seq(&bytes).unwrap(), 5);
+}
+
+#[test]
+fn test_rpc_sequence_skippable() {
     let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
-    CallEncoder::new(1, "a", "b", &empty_bytes).encode(&mut enc).unwrap();
-    ReplyErrEncoder::new(1, FailureReason::AppTrapped).encode(&mut enc).unwrap();
+    let call = CallEncoder::new(1, "a", "b", &empty_bytes, None).into_bytes().unwrap();
+    let reply = ReplyErrEncoder::new(1, FailureReason::AppTrapped).into_bytes().unwrap();
 
-    let bytes = enc.into_bytes().unwrap();
-    let mut dec = Decoder::new(&bytes);
+    assert!(matches!(RpcFrame::decode(&call).unwrap(), RpcFrame::Call(_)));
+    assert!(matches!(RpcFrame::decode(&reply).unwrap(), RpcFrame::Reply(_)));
+}
+
+#[test]
+fn test_rpc_call_deadline() {
+    let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
+    let bytes = CallEncoder::new(5, "svc", "m", &empty_bytes, Some(1_700_000_000_123)).into_bytes().unwrap();
+    assert_eq!(decode_seq(&bytes).unwrap(), 5);
+
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Call(c) => {
+            assert_eq!(c.deadline_ms, Some(1_700_000_000_123));
+            assert!(decode_vals(c.args, &[]).unwrap().is_empty());
+        }
+        _ => panic!("Expected Call"),
+    }
 
-    assert!(matches!(RpcFrame::decode(&mut dec).unwrap(), RpcFrame::Call(_)));
-    assert!(matches!(RpcFrame::decode(&mut dec).unwrap(), RpcFrame::Reply(_)));
+    let bytes = CallEncoder::new(6, "svc", "m", &empty_bytes, None).into_bytes().unwrap();
+    assert_eq!(decode_seq(&bytes).unwrap(), 6);
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Call(c) => assert_eq!(c.deadline_ms, None),
+        _ => panic!("Expected Call"),
+    }
+}
+
+#[test]
+fn test_rpc_call_trace_id() {
+    let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
+    let trace_id = u128::MAX - 7;
+    let bytes = CallEncoder::new(8, "svc", "m", &empty_bytes, None)
+        .with_trace_id(trace_id)
+        .into_bytes()
+        .unwrap();
+    assert_eq!(decode_seq(&bytes).unwrap(), 8);
+
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Call(c) => {
+            assert_eq!(c.trace_id, Some(trace_id));
+            assert!(decode_vals(c.args, &[]).unwrap().is_empty());
+        }
+        _ => panic!("Expected Call"),
+    }
+
+    let bytes = CallEncoder::new(9, "svc", "m", &empty_bytes, None).into_bytes().unwrap();
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Call(c) => assert_eq!(c.trace_id, None),
+        _ => panic!("Expected Call"),
+    }
+}
+
+#[test]
+fn test_rpc_call_compact() {
+    let ctx = TypeContext::new(r#"(type $t (list u32))"#, &["t"]);
+    let arg_types = vec![ctx.get(0)];
+    let args = vec![Val::List(vec![Val::U32(1), Val::U32(2)])];
+    let args_bytes = encode_vals_to_bytes(&args).unwrap();
+
+    let verbose = CallEncoder::new(12, "svc", "method", &args_bytes, None).into_bytes().unwrap();
+    let compact = CallEncoder::new(12, "svc", "method", &args_bytes, None).into_compact_bytes().unwrap();
+    assert!(compact.len() < verbose.len(), "compact {} >= verbose {}", compact.len(), verbose.len());
+    assert_eq!(decode_seq(&compact).unwrap(), 12);
+
+    // Both forms decode to the same call
+    for bytes in [&compact, &verbose] {
+        match RpcFrame::decode(bytes).unwrap() {
+            RpcFrame::Call(c) => {
+                assert_eq!(c.seq, 12);
+                assert_eq!(c.target, "svc");
+                assert_eq!(c.method, "method");
+                assert_eq!(c.deadline_ms, None);
+                let d_args = decode_vals(c.args, &arg_types).unwrap();
+                assert_eq!(format!("{:?}", args), format!("{:?}", d_args));
+            }
+            _ => panic!("Expected Call"),
+        }
+    }
+
+    // Fields the compact form can't carry are refused, not dropped
+    let err = CallEncoder::new(13, "svc", "m", &args_bytes, Some(5)).into_compact_bytes();
+    assert!(matches!(err, Err(Error::ProtocolViolation(_))));
+}
+
+#[test]
+fn test_err_compact_call_truncated() {
+    let mut enc = Encoder::new();
+    enc.variant_begin("CallC").unwrap();
+    enc.list_begin().unwrap();
+    enc.u64(3).unwrap();
+    enc.str("svc").unwrap();
+    enc.list_end().unwrap();
+    enc.variant_end().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
+
+    let err = RpcFrame::decode(&bytes);
+    assert!(matches!(err, Err(Error::ProtocolViolation(msg)) if msg == "Missing method"));
+}
+
+#[test]
+fn test_rpc_cancel_roundtrip() {
+    let bytes = CancelEncoder::new(77).into_bytes().unwrap();
+    assert_eq!(decode_seq(&bytes).unwrap(), 77);
+
+    match RpcFrame::decode(&bytes).unwrap() {
+        RpcFrame::Cancel(c) => assert_eq!(c.seq, 77),
+        _ => panic!("Expected Cancel"),
+    }
+}
+
+#[test]
+fn test_rpc_notify_roundtrip() {
+    let args = encode_vals_to_bytes(&[Val::String("shipped".into()), Val::U32(3)]).unwrap();
+    let bytes = NotifyEncoder::new("log", "append", &args).into_bytes().unwrap();
+    assert!(matches!(decode_seq(&bytes), Err(Error::ProtocolViolation(_))));
+
+    let RpcFrame::Notify(notify) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Notify");
+    };
+    assert_eq!(notify.target, "log");
+    assert_eq!(notify.method, "append");
+    let vals = decode_vals(notify.args, &[Type::String, Type::U32]).unwrap();
+    assert_eq!(vals, vec![Val::String("shipped".into()), Val::U32(3)]);
+}
+
+#[test]
+fn test_err_notify_missing_args() {
+    let mut enc = Encoder::new();
+    enc.variant_begin("Notify").unwrap();
+    enc.map_begin().unwrap();
+    write_map_str(&mut enc, "target", "log").unwrap();
+    write_map_str(&mut enc, "method", "append").unwrap();
+    enc.map_end().unwrap();
+    enc.variant_end().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
+
+    let err = RpcFrame::decode(&bytes);
+    assert!(matches!(err, Err(Error::ProtocolViolation(msg)) if msg == "Missing args"));
+}
+
+#[test]
+fn test_err_unknown_frame_type() {
+    let mut enc = Encoder::new();
+    enc.variant_begin("Subscribe").unwrap();
+    enc.map_begin().unwrap();
+    write_map_u64(&mut enc, "seq", 3).unwrap();
+    enc.map_end().unwrap();
+    enc.variant_end().unwrap();
+
+    let bytes = frame_bytes(enc).unwrap();
+    match RpcFrame::decode(&bytes) {
+        Err(Error::UnknownVariant(msg)) => assert!(msg.contains("Subscribe")),
+        _ => panic!("Expected UnknownVariant"),
+    }
+    assert!(matches!(decode_seq(&bytes), Err(Error::UnknownVariant(_))));
+}
+
+fn decode_chunk(bytes: &[u8]) -> ReplyChunkDecoder<'_> {
+    match RpcFrame::decode(bytes).unwrap() {
+        RpcFrame::ReplyChunk(c) => c,
+        _ => panic!("Expected ReplyChunk"),
+    }
+}
+
+#[test]
+fn test_rpc_reply_chunks_out_of_order() {
+    let results = encode_vals_to_bytes(&[Val::String("a".repeat(100))]).unwrap();
+    let (a, rest) = results.split_at(40);
+    let (b, c) = rest.split_at(40);
+
+    let frames = [
+        ReplyChunkEncoder::new(9, 2, true, c).into_bytes().unwrap(),
+        ReplyChunkEncoder::new(9, 0, false, a).into_bytes().unwrap(),
+        ReplyChunkEncoder::new(9, 1, false, b).into_bytes().unwrap(),
+    ];
+
+    let mut asm = ChunkReassembler::new(9);
+    for frame in &frames {
+        assert_eq!(decode_seq(frame).unwrap(), 9);
+        assert!(!asm.is_complete());
+        asm.push(&decode_chunk(frame)).unwrap();
+    }
+    assert!(asm.is_complete());
+    assert_eq!(asm.finish().unwrap(), results);
+}
+
+#[test]
+fn test_err_reply_chunk_gap() {
+    let first = ReplyChunkEncoder::new(4, 0, false, b"ab").into_bytes().unwrap();
+    let last = ReplyChunkEncoder::new(4, 2, true, b"cd").into_bytes().unwrap();
+
+    let mut asm = ChunkReassembler::new(4);
+    asm.push(&decode_chunk(&last)).unwrap();
+    asm.push(&decode_chunk(&first)).unwrap();
+    assert!(!asm.is_complete());
+    match asm.finish() {
+        Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("missing 1")),
+        _ => panic!("Expected ProtocolViolation"),
+    }
+}
+
+#[test]
+fn test_err_reply_chunk_duplicate_and_overrun() {
+    let mut asm = ChunkReassembler::new(4);
+    asm.push(&decode_chunk(&ReplyChunkEncoder::new(4, 1, true, b"x").into_bytes().unwrap())).unwrap();
+
+    let dup = ReplyChunkEncoder::new(4, 1, false, b"y").into_bytes().unwrap();
+    assert!(matches!(asm.push(&decode_chunk(&dup)), Err(Error::ProtocolViolation(_))));
+
+    let past = ReplyChunkEncoder::new(4, 2, false, b"z").into_bytes().unwrap();
+    assert!(matches!(asm.push(&decode_chunk(&past)), Err(Error::ProtocolViolation(_))));
+
+    let other = ReplyChunkEncoder::new(5, 0, false, b"w").into_bytes().unwrap();
+    assert!(matches!(asm.push(&decode_chunk(&other)), Err(Error::ProtocolViolation(_))));
+}
+
+#[test]
+fn test_err_reply_chunk_over_limits() {
+    let chunk = |index, payload: &[u8]| ReplyChunkEncoder::new(4, index, false, payload).into_bytes().unwrap();
+
+    let mut asm = ChunkReassembler::with_limits(4, 2, 1024);
+    asm.push(&decode_chunk(&chunk(0, b"a"))).unwrap();
+    asm.push(&decode_chunk(&chunk(1, b"b"))).unwrap();
+    match asm.push(&decode_chunk(&chunk(2, b"c"))) {
+        Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("More than 2 chunks")),
+        _ => panic!("Expected ProtocolViolation"),
+    }
+
+    let mut asm = ChunkReassembler::with_limits(4, 16, 4);
+    asm.push(&decode_chunk(&chunk(0, b"abc"))).unwrap();
+    match asm.push(&decode_chunk(&chunk(1, b"de"))) {
+        Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("More than 4 chunk bytes")),
+        _ => panic!("Expected ProtocolViolation"),
+    }
+}
+
+fn batch_args(a: u32) -> Vec<u8> {
+    encode_vals_to_bytes(&[Val::U32(a)]).unwrap()
+}
+
+#[test]
+fn test_rpc_batch_roundtrip() {
+    let args = [batch_args(1), batch_args(2), batch_args(3)];
+    let mut batch = BatchCallEncoder::new();
+    batch.push(CallEncoder::new(10, "svc", "add", &args[0], None));
+    batch.push(CallEncoder::new(11, "svc", "sub", &args[1], Some(99)));
+    batch.push(CallEncoder::new(12, "other", "mul", &args[2], None));
+    let bytes = batch.into_bytes().unwrap();
+
+    let RpcFrame::Batch(calls) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Batch");
+    };
+    let calls: Vec<CallDecoder> = calls.map(|c| c.unwrap()).collect();
+    assert_eq!(calls.len(), 3);
+
+    let expected = [(10, "svc", "add", None, 1), (11, "svc", "sub", Some(99), 2), (12, "other", "mul", None, 3)];
+    for (call, (seq, target, method, deadline, arg)) in calls.into_iter().zip(expected) {
+        assert_eq!(call.seq, seq);
+        assert_eq!(call.target, target);
+        assert_eq!(call.method, method);
+        assert_eq!(call.deadline_ms, deadline);
+        let vals = decode_vals(call.args, &[Type::U32]).unwrap();
+        assert_eq!(format!("{:?}", vals), format!("{:?}", [Val::U32(arg)]));
+    }
+    assert!(matches!(decode_seq(&bytes), Err(Error::ProtocolViolation(_))));
+}
+
+#[test]
+fn test_err_batch_malformed_entry_is_isolated() {
+    let args = batch_args(7);
+    let mut enc = Encoder::new();
+    enc.variant_begin("Batch").unwrap();
+    enc.list_begin().unwrap();
+    CallEncoder::new(1, "svc", "a", &args, None).encode(&mut enc).unwrap();
+    // A Call without a seq
+    enc.variant_begin("Call").unwrap();
+    enc.map_begin().unwrap();
+    enc.variant_begin("target").unwrap();
+    enc.str("svc").unwrap();
+    enc.variant_end().unwrap();
+    enc.map_end().unwrap();
+    enc.variant_end().unwrap();
+    CallEncoder::new(3, "svc", "c", &args, None).encode(&mut enc).unwrap();
+    enc.list_end().unwrap();
+    enc.variant_end().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
+
+    let RpcFrame::Batch(mut calls) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Batch");
+    };
+    assert_eq!(calls.next().unwrap().unwrap().seq, 1);
+    match calls.next().unwrap() {
+        Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("entry 1"), "{}", msg),
+        _ => panic!("Expected ProtocolViolation for entry 1"),
+    }
+    assert_eq!(calls.next().unwrap().unwrap().seq, 3);
+    assert!(calls.next().is_none());
+}
+
+#[test]
+fn test_rpc_reply_batch_roundtrip() {
+    let results = encode_vals_to_bytes(&[Val::U32(42)]).unwrap();
+    let mut batch = ReplyBatchEncoder::new();
+    batch.push_ok(5, &results);
+    batch.push_err(6, FailureReason::MethodNotFound);
+    let bytes = batch.into_bytes().unwrap();
+
+    let RpcFrame::ReplyBatch(mut replies) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected ReplyBatch");
+    };
+    let ok = replies.next().unwrap().unwrap();
+    assert_eq!(ok.seq, 5);
+    let vals = decode_vals(ok.status.unwrap(), &[Type::U32]).unwrap();
+    assert_eq!(format!("{:?}", vals), format!("{:?}", [Val::U32(42)]));
+
+    let err = replies.next().unwrap().unwrap();
+    assert_eq!(err.seq, 6);
+    assert_eq!(err.status.err(), Some(FailureReason::MethodNotFound));
+    assert!(replies.next().is_none());
+}
+
+#[test]
+fn test_rpc_ping_pong_roundtrip() {
+    let ping = PingEncoder::new(7).into_bytes().unwrap();
+    match RpcFrame::decode(&ping).unwrap() {
+        RpcFrame::Ping(p) => assert_eq!(p.nonce, 7),
+        _ => panic!("Expected Ping"),
+    }
+    assert!(matches!(decode_seq(&ping), Err(Error::ProtocolViolation(_))));
+
+    let pong = PongEncoder::new(7).into_bytes().unwrap();
+    match RpcFrame::decode(&pong).unwrap() {
+        RpcFrame::Pong(p) => assert_eq!(p.nonce, 7),
+        _ => panic!("Expected Pong"),
+    }
+}
+
+#[test]
+fn test_rpc_handshake_roundtrip() {
+    let bytes = HandshakeEncoder::new(0xDEAD_BEEF).into_bytes().unwrap();
+    assert!(matches!(decode_seq(&bytes), Err(Error::ProtocolViolation(_))));
+
+    let RpcFrame::Handshake(handshake) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Handshake");
+    };
+    assert_eq!(handshake.fingerprint, 0xDEAD_BEEF);
+    assert_eq!(handshake.verify(0xDEAD_BEEF), Ok(()));
+    assert_eq!(
+        handshake.verify(1),
+        Err(FailureReason::DomainSpecific(SCHEMA_MISMATCH, "schema mismatch".into()))
+    );
+}
+
+#[test]
+fn test_rpc_handshake_targets() {
+    let bytes = HandshakeEncoder::new(7)
+        .with_targets(vec!["math".into(), "kv".into()])
+        .into_bytes()
+        .unwrap();
+    let RpcFrame::Handshake(handshake) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Handshake");
+    };
+    assert_eq!(handshake.fingerprint, 7);
+    assert_eq!(handshake.targets, vec!["math", "kv"]);
+
+    // A handshake listing no targets decodes with none
+    let bytes = HandshakeEncoder::new(7).into_bytes().unwrap();
+    let RpcFrame::Handshake(handshake) = RpcFrame::decode(&bytes).unwrap() else {
+        panic!("Expected Handshake");
+    };
+    assert!(handshake.targets.is_empty());
+}
+
+#[test]
+fn test_frames_start_with_version() {
+    let args = encode_vals_to_bytes(&[Val::U32(1)]).unwrap();
+    let call = CallEncoder::new(1, "svc", "m", &args, None).into_bytes().unwrap();
+    let compact = CallEncoder::new(1, "svc", "m", &args, None).into_compact_bytes().unwrap();
+    let ping = PingEncoder::new(1).into_bytes().unwrap();
+    for bytes in [call, compact, ping] {
+        assert_eq!(bytes[0], PROTO_VERSION);
+    }
+}
+
+#[test]
+fn test_err_version_mismatch() {
+    let args = encode_vals_to_bytes(&[]).unwrap();
+    let mut bytes = CallEncoder::new(5, "svc", "m", &args, None).into_bytes().unwrap();
+    bytes[0] = PROTO_VERSION + 1;
+
+    let expected = format!("version mismatch: got {}", PROTO_VERSION + 1);
+    match RpcFrame::decode(&bytes) {
+        Err(Error::ProtocolViolation(msg)) => assert_eq!(msg, expected),
+        other => panic!("Expected ProtocolViolation, got {:?}", other.map(|_| ())),
+    }
+    match decode_seq(&bytes) {
+        Err(Error::ProtocolViolation(msg)) => assert_eq!(msg, expected),
+        other => panic!("Expected ProtocolViolation, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_fingerprint_ignores_order_but_not_types() {
+    let ctx = TypeContext::new(r#"
+        (type $a (record (field "x" u32)))
+        (type $b (record (field "y" u32)))
+    "#, &["a", "b"]);
+    let (a, b) = ([ctx.get(0)], [ctx.get(1)]);
+    let method = |method, params| MethodSchema { interface: "svc", method, params, results: &[] };
+
+    let forward = fingerprint([method("get", &a[..]), method("put", &[Type::String])]);
+    let backward = fingerprint([method("put", &[Type::String]), method("get", &a[..])]);
+    assert_eq!(forward, backward);
+
+    // A renamed field changes how the record encodes, so it changes the fingerprint
+    let renamed = fingerprint([method("get", &b[..]), method("put", &[Type::String])]);
+    assert_ne!(forward, renamed);
+
+    // Types on the other side of the arrow are distinct
+    let swapped = fingerprint([MethodSchema { interface: "svc", method: "get", params: &[], results: &a }, method("put", &[Type::String])]);
+    assert_ne!(forward, swapped);
+
+    // Fixed across runs: no seeded hashing
+    assert_eq!(fingerprint([]), 0xcbf2_9ce4_8422_2325);
 }
 
 #[test]
@@ -344,6 +811,75 @@ fn test_err_unknown_flag() {
     }
 }
 
+#[test]
+fn test_resource_token_roundtrip() {
+    use wasmtime::Store;
+    use wasmtime::component::Resource;
+    use wasmtime::component::ResourceAny;
+    use wasmtime::component::ResourceType;
+
+    struct Handle;
+
+    let mut store = Store::new(&Engine::default(), ());
+    let handle = ResourceAny::try_from_resource(Resource::<Handle>::new_own(7), &mut store).unwrap();
+    let ty = Type::Own(ResourceType::host::<Handle>());
+    let val = Val::Resource(handle);
+
+    let mut enc = Encoder::new();
+    assert!(matches!(encode_val(&mut enc, &val), Err(Error::UnsupportedType(_))));
+
+    let opts = CodecOptions { allow_resource_tokens: true };
+    let mut tokens = ResourceTokens::new();
+    let mut enc = Encoder::new();
+    encode_val_with(&mut enc, &val, &opts, &mut tokens).unwrap();
+    let bytes = enc.into_bytes().unwrap();
+
+    let (name, mut body) = Decoder::new(&bytes).variant().unwrap();
+    assert_eq!((name, body.u32().unwrap()), ("ResourceToken", 0));
+
+    let decoded = decode_val_with(&mut Decoder::new(&bytes), &ty, &opts, &tokens).unwrap();
+    assert_eq!(decoded, val);
+
+    assert!(matches!(decode_val(&mut Decoder::new(&bytes), &ty), Err(Error::UnsupportedType(_))));
+    let empty = ResourceTokens::new();
+    assert!(matches!(decode_val_with(&mut Decoder::new(&bytes), &ty, &opts, &empty), Err(Error::ProtocolViolation(_))));
+}
+
+#[test]
+fn test_lenient_flags_policy() {
+    let ctx = TypeContext::new(r#"(type $t (flags "a" "c"))"#, &["t"]);
+    let ty = ctx.get(0);
+
+    let mut enc = Encoder::new();
+    enc.list_begin().unwrap(); enc.str("a").unwrap(); enc.str("b").unwrap(); enc.list_end().unwrap();
+    let bytes = enc.into_bytes().unwrap();
+
+    match decode_val_lenient(&mut Decoder::new(&bytes), &ty, UnknownPolicy::Error) {
+        Err(Error::UnknownVariant(f)) => assert_eq!(f, "b"),
+        _ => panic!("Expected UnknownVariant for flags"),
+    }
+
+    let (val, warnings) = decode_val_lenient(&mut Decoder::new(&bytes), &ty, UnknownPolicy::Skip).unwrap();
+    assert_eq!(val, Val::Flags(vec!["a".into()]));
+    assert_eq!(warnings, 1);
+}
+
+#[test]
+fn test_lenient_enum_substitutes_first_case() {
+    let ctx = TypeContext::new(r#"(type $t (enum "x" "y"))"#, &["t"]);
+    let ty = ctx.get(0);
+
+    let mut enc = Encoder::new();
+    enc.variant_begin("z").unwrap(); enc.unit().unwrap(); enc.variant_end().unwrap();
+    let bytes = enc.into_bytes().unwrap();
+
+    assert!(matches!(decode_val(&mut Decoder::new(&bytes), &ty), Err(Error::UnknownVariant(_))));
+
+    let (val, warnings) = decode_val_lenient(&mut Decoder::new(&bytes), &ty, UnknownPolicy::Skip).unwrap();
+    assert_eq!(val, Val::Enum("x".into()));
+    assert_eq!(warnings, 1);
+}
+
 #[test]
 fn test_err_type_mismatch_scalar() {
     let mut enc = Encoder::new();
@@ -426,8 +962,8 @@ fn test_err_rpc_protocol_missing_seq() {
     enc.map_end().unwrap();
     enc.variant_end().unwrap();
 
-    let bytes = enc.into_bytes().unwrap();
-    match RpcFrame::decode(&mut Decoder::new(&bytes)) {
+    let bytes = frame_bytes(enc).unwrap();
+    match RpcFrame::decode(&bytes) {
         Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("Missing seq")),
         _ => panic!("Expected ProtocolViolation"),
     }
@@ -444,8 +980,8 @@ fn test_err_rpc_protocol_missing_target() {
     enc.map_end().unwrap();
     enc.variant_end().unwrap();
 
-    let bytes = enc.into_bytes().unwrap();
-    match RpcFrame::decode(&mut Decoder::new(&bytes)) {
+    let bytes = frame_bytes(enc).unwrap();
+    match RpcFrame::decode(&bytes) {
         Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("Missing target")),
         _ => panic!("Expected ProtocolViolation"),
     }
@@ -462,8 +998,8 @@ fn test_err_rpc_protocol_missing_results_in_reply() {
     enc.result_ok_end().unwrap();
     enc.variant_end().unwrap();
 
-    let bytes = enc.into_bytes().unwrap();
-    match RpcFrame::decode(&mut Decoder::new(&bytes)) {
+    let bytes = frame_bytes(enc).unwrap();
+    match RpcFrame::decode(&bytes) {
         Err(Error::ProtocolViolation(msg)) => assert!(msg.contains("Missing results")),
         _ => panic!("Expected ProtocolViolation"),
     }
@@ -523,11 +1059,10 @@ fn test_boundary_recursion_limit_exceeded() {
 fn test_boundary_empty_strings_in_rpc_call() {
     let mut enc = Encoder::new();
     let empty_bytes = encode_vals_to_bytes(&[]).unwrap();
-    CallEncoder::new(0, "", "", &empty_bytes).encode(&mut enc).unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    CallEncoder::new(0, "", "", &empty_bytes, None).encode(&mut enc).unwrap();
+    let bytes = frame_bytes(enc).unwrap();
 
-    let mut dec = Decoder::new(&bytes);
-    match RpcFrame::decode(&mut dec).unwrap() {
+    match RpcFrame::decode(&bytes).unwrap() {
         RpcFrame::Call(c) => {
             assert_eq!(c.seq, 0);
             assert_eq!(c.target, "");
@@ -616,10 +1151,8 @@ fn test_boundary_rpc_call_with_unknown_header_fields() {
     enc.map_end().unwrap();
     enc.variant_end().unwrap();
 
-    let bytes = enc.into_bytes().unwrap();
-    let mut dec = Decoder::new(&bytes);
-
-    match RpcFrame::decode(&mut dec).unwrap() {
+    let bytes = frame_bytes(enc).unwrap();
+    match RpcFrame::decode(&bytes).unwrap() {
         RpcFrame::Call(c) => {
             assert_eq!(c.seq, 100);
             assert_eq!(c.target, "svc");
@@ -674,14 +1207,13 @@ fn test_frame_isolation_call_encoder() {
     
     // Now encode a Call frame using this pre-encoded payload
     let mut enc = Encoder::new();
-    CallEncoder::new(42, "test-target", "test-method", &args_payload)
+    CallEncoder::new(42, "test-target", "test-method", &args_payload, None)
         .encode(&mut enc)
         .unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
     
     // Decode and verify the frame structure
-    let mut dec = Decoder::new(&bytes);
-    let frame = RpcFrame::decode(&mut dec).unwrap();
+    let frame = RpcFrame::decode(&bytes).unwrap();
     
     match frame {
         RpcFrame::Call(mut call) => {
@@ -714,11 +1246,10 @@ fn test_frame_isolation_reply_ok_encoder() {
     ReplyOkEncoder::new(99, &results_payload)
         .encode(&mut enc)
         .unwrap();
-    let bytes = enc.into_bytes().unwrap();
+    let bytes = frame_bytes(enc).unwrap();
     
     // Decode and verify the frame structure
-    let mut dec = Decoder::new(&bytes);
-    let frame = RpcFrame::decode(&mut dec).unwrap();
+    let frame = RpcFrame::decode(&bytes).unwrap();
     
     match frame {
         RpcFrame::Reply(reply) => {